@@ -8,7 +8,7 @@ use std::{
     thread::{self, JoinHandle},
 };
 
-use crate::message::{CreationError, Message};
+use crate::message::{CreationError, LineReader, Message};
 
 /// Represents a client that can connect to a Server.
 pub struct AsyncReader {
@@ -48,8 +48,10 @@ fn async_send(
     sender: mpsc::Sender<Result<Message, CreationError>>,
     running: Arc<AtomicBool>,
 ) -> MessageSendError {
+    let mut reader = LineReader::new();
+
     while running.as_ref().load(Ordering::Relaxed) {
-        let message = Message::read_from(&mut stream);
+        let message = reader.read_message(&mut stream);
         if let Err(CreationError::IoError(io_error)) = &message {
             if let io::ErrorKind::WouldBlock = io_error.kind() {
                 continue;
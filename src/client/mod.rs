@@ -5,23 +5,27 @@ use std::{
     net::TcpStream,
 };
 
-use crate::message::{CreationError, Message, CRLF};
+use crate::message::{Command, CreationError, LineReader, Message, CRLF};
 
 /// Represents a client that can connect to a Server.
 pub struct Client {
     pub stream: TcpStream,
+    reader: LineReader,
 }
 
 impl Client {
     pub fn new(stream: TcpStream) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            reader: LineReader::new(),
+        }
     }
 
     /// Creates new [`Client`] connected to received address.
     pub fn connect(address: String) -> io::Result<Self> {
         let stream = TcpStream::connect(address)?;
 
-        Ok(Self { stream })
+        Ok(Self::new(stream))
     }
 
     /// Sends message to connected stream.
@@ -32,15 +36,21 @@ impl Client {
         self.stream.write_all(CRLF)
     }
 
-    /// Sends message to connected stream.
+    /// Sends command to connected stream.
+    pub fn send_command(&mut self, command: Command) -> io::Result<()> {
+        let message = Message::from(command);
+
+        self.send(&message.to_string())
+    }
+
+    /// Reads next message from connected stream, keeping partial lines buffered between calls.
     pub fn read(&mut self) -> Result<Message, CreationError> {
-        Message::read_from(&mut self.stream)
+        self.reader.read_message(&mut self.stream)
     }
 
+    /// Clones the connection, the clone starts reading with an empty buffer.
     pub fn try_clone(&mut self) -> io::Result<Self> {
-        Ok(Self {
-            stream: self.stream.try_clone()?,
-        })
+        Ok(Self::new(self.stream.try_clone()?))
     }
 
     pub fn get_stream(&self) -> io::Result<TcpStream> {
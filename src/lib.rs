@@ -7,7 +7,7 @@ pub mod client;
 
 /// This module contains a server's functionality.
 /// A server listens through an address waiting to hear from and then handle a client.
-/// Multiple clients can connect to a single server, a handler thread is created for each client.
+/// Multiple clients can connect to a single server, all of them served by a few event loop threads.
 /// A server han handle connection from other servers, sharing information throughout the network.
 pub mod server;
 
@@ -16,15 +16,8 @@ pub mod server;
 /// Each message is parsed in: prefix, command, parameters, trailing.
 pub mod message;
 
-/// This module contains a threadPool's functionality.
-/// A threadPool creates n threads to handle n requests simultaneously.
-/// This prevents an overflow attack on the server.
-pub mod thread_pool;
-
 /// This module contains useful macros used across the project.
 pub mod macros;
 
-/// Default parameters for client-server connection
-pub const ADDRESS: &str = "127.0.0.1:9000";
-pub const SERVERNAME: &str = "lemonpie";
-pub const SERVERINFO: &str = "serverinfo";
+/// Configuration file read when none is given
+pub const CONFIG_FILE: &str = "server.conf";
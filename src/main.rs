@@ -1,21 +1,46 @@
-use std::env;
 use std::io::{stdin, BufRead, BufReader};
+use std::path::Path;
+use std::{env, process};
 
-use internet_relay_chat::server::Server;
-use internet_relay_chat::{ADDRESS, SERVERINFO, SERVERNAME};
+use internet_relay_chat::server::{PasswordHash, Server, ServerConfig};
+use internet_relay_chat::CONFIG_FILE;
 
 const QUIT_CONNECTION_COMMAND: &str = "QUIT";
 const CONNECT_TO_SERVER_COMMAND: &str = "CONNECT";
+const HASH_PASSWORD_COMMAND: &str = "MKPASSWD";
+const REHASH_COMMAND: &str = "REHASH";
+const STATS_COMMAND: &str = "STATS";
+const SNAPSHOT_COMMAND: &str = "SNAPSHOT";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let (address, servername) = unpack_args(args);
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| CONFIG_FILE.to_string());
+    let config = match ServerConfig::from_file(Path::new(&path)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("Error: Reading configuration {path}: {error}");
+            process::exit(1);
+        }
+    };
 
-    let serverinfo = SERVERINFO.to_string();
+    let servername = config.servername.clone();
+    let serverinfo = config.serverinfo.clone();
     let mut server = Server::start(servername, serverinfo);
+    server.configure(&config);
 
-    if let Err(error) = server.listen_to(address) {
-        return eprintln!("Error: Binding to address: {error:?}");
+    if let Some(snapshot) = &config.snapshot_file {
+        match server.restore_snapshot(snapshot) {
+            Ok(true) => println!("Restored snapshot {}", snapshot.display()),
+            Ok(false) => (),
+            Err(error) => eprintln!("Error: Restoring snapshot {}: {error}", snapshot.display()),
+        }
+    }
+
+    for address in &config.listen {
+        if let Err(error) = server.listen_to(address.clone()) {
+            return eprintln!("Error: Binding to address {address}: {error:?}");
+        }
     }
 
     let reader = BufReader::new(stdin());
@@ -31,21 +56,53 @@ fn main() {
         }
         match split[0] {
             QUIT_CONNECTION_COMMAND => {
+                save_snapshot(&server, &config);
                 server.quit();
                 return;
             }
-            CONNECT_TO_SERVER_COMMAND => {
-                server.connect_to(split[1]);
+            SNAPSHOT_COMMAND => save_snapshot(&server, &config),
+            CONNECT_TO_SERVER_COMMAND if split.len() == 3 => {
+                server.connect_to(split[1], split[2]);
+            }
+            CONNECT_TO_SERVER_COMMAND if split.len() == 2 => {
+                match config.link(split[1]).and_then(|link| link.address.as_ref()) {
+                    Some(address) => server.connect_to(split[1], address),
+                    None => eprintln!("No address configured for {}", split[1]),
+                }
+            }
+            REHASH_COMMAND => match server.rehash() {
+                Ok(restart_required) => {
+                    println!("Rehashed {path}");
+                    for setting in restart_required {
+                        println!("Setting {setting} changed, it takes effect on restart");
+                    }
+                }
+                Err(error) => eprintln!("Error: Rehashing {path}: {error}"),
+            },
+            STATS_COMMAND => {
+                let refusals = server.refused_connections();
+                println!("Refused connections: {}", refusals.total());
+                println!("  server full: {}", refusals.server_full);
+                println!("  too many from host: {}", refusals.too_many_from_host);
+                println!("  throttled: {}", refusals.throttled);
+            }
+            HASH_PASSWORD_COMMAND if split.len() == 2 => {
+                println!("{}", PasswordHash::generate(split[1]));
             }
             _ => (),
         }
     }
 }
 
-fn unpack_args(mut args: Vec<String>) -> (String, String) {
-    if args.len() == 3 {
-        (args.remove(2), args.remove(1))
-    } else {
-        (ADDRESS.to_string(), SERVERNAME.to_string())
+/// Saves the state of the network to the configured snapshot file, if any.
+fn save_snapshot(server: &Server, config: &ServerConfig) {
+    let snapshot = match &config.snapshot_file {
+        Some(snapshot) => snapshot,
+        None => return,
+    };
+
+    match server.save_snapshot(snapshot) {
+        Ok(()) => println!("Saved snapshot {}", snapshot.display()),
+        Err(error) => eprintln!("Error: Saving snapshot {}: {error}", snapshot.display()),
     }
 }
@@ -0,0 +1,171 @@
+use super::{
+    parsing::MAX_PARAMETERS, Message, ParsingError, Tags, INVALID_CHARACTERS, MAX_LENGTH,
+    PREFIX_CHARACTER,
+};
+
+const SPACE: char = ' ';
+
+/// Builds a [`Message`] part by part, validating every part as it is added.
+///
+/// The first invalid part is remembered and returned by [`MessageBuilder::build`],
+/// so a message that could not be parsed back is never built.
+#[derive(Debug, Default)]
+pub struct MessageBuilder {
+    prefix: Option<String>,
+    command: Option<String>,
+    parameters: Vec<String>,
+    trailing: Option<String>,
+    error: Option<ParsingError>,
+}
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the message prefix, which must be a single non empty token.
+    pub fn prefix(self, prefix: impl ToString) -> Self {
+        let prefix = prefix.to_string();
+
+        if prefix.is_empty() {
+            return self.fail(ParsingError::EmptyPrefix);
+        }
+        if prefix.contains(SPACE) {
+            return self.fail(ParsingError::InvalidPrefix);
+        }
+
+        let mut builder = self.validate_characters(&prefix);
+        builder.prefix = Some(prefix);
+        builder
+    }
+
+    /// Sets the message prefix, if there is one.
+    pub fn optional_prefix(self, prefix: Option<impl ToString>) -> Self {
+        match prefix {
+            Some(prefix) => self.prefix(prefix),
+            None => self,
+        }
+    }
+
+    /// Sets the message command, which must be a single non empty token.
+    pub fn command(self, command: impl ToString) -> Self {
+        let command = command.to_string();
+
+        if command.is_empty() {
+            return self.fail(ParsingError::NoCommand);
+        }
+        if command.contains(SPACE) || command.as_bytes()[0] == PREFIX_CHARACTER {
+            return self.fail(ParsingError::InvalidCommand);
+        }
+
+        let mut builder = self.validate_characters(&command);
+        builder.command = Some(command);
+        builder
+    }
+
+    /// Appends a middle parameter.
+    /// It must not be empty, contain spaces or start with ':', those belong in the trailing parameter.
+    pub fn param(self, parameter: impl ToString) -> Self {
+        let parameter = parameter.to_string();
+
+        if parameter.is_empty()
+            || parameter.contains(SPACE)
+            || parameter.as_bytes()[0] == PREFIX_CHARACTER
+        {
+            return self.fail(ParsingError::InvalidParameter);
+        }
+        if self.parameters.len() == MAX_PARAMETERS {
+            return self.fail(ParsingError::TooManyParameters);
+        }
+
+        let mut builder = self.validate_characters(&parameter);
+        builder.parameters.push(parameter);
+        builder
+    }
+
+    /// Appends a middle parameter, if there is one.
+    pub fn optional_param(self, parameter: Option<impl ToString>) -> Self {
+        match parameter {
+            Some(parameter) => self.param(parameter),
+            None => self,
+        }
+    }
+
+    /// Appends every parameter in order.
+    pub fn params<T: ToString>(self, parameters: impl IntoIterator<Item = T>) -> Self {
+        parameters
+            .into_iter()
+            .fold(self, |builder, parameter| builder.param(parameter))
+    }
+
+    /// Sets the trailing parameter, which may contain spaces.
+    pub fn trailing(self, trailing: impl ToString) -> Self {
+        let trailing = trailing.to_string();
+
+        let mut builder = self.validate_characters(&trailing);
+        builder.trailing = Some(trailing);
+        builder
+    }
+
+    /// Sets the trailing parameter, if there is one.
+    pub fn optional_trailing(self, trailing: Option<impl ToString>) -> Self {
+        match trailing {
+            Some(trailing) => self.trailing(trailing),
+            None => self,
+        }
+    }
+
+    /// Returns the built message, or the first error found while building it.
+    /// A message over the length limit has its trailing parameter cut to fit,
+    /// failing only if no command was set or the message is too long without it.
+    pub fn build(self) -> Result<Message, ParsingError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let command = self.command.ok_or(ParsingError::NoCommand)?;
+
+        let mut message = Message {
+            tags: Tags::new(),
+            prefix: self.prefix,
+            command,
+            parameters: self.parameters,
+            trailing: self.trailing,
+        };
+
+        let excess = message.to_string().len().saturating_sub(MAX_LENGTH);
+        if excess > 0 {
+            let trailing = message.trailing.as_mut().ok_or(ParsingError::LineTooLong)?;
+            if trailing.len() < excess {
+                return Err(ParsingError::LineTooLong);
+            }
+            truncate_at_char_boundary(trailing, trailing.len() - excess);
+        }
+
+        Ok(message)
+    }
+
+    fn validate_characters(self, part: &str) -> Self {
+        if part.contains(INVALID_CHARACTERS) {
+            return self.fail(ParsingError::InvalidCharacter);
+        }
+        self
+    }
+
+    fn fail(mut self, error: ParsingError) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+}
+
+/// Shortens text to at most length bytes, without splitting a character.
+pub fn truncate_at_char_boundary(text: &mut String, length: usize) {
+    if text.len() <= length {
+        return;
+    }
+    let boundary = (0..=length)
+        .rev()
+        .find(|&index| text.is_char_boundary(index))
+        .unwrap_or(0);
+    text.truncate(boundary);
+}
@@ -0,0 +1,287 @@
+use super::names::*;
+use super::{join_list, split_list, Arguments, CommandArguments, CommandError};
+
+const ADD_MODE: char = '+';
+const REMOVE_MODE: char = '-';
+
+/// `JOIN <channel>{,<channel>} [<key>{,<key>}]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Join {
+    pub channels: Vec<String>,
+    pub keys: Vec<String>,
+}
+
+/// `PART <channel>{,<channel>} [:<comment>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    pub channels: Vec<String>,
+    pub comment: Option<String>,
+}
+
+/// `INVITE <nickname> <channel>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invite {
+    pub nickname: String,
+    pub channel: String,
+}
+
+/// `NAMES [<channel>{,<channel>}]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Names {
+    pub channels: Option<Vec<String>>,
+}
+
+/// `LIST [<channel>{,<channel>}]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct List {
+    pub channels: Option<Vec<String>>,
+}
+
+/// `TOPIC <channel> [[:]<topic>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topic {
+    pub channel: String,
+    pub topic: Option<String>,
+}
+
+/// `KICK <channel>{,<channel>} <nickname>{,<nickname>} [:<comment>]`
+///
+/// Each channel is paired with the nickname in the same position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kick {
+    pub channels: Vec<String>,
+    pub nicknames: Vec<String>,
+    pub comment: Option<String>,
+}
+
+/// `MODE <target> [<changes> {<argument>}]`
+///
+/// Changes are interpreted differently for channels and users,
+/// arguments are taken in order by the changes that need one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mode {
+    pub target: String,
+    pub changes: Option<Vec<ModeChange>>,
+    pub arguments: Vec<String>,
+}
+
+/// A single mode set or unset by a MODE command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeChange {
+    pub add: bool,
+    pub mode: char,
+}
+
+impl CommandArguments for Join {
+    const NAME: &'static str = JOIN_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let channels = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let keys = parameters.next();
+
+        Ok(Self {
+            channels: split_list(&channels),
+            keys: keys.map(|keys| split_list(&keys)).unwrap_or_default(),
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let mut parameters = vec![join_list(&self.channels)];
+        if !self.keys.is_empty() {
+            parameters.push(join_list(&self.keys));
+        }
+
+        (parameters, None)
+    }
+}
+
+impl CommandArguments for Part {
+    const NAME: &'static str = PART_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let channels = parameters
+            .first()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+
+        Ok(Self {
+            channels: split_list(channels),
+            comment: trailing,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![join_list(&self.channels)], self.comment)
+    }
+}
+
+impl CommandArguments for Invite {
+    const NAME: &'static str = INVITE_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        match (parameters.next(), parameters.next()) {
+            (Some(nickname), Some(channel)) => Ok(Self { nickname, channel }),
+            _ => Err(CommandError::need_more_parameters(Self::NAME)),
+        }
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.nickname, self.channel], None)
+    }
+}
+
+impl CommandArguments for Names {
+    const NAME: &'static str = NAMES_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let channels = parameters.first().map(|channels| split_list(channels));
+
+        Ok(Self { channels })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let parameters = self.channels.iter().map(|list| join_list(list)).collect();
+
+        (parameters, None)
+    }
+}
+
+impl CommandArguments for List {
+    const NAME: &'static str = LIST_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let channels = parameters.first().map(|channels| split_list(channels));
+
+        Ok(Self { channels })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let parameters = self.channels.iter().map(|list| join_list(list)).collect();
+
+        (parameters, None)
+    }
+}
+
+impl CommandArguments for Topic {
+    const NAME: &'static str = TOPIC_COMMAND;
+
+    /// Topic may be sent as trailing or as a middle parameter.
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let channel = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let topic = parameters.next().or(trailing);
+
+        Ok(Self { channel, topic })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.channel], self.topic)
+    }
+}
+
+impl CommandArguments for Kick {
+    const NAME: &'static str = KICK_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        match (parameters.next(), parameters.next()) {
+            (Some(channels), Some(nicknames)) => Ok(Self {
+                channels: split_list(&channels),
+                nicknames: split_list(&nicknames),
+                comment: trailing,
+            }),
+            _ => Err(CommandError::need_more_parameters(Self::NAME)),
+        }
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let parameters = vec![join_list(&self.channels), join_list(&self.nicknames)];
+
+        (parameters, self.comment)
+    }
+}
+
+impl CommandArguments for Mode {
+    const NAME: &'static str = MODE_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let target = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let changes = parameters
+            .next()
+            .map(|changes| parse_mode_changes(&changes))
+            .transpose()?;
+        let arguments = parameters.collect();
+
+        Ok(Self {
+            target,
+            changes,
+            arguments,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let mut parameters = vec![self.target];
+        parameters.extend(self.changes.map(|changes| format_mode_changes(&changes)));
+        parameters.extend(self.arguments);
+
+        (parameters, None)
+    }
+}
+
+/// Splits changes into each mode set or unset, in the order they were sent.
+/// Fails unless they start with a sign.
+fn parse_mode_changes(changes: &str) -> Result<Vec<ModeChange>, CommandError> {
+    if !changes.starts_with([ADD_MODE, REMOVE_MODE]) {
+        let message = "Modes should start with + or -";
+        return Err(CommandError::invalid_parameter(Mode::NAME, message));
+    }
+
+    let mut add = true;
+    let mut parsed = vec![];
+    for character in changes.chars() {
+        match character {
+            ADD_MODE => add = true,
+            REMOVE_MODE => add = false,
+            mode => parsed.push(ModeChange { add, mode }),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Joins changes back as they are sent, with a sign wherever they switch between setting and unsetting.
+pub fn format_mode_changes(changes: &[ModeChange]) -> String {
+    let mut formatted = String::new();
+    let mut add = None;
+    for change in changes {
+        if add != Some(change.add) {
+            formatted.push(if change.add { ADD_MODE } else { REMOVE_MODE });
+            add = Some(change.add);
+        }
+        formatted.push(change.mode);
+    }
+
+    formatted
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Reasons a message cannot be turned into a [`super::Command`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    UnknownCommand { command: String },
+    NeedMoreParameters { command: String },
+    NoNicknameGiven,
+    NoRecipient { command: String },
+    NoTextToSend,
+    NoOrigin,
+    InvalidCapCommand { subcommand: String },
+    InvalidParameter { command: String, message: String },
+}
+
+impl CommandError {
+    pub fn need_more_parameters(command: &str) -> Self {
+        let command = command.to_string();
+        Self::NeedMoreParameters { command }
+    }
+
+    pub fn no_recipient(command: &str) -> Self {
+        let command = command.to_string();
+        Self::NoRecipient { command }
+    }
+
+    pub fn invalid_parameter(command: &str, message: &str) -> Self {
+        let command = command.to_string();
+        let message = message.to_string();
+        Self::InvalidParameter { command, message }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand { command } => write!(f, "unknown command {command}"),
+            Self::NeedMoreParameters { command } => {
+                write!(f, "{command} needs more parameters")
+            }
+            Self::NoNicknameGiven => write!(f, "no nickname given"),
+            Self::NoRecipient { command } => write!(f, "{command} has no recipient"),
+            Self::NoTextToSend => write!(f, "no text to send"),
+            Self::NoOrigin => write!(f, "no origin specified"),
+            Self::InvalidCapCommand { subcommand } => {
+                write!(f, "invalid CAP subcommand {subcommand}")
+            }
+            Self::InvalidParameter { command, message } => write!(f, "{command}: {message}"),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use super::names::*;
+
+use super::{join_list, split_list, Arguments, CommandArguments, CommandError};
+
+/// `PRIVMSG <target>{,<target>} :<text>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Privmsg {
+    pub targets: Vec<String>,
+    pub text: String,
+}
+
+/// `NOTICE <target>{,<target>} :<text>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notice {
+    pub targets: Vec<String>,
+    pub text: String,
+}
+
+/// `CTCP <target> :<text>`
+///
+/// Sent to the target as a PRIVMSG, with the text between CTCP delimiters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ctcp {
+    pub target: String,
+    pub text: String,
+}
+
+impl CommandArguments for Privmsg {
+    const NAME: &'static str = PRIVMSG_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let (targets, text) = parse_message(Self::NAME, parameters, trailing)?;
+
+        Ok(Self { targets, text })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![join_list(&self.targets)], Some(self.text))
+    }
+}
+
+impl CommandArguments for Notice {
+    const NAME: &'static str = NOTICE_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let (targets, text) = parse_message(Self::NAME, parameters, trailing)?;
+
+        Ok(Self { targets, text })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![join_list(&self.targets)], Some(self.text))
+    }
+}
+
+impl CommandArguments for Ctcp {
+    const NAME: &'static str = CTCP_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let target = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let text = trailing.ok_or(CommandError::NoTextToSend)?;
+
+        Ok(Self { target, text })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.target], Some(self.text))
+    }
+}
+
+/// Parses the target list and text shared by PRIVMSG and NOTICE.
+fn parse_message(
+    command: &str,
+    parameters: Vec<String>,
+    trailing: Option<String>,
+) -> Result<(Vec<String>, String), CommandError> {
+    let targets = parameters
+        .first()
+        .ok_or_else(|| CommandError::no_recipient(command))?;
+    let text = trailing.ok_or(CommandError::NoTextToSend)?;
+
+    Ok((split_list(targets), text))
+}
@@ -0,0 +1,240 @@
+mod channel;
+mod command_error;
+mod messaging;
+/// Names commands are sent with, and the fixed arguments some of them take.
+pub mod names;
+mod registration;
+mod server;
+mod user;
+
+#[cfg(test)]
+mod tests;
+
+pub use channel::{
+    format_mode_changes, Invite, Join, Kick, List, Mode, ModeChange, Names, Part, Topic,
+};
+pub use command_error::CommandError;
+pub use messaging::{Ctcp, Notice, Privmsg};
+pub use registration::{Authenticate, Cap, CapSubcommand, Nick, Pass, Quit, Server, User};
+pub use server::{Kill, Motd, Oper, Ping, Pong, Rehash, Squit};
+pub use user::{Account, Away, Who, Whois};
+
+use names::*;
+
+use super::{Message, Tags};
+
+/// Middle parameters and trailing parameter of a message.
+pub type Arguments = (Vec<String>, Option<String>);
+
+const LIST_SEPARATOR: char = ',';
+
+/// Arguments of a single command, checked and typed.
+pub trait CommandArguments: Sized {
+    /// Name the command is sent with.
+    const NAME: &'static str;
+
+    /// Builds the command from its raw parameters.
+    /// Fails if a required parameter is missing or malformed.
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError>;
+
+    /// Turns the command back into its raw parameters.
+    fn into_arguments(self) -> Arguments;
+}
+
+/// Commands understood by the server, with their arguments already validated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Pass(Pass),
+    Nick(Nick),
+    User(User),
+    Oper(Oper),
+    Privmsg(Privmsg),
+    Notice(Notice),
+    Join(Join),
+    Part(Part),
+    Invite(Invite),
+    Names(Names),
+    List(List),
+    Who(Who),
+    Whois(Whois),
+    Away(Away),
+    Topic(Topic),
+    Kick(Kick),
+    Mode(Mode),
+    Quit(Quit),
+    Server(Server),
+    Squit(Squit),
+    Ctcp(Ctcp),
+    Ping(Ping),
+    Pong(Pong),
+    Motd(Motd),
+    Rehash(Rehash),
+    Kill(Kill),
+    Cap(Cap),
+    Authenticate(Authenticate),
+    Account(Account),
+}
+
+impl Command {
+    /// Creates new [`Command`] from its name and raw parameters.
+    /// Fails if the command is unknown or its parameters are invalid.
+    pub fn new(
+        command: &str,
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let arguments = (parameters, trailing);
+
+        match command {
+            PASS_COMMAND => parse(arguments).map(Self::Pass),
+            NICK_COMMAND => parse(arguments).map(Self::Nick),
+            USER_COMMAND => parse(arguments).map(Self::User),
+            OPER_COMMAND => parse(arguments).map(Self::Oper),
+            PRIVMSG_COMMAND => parse(arguments).map(Self::Privmsg),
+            NOTICE_COMMAND => parse(arguments).map(Self::Notice),
+            JOIN_COMMAND => parse(arguments).map(Self::Join),
+            PART_COMMAND => parse(arguments).map(Self::Part),
+            INVITE_COMMAND => parse(arguments).map(Self::Invite),
+            NAMES_COMMAND => parse(arguments).map(Self::Names),
+            LIST_COMMAND => parse(arguments).map(Self::List),
+            WHO_COMMAND => parse(arguments).map(Self::Who),
+            WHOIS_COMMAND => parse(arguments).map(Self::Whois),
+            AWAY_COMMAND => parse(arguments).map(Self::Away),
+            TOPIC_COMMAND => parse(arguments).map(Self::Topic),
+            KICK_COMMAND => parse(arguments).map(Self::Kick),
+            MODE_COMMAND => parse(arguments).map(Self::Mode),
+            QUIT_COMMAND => parse(arguments).map(Self::Quit),
+            SERVER_COMMAND => parse(arguments).map(Self::Server),
+            SQUIT_COMMAND => parse(arguments).map(Self::Squit),
+            CTCP_COMMAND => parse(arguments).map(Self::Ctcp),
+            PING_COMMAND => parse(arguments).map(Self::Ping),
+            PONG_COMMAND => parse(arguments).map(Self::Pong),
+            MOTD_COMMAND => parse(arguments).map(Self::Motd),
+            REHASH_COMMAND => parse(arguments).map(Self::Rehash),
+            KILL_COMMAND => parse(arguments).map(Self::Kill),
+            CAP_COMMAND => parse(arguments).map(Self::Cap),
+            AUTHENTICATE_COMMAND => parse(arguments).map(Self::Authenticate),
+            ACCOUNT_COMMAND => parse(arguments).map(Self::Account),
+            _ => Err(CommandError::UnknownCommand {
+                command: command.to_string(),
+            }),
+        }
+    }
+
+    /// Returns the name the command is sent with.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pass(_) => Pass::NAME,
+            Self::Nick(_) => Nick::NAME,
+            Self::User(_) => User::NAME,
+            Self::Oper(_) => Oper::NAME,
+            Self::Privmsg(_) => Privmsg::NAME,
+            Self::Notice(_) => Notice::NAME,
+            Self::Join(_) => Join::NAME,
+            Self::Part(_) => Part::NAME,
+            Self::Invite(_) => Invite::NAME,
+            Self::Names(_) => Names::NAME,
+            Self::List(_) => List::NAME,
+            Self::Who(_) => Who::NAME,
+            Self::Whois(_) => Whois::NAME,
+            Self::Away(_) => Away::NAME,
+            Self::Topic(_) => Topic::NAME,
+            Self::Kick(_) => Kick::NAME,
+            Self::Mode(_) => Mode::NAME,
+            Self::Quit(_) => Quit::NAME,
+            Self::Server(_) => Server::NAME,
+            Self::Squit(_) => Squit::NAME,
+            Self::Ctcp(_) => Ctcp::NAME,
+            Self::Ping(_) => Ping::NAME,
+            Self::Pong(_) => Pong::NAME,
+            Self::Motd(_) => Motd::NAME,
+            Self::Rehash(_) => Rehash::NAME,
+            Self::Kill(_) => Kill::NAME,
+            Self::Cap(_) => Cap::NAME,
+            Self::Authenticate(_) => Authenticate::NAME,
+            Self::Account(_) => Account::NAME,
+        }
+    }
+
+    /// Turns the command back into its raw parameters.
+    pub fn into_arguments(self) -> Arguments {
+        match self {
+            Self::Pass(command) => command.into_arguments(),
+            Self::Nick(command) => command.into_arguments(),
+            Self::User(command) => command.into_arguments(),
+            Self::Oper(command) => command.into_arguments(),
+            Self::Privmsg(command) => command.into_arguments(),
+            Self::Notice(command) => command.into_arguments(),
+            Self::Join(command) => command.into_arguments(),
+            Self::Part(command) => command.into_arguments(),
+            Self::Invite(command) => command.into_arguments(),
+            Self::Names(command) => command.into_arguments(),
+            Self::List(command) => command.into_arguments(),
+            Self::Who(command) => command.into_arguments(),
+            Self::Whois(command) => command.into_arguments(),
+            Self::Away(command) => command.into_arguments(),
+            Self::Topic(command) => command.into_arguments(),
+            Self::Kick(command) => command.into_arguments(),
+            Self::Mode(command) => command.into_arguments(),
+            Self::Quit(command) => command.into_arguments(),
+            Self::Server(command) => command.into_arguments(),
+            Self::Squit(command) => command.into_arguments(),
+            Self::Ctcp(command) => command.into_arguments(),
+            Self::Ping(command) => command.into_arguments(),
+            Self::Pong(command) => command.into_arguments(),
+            Self::Motd(command) => command.into_arguments(),
+            Self::Rehash(command) => command.into_arguments(),
+            Self::Kill(command) => command.into_arguments(),
+            Self::Cap(command) => command.into_arguments(),
+            Self::Authenticate(command) => command.into_arguments(),
+            Self::Account(command) => command.into_arguments(),
+        }
+    }
+
+    /// Turns the command into a [`Message`] sent from prefix.
+    pub fn into_message(self, prefix: Option<String>) -> Message {
+        let command = self.name().to_string();
+        let (parameters, trailing) = self.into_arguments();
+
+        Message {
+            tags: Tags::new(),
+            prefix,
+            command,
+            parameters,
+            trailing,
+        }
+    }
+}
+
+impl TryFrom<Message> for Command {
+    type Error = CommandError;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        let (_, command, parameters, trailing) = message.unpack();
+
+        Self::new(&command, parameters, trailing)
+    }
+}
+
+impl From<Command> for Message {
+    fn from(command: Command) -> Self {
+        command.into_message(None)
+    }
+}
+
+fn parse<T: CommandArguments>(arguments: Arguments) -> Result<T, CommandError> {
+    let (parameters, trailing) = arguments;
+    T::from_arguments(parameters, trailing)
+}
+
+/// Splits a comma separated list parameter.
+fn split_list(list: &str) -> Vec<String> {
+    list.split(LIST_SEPARATOR).map(str::to_string).collect()
+}
+
+fn join_list(list: &[String]) -> String {
+    list.join(&LIST_SEPARATOR.to_string())
+}
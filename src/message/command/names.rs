@@ -25,3 +25,25 @@ pub const SERVER_COMMAND: &str = "SERVER";
 pub const SQUIT_COMMAND: &str = "SQUIT";
 
 pub const CTCP_COMMAND: &str = "CTCP";
+
+pub const PING_COMMAND: &str = "PING";
+pub const PONG_COMMAND: &str = "PONG";
+pub const ERROR_COMMAND: &str = "ERROR";
+
+pub const MOTD_COMMAND: &str = "MOTD";
+pub const REHASH_COMMAND: &str = "REHASH";
+pub const KILL_COMMAND: &str = "KILL";
+
+pub const AUTHENTICATE_COMMAND: &str = "AUTHENTICATE";
+pub const AUTHENTICATE_ABORT: &str = "*";
+pub const AUTHENTICATE_EMPTY: &str = "+";
+pub const ACCOUNT_COMMAND: &str = "ACCOUNT";
+pub const LOGGED_OUT_ACCOUNT: &str = "*";
+
+pub const CAP_COMMAND: &str = "CAP";
+pub const CAP_LS: &str = "LS";
+pub const CAP_LIST: &str = "LIST";
+pub const CAP_REQ: &str = "REQ";
+pub const CAP_ACK: &str = "ACK";
+pub const CAP_NAK: &str = "NAK";
+pub const CAP_END: &str = "END";
@@ -0,0 +1,270 @@
+use std::fmt;
+
+use super::names::*;
+
+use super::{Arguments, CommandArguments, CommandError};
+
+/// `PASS <password>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pass {
+    pub password: String,
+}
+
+/// `NICK <nickname> [<hopcount> [<signon>]]`
+///
+/// Servers introduce new clients with hopcount and signon,
+/// nickname changes are sent with the old nickname as prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nick {
+    pub nickname: String,
+    pub hopcount: Option<usize>,
+    pub signon: Option<u64>,
+}
+
+/// `USER <username> [<hostname> <servername>] :<realname>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub username: String,
+    pub hostname: Option<String>,
+    pub servername: Option<String>,
+    pub realname: String,
+}
+
+/// `SERVER <servername> <hopcount> :<info>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Server {
+    pub servername: String,
+    pub hopcount: usize,
+    pub info: String,
+}
+
+/// `QUIT [:<message>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quit {
+    pub message: Option<String>,
+}
+
+/// `CAP <subcommand> [[:]<capabilities>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cap {
+    pub subcommand: CapSubcommand,
+    pub arguments: Option<String>,
+}
+
+/// `AUTHENTICATE <mechanism | data>`
+///
+/// Logs into an account with SASL, while capabilities are being negotiated.
+/// Data is sent base64 encoded, `+` when empty and `*` to abort.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Authenticate {
+    pub data: String,
+}
+
+/// Subcommands a client may send during capability negotiation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapSubcommand {
+    Ls,
+    List,
+    Req,
+    End,
+}
+
+impl CommandArguments for Pass {
+    const NAME: &'static str = PASS_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let password = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+
+        Ok(Self { password })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.password], None)
+    }
+}
+
+impl CommandArguments for Nick {
+    const NAME: &'static str = NICK_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let nickname = parameters.next().ok_or(CommandError::NoNicknameGiven)?;
+        let hopcount = parameters
+            .next()
+            .map(|hopcount| hopcount.parse::<usize>())
+            .transpose()
+            .map_err(|_| CommandError::invalid_parameter(Self::NAME, "Hopcount is not numeric"))?;
+        let signon = parameters
+            .next()
+            .map(|signon| signon.parse::<u64>())
+            .transpose()
+            .map_err(|_| CommandError::invalid_parameter(Self::NAME, "Signon is not numeric"))?;
+
+        Ok(Self {
+            nickname,
+            hopcount,
+            signon,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let mut parameters = vec![self.nickname];
+        parameters.extend(self.hopcount.map(|hopcount| hopcount.to_string()));
+        parameters.extend(self.signon.map(|signon| signon.to_string()));
+
+        (parameters, None)
+    }
+}
+
+impl CommandArguments for User {
+    const NAME: &'static str = USER_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let username = parameters.next();
+        let (username, realname) = match (username, trailing) {
+            (Some(username), Some(realname)) => (username, realname),
+            _ => return Err(CommandError::need_more_parameters(Self::NAME)),
+        };
+        let hostname = parameters.next();
+        let servername = parameters.next();
+
+        Ok(Self {
+            username,
+            hostname,
+            servername,
+            realname,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let mut parameters = vec![self.username];
+        parameters.extend(self.hostname);
+        parameters.extend(self.servername);
+
+        (parameters, Some(self.realname))
+    }
+}
+
+impl CommandArguments for Server {
+    const NAME: &'static str = SERVER_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let (servername, hopcount, info) = match (parameters.next(), parameters.next(), trailing) {
+            (Some(servername), Some(hopcount), Some(info)) => (servername, hopcount, info),
+            _ => return Err(CommandError::need_more_parameters(Self::NAME)),
+        };
+        let hopcount = hopcount
+            .parse::<usize>()
+            .map_err(|_| CommandError::invalid_parameter(Self::NAME, "Hopcount is not numeric"))?;
+
+        Ok(Self {
+            servername,
+            hopcount,
+            info,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let parameters = vec![self.servername, self.hopcount.to_string()];
+
+        (parameters, Some(self.info))
+    }
+}
+
+impl CommandArguments for Quit {
+    const NAME: &'static str = QUIT_COMMAND;
+
+    fn from_arguments(_: Vec<String>, trailing: Option<String>) -> Result<Self, CommandError> {
+        Ok(Self { message: trailing })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![], self.message)
+    }
+}
+
+impl CommandArguments for Cap {
+    const NAME: &'static str = CAP_COMMAND;
+
+    /// Capabilities may be sent as trailing or as the last middle parameter.
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        let subcommand = parameters
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let subcommand = CapSubcommand::parse(&subcommand)?;
+        let arguments = trailing.or_else(|| parameters.last());
+
+        Ok(Self {
+            subcommand,
+            arguments,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.subcommand.to_string()], self.arguments)
+    }
+}
+
+impl CommandArguments for Authenticate {
+    const NAME: &'static str = AUTHENTICATE_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let data = parameters
+            .into_iter()
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+
+        Ok(Self { data })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.data], None)
+    }
+}
+
+impl CapSubcommand {
+    /// Subcommands are case insensitive.
+    fn parse(subcommand: &str) -> Result<Self, CommandError> {
+        let subcommand = subcommand.to_uppercase();
+
+        match subcommand.as_str() {
+            CAP_LS => Ok(Self::Ls),
+            CAP_LIST => Ok(Self::List),
+            CAP_REQ => Ok(Self::Req),
+            CAP_END => Ok(Self::End),
+            _ => Err(CommandError::InvalidCapCommand { subcommand }),
+        }
+    }
+}
+
+impl fmt::Display for CapSubcommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subcommand = match self {
+            Self::Ls => CAP_LS,
+            Self::List => CAP_LIST,
+            Self::Req => CAP_REQ,
+            Self::End => CAP_END,
+        };
+        write!(f, "{subcommand}")
+    }
+}
@@ -0,0 +1,170 @@
+use super::names::*;
+
+use super::{Arguments, CommandArguments, CommandError};
+
+/// `OPER <username> <password>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Oper {
+    pub username: String,
+    pub password: String,
+}
+
+/// `SQUIT <servername> [:<comment>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Squit {
+    pub servername: String,
+    pub comment: Option<String>,
+}
+
+/// `PING <token>`
+///
+/// Token may be sent as trailing or as a middle parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ping {
+    pub token: String,
+}
+
+/// `PONG [<token>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pong {
+    pub token: Option<String>,
+}
+
+/// `MOTD [<target>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Motd {
+    pub target: Option<String>,
+}
+
+/// `REHASH`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rehash;
+
+/// `KILL <nickname> :<comment>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kill {
+    pub nickname: String,
+    pub comment: String,
+}
+
+impl CommandArguments for Oper {
+    const NAME: &'static str = OPER_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mut parameters = parameters.into_iter();
+
+        match (parameters.next(), parameters.next()) {
+            (Some(username), Some(password)) => Ok(Self { username, password }),
+            _ => Err(CommandError::need_more_parameters(Self::NAME)),
+        }
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.username, self.password], None)
+    }
+}
+
+impl CommandArguments for Squit {
+    const NAME: &'static str = SQUIT_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let servername = parameters
+            .into_iter()
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+
+        Ok(Self {
+            servername,
+            comment: trailing,
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.servername], self.comment)
+    }
+}
+
+impl CommandArguments for Ping {
+    const NAME: &'static str = PING_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let token = trailing
+            .or_else(|| parameters.into_iter().next())
+            .ok_or(CommandError::NoOrigin)?;
+
+        Ok(Self { token })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![], Some(self.token))
+    }
+}
+
+impl CommandArguments for Pong {
+    const NAME: &'static str = PONG_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let token = trailing.or_else(|| parameters.into_iter().last());
+
+        Ok(Self { token })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![], self.token)
+    }
+}
+
+impl CommandArguments for Motd {
+    const NAME: &'static str = MOTD_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let target = parameters.into_iter().next();
+
+        Ok(Self { target })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (self.target.into_iter().collect(), None)
+    }
+}
+
+impl CommandArguments for Rehash {
+    const NAME: &'static str = REHASH_COMMAND;
+
+    fn from_arguments(_: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        Ok(Self)
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![], None)
+    }
+}
+
+impl CommandArguments for Kill {
+    const NAME: &'static str = KILL_COMMAND;
+
+    fn from_arguments(
+        parameters: Vec<String>,
+        trailing: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let nickname = parameters.into_iter().next();
+
+        match (nickname, trailing) {
+            (Some(nickname), Some(comment)) => Ok(Self { nickname, comment }),
+            _ => Err(CommandError::need_more_parameters(Self::NAME)),
+        }
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![self.nickname], Some(self.comment))
+    }
+}
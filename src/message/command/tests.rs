@@ -0,0 +1,261 @@
+use super::*;
+
+fn command(content: &str) -> Result<Command, CommandError> {
+    Command::try_from(Message::new(content).unwrap())
+}
+
+#[test]
+fn privmsg_splits_targets() {
+    let expected = Command::Privmsg(Privmsg {
+        targets: vec!["nick".to_string(), "#channel".to_string()],
+        text: "hello  world ".to_string(),
+    });
+
+    assert_eq!(
+        expected,
+        command("PRIVMSG nick,#channel :hello  world ").unwrap()
+    );
+}
+
+#[test]
+fn privmsg_without_target_has_no_recipient() {
+    let expected = CommandError::NoRecipient {
+        command: "PRIVMSG".to_string(),
+    };
+
+    assert_eq!(expected, command("PRIVMSG :hello").unwrap_err());
+}
+
+#[test]
+fn privmsg_without_trailing_has_no_text() {
+    assert_eq!(
+        CommandError::NoTextToSend,
+        command("PRIVMSG nick").unwrap_err()
+    );
+}
+
+#[test]
+fn join_pairs_channels_with_keys() {
+    let expected = Command::Join(Join {
+        channels: vec!["#a".to_string(), "#b".to_string()],
+        keys: vec!["key".to_string()],
+    });
+
+    assert_eq!(expected, command("JOIN #a,#b key").unwrap());
+}
+
+#[test]
+fn missing_parameters_are_reported_with_command() {
+    let expected = CommandError::NeedMoreParameters {
+        command: "KICK".to_string(),
+    };
+
+    assert_eq!(expected, command("KICK #channel").unwrap_err());
+}
+
+#[test]
+fn nick_without_nickname_fails() {
+    assert_eq!(CommandError::NoNicknameGiven, command("NICK").unwrap_err());
+}
+
+#[test]
+fn nick_with_non_numeric_hopcount_fails() {
+    let error = command("NICK nickname one").unwrap_err();
+
+    assert!(matches!(error, CommandError::InvalidParameter { .. }));
+}
+
+#[test]
+fn nick_from_server_has_hopcount_and_signon() {
+    let expected = Command::Nick(Nick {
+        nickname: "nickname".to_string(),
+        hopcount: Some(2),
+        signon: Some(1000),
+    });
+
+    assert_eq!(expected, command("NICK nickname 2 1000").unwrap());
+}
+
+#[test]
+fn server_with_non_numeric_hopcount_fails() {
+    let expected = CommandError::InvalidParameter {
+        command: "SERVER".to_string(),
+        message: "Hopcount is not numeric".to_string(),
+    };
+
+    assert_eq!(
+        expected,
+        command("SERVER servername one :info").unwrap_err()
+    );
+}
+
+#[test]
+fn cap_subcommand_is_case_insensitive() {
+    let expected = Command::Cap(Cap {
+        subcommand: CapSubcommand::Req,
+        arguments: Some("multi-prefix away-notify".to_string()),
+    });
+
+    assert_eq!(
+        expected,
+        command("CAP req :multi-prefix away-notify").unwrap()
+    );
+}
+
+#[test]
+fn cap_with_invalid_subcommand_fails() {
+    let expected = CommandError::InvalidCapCommand {
+        subcommand: "FOO".to_string(),
+    };
+
+    assert_eq!(expected, command("CAP foo").unwrap_err());
+}
+
+#[test]
+fn authenticate_keeps_its_data() {
+    let expected = Command::Authenticate(Authenticate {
+        data: "PLAIN".to_string(),
+    });
+
+    assert_eq!(expected, command("AUTHENTICATE PLAIN").unwrap());
+}
+
+#[test]
+fn account_star_means_logged_out() {
+    let expected = Command::Account(Account { account: None });
+
+    assert_eq!(expected, command("ACCOUNT *").unwrap());
+    assert_eq!(
+        (vec!["*".to_string()], None),
+        Command::Account(Account { account: None }).into_arguments()
+    );
+}
+
+#[test]
+fn mode_keeps_changes_and_arguments() {
+    let expected = Command::Mode(Mode {
+        target: "#channel".to_string(),
+        changes: Some(vec![
+            ModeChange {
+                add: true,
+                mode: 'o',
+            },
+            ModeChange {
+                add: true,
+                mode: 'l',
+            },
+        ]),
+        arguments: vec!["nick".to_string(), "10".to_string()],
+    });
+
+    assert_eq!(expected, command("MODE #channel +ol nick 10").unwrap());
+}
+
+#[test]
+fn mode_changes_keep_their_sign() {
+    let mode = command("MODE #channel +o-v+k nick1 nick2 key").unwrap();
+
+    assert_eq!(
+        (
+            vec![
+                "#channel".to_string(),
+                "+o-v+k".to_string(),
+                "nick1".to_string(),
+                "nick2".to_string(),
+                "key".to_string()
+            ],
+            None
+        ),
+        mode.into_arguments()
+    );
+}
+
+#[test]
+fn mode_changes_must_start_with_a_sign() {
+    assert!(matches!(
+        command("MODE #channel o nick"),
+        Err(CommandError::InvalidParameter { .. })
+    ));
+}
+
+#[test]
+fn whois_server_precedes_nickmasks() {
+    let expected = Command::Whois(Whois {
+        server: Some("servername".to_string()),
+        nickmasks: vec!["nick*".to_string()],
+    });
+
+    assert_eq!(expected, command("WHOIS servername nick*").unwrap());
+}
+
+#[test]
+fn ping_token_may_be_middle_or_trailing() {
+    let expected = Command::Ping(Ping {
+        token: "token".to_string(),
+    });
+
+    assert_eq!(expected, command("PING token").unwrap());
+    assert_eq!(expected, command("PING :token").unwrap());
+    assert_eq!(CommandError::NoOrigin, command("PING").unwrap_err());
+}
+
+#[test]
+fn unknown_command_fails() {
+    let expected = CommandError::UnknownCommand {
+        command: "FOO".to_string(),
+    };
+
+    assert_eq!(expected, command("FOO bar").unwrap_err());
+}
+
+#[test]
+fn command_serializes_to_message() {
+    let privmsg = Command::Privmsg(Privmsg {
+        targets: vec!["nick".to_string(), "#channel".to_string()],
+        text: "hello world".to_string(),
+    });
+
+    let message = privmsg.into_message(Some("sender".to_string()));
+
+    assert_eq!(
+        ":sender PRIVMSG nick,#channel :hello world",
+        message.to_string()
+    );
+}
+
+#[test]
+fn commands_survive_round_trip() {
+    let contents = [
+        "PASS secret",
+        "NICK nickname 1 1000",
+        "USER username hostname servername :real name",
+        "OPER admin password",
+        "NOTICE #a,#b :text",
+        "JOIN #a,#b key1,key2",
+        "PART #a :comment",
+        "INVITE nickname #channel",
+        "NAMES #a,#b",
+        "LIST",
+        "WHO *mask*",
+        "WHOIS nick1,nick2",
+        "AWAY :be right back",
+        "TOPIC #channel :new topic",
+        "KICK #channel nickname :comment",
+        "MODE nickname +i",
+        "QUIT :bye",
+        "SERVER servername 1 :info",
+        "SQUIT servername :comment",
+        "CTCP nickname :VERSION",
+        "PING :token",
+        "PONG :token",
+        "MOTD",
+        "REHASH",
+        "KILL nickname :comment",
+        "CAP LS :302",
+    ];
+
+    for content in contents {
+        let message: Message = command(content).unwrap().into();
+        assert_eq!(content, message.to_string());
+    }
+}
@@ -0,0 +1,104 @@
+use super::names::*;
+
+use super::{join_list, split_list, Arguments, CommandArguments, CommandError};
+
+/// `WHO [<mask>]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Who {
+    pub mask: Option<String>,
+}
+
+/// `WHOIS [<server>] <nickmask>{,<nickmask>}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Whois {
+    pub server: Option<String>,
+    pub nickmasks: Vec<String>,
+}
+
+/// `AWAY [:<message>]`
+///
+/// Without message, the away status is removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Away {
+    pub message: Option<String>,
+}
+
+/// `ACCOUNT <account>`
+///
+/// Servers tell each other which account a client logged into, `*` when it logged out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub account: Option<String>,
+}
+
+impl CommandArguments for Who {
+    const NAME: &'static str = WHO_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let mask = parameters.into_iter().next();
+
+        Ok(Self { mask })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (self.mask.into_iter().collect(), None)
+    }
+}
+
+impl CommandArguments for Whois {
+    const NAME: &'static str = WHOIS_COMMAND;
+
+    /// Nickmasks are always the last parameter, a server may precede them.
+    fn from_arguments(
+        mut parameters: Vec<String>,
+        _: Option<String>,
+    ) -> Result<Self, CommandError> {
+        let nickmasks = parameters.pop().ok_or(CommandError::NoNicknameGiven)?;
+        let server = parameters.into_iter().next();
+
+        Ok(Self {
+            server,
+            nickmasks: split_list(&nickmasks),
+        })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let mut parameters: Vec<String> = self.server.into_iter().collect();
+        parameters.push(join_list(&self.nickmasks));
+
+        (parameters, None)
+    }
+}
+
+impl CommandArguments for Away {
+    const NAME: &'static str = AWAY_COMMAND;
+
+    fn from_arguments(_: Vec<String>, trailing: Option<String>) -> Result<Self, CommandError> {
+        Ok(Self { message: trailing })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        (vec![], self.message)
+    }
+}
+
+impl CommandArguments for Account {
+    const NAME: &'static str = ACCOUNT_COMMAND;
+
+    fn from_arguments(parameters: Vec<String>, _: Option<String>) -> Result<Self, CommandError> {
+        let account = parameters
+            .into_iter()
+            .next()
+            .ok_or_else(|| CommandError::need_more_parameters(Self::NAME))?;
+        let account = Some(account).filter(|account| account != LOGGED_OUT_ACCOUNT);
+
+        Ok(Self { account })
+    }
+
+    fn into_arguments(self) -> Arguments {
+        let account = self
+            .account
+            .unwrap_or_else(|| LOGGED_OUT_ACCOUNT.to_string());
+        (vec![account], None)
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::{self, Read};
+
+use super::tags::{MAX_TAGS_LENGTH, TAGS_CHARACTER};
+use super::{unexpected_eof_error, CreationError, Message, ParsingError, LF, MAX_LENGTH};
+
+/// Bytes requested from the stream on each read.
+const READ_CHUNK_SIZE: usize = 4096;
+/// Line limit, including CRLF.
+pub const MAX_LINE_LENGTH: usize = MAX_LENGTH + 2;
+
+const CR: u8 = b'\r';
+
+/// Frames messages from a stream, one line at a time.
+///
+/// Reads are done in chunks, and bytes past the end of a line stay buffered
+/// for the next call, even across read timeouts.
+/// Lines end in CRLF, though a bare LF is accepted too.
+#[derive(Debug, Default)]
+pub struct LineReader {
+    buffer: Vec<u8>,
+    /// true while the rest of an oversized line is being dropped
+    discarding: bool,
+}
+
+impl LineReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads next message from stream.
+    /// Fails on an IO error, including read timeouts, or when the line is not a valid message.
+    /// Lines over the limit fail with [`ParsingError::LineTooLong`] and are discarded entirely.
+    pub fn read_message(&mut self, stream: &mut dyn Read) -> Result<Message, CreationError> {
+        loop {
+            if let Some(line) = self.next_line()? {
+                return Ok(Message::new(&decode(&line))?);
+            }
+
+            if self.discarding {
+                self.buffer.clear();
+            } else if self.buffer.len() > line_limit(&self.buffer) {
+                self.buffer.clear();
+                self.discarding = true;
+                return Err(ParsingError::LineTooLong.into());
+            }
+
+            self.fill_buffer(stream)?;
+        }
+    }
+
+    /// Takes the next complete line from buffer, without its line ending.
+    fn next_line(&mut self) -> Result<Option<Vec<u8>>, ParsingError> {
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == LF[0]) {
+            let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+
+            if self.discarding {
+                self.discarding = false;
+                continue;
+            }
+
+            line.pop();
+            if line.last() == Some(&CR) {
+                line.pop();
+            }
+
+            if line.len() + 2 > line_limit(&line) {
+                return Err(ParsingError::LineTooLong);
+            }
+
+            return Ok(Some(line));
+        }
+
+        Ok(None)
+    }
+
+    fn fill_buffer(&mut self, stream: &mut dyn Read) -> io::Result<()> {
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        let read = stream.read(&mut chunk)?;
+        if read == 0 {
+            return Err(unexpected_eof_error());
+        }
+
+        self.buffer.extend_from_slice(&chunk[..read]);
+
+        Ok(())
+    }
+}
+
+/// Tagged lines may use the budget for tags on top of the line limit.
+fn line_limit(line: &[u8]) -> usize {
+    match line.first() {
+        Some(&TAGS_CHARACTER) => MAX_LINE_LENGTH + MAX_TAGS_LENGTH,
+        _ => MAX_LINE_LENGTH,
+    }
+}
+
+/// Decodes line as UTF-8, falling back to Latin-1 for clients using legacy encodings.
+fn decode(line: &[u8]) -> String {
+    match std::str::from_utf8(line) {
+        Ok(line) => line.to_string(),
+        Err(_) => line.iter().map(|byte| *byte as char).collect(),
+    }
+}
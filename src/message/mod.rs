@@ -1,15 +1,26 @@
+mod builder;
+pub mod command;
 mod creation_error;
+mod line_reader;
 mod parsing;
 mod parsing_error;
+mod tags;
 
 #[cfg(test)]
 mod tests;
 
+pub use builder::{truncate_at_char_boundary, MessageBuilder};
+pub use command::{Command, CommandError};
 pub use creation_error::CreationError;
+pub use line_reader::{LineReader, MAX_LINE_LENGTH};
 pub use parsing_error::ParsingError;
+pub use tags::{Tags, MAX_TAGS_LENGTH};
 
-use std::io::{self, BufRead, BufReader, Error, ErrorKind, Read, Write};
+use std::io::{self, Error, ErrorKind, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Message {
+    tags: Tags,
     prefix: Option<String>,
     command: String,
     parameters: Vec<String>,
@@ -22,13 +33,20 @@ const PREFIX_CHARACTER: u8 = b':';
 const MAX_LENGTH: usize = 510;
 const INVALID_CHARACTERS: [char; 3] = ['\r', '\n', '\0'];
 
+/// Types that can be turned into a [`Message`] before being sent.
+pub trait ToMessage {
+    /// Fails if the result would not be a valid message.
+    fn to_message(&self) -> Result<Message, ParsingError>;
+}
+
 impl Message {
     /// Creates new [`Message`] from a string
     /// Fails if message could not be parsed
     pub fn new(content: &str) -> Result<Self, ParsingError> {
-        let (prefix, command, parameters, trailing) = parsing::parse(content)?;
+        let (tags, prefix, command, parameters, trailing) = parsing::parse(content)?;
 
         Ok(Self {
+            tags,
             prefix,
             command,
             parameters,
@@ -36,6 +54,11 @@ impl Message {
         })
     }
 
+    /// Creates new [`MessageBuilder`], which validates every part as it is added
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+
     /// Sends message to a stream, trailed with CRLF
     pub fn send_to(&self, stream: &mut dyn Write) -> io::Result<()> {
         let string = self.to_string();
@@ -47,48 +70,6 @@ impl Message {
         Ok(())
     }
 
-    /// Reads message from a stream
-    /// Fails on an IO error or a Parsing error.
-    pub fn read_from(stream: &mut dyn Read) -> Result<Self, CreationError> {
-        let mut content = String::new();
-
-        read_line(stream, &mut content)?;
-
-        if content.as_bytes().ends_with(CRLF) {
-            content.pop();
-            content.pop();
-        } else {
-            return Err(CreationError::ParsingError(ParsingError::NoTrailingCRLF));
-        }
-
-        let message = Self::new(&content)?;
-
-        Ok(message)
-    }
-
-    /// Reads message from a BufReader
-    /// This performs better than `read_from` as a buffer performs
-    ///  large, infrequent reads on the underlying Read
-    ///  and maintains an in-memory buffer of the results.
-    pub fn read_from_buffer<R: Read>(buffer: &mut BufReader<R>) -> Result<Self, CreationError> {
-        let mut content = String::new();
-        let read = buffer.read_line(&mut content)?;
-        if read == 0 {
-            Err(unexpected_eof_error())?;
-        }
-
-        if content.as_bytes().ends_with(CRLF) {
-            content.pop();
-            content.pop();
-        } else {
-            return Err(CreationError::ParsingError(ParsingError::NoTrailingCRLF));
-        }
-
-        let message = Self::new(&content)?;
-
-        Ok(message)
-    }
-
     pub fn unpack(self) -> (Option<String>, String, Vec<String>, Option<String>) {
         (self.prefix, self.command, self.parameters, self.trailing)
     }
@@ -108,14 +89,49 @@ impl Message {
     pub fn get_trailing(&self) -> &Option<String> {
         &self.trailing
     }
+
+    pub fn get_tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    /// Returns value of tag, empty if it was sent without one
+    pub fn get_tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_key, _)| tag_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets value of tag, replacing it if already present
+    pub fn set_tag(&mut self, key: &str, value: &str) {
+        tags::set_tag(&mut self.tags, key, value)
+    }
+}
+
+impl ToMessage for Message {
+    fn to_message(&self) -> Result<Message, ParsingError> {
+        Ok(self.clone())
+    }
+}
+
+impl From<ParsingError> for io::Error {
+    fn from(error: ParsingError) -> Self {
+        io::Error::new(ErrorKind::InvalidData, error.to_string())
+    }
 }
 
 fn unexpected_eof_error() -> Error {
     Error::new(ErrorKind::UnexpectedEof, "")
 }
 
+/// Writes tokens separated by a single space, so parsed messages round trip
+/// unless their tokens were separated by runs of spaces.
 impl std::fmt::Display for Message {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.tags.is_empty() {
+            write!(f, "@{} ", tags::format_tags(&self.tags))?;
+        }
+
         if let Some(prefix) = &self.prefix {
             write!(f, ":{prefix} ")?;
         }
@@ -133,18 +149,3 @@ impl std::fmt::Display for Message {
         }
     }
 }
-
-/// Reads single line from a stream, calling a read for every byte read
-pub fn read_line(stream: &mut dyn Read, buffer: &mut String) -> io::Result<()> {
-    let mut content = String::new();
-
-    while !content.as_bytes().ends_with(LF) {
-        let mut buffer = [0; 1];
-        stream.read_exact(&mut buffer)?;
-        content.push(buffer[0] as char)
-    }
-
-    buffer.push_str(&content);
-
-    Ok(())
-}
@@ -1,111 +1,115 @@
+use super::tags::{self, Tags};
 use super::ParsingError;
 use super::INVALID_CHARACTERS;
 use super::MAX_LENGTH;
 use super::PREFIX_CHARACTER;
 
-use std::iter::Peekable;
-use std::str::SplitWhitespace;
-
 type Prefix = Option<String>;
 type Command = String;
 type Parameters = Vec<String>;
 type Trailing = Option<String>;
-type MessageParse = (Prefix, Command, Parameters, Trailing);
+type MessageParse = (Tags, Prefix, Command, Parameters, Trailing);
+
+const SPACE: char = ' ';
+pub const MAX_PARAMETERS: usize = 15;
 
-/// Parses string into prefix, command, parameters and trailing
+/// Parses string into tags, prefix, command, parameters and trailing
+/// Tags have their own length budget, separate from the rest of the message
+/// Runs of spaces between tokens are not kept, only the trailing is taken verbatim
 pub fn parse(content: &str) -> Result<MessageParse, ParsingError> {
     if content.is_empty() {
         return Err(ParsingError::EmptyMessage);
     }
-    if content.len() > MAX_LENGTH {
-        return Err(ParsingError::TooManyParameters);
-    }
     if content.contains(INVALID_CHARACTERS) {
         return Err(ParsingError::InvalidCharacter);
     }
 
-    let mut words = content.split_whitespace().peekable();
+    let (tags, content) = tags::split_tags(content)?;
+
+    if content.len() > MAX_LENGTH {
+        return Err(ParsingError::LineTooLong);
+    }
+
+    let mut rest = content;
 
-    let prefix = get_prefix(&mut words)?;
-    let command = get_command(&mut words)?;
-    let parameters = get_parameters(&mut words)?;
-    let trailing = get_trailing(&mut words)?;
+    let prefix = get_prefix(&mut rest)?;
+    let command = get_command(&mut rest)?;
+    let parameters = get_parameters(&mut rest)?;
+    let trailing = get_trailing(rest);
 
-    Ok((prefix, command, parameters, trailing))
+    Ok((tags, prefix, command, parameters, trailing))
 }
 
-/// If next iter item is a prefix, it consumes it and returns its value
-fn get_prefix(split: &mut Peekable<SplitWhitespace>) -> Result<Prefix, ParsingError> {
-    let possible_prefix = match split.peek() {
-        None => return Err(ParsingError::EmptyMessage),
-        Some(possible_prefix) => possible_prefix,
-    };
+/// Splits next token from content, advancing it to the separator that follows.
+/// Only spaces separate tokens, other whitespace is part of them.
+fn next_token<'a>(content: &mut &'a str) -> Option<&'a str> {
+    let start = content.trim_start_matches(SPACE);
+    if start.is_empty() {
+        return None;
+    }
+
+    let end = start.find(SPACE).unwrap_or(start.len());
+    let (token, rest) = start.split_at(end);
+    *content = rest;
+
+    Some(token)
+}
 
-    let first_character = *possible_prefix
-        .as_bytes()
-        .first()
-        .expect("SplitWhitespace does not generate empty elements");
+/// Returns whether the next token starts with ':'
+fn next_starts_with_colon(content: &str) -> bool {
+    content.trim_start_matches(SPACE).as_bytes().first() == Some(&PREFIX_CHARACTER)
+}
 
-    if first_character == PREFIX_CHARACTER {
-        let prefix = split.next().expect("Existance was verified on peek");
+/// If next token is a prefix, it consumes it and returns its value
+fn get_prefix(content: &mut &str) -> Result<Prefix, ParsingError> {
+    if content.trim_start_matches(SPACE).is_empty() {
+        return Err(ParsingError::EmptyMessage);
+    }
 
-        if prefix.len() == 1 {
-            return Err(ParsingError::EmptyPrefix);
-        }
+    if !next_starts_with_colon(content) {
+        return Ok(None);
+    }
 
-        let prefix = &prefix[1..];
+    let prefix = next_token(content).expect("Existance was verified on check");
 
-        return Ok(Some(prefix.to_string()));
+    if prefix.len() == 1 {
+        return Err(ParsingError::EmptyPrefix);
     }
 
-    Ok(None)
+    Ok(Some(prefix[1..].to_string()))
 }
 
-/// If next iter item is a command, it consumes it and returns its value
-fn get_command(split: &mut Peekable<SplitWhitespace>) -> Result<Command, ParsingError> {
-    let possible_command = match split.next() {
-        None => return Err(ParsingError::NoCommand),
-        Some(possible_command) => possible_command,
-    };
+/// Consumes command from content and returns its value
+fn get_command(content: &mut &str) -> Result<Command, ParsingError> {
+    let command = next_token(content).ok_or(ParsingError::NoCommand)?;
 
-    Ok(possible_command.to_string())
+    Ok(command.to_string())
 }
 
-/// Consumes parameters from iterator and returns them
-fn get_parameters(split: &mut Peekable<SplitWhitespace>) -> Result<Parameters, ParsingError> {
+/// Consumes middle parameters from content and returns them, stopping at the trailing parameter
+fn get_parameters(content: &mut &str) -> Result<Parameters, ParsingError> {
     let mut parameters = Vec::new();
 
-    while let Some(possible_parameter) = split.peek() {
-        let first_character = *possible_parameter
-            .as_bytes()
-            .first()
-            .expect("SplitWhitespace does not generate empty elements");
-
-        if first_character == PREFIX_CHARACTER {
-            break;
-        }
-
-        let parameter = split.next().expect("Existance was verified on peek");
+    while !next_starts_with_colon(content) {
+        let parameter = match next_token(content) {
+            Some(parameter) => parameter,
+            None => break,
+        };
         parameters.push(parameter.to_string());
     }
 
-    if parameters.len() > 15 {
+    if parameters.len() > MAX_PARAMETERS {
         return Err(ParsingError::TooManyParameters);
     }
 
     Ok(parameters)
 }
 
-/// If next iter item is a trailing parameter, it consumes it and returns its value
-fn get_trailing(split: &mut Peekable<SplitWhitespace>) -> Result<Trailing, ParsingError> {
-    if split.peek().is_none() {
-        return Ok(None);
-    }
-
-    let string_list: Vec<String> = split.map(|word| word.to_string()).collect();
-    let mut joined_string = string_list.join(" ");
-
-    joined_string.remove(0);
+/// If a trailing parameter remains, returns it exactly as sent, whitespace included
+fn get_trailing(content: &str) -> Trailing {
+    let start = content.trim_start_matches(SPACE);
 
-    Ok(Some(joined_string))
+    start
+        .strip_prefix(PREFIX_CHARACTER as char)
+        .map(|trailing| trailing.to_string())
 }
@@ -1,24 +1,39 @@
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ParsingError {
-    NoTrailingCRLF,
     EmptyMessage,
     EmptyPrefix,
     NoCommand,
     InvalidCharacter,
     TooManyParameters,
+    TagsTooLong,
+    InvalidTag,
+    LineTooLong,
+    InvalidPrefix,
+    InvalidCommand,
+    InvalidParameter,
 }
 
 impl fmt::Display for ParsingError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::NoTrailingCRLF => write!(f, "message should have trailing CRLF"),
             Self::EmptyMessage => write!(f, "message should not be empty"),
             Self::EmptyPrefix => write!(f, "prefix should not be empty"),
             Self::NoCommand => write!(f, "message should have a command"),
             Self::InvalidCharacter => write!(f, "encountered ilegal character"),
             Self::TooManyParameters => write!(f, "encountered too many parameters"),
+            Self::TagsTooLong => write!(f, "message tags exceed their length limit"),
+            Self::InvalidTag => write!(f, "encountered invalid tag"),
+            Self::LineTooLong => write!(f, "line exceeds the length limit"),
+            Self::InvalidPrefix => write!(f, "prefix should be a single word"),
+            Self::InvalidCommand => write!(f, "command should be a single word"),
+            Self::InvalidParameter => {
+                write!(
+                    f,
+                    "middle parameter should be a single word not starting with ':'"
+                )
+            }
         }
     }
 }
@@ -0,0 +1,123 @@
+use super::ParsingError;
+
+/// Key and value of each tag, in the order they were received or set.
+/// A tag without value is stored with an empty one.
+pub type Tags = Vec<(String, String)>;
+
+pub const TAGS_CHARACTER: u8 = b'@';
+/// Prefix marking a tag as client-only. They are parsed, but the server does not relay any tags,
+/// it only attaches its own `time` tag for clients that enabled server-time.
+const CLIENT_ONLY_PREFIX: char = '+';
+/// Budget for the tags section, including the leading '@' and the trailing space.
+/// It is independent from the budget of the rest of the message.
+pub const MAX_TAGS_LENGTH: usize = 8191;
+
+const TAG_SEPARATOR: char = ';';
+const VALUE_SEPARATOR: char = '=';
+const ESCAPE_CHARACTER: char = '\\';
+
+/// If content starts with a tags section, splits it from the rest of the message and parses it.
+pub fn split_tags(content: &str) -> Result<(Tags, &str), ParsingError> {
+    if content.as_bytes().first() != Some(&TAGS_CHARACTER) {
+        return Ok((Tags::new(), content));
+    }
+
+    let (tags, rest) = content.split_once(' ').ok_or(ParsingError::NoCommand)?;
+
+    if tags.len() + 1 > MAX_TAGS_LENGTH {
+        return Err(ParsingError::TagsTooLong);
+    }
+
+    Ok((parse_tags(&tags[1..])?, rest.trim_start_matches(' ')))
+}
+
+/// Parses `key=value;key2` into its tags, unescaping values.
+/// When a key is repeated, the last value is kept.
+fn parse_tags(content: &str) -> Result<Tags, ParsingError> {
+    let mut tags = Tags::new();
+
+    for tag in content.split(TAG_SEPARATOR).filter(|tag| !tag.is_empty()) {
+        let (key, value) = tag.split_once(VALUE_SEPARATOR).unwrap_or((tag, ""));
+
+        if !is_valid_key(key) {
+            return Err(ParsingError::InvalidTag);
+        }
+
+        set_tag(&mut tags, key, &unescape_value(value));
+    }
+
+    Ok(tags)
+}
+
+/// Sets value of tag, replacing it if already present.
+pub fn set_tag(tags: &mut Tags, key: &str, value: &str) {
+    match tags.iter_mut().find(|(tag_key, _)| tag_key == key) {
+        Some((_, tag_value)) => *tag_value = value.to_string(),
+        None => tags.push((key.to_string(), value.to_string())),
+    }
+}
+
+/// Formats tags as `key=value;key2`, escaping values.
+pub fn format_tags(tags: &Tags) -> String {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|(key, value)| match value.is_empty() {
+            true => key.to_string(),
+            false => format!("{key}{VALUE_SEPARATOR}{}", escape_value(value)),
+        })
+        .collect();
+
+    tags.join(&TAG_SEPARATOR.to_string())
+}
+
+/// Keys may have the client-only prefix and a vendor, followed by letters, digits and hyphens.
+fn is_valid_key(key: &str) -> bool {
+    let key = key.strip_prefix(CLIENT_ONLY_PREFIX).unwrap_or(key);
+    let name = key.rsplit('/').next().unwrap_or_default();
+
+    !name.is_empty()
+        && key
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-./".contains(character))
+}
+
+pub fn escape_value(value: &str) -> String {
+    let mut escaped = String::new();
+
+    for character in value.chars() {
+        match character {
+            ';' => escaped.push_str("\\:"),
+            ' ' => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Unknown escape sequences drop the backslash, and a trailing backslash is ignored.
+pub fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character != ESCAPE_CHARACTER {
+            unescaped.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some(':') => unescaped.push(';'),
+            Some('s') => unescaped.push(' '),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(character) => unescaped.push(character),
+            None => {}
+        }
+    }
+
+    unescaped
+}
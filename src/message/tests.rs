@@ -1,8 +1,11 @@
+use std::io::Read;
+
 use super::*;
 
 #[test]
 fn display_only_command() {
     let message = Message {
+        tags: vec![],
         prefix: None,
         command: "COMMAND".to_string(),
         parameters: vec![],
@@ -18,6 +21,7 @@ fn display_only_command() {
 #[test]
 fn display_with_prefix() {
     let message = Message {
+        tags: vec![],
         prefix: Some("prefix".to_string()),
         command: "COMMAND".to_string(),
         parameters: vec![],
@@ -33,6 +37,7 @@ fn display_with_prefix() {
 #[test]
 fn display_with_one_parameter() {
     let message = Message {
+        tags: vec![],
         prefix: None,
         command: "COMMAND".to_string(),
         parameters: vec!["param1".to_string()],
@@ -48,6 +53,7 @@ fn display_with_one_parameter() {
 #[test]
 fn display_with_two_parameters() {
     let message = Message {
+        tags: vec![],
         prefix: None,
         command: "COMMAND".to_string(),
         parameters: vec!["param1".to_string(), "param2".to_string()],
@@ -63,6 +69,7 @@ fn display_with_two_parameters() {
 #[test]
 fn display_with_trailing() {
     let message = Message {
+        tags: vec![],
         prefix: None,
         command: "COMMAND".to_string(),
         parameters: vec![],
@@ -78,6 +85,7 @@ fn display_with_trailing() {
 #[test]
 fn display_with_trailing_with_spaces() {
     let message = Message {
+        tags: vec![],
         prefix: None,
         command: "COMMAND".to_string(),
         parameters: vec![],
@@ -93,6 +101,7 @@ fn display_with_trailing_with_spaces() {
 #[test]
 fn display_full_message() {
     let message = Message {
+        tags: vec![],
         prefix: Some("prefix".to_string()),
         command: "COMMAND".to_string(),
         parameters: vec!["param1".to_string(), "param2".to_string()],
@@ -180,3 +189,424 @@ fn parsing_full_message() {
     );
     assert_eq!(Some("trailing with spaces".to_string()), message.trailing);
 }
+
+#[test]
+fn parsing_preserves_trailing_whitespace() {
+    let message = Message::new("COMMAND :  two  spaces\tand\ttabs  ").unwrap();
+
+    assert_eq!(
+        Some("  two  spaces\tand\ttabs  ".to_string()),
+        message.trailing
+    );
+}
+
+#[test]
+fn parsing_w_empty_trailing() {
+    let message = Message::new("COMMAND param1 :").unwrap();
+
+    assert_eq!(vec!["param1".to_string()], message.parameters);
+    assert_eq!(Some(String::new()), message.trailing);
+}
+
+#[test]
+fn parsing_w_colons_inside_trailing() {
+    let message = Message::new("COMMAND :a :b: c").unwrap();
+
+    assert_eq!(Some("a :b: c".to_string()), message.trailing);
+}
+
+#[test]
+fn parsing_splits_parameters_on_spaces_only() {
+    let message = Message::new("COMMAND param\t1 param2").unwrap();
+
+    assert_eq!(
+        vec!["param\t1".to_string(), "param2".to_string()],
+        message.parameters
+    );
+}
+
+#[test]
+fn parsing_w_too_many_parameters_fails() {
+    let parameters = vec!["p"; 16].join(" ");
+    let content = format!("COMMAND {parameters}");
+
+    assert!(matches!(
+        Message::new(&content),
+        Err(ParsingError::TooManyParameters)
+    ));
+}
+
+#[test]
+fn display_round_trips_parsed_messages() {
+    let contents = [
+        "COMMAND",
+        ":prefix COMMAND param1 param2",
+        ":prefix PRIVMSG #channel :  ( o_o )   \t<- ascii art  ",
+        "PRIVMSG nick :",
+        "@time=2022-11-30T18:05:42.123Z :nick NOTICE #channel :a  b",
+    ];
+
+    for content in contents {
+        assert_eq!(content, Message::new(content).unwrap().to_string());
+    }
+}
+
+#[test]
+fn display_collapses_spaces_between_tokens() {
+    let message = Message::new(":prefix   COMMAND  param1   param2  :a  b").unwrap();
+
+    assert_eq!(":prefix COMMAND param1 param2 :a  b", message.to_string());
+}
+
+#[test]
+fn parsing_w_line_over_length_limit_fails() {
+    let content = format!("COMMAND :{}", "a".repeat(MAX_LENGTH));
+
+    assert_eq!(Err(ParsingError::LineTooLong), Message::new(&content));
+}
+
+#[test]
+fn parsing_w_tags() {
+    let message =
+        Message::new("@id=123;flag;+example.com/key=value :prefix COMMAND param1").unwrap();
+
+    assert_eq!(Some("123"), message.get_tag("id"));
+    assert_eq!(Some(""), message.get_tag("flag"));
+    assert_eq!(Some("value"), message.get_tag("+example.com/key"));
+    assert_eq!(None, message.get_tag("missing"));
+    assert_eq!(Some("prefix".to_string()), message.prefix);
+    assert_eq!("COMMAND", &message.command);
+    assert_eq!(vec!["param1".to_string()], message.parameters);
+}
+
+#[test]
+fn parsing_w_escaped_tag_values() {
+    let message = Message::new(r"@key=a\:b\sc\\d\re\nf\x;end=\ COMMAND").unwrap();
+
+    assert_eq!(Some("a;b c\\d\re\nfx"), message.get_tag("key"));
+    assert_eq!(Some(""), message.get_tag("end"));
+}
+
+#[test]
+fn parsing_w_repeated_tag_keeps_last_value() {
+    let message = Message::new("@key=first;key=second COMMAND").unwrap();
+
+    assert_eq!(
+        &vec![("key".to_string(), "second".to_string())],
+        message.get_tags()
+    );
+}
+
+#[test]
+fn parsing_w_invalid_tag_fails() {
+    assert!(matches!(
+        Message::new("@=value COMMAND"),
+        Err(ParsingError::InvalidTag)
+    ));
+    assert!(matches!(Message::new("@key"), Err(ParsingError::NoCommand)));
+}
+
+#[test]
+fn tags_do_not_count_against_message_length() {
+    let tags = format!("@key={}", "a".repeat(1000));
+    let content = format!("{tags} COMMAND :{}", "b".repeat(400));
+
+    assert!(Message::new(&content).is_ok());
+}
+
+#[test]
+fn parsing_w_tags_over_their_budget_fails() {
+    let content = format!("@key={} COMMAND", "a".repeat(MAX_TAGS_LENGTH));
+
+    assert!(matches!(
+        Message::new(&content),
+        Err(ParsingError::TagsTooLong)
+    ));
+}
+
+#[test]
+fn display_with_tags_escapes_values() {
+    let mut message = Message::new(":prefix COMMAND :trailing").unwrap();
+    message.set_tag("time", "2023-01-01T00:00:00.000Z");
+    message.set_tag("flag", "");
+    message.set_tag("+note", "a; b\\c");
+
+    let actual = message.to_string();
+    let expected = r"@time=2023-01-01T00:00:00.000Z;flag;+note=a\:\sb\\c :prefix COMMAND :trailing";
+
+    assert_eq!(&actual, expected);
+}
+
+#[test]
+fn tags_survive_parsing_and_display() {
+    let content = r"@msgid=abc;+reply=a\sb :nick PRIVMSG #channel :hello";
+
+    assert_eq!(content, Message::new(content).unwrap().to_string());
+}
+
+#[test]
+fn set_tag_replaces_value() {
+    let mut message = Message::new("@msgid=old;other COMMAND").unwrap();
+
+    message.set_tag("msgid", "new");
+    assert_eq!(Some("new"), message.get_tag("msgid"));
+    assert_eq!(2, message.get_tags().len());
+}
+
+/// Stream that returns each chunk on a separate read, timing out once a `None` is reached.
+struct ChunkedStream {
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+impl ChunkedStream {
+    fn new(chunks: Vec<Option<&[u8]>>) -> Self {
+        let chunks = chunks.into_iter().rev().map(|c| c.map(Vec::from)).collect();
+        Self { chunks }
+    }
+}
+
+impl Read for ChunkedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.chunks.pop() {
+            None => Ok(0),
+            Some(None) => Err(Error::new(ErrorKind::WouldBlock, "")),
+            Some(Some(chunk)) => {
+                buf[..chunk.len()].copy_from_slice(&chunk);
+                Ok(chunk.len())
+            }
+        }
+    }
+}
+
+#[test]
+fn line_reader_frames_crlf_and_bare_lf() {
+    let mut stream = ChunkedStream::new(vec![Some(b"NICK nick\r\nUSER user\nPING")]);
+    let mut reader = LineReader::new();
+
+    let first = reader.read_message(&mut stream).unwrap();
+    let second = reader.read_message(&mut stream).unwrap();
+
+    assert_eq!("NICK nick", first.to_string());
+    assert_eq!("USER user", second.to_string());
+}
+
+#[test]
+fn line_reader_keeps_partial_line_across_timeouts() {
+    let mut stream = ChunkedStream::new(vec![Some(b"PRIVMSG #ch"), None, Some(b" :hi\r\n")]);
+    let mut reader = LineReader::new();
+
+    assert!(matches!(
+        reader.read_message(&mut stream),
+        Err(CreationError::IoError(error)) if error.kind() == ErrorKind::WouldBlock
+    ));
+
+    let message = reader.read_message(&mut stream).unwrap();
+    assert_eq!("PRIVMSG #ch :hi", message.to_string());
+}
+
+#[test]
+fn line_reader_decodes_utf8_split_across_reads() {
+    let content = "PRIVMSG #ch :ñandú 🦀\r\n".as_bytes();
+    let (first, second) = content.split_at(15);
+    let mut stream = ChunkedStream::new(vec![Some(first), Some(second)]);
+    let mut reader = LineReader::new();
+
+    let message = reader.read_message(&mut stream).unwrap();
+
+    assert_eq!(&Some("ñandú 🦀".to_string()), message.get_trailing());
+}
+
+#[test]
+fn line_reader_falls_back_to_latin1() {
+    let mut stream = ChunkedStream::new(vec![Some(b"PRIVMSG #ch :caf\xe9\r\n")]);
+    let mut reader = LineReader::new();
+
+    let message = reader.read_message(&mut stream).unwrap();
+
+    assert_eq!(&Some("café".to_string()), message.get_trailing());
+}
+
+#[test]
+fn line_reader_discards_oversized_line() {
+    let long_line = format!("PRIVMSG #ch :{}\r\nPING :token\r\n", "a".repeat(600));
+    let mut stream = ChunkedStream::new(vec![Some(long_line.as_bytes())]);
+    let mut reader = LineReader::new();
+
+    assert!(matches!(
+        reader.read_message(&mut stream),
+        Err(CreationError::ParsingError(ParsingError::LineTooLong))
+    ));
+    let message = reader.read_message(&mut stream).unwrap();
+    assert_eq!("PING :token", message.to_string());
+}
+
+#[test]
+fn line_reader_discards_unterminated_oversized_line_without_buffering_it() {
+    let chunk = [b'a'; 400];
+    let mut stream = ChunkedStream::new(vec![
+        Some(&chunk),
+        Some(&chunk),
+        Some(&chunk),
+        Some(b"aaa\r\nPING :token\r\n"),
+    ]);
+    let mut reader = LineReader::new();
+
+    assert!(matches!(
+        reader.read_message(&mut stream),
+        Err(CreationError::ParsingError(ParsingError::LineTooLong))
+    ));
+    let message = reader.read_message(&mut stream).unwrap();
+    assert_eq!("PING :token", message.to_string());
+}
+
+#[test]
+fn line_reader_allows_tags_beyond_line_limit() {
+    let content = format!("@key={} PING :token\r\n", "a".repeat(600));
+    let mut stream = ChunkedStream::new(vec![Some(content.as_bytes())]);
+    let mut reader = LineReader::new();
+
+    let message = reader.read_message(&mut stream).unwrap();
+
+    assert_eq!(Some("a".repeat(600).as_str()), message.get_tag("key"));
+}
+
+#[test]
+fn line_reader_fails_on_closed_stream() {
+    let mut stream = ChunkedStream::new(vec![Some(b"PING")]);
+    let mut reader = LineReader::new();
+
+    assert!(matches!(
+        reader.read_message(&mut stream),
+        Err(CreationError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof
+    ));
+}
+
+#[test]
+fn builder_builds_complete_message() {
+    let message = Message::builder()
+        .prefix("prefix")
+        .command("PRIVMSG")
+        .param("#channel")
+        .trailing("hello  world")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        ":prefix PRIVMSG #channel :hello  world",
+        message.to_string()
+    );
+}
+
+#[test]
+fn builder_fails_on_parameter_with_space() {
+    let result = Message::builder()
+        .command("PRIVMSG")
+        .param("two words")
+        .build();
+
+    assert_eq!(Err(ParsingError::InvalidParameter), result);
+}
+
+#[test]
+fn builder_fails_on_parameter_starting_with_colon() {
+    let result = Message::builder()
+        .command("NICK")
+        .param(":nickname")
+        .build();
+
+    assert_eq!(Err(ParsingError::InvalidParameter), result);
+}
+
+#[test]
+fn builder_fails_on_empty_parameter() {
+    let result = Message::builder().command("NICK").param("").build();
+
+    assert_eq!(Err(ParsingError::InvalidParameter), result);
+}
+
+#[test]
+fn builder_fails_on_invalid_character_in_trailing() {
+    let result = Message::builder()
+        .command("PRIVMSG")
+        .param("#channel")
+        .trailing("hello\r\nQUIT")
+        .build();
+
+    assert_eq!(Err(ParsingError::InvalidCharacter), result);
+}
+
+#[test]
+fn builder_fails_without_command() {
+    let result = Message::builder().prefix("prefix").build();
+
+    assert_eq!(Err(ParsingError::NoCommand), result);
+}
+
+#[test]
+fn builder_fails_on_too_many_parameters() {
+    let result = Message::builder().command("COMMAND").params(0..16).build();
+
+    assert_eq!(Err(ParsingError::TooManyParameters), result);
+}
+
+#[test]
+fn builder_truncates_trailing_of_long_message() {
+    let message = Message::builder()
+        .prefix("nickname")
+        .command("PRIVMSG")
+        .param("#channel")
+        .trailing("a".repeat(MAX_LENGTH))
+        .build()
+        .unwrap();
+
+    assert_eq!(MAX_LENGTH, message.to_string().len());
+    assert!(message
+        .to_string()
+        .starts_with(":nickname PRIVMSG #channel :aaa"));
+}
+
+#[test]
+fn builder_truncates_trailing_without_splitting_characters() {
+    let message = Message::builder()
+        .command("PRIVMSG")
+        .param("#chan")
+        .trailing("é".repeat(MAX_LENGTH))
+        .build()
+        .unwrap();
+
+    assert_eq!(MAX_LENGTH - 1, message.to_string().len());
+}
+
+#[test]
+fn builder_fails_on_long_message_without_trailing() {
+    let result = Message::builder()
+        .command("PRIVMSG")
+        .param("a".repeat(MAX_LENGTH))
+        .build();
+
+    assert_eq!(Err(ParsingError::LineTooLong), result);
+}
+
+#[test]
+fn builder_keeps_first_error() {
+    let result = Message::builder()
+        .prefix("")
+        .command("COMMAND")
+        .param("two words")
+        .build();
+
+    assert_eq!(Err(ParsingError::EmptyPrefix), result);
+}
+
+#[test]
+fn built_message_can_be_parsed_back() {
+    let message = Message::builder()
+        .prefix("servername")
+        .command("353")
+        .params(["nickname", "#channel"])
+        .trailing(":nick1 nick2")
+        .build()
+        .unwrap();
+
+    assert_eq!(message, Message::new(&message.to_string()).unwrap());
+}
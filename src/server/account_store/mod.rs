@@ -0,0 +1,95 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::server::config::{
+    parse_sections, read_if_exists, write_atomically, ConfigError, Section,
+};
+use crate::server::data_structures::{Account, PasswordHash};
+
+#[cfg(test)]
+mod tests;
+
+const ACCOUNT_SECTION: &str = "account";
+const ACCOUNT_KEYS: [&str; 2] = ["password", "registered"];
+const HEADER: &str = "# Registered nicknames, rewritten by the server whenever they change.\n";
+
+/// File nickname accounts are saved to, in the same format as the configuration file:
+///
+/// ```text
+/// [account nickname]
+/// password = salt$digest
+/// registered = 1669831542
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountStore {
+    path: PathBuf,
+}
+
+impl AccountStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads every account. A store that was never saved holds none.
+    pub fn load(&self) -> Result<Vec<Account>, ConfigError> {
+        match read_if_exists(&self.path)? {
+            Some(content) => parse_accounts(&content),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Replaces every saved account with accounts.
+    pub fn save(&self, accounts: &[Account]) -> io::Result<()> {
+        write_atomically(&self.path, &format_accounts(accounts))
+    }
+}
+
+fn parse_accounts(content: &str) -> Result<Vec<Account>, ConfigError> {
+    let mut accounts = vec![];
+
+    for section in parse_sections(content)? {
+        match (section.name.as_str(), &section.argument) {
+            (ACCOUNT_SECTION, Some(name)) => accounts.push(parse_account(&section, name)?),
+            _ => {
+                let line = section.line;
+                let section = section.name.clone();
+                return Err(ConfigError::UnknownSection { line, section });
+            }
+        }
+    }
+
+    Ok(accounts)
+}
+
+fn parse_account(section: &Section, name: &str) -> Result<Account, ConfigError> {
+    section.check_keys(&ACCOUNT_KEYS)?;
+
+    let entry = section.required("password")?;
+    let password: PasswordHash = entry
+        .value
+        .parse()
+        .map_err(|error: String| entry.invalid(&error))?;
+    let registered = section.required("registered")?.number()? as u64;
+
+    Ok(Account {
+        name: name.to_string(),
+        password,
+        registered,
+    })
+}
+
+fn format_accounts(accounts: &[Account]) -> String {
+    let mut content = HEADER.to_string();
+
+    for account in accounts {
+        content.push_str(&format!("\n[{ACCOUNT_SECTION} {}]\n", account.name));
+        content.push_str(&format!("password = {}\n", account.password));
+        content.push_str(&format!("registered = {}\n", account.registered));
+    }
+
+    content
+}
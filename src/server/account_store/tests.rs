@@ -0,0 +1,46 @@
+use std::{env, fs};
+
+use super::*;
+
+fn temporary_store(name: &str) -> AccountStore {
+    let path = env::temp_dir().join(format!("{name}-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+    AccountStore::new(path)
+}
+
+#[test]
+fn store_that_was_never_saved_holds_no_accounts() {
+    let store = temporary_store("missing-accounts");
+
+    assert_eq!(vec![] as Vec<Account>, store.load().unwrap());
+}
+
+#[test]
+fn saved_accounts_can_be_loaded() {
+    let store = temporary_store("saved-accounts");
+    let accounts = vec![
+        Account::new("nick", "password", 1669831542),
+        Account::new("other", "secret", 1669831600),
+    ];
+
+    store.save(&accounts).unwrap();
+
+    let loaded = store.load().unwrap();
+    assert_eq!(accounts, loaded);
+    assert!(loaded[0].verify("password"));
+    fs::remove_file(store.path()).unwrap();
+}
+
+#[test]
+fn account_without_password_is_invalid() {
+    let content = "[account nick]\nregistered = 1669831542\n";
+
+    assert!(parse_accounts(content).is_err());
+}
+
+#[test]
+fn account_with_malformed_password_is_invalid() {
+    let content = "[account nick]\npassword = plaintext\nregistered = 1669831542\n";
+
+    assert!(parse_accounts(content).is_err());
+}
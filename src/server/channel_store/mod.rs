@@ -0,0 +1,179 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::server::config::{
+    parse_sections, read_if_exists, write_atomically, ConfigError, Entry, Section,
+};
+use crate::server::consts::channel_flag::ChannelFlag;
+use crate::server::data_structures::ChannelConfiguration;
+
+#[cfg(test)]
+mod tests;
+
+const CHANNEL_SECTION: &str = "channel";
+/// Keys of a channel's configuration, shared with database snapshots.
+pub const CONFIGURATION_KEYS: [&str; 7] = [
+    "topic",
+    "key",
+    "limit",
+    "flags",
+    "operators",
+    "speakers",
+    "banmasks",
+];
+const HEADER: &str = "# Registered channels, rewritten by the server whenever they change.\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A channel registered to its founder, with the configuration it is restored with.
+pub struct RegisteredChannel {
+    pub name: String,
+    /// account of the client that registered the channel
+    pub founder: String,
+    pub config: ChannelConfiguration,
+}
+
+/// File registered channels are saved to, in the same format as the configuration file:
+///
+/// ```text
+/// [channel #channel]
+/// founder = account
+/// topic = "channel topic"
+/// flags = nt
+/// operators = nickname other
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelStore {
+    path: PathBuf,
+}
+
+impl ChannelStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads every registered channel. A store that was never saved holds none.
+    pub fn load(&self) -> Result<Vec<RegisteredChannel>, ConfigError> {
+        match read_if_exists(&self.path)? {
+            Some(content) => parse_channels(&content),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Replaces every saved channel with channels.
+    pub fn save(&self, channels: &[RegisteredChannel]) -> io::Result<()> {
+        write_atomically(&self.path, &format_channels(channels))
+    }
+}
+
+fn parse_channels(content: &str) -> Result<Vec<RegisteredChannel>, ConfigError> {
+    let mut channels = vec![];
+
+    for section in parse_sections(content)? {
+        match (section.name.as_str(), &section.argument) {
+            (CHANNEL_SECTION, Some(name)) => channels.push(parse_channel(&section, name)?),
+            _ => {
+                let line = section.line;
+                let section = section.name.clone();
+                return Err(ConfigError::UnknownSection { line, section });
+            }
+        }
+    }
+
+    Ok(channels)
+}
+
+fn parse_channel(section: &Section, name: &str) -> Result<RegisteredChannel, ConfigError> {
+    section.check_keys(&[&["founder"][..], &CONFIGURATION_KEYS].concat())?;
+
+    let founder = section.required("founder")?.word()?;
+    let config = parse_configuration(section)?;
+
+    Ok(RegisteredChannel {
+        name: name.to_string(),
+        founder,
+        config,
+    })
+}
+
+/// Reads the channel configuration keys of section.
+pub fn parse_configuration(section: &Section) -> Result<ChannelConfiguration, ConfigError> {
+    let mut config = ChannelConfiguration::new();
+    config.topic = section.optional("topic")?.map(Entry::text);
+    config.key = match section.optional("key")? {
+        Some(entry) => Some(entry.word()?),
+        None => None,
+    };
+    config.user_limit = match section.optional("limit")? {
+        Some(entry) => Some(entry.number()?),
+        None => None,
+    };
+    if let Some(entry) = section.optional("flags")? {
+        for character in entry.value.chars() {
+            match ChannelFlag::from_char(character) {
+                ChannelFlag::InvalidFlag => {
+                    return Err(entry.invalid(&format!("unknown flag {character}")))
+                }
+                flag => config.flags.push(flag),
+            }
+        }
+    }
+    config.operators = words(section, "operators")?;
+    config.speakers = words(section, "speakers")?;
+    config.banmasks = words(section, "banmasks")?;
+
+    Ok(config)
+}
+
+/// Returns the space separated values of key, if defined.
+pub fn words(section: &Section, key: &str) -> Result<Vec<String>, ConfigError> {
+    let entry = section.optional(key)?;
+    let words = entry.map(|entry| entry.value.split_whitespace().map(str::to_string));
+
+    Ok(words.into_iter().flatten().collect())
+}
+
+fn format_channels(channels: &[RegisteredChannel]) -> String {
+    let mut content = HEADER.to_string();
+
+    for channel in channels {
+        content.push_str(&format!("\n[{CHANNEL_SECTION} {}]\n", channel.name));
+        content.push_str(&format!("founder = {}\n", channel.founder));
+        format_configuration(&mut content, &channel.config);
+    }
+
+    content
+}
+
+/// Writes the channel configuration keys of config.
+pub fn format_configuration(content: &mut String, config: &ChannelConfiguration) {
+    if let Some(topic) = &config.topic {
+        push_text(content, "topic", topic);
+    }
+    if let Some(key) = &config.key {
+        push_entry(content, "key", key);
+    }
+    if let Some(limit) = config.user_limit {
+        push_entry(content, "limit", &limit.to_string());
+    }
+    let flags: String = config.flags.iter().map(ChannelFlag::to_char).collect();
+    push_entry(content, "flags", &flags);
+    push_entry(content, "operators", &config.operators.join(" "));
+    push_entry(content, "speakers", &config.speakers.join(" "));
+    push_entry(content, "banmasks", &config.banmasks.join(" "));
+}
+
+/// Empty values are left out, since the format does not allow them.
+/// Writes free text between double quotes, keeping it as it is even if empty.
+pub fn push_text(content: &mut String, key: &str, value: &str) {
+    content.push_str(&format!("{key} = \"{value}\"\n"));
+}
+
+pub fn push_entry(content: &mut String, key: &str, value: &str) {
+    if !value.trim().is_empty() {
+        content.push_str(&format!("{key} = {}\n", value.trim()));
+    }
+}
@@ -0,0 +1,71 @@
+use std::{env, fs};
+
+use super::*;
+
+fn temporary_store(name: &str) -> ChannelStore {
+    let path = env::temp_dir().join(format!("{name}-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+    ChannelStore::new(path)
+}
+
+fn registered_channel() -> RegisteredChannel {
+    let mut config = ChannelConfiguration::new();
+    config.topic = Some("a channel topic".to_string());
+    config.key = Some("key".to_string());
+    config.user_limit = Some(5);
+    config.flags = vec![
+        ChannelFlag::NoOutsideMessages,
+        ChannelFlag::TopicByOperatorOnly,
+    ];
+    config.operators = vec!["nick".to_string(), "other".to_string()];
+    config.banmasks = vec!["*!*@host".to_string()];
+
+    RegisteredChannel {
+        name: "#channel".to_string(),
+        founder: "nick".to_string(),
+        config,
+    }
+}
+
+#[test]
+fn store_that_was_never_saved_holds_no_channels() {
+    let store = temporary_store("missing-channels");
+
+    assert_eq!(vec![] as Vec<RegisteredChannel>, store.load().unwrap());
+}
+
+#[test]
+fn saved_channels_can_be_loaded() {
+    let store = temporary_store("saved-channels");
+    let channels = vec![registered_channel()];
+
+    store.save(&channels).unwrap();
+
+    assert_eq!(channels, store.load().unwrap());
+    fs::remove_file(store.path()).unwrap();
+}
+
+#[test]
+fn saving_replaces_previous_channels() {
+    let store = temporary_store("replaced-channels");
+
+    store.save(&[registered_channel()]).unwrap();
+    store.save(&[]).unwrap();
+
+    assert_eq!(vec![] as Vec<RegisteredChannel>, store.load().unwrap());
+    fs::remove_file(store.path()).unwrap();
+}
+
+#[test]
+fn channel_without_founder_is_invalid() {
+    let content = "[channel #channel]\ntopic = topic\n";
+
+    assert!(parse_channels(content).is_err());
+}
+
+#[test]
+fn unknown_flag_is_invalid() {
+    let content = "[channel #channel]\nfounder = nick\nflags = nz\n";
+
+    assert!(parse_channels(content).is_err());
+}
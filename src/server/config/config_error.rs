@@ -0,0 +1,76 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+/// Errors that may be found when reading a configuration file.
+pub enum ConfigError {
+    CouldNotRead {
+        reason: String,
+    },
+    NoFile,
+    InvalidLine {
+        line: usize,
+    },
+    OutsideSection {
+        line: usize,
+    },
+    UnknownSection {
+        line: usize,
+        section: String,
+    },
+    DuplicateSection {
+        line: usize,
+        section: String,
+    },
+    MissingSection {
+        section: String,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    DuplicateKey {
+        line: usize,
+        key: String,
+    },
+    MissingKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CouldNotRead { reason } => write!(f, "could not read file: {reason}"),
+            Self::NoFile => write!(f, "server was not configured from a file"),
+            Self::InvalidLine { line } => {
+                write!(f, "line {line}: expected [section] or key = value")
+            }
+            Self::OutsideSection { line } => {
+                write!(f, "line {line}: key should be inside a section")
+            }
+            Self::UnknownSection { line, section } => {
+                write!(f, "line {line}: unknown section [{section}]")
+            }
+            Self::DuplicateSection { line, section } => {
+                write!(f, "line {line}: section [{section}] was already defined")
+            }
+            Self::MissingSection { section } => write!(f, "missing section [{section}]"),
+            Self::UnknownKey { line, key } => write!(f, "line {line}: unknown key {key}"),
+            Self::DuplicateKey { line, key } => {
+                write!(f, "line {line}: key {key} was already defined")
+            }
+            Self::MissingKey { line, key } => {
+                write!(f, "line {line}: section is missing key {key}")
+            }
+            Self::InvalidValue { line, key, reason } => {
+                write!(f, "line {line}: invalid value for {key}, {reason}")
+            }
+        }
+    }
+}
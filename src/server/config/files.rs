@@ -0,0 +1,27 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use super::ConfigError;
+
+/// Reads the file at path. Returns None if it does not exist, as for a store that was never saved.
+pub fn read_if_exists(path: &Path) -> Result<Option<String>, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => {
+            let reason = error.to_string();
+            Err(ConfigError::CouldNotRead { reason })
+        }
+    }
+}
+
+/// Replaces the file at path with content.
+/// A temporary file is written first, so a failed save keeps the previous content.
+pub fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    fs::write(&temporary, content)?;
+    fs::rename(&temporary, path)
+}
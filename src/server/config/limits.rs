@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use crate::server::consts::channel::MAX_CHANNELS;
+use crate::server::consts::user::MAX_NICKNAME_LENGTH;
+use crate::server::{
+    IPV4_CIDR, IPV6_CIDR, MAX_CLIENTS, MAX_CONNECTIONS_PER_IP, PING_INTERVAL_SECS,
+    PING_TIMEOUT_SECS, REGISTRATION_TIMELIMIT_SECS, SENDQ_BYTES, THROTTLE_CONNECTIONS,
+    THROTTLE_PERIOD_SECS,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Limits the server enforces on its connections.
+pub struct Limits {
+    /// how many connections the server handles at the same time
+    pub max_clients: usize,
+    /// how many connections the server handles from the same address block
+    pub max_per_ip: usize,
+    /// prefix length grouping IPv4 addresses into the same block
+    pub ipv4_cidr: u8,
+    /// prefix length grouping IPv6 addresses into the same block
+    pub ipv6_cidr: u8,
+    /// how many times an address may connect within the throttle period
+    pub throttle_connections: usize,
+    pub throttle_period: Duration,
+    /// how many channels a client may join
+    pub max_channels: usize,
+    pub nickname_length: usize,
+    /// how long a connection has to register
+    pub registration_timeout: Duration,
+    /// how long a connection may stay idle before being pinged
+    pub ping_interval: Duration,
+    /// how long a connection has to answer a ping
+    pub ping_timeout: Duration,
+    /// how many bytes may wait to be written to a connection before it is dropped
+    pub sendq: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_clients: MAX_CLIENTS,
+            max_per_ip: MAX_CONNECTIONS_PER_IP,
+            ipv4_cidr: IPV4_CIDR,
+            ipv6_cidr: IPV6_CIDR,
+            throttle_connections: THROTTLE_CONNECTIONS,
+            throttle_period: Duration::from_secs(THROTTLE_PERIOD_SECS),
+            max_channels: MAX_CHANNELS,
+            nickname_length: MAX_NICKNAME_LENGTH,
+            registration_timeout: Duration::from_secs(REGISTRATION_TIMELIMIT_SECS),
+            ping_interval: Duration::from_secs(PING_INTERVAL_SECS),
+            ping_timeout: Duration::from_secs(PING_TIMEOUT_SECS),
+            sendq: SENDQ_BYTES,
+        }
+    }
+}
@@ -0,0 +1,361 @@
+/// This module contains the errors that may be found in a configuration file.
+mod config_error;
+/// This module reads and writes the files the server keeps its state in.
+mod files;
+/// This module contains the limits the server enforces on its connections.
+mod limits;
+/// This module splits a configuration file in sections and entries.
+mod parsing;
+/// This module reloads the configuration of a running server.
+mod rehash;
+
+#[cfg(test)]
+mod tests;
+
+pub use config_error::ConfigError;
+pub(crate) use files::{read_if_exists, write_atomically};
+pub use limits::Limits;
+pub(crate) use parsing::{parse_sections, Entry, Section};
+pub(crate) use rehash::rehash;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::server::data_structures::{OperatorBlock, OperatorPrivilege, PasswordHash};
+
+const SERVER_SECTION: &str = "server";
+const LIMITS_SECTION: &str = "limits";
+const OPERATOR_SECTION: &str = "operator";
+const LINK_SECTION: &str = "link";
+const BAN_SECTION: &str = "ban";
+
+const SERVER_KEYS: [&str; 8] = [
+    "name", "info", "listen", "motd", "password", "channels", "accounts", "snapshot",
+];
+const LIMITS_KEYS: [&str; 12] = [
+    "max_clients",
+    "max_per_ip",
+    "ipv4_cidr",
+    "ipv6_cidr",
+    "throttle_connections",
+    "throttle_period",
+    "max_channels",
+    "nickname_length",
+    "registration_timeout",
+    "ping_interval",
+    "ping_timeout",
+    "sendq",
+];
+const OPERATOR_KEYS: [&str; 3] = ["password", "host", "privileges"];
+const LINK_KEYS: [&str; 2] = ["password", "address"];
+const BAN_KEYS: [&str; 1] = ["reason"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A server this server may link with.
+pub struct LinkBlock {
+    pub servername: String,
+    /// password both servers exchange when linking
+    pub password: String,
+    /// address the server listens from, used when connecting to it
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Clients that are not allowed to register.
+pub struct BanBlock {
+    /// user@host mask banned clients match
+    pub mask: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Configuration the server is started with, read from an INI-like file:
+///
+/// ```text
+/// [server]
+/// name = lemonpie
+/// info = serverinfo
+/// listen = 127.0.0.1:9000
+/// channels = channels.db
+/// accounts = accounts.db
+/// snapshot = snapshot.db
+///
+/// [operator admin]
+/// password = salt$digest
+/// host = *@127.0.0.1
+/// privileges = kill, rehash
+///
+/// [link otherserver]
+/// password = secret
+/// address = 127.0.0.1:9001
+///
+/// [ban *@10.0.0.*]
+/// reason = spam
+/// ```
+pub struct ServerConfig {
+    pub servername: String,
+    pub serverinfo: String,
+    pub listen: Vec<String>,
+    pub motd_file: Option<PathBuf>,
+    /// file registered channels are saved to, channels cannot be registered without it
+    pub channels_file: Option<PathBuf>,
+    /// file nickname accounts are saved to, nicknames cannot be registered without it
+    pub accounts_file: Option<PathBuf>,
+    /// file the state of the network is saved to on quit, and restored from at startup
+    pub snapshot_file: Option<PathBuf>,
+    /// password clients must send in order to register
+    pub password: Option<String>,
+    pub operators: Vec<OperatorBlock>,
+    pub links: Vec<LinkBlock>,
+    pub bans: Vec<BanBlock>,
+    pub limits: Limits,
+    /// file the configuration was read from, reread on rehash
+    pub file: Option<PathBuf>,
+}
+
+impl ServerConfig {
+    /// Reads and validates configuration file.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path).map_err(|error| ConfigError::CouldNotRead {
+            reason: error.to_string(),
+        })?;
+
+        let mut config: Self = content.parse()?;
+        config.file = Some(path.to_path_buf());
+
+        Ok(config)
+    }
+
+    /// Returns the settings that differ from other and can only change on restart.
+    /// Ping settings are among them, since every connection keeps the ones it started with.
+    pub fn restart_required(&self, other: &ServerConfig) -> Vec<&'static str> {
+        let mut settings = vec![];
+        if self.servername != other.servername {
+            settings.push("name");
+        }
+        if self.serverinfo != other.serverinfo {
+            settings.push("info");
+        }
+        if self.listen != other.listen {
+            settings.push("listen");
+        }
+        if self.limits.max_clients != other.limits.max_clients {
+            settings.push("max_clients");
+        }
+        if self.limits.ping_interval != other.limits.ping_interval {
+            settings.push("ping_interval");
+        }
+        if self.limits.ping_timeout != other.limits.ping_timeout {
+            settings.push("ping_timeout");
+        }
+        settings
+    }
+
+    /// Returns the link block for servername, if configured.
+    pub fn link(&self, servername: &str) -> Option<&LinkBlock> {
+        self.links.iter().find(|link| link.servername == servername)
+    }
+}
+
+impl std::str::FromStr for ServerConfig {
+    type Err = ConfigError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let sections = parse_sections(content)?;
+
+        let mut server = None;
+        let mut limits = None;
+        let mut operators = vec![];
+        let mut links = vec![];
+        let mut bans = vec![];
+        let mut seen = HashSet::new();
+
+        for section in &sections {
+            let name = match &section.argument {
+                Some(argument) => format!("{} {argument}", section.name),
+                None => section.name.clone(),
+            };
+            if !seen.insert(name.clone()) {
+                let line = section.line;
+                return Err(ConfigError::DuplicateSection {
+                    line,
+                    section: name,
+                });
+            }
+
+            match (section.name.as_str(), &section.argument) {
+                (SERVER_SECTION, None) => server = Some(section),
+                (LIMITS_SECTION, None) => limits = Some(parse_limits(section)?),
+                (OPERATOR_SECTION, Some(name)) => operators.push(parse_operator(section, name)?),
+                (LINK_SECTION, Some(name)) => links.push(parse_link(section, name)?),
+                (BAN_SECTION, Some(mask)) => bans.push(parse_ban(section, mask)?),
+                _ => {
+                    let line = section.line;
+                    return Err(ConfigError::UnknownSection {
+                        line,
+                        section: name,
+                    });
+                }
+            }
+        }
+
+        let server = server.ok_or_else(|| ConfigError::MissingSection {
+            section: SERVER_SECTION.to_string(),
+        })?;
+
+        let mut config = parse_server(server, limits.unwrap_or_default())?;
+        config.operators = operators;
+        config.links = links;
+        config.bans = bans;
+
+        Ok(config)
+    }
+}
+
+fn parse_server(section: &Section, limits: Limits) -> Result<ServerConfig, ConfigError> {
+    section.check_keys(&SERVER_KEYS)?;
+
+    let servername = section.required("name")?.word()?;
+    let serverinfo = section.required("info")?.value.clone();
+    let motd_file = section
+        .optional("motd")?
+        .map(|entry| entry.value.clone().into());
+    let channels_file = section
+        .optional("channels")?
+        .map(|entry| entry.value.clone().into());
+    let accounts_file = section
+        .optional("accounts")?
+        .map(|entry| entry.value.clone().into());
+    let snapshot_file = section
+        .optional("snapshot")?
+        .map(|entry| entry.value.clone().into());
+    let password = section
+        .optional("password")?
+        .map(|entry| entry.value.clone());
+
+    let listen = section
+        .all("listen")
+        .into_iter()
+        .map(|entry| entry.address())
+        .collect::<Result<Vec<_>, _>>()?;
+    if listen.is_empty() {
+        let line = section.line;
+        let key = "listen".to_string();
+        return Err(ConfigError::MissingKey { line, key });
+    }
+
+    Ok(ServerConfig {
+        servername,
+        serverinfo,
+        listen,
+        motd_file,
+        channels_file,
+        accounts_file,
+        snapshot_file,
+        password,
+        operators: vec![],
+        links: vec![],
+        bans: vec![],
+        limits,
+        file: None,
+    })
+}
+
+fn parse_limits(section: &Section) -> Result<Limits, ConfigError> {
+    section.check_keys(&LIMITS_KEYS)?;
+
+    let mut limits = Limits::default();
+    if let Some(entry) = section.optional("max_clients")? {
+        limits.max_clients = entry.number()?;
+    }
+    if let Some(entry) = section.optional("max_per_ip")? {
+        limits.max_per_ip = entry.number()?;
+    }
+    if let Some(entry) = section.optional("ipv4_cidr")? {
+        limits.ipv4_cidr = entry.prefix_length(32)?;
+    }
+    if let Some(entry) = section.optional("ipv6_cidr")? {
+        limits.ipv6_cidr = entry.prefix_length(128)?;
+    }
+    if let Some(entry) = section.optional("throttle_connections")? {
+        limits.throttle_connections = entry.number()?;
+    }
+    if let Some(entry) = section.optional("throttle_period")? {
+        limits.throttle_period = Duration::from_secs(entry.number()? as u64);
+    }
+    if let Some(entry) = section.optional("max_channels")? {
+        limits.max_channels = entry.number()?;
+    }
+    if let Some(entry) = section.optional("nickname_length")? {
+        limits.nickname_length = entry.number()?;
+    }
+    if let Some(entry) = section.optional("registration_timeout")? {
+        limits.registration_timeout = Duration::from_secs(entry.number()? as u64);
+    }
+    if let Some(entry) = section.optional("ping_interval")? {
+        limits.ping_interval = Duration::from_secs(entry.number()? as u64);
+    }
+    if let Some(entry) = section.optional("ping_timeout")? {
+        limits.ping_timeout = Duration::from_secs(entry.number()? as u64);
+    }
+    if let Some(entry) = section.optional("sendq")? {
+        limits.sendq = entry.number()?;
+    }
+
+    Ok(limits)
+}
+
+fn parse_operator(section: &Section, name: &str) -> Result<OperatorBlock, ConfigError> {
+    section.check_keys(&OPERATOR_KEYS)?;
+
+    let entry = section.required("password")?;
+    let password: PasswordHash = entry
+        .value
+        .parse()
+        .map_err(|error: String| entry.invalid(&error))?;
+
+    let host_mask = match section.optional("host")? {
+        Some(entry) => Some(entry.word()?),
+        None => None,
+    };
+
+    let mut privileges = vec![];
+    if let Some(entry) = section.optional("privileges")? {
+        for privilege in entry.value.split(',').map(str::trim) {
+            let privilege = OperatorPrivilege::from_name(privilege)
+                .ok_or_else(|| entry.invalid(&format!("unknown privilege {privilege}")))?;
+            privileges.push(privilege);
+        }
+    }
+
+    Ok(OperatorBlock::new(name, password, host_mask, privileges))
+}
+
+fn parse_link(section: &Section, servername: &str) -> Result<LinkBlock, ConfigError> {
+    section.check_keys(&LINK_KEYS)?;
+
+    let password = section.required("password")?.word()?;
+    let address = match section.optional("address")? {
+        Some(entry) => Some(entry.address()?),
+        None => None,
+    };
+
+    Ok(LinkBlock {
+        servername: servername.to_string(),
+        password,
+        address,
+    })
+}
+
+fn parse_ban(section: &Section, mask: &str) -> Result<BanBlock, ConfigError> {
+    section.check_keys(&BAN_KEYS)?;
+
+    let reason = section.optional("reason")?.map(|entry| entry.value.clone());
+
+    Ok(BanBlock {
+        mask: mask.to_string(),
+        reason,
+    })
+}
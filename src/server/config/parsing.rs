@@ -0,0 +1,185 @@
+use super::ConfigError;
+
+const COMMENT_PREFIXES: [char; 2] = ['#', ';'];
+
+/// A `[name argument]` header followed by its `key = value` entries.
+pub struct Section {
+    pub name: String,
+    pub argument: Option<String>,
+    pub line: usize,
+    pub entries: Vec<Entry>,
+}
+
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// Splits content in sections, ignoring empty lines and comments.
+pub fn parse_sections(content: &str) -> Result<Vec<Section>, ConfigError> {
+    let mut sections: Vec<Section> = vec![];
+
+    for (index, line) in content.lines().enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(COMMENT_PREFIXES) {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            sections.push(parse_header(header, number)?);
+            continue;
+        }
+
+        let entry = parse_entry(line, number)?;
+        let section = sections
+            .last_mut()
+            .ok_or(ConfigError::OutsideSection { line: number })?;
+        section.entries.push(entry);
+    }
+
+    Ok(sections)
+}
+
+fn parse_header(header: &str, line: usize) -> Result<Section, ConfigError> {
+    let header = header
+        .strip_suffix(']')
+        .ok_or(ConfigError::InvalidLine { line })?;
+
+    let mut words = header.split_whitespace();
+    let name = words.next().ok_or(ConfigError::InvalidLine { line })?;
+    let argument = words.next();
+    if words.next().is_some() {
+        return Err(ConfigError::InvalidLine { line });
+    }
+
+    Ok(Section {
+        name: name.to_lowercase(),
+        argument: argument.map(str::to_string),
+        line,
+        entries: vec![],
+    })
+}
+
+fn parse_entry(line_content: &str, line: usize) -> Result<Entry, ConfigError> {
+    let (key, value) = line_content
+        .split_once('=')
+        .ok_or(ConfigError::InvalidLine { line })?;
+    let key = key.trim().to_lowercase();
+    let value = value.trim().to_string();
+
+    if key.is_empty() {
+        return Err(ConfigError::InvalidLine { line });
+    }
+    if value.is_empty() {
+        let reason = "should not be empty".to_string();
+        return Err(ConfigError::InvalidValue { line, key, reason });
+    }
+
+    Ok(Entry { key, value, line })
+}
+
+impl Section {
+    /// Fails if the section contains a key not in `known`.
+    pub fn check_keys(&self, known: &[&str]) -> Result<(), ConfigError> {
+        match self
+            .entries
+            .iter()
+            .find(|entry| !known.contains(&entry.key.as_str()))
+        {
+            Some(entry) => Err(ConfigError::UnknownKey {
+                line: entry.line,
+                key: entry.key.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns every entry with the given key.
+    pub fn all(&self, key: &str) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.key == key)
+            .collect()
+    }
+
+    /// Returns the entry with the given key, failing if it is defined more than once.
+    pub fn optional(&self, key: &str) -> Result<Option<&Entry>, ConfigError> {
+        let entries = self.all(key);
+        if let Some(duplicate) = entries.get(1) {
+            let line = duplicate.line;
+            let key = key.to_string();
+            return Err(ConfigError::DuplicateKey { line, key });
+        }
+
+        Ok(entries.first().copied())
+    }
+
+    /// Returns the entry with the given key, failing if it is not defined exactly once.
+    pub fn required(&self, key: &str) -> Result<&Entry, ConfigError> {
+        self.optional(key)?.ok_or_else(|| ConfigError::MissingKey {
+            line: self.line,
+            key: key.to_string(),
+        })
+    }
+}
+
+impl Entry {
+    pub fn invalid(&self, reason: &str) -> ConfigError {
+        ConfigError::InvalidValue {
+            line: self.line,
+            key: self.key.clone(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn number(&self) -> Result<usize, ConfigError> {
+        match self.value.parse() {
+            Ok(number) if number > 0 => Ok(number),
+            _ => Err(self.invalid("expected a positive number")),
+        }
+    }
+
+    /// Parses a CIDR prefix length, no longer than the address it applies to.
+    pub fn prefix_length(&self, bits: u8) -> Result<u8, ConfigError> {
+        match self.value.parse() {
+            Ok(length) if length > 0 && length <= bits => Ok(length),
+            _ => Err(self.invalid(&format!("expected a prefix length from 1 to {bits}"))),
+        }
+    }
+
+    pub fn address(&self) -> Result<String, ConfigError> {
+        let (host, port) = self
+            .value
+            .rsplit_once(':')
+            .ok_or_else(|| self.invalid("expected host:port"))?;
+
+        if host.is_empty() || port.parse::<u16>().is_err() {
+            return Err(self.invalid("expected host:port"));
+        }
+
+        Ok(self.value.clone())
+    }
+
+    /// Returns free text, which may be written between double quotes to keep
+    /// surrounding spaces or to be empty.
+    pub fn text(&self) -> String {
+        let quoted =
+            self.value.len() >= 2 && self.value.starts_with('"') && self.value.ends_with('"');
+        if quoted {
+            return self.value[1..self.value.len() - 1].to_string();
+        }
+
+        self.value.clone()
+    }
+
+    pub fn word(&self) -> Result<String, ConfigError> {
+        if self.value.contains(char::is_whitespace) {
+            return Err(self.invalid("should not contain spaces"));
+        }
+
+        Ok(self.value.clone())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::server::connection::Connection;
+use crate::server::database::DatabaseHandle;
+
+use super::{ConfigError, ServerConfig};
+
+/// Rereads the file the server was configured from, applying every setting that may change while running.
+/// Returns the settings that changed but only take effect on restart.
+pub fn rehash<C: Connection>(
+    database: &DatabaseHandle<C>,
+) -> Result<Vec<&'static str>, ConfigError> {
+    let running = database.get_config().ok_or(ConfigError::NoFile)?;
+    let file = running.file.as_ref().ok_or(ConfigError::NoFile)?;
+
+    let mut config = ServerConfig::from_file(file)?;
+    let restart_required = running.restart_required(&config);
+
+    config.servername = running.servername;
+    config.serverinfo = running.serverinfo;
+    config.listen = running.listen;
+    config.limits.max_clients = running.limits.max_clients;
+    config.limits.ping_interval = running.limits.ping_interval;
+    config.limits.ping_timeout = running.limits.ping_timeout;
+    database.configure(config);
+
+    Ok(restart_required)
+}
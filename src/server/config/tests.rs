@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use super::*;
+
+const MINIMAL_CONFIG: &str = "[server]
+name = servername
+info = serverinfo
+listen = 127.0.0.1:9000
+";
+
+#[test]
+fn can_parse_minimal_configuration() {
+    let config: ServerConfig = MINIMAL_CONFIG.parse().unwrap();
+
+    assert_eq!("servername", config.servername);
+    assert_eq!("serverinfo", config.serverinfo);
+    assert_eq!(vec!["127.0.0.1:9000".to_string()], config.listen);
+    assert_eq!(None, config.motd_file);
+    assert_eq!(None, config.channels_file);
+    assert_eq!(None, config.accounts_file);
+    assert_eq!(None, config.snapshot_file);
+    assert_eq!(None, config.password);
+    assert!(config.operators.is_empty());
+    assert!(config.links.is_empty());
+    assert_eq!(Limits::default(), config.limits);
+}
+
+#[test]
+fn example_configuration_is_valid() {
+    let config: ServerConfig = include_str!("../../../server.conf").parse().unwrap();
+
+    assert_eq!(Limits::default(), config.limits);
+}
+
+#[test]
+fn can_parse_every_section() {
+    let hash = PasswordHash::with_salt("secret", "salt");
+    let content = format!(
+        "# comment
+[server]
+name = servername
+info = server info with spaces
+listen = 127.0.0.1:9000
+listen = 0.0.0.0:6667
+motd = motd.txt
+channels = channels.db
+accounts = accounts.db
+snapshot = snapshot.db
+password = password
+
+[limits]
+max_clients = 5
+max_per_ip = 2
+ipv4_cidr = 24
+ipv6_cidr = 48
+throttle_connections = 4
+throttle_period = 30
+max_channels = 3
+nickname_length = 15
+registration_timeout = 10
+ping_interval = 20
+ping_timeout = 30
+sendq = 4096
+
+; another comment
+[operator admin]
+password = {hash}
+host = *@127.0.0.1
+privileges = kill, Rehash
+
+[link servername2]
+password = linkpassword
+address = 127.0.0.1:9001
+"
+    );
+
+    let config: ServerConfig = content.parse().unwrap();
+
+    assert_eq!("server info with spaces", config.serverinfo);
+    assert_eq!(vec!["127.0.0.1:9000", "0.0.0.0:6667"], config.listen);
+    assert_eq!(Some("motd.txt".into()), config.motd_file);
+    assert_eq!(Some("channels.db".into()), config.channels_file);
+    assert_eq!(Some("accounts.db".into()), config.accounts_file);
+    assert_eq!(Some("snapshot.db".into()), config.snapshot_file);
+    assert_eq!(Some("password".to_string()), config.password);
+
+    let limits = Limits {
+        max_clients: 5,
+        max_per_ip: 2,
+        ipv4_cidr: 24,
+        ipv6_cidr: 48,
+        throttle_connections: 4,
+        throttle_period: Duration::from_secs(30),
+        max_channels: 3,
+        nickname_length: 15,
+        registration_timeout: Duration::from_secs(10),
+        ping_interval: Duration::from_secs(20),
+        ping_timeout: Duration::from_secs(30),
+        sendq: 4096,
+    };
+    assert_eq!(limits, config.limits);
+
+    let privileges = vec![OperatorPrivilege::Kill, OperatorPrivilege::Rehash];
+    let operator = OperatorBlock::new("admin", hash, Some("*@127.0.0.1".to_string()), privileges);
+    assert_eq!(vec![operator], config.operators);
+
+    let link = LinkBlock {
+        servername: "servername2".to_string(),
+        password: "linkpassword".to_string(),
+        address: Some("127.0.0.1:9001".to_string()),
+    };
+    assert_eq!(Some(&link), config.link("servername2"));
+}
+
+#[test]
+fn server_section_is_required() {
+    let error = "[limits]\nmax_clients = 5\n"
+        .parse::<ServerConfig>()
+        .unwrap_err();
+
+    assert_eq!(
+        ConfigError::MissingSection {
+            section: "server".to_string()
+        },
+        error
+    );
+}
+
+#[test]
+fn listen_address_is_required() {
+    let content = "\n[server]\nname = servername\ninfo = serverinfo\n";
+    let error = content.parse::<ServerConfig>().unwrap_err();
+
+    assert_eq!("line 2: section is missing key listen", error.to_string());
+}
+
+#[test]
+fn errors_report_line_number() {
+    let cases = [
+        (
+            "name = servername\n",
+            "line 1: key should be inside a section",
+        ),
+        ("[server\n", "line 1: expected [section] or key = value"),
+        (
+            "[server]\nname\n",
+            "line 2: expected [section] or key = value",
+        ),
+        ("[channels]\n", "line 1: unknown section [channels]"),
+        (
+            "[server]\n\n[server]\n",
+            "line 3: section [server] was already defined",
+        ),
+        ("[server]\nport = 9000\n", "line 2: unknown key port"),
+        (
+            "[server]\nname = a\nname = b\n",
+            "line 3: key name was already defined",
+        ),
+        (
+            "[server]\ninfo =\n",
+            "line 2: invalid value for info, should not be empty",
+        ),
+    ];
+
+    for (content, expected) in cases {
+        let error = content.parse::<ServerConfig>().unwrap_err();
+        assert_eq!(expected, error.to_string(), "parsing {content:?}");
+    }
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let cases = [
+        ("[server]\nname = server name\n", "name"),
+        (
+            "[server]\nlisten = 127.0.0.1\nname = a\ninfo = b\n",
+            "listen",
+        ),
+        (
+            "[server]\nlisten = 127.0.0.1:port\nname = a\ninfo = b\n",
+            "listen",
+        ),
+        ("[limits]\nmax_clients = 0\n", "max_clients"),
+        ("[limits]\nping_timeout = -1\n", "ping_timeout"),
+        ("[limits]\nipv4_cidr = 33\n", "ipv4_cidr"),
+        ("[limits]\nipv6_cidr = 0\n", "ipv6_cidr"),
+        ("[operator admin]\npassword = admin\n", "password"),
+        (
+            "[link servername2]\naddress = servername2\npassword = a\n",
+            "address",
+        ),
+    ];
+
+    for (content, key) in cases {
+        let error = content.parse::<ServerConfig>().unwrap_err();
+        match error {
+            ConfigError::InvalidValue {
+                line: 2,
+                key: found,
+                ..
+            } => assert_eq!(key, found),
+            error => panic!("unexpected error {error} parsing {content:?}"),
+        }
+    }
+}
+
+#[test]
+fn unknown_privileges_are_rejected() {
+    let hash = PasswordHash::with_salt("secret", "salt");
+    let content =
+        format!("{MINIMAL_CONFIG}[operator admin]\npassword = {hash}\nprivileges = kill, fly\n");
+
+    let error = content.parse::<ServerConfig>().unwrap_err();
+
+    assert_eq!(
+        "line 7: invalid value for privileges, unknown privilege fly",
+        error.to_string()
+    );
+}
+
+#[test]
+fn operator_and_link_blocks_require_password() {
+    let content = format!("{MINIMAL_CONFIG}[link servername2]\naddress = 127.0.0.1:9001\n");
+    let error = content.parse::<ServerConfig>().unwrap_err();
+    assert_eq!("line 5: section is missing key password", error.to_string());
+
+    let content = format!("{MINIMAL_CONFIG}[operator admin]\nhost = *@*\n");
+    let error = content.parse::<ServerConfig>().unwrap_err();
+    assert_eq!("line 5: section is missing key password", error.to_string());
+}
+
+#[test]
+fn operators_must_have_unique_names() {
+    let hash = PasswordHash::with_salt("secret", "salt");
+    let block = format!("[operator admin]\npassword = {hash}\n");
+    let content = format!("{MINIMAL_CONFIG}{block}{block}");
+
+    let error = content.parse::<ServerConfig>().unwrap_err();
+
+    assert_eq!(
+        "line 7: section [operator admin] was already defined",
+        error.to_string()
+    );
+}
+
+#[test]
+fn missing_file_can_not_be_read() {
+    let error = ServerConfig::from_file(Path::new("does/not/exist.conf")).unwrap_err();
+
+    assert!(matches!(error, ConfigError::CouldNotRead { .. }));
+}
+
+#[test]
+fn can_parse_ban_blocks() {
+    let content = format!("{MINIMAL_CONFIG}[ban *@10.0.0.*]\nreason = spam\n[ban user@*]\n");
+
+    let config: ServerConfig = content.parse().unwrap();
+
+    let bans = vec![
+        BanBlock {
+            mask: "*@10.0.0.*".to_string(),
+            reason: Some("spam".to_string()),
+        },
+        BanBlock {
+            mask: "user@*".to_string(),
+            reason: None,
+        },
+    ];
+    assert_eq!(bans, config.bans);
+}
+
+#[test]
+fn configuration_read_from_file_remembers_path() {
+    let path = crate::server::testing::dummy_config_file("remembers_path", MINIMAL_CONFIG);
+
+    let config = ServerConfig::from_file(&path).unwrap();
+
+    assert_eq!(Some(path), config.file);
+}
+
+#[test]
+fn restart_is_required_only_for_startup_settings() {
+    let config: ServerConfig = MINIMAL_CONFIG.parse().unwrap();
+
+    let mut other = config.clone();
+    other.password = Some("password".to_string());
+    other.limits.max_channels = 1;
+    assert!(config.restart_required(&other).is_empty());
+
+    other.servername = "other".to_string();
+    other.listen.push("127.0.0.1:9001".to_string());
+    other.limits.max_clients = 1;
+    other.limits.ping_timeout = Duration::from_secs(1);
+    assert_eq!(
+        vec!["name", "listen", "max_clients", "ping_timeout"],
+        config.restart_required(&other)
+    );
+}
@@ -1,10 +1,8 @@
-use std::fmt::Display;
 use std::io;
 use std::io::{Read, Write};
 use std::net::{Shutdown, SocketAddr, TcpStream};
-use std::time::Duration;
 
-use crate::message::Message;
+use crate::message::{ParsingError, ToMessage};
 
 /// An implementor of Connection must behave as a TCPStream for testing purposes
 /// For convenience, it also implements `send`
@@ -12,14 +10,15 @@ pub trait Connection: Read + Write + Sized + Send + 'static {
     fn try_clone(&self) -> io::Result<Self>;
     fn peer_address(&self) -> io::Result<SocketAddr>;
     fn shutdown(&self) -> io::Result<()>;
-    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()>;
 
-    fn send(&mut self, message: &dyn Display) -> io::Result<()> {
-        if let Ok(message) = Message::new(&message.to_string()) {
-            return message.send_to(self);
+    /// Sends message trailed with CRLF.
+    /// Fails if the message could not be built, empty responses are not sent.
+    fn send(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        match message.to_message() {
+            Ok(message) => message.send_to(self),
+            Err(ParsingError::EmptyMessage) => Ok(()),
+            Err(error) => Err(error.into()),
         }
-
-        Ok(())
     }
 }
 
@@ -35,8 +34,4 @@ impl Connection for TcpStream {
     fn shutdown(&self) -> io::Result<()> {
         self.shutdown(Shutdown::Both)
     }
-
-    fn set_read_timeout(&self, duration: Option<Duration>) -> io::Result<()> {
-        self.set_read_timeout(duration)
-    }
 }
@@ -1,27 +1,151 @@
+use crate::message::command::*;
 use crate::server::{connection::Connection, responses::ErrorReply};
 
-use super::structure::CommandArgs;
-
+/// Each assert receives the command's prefix and its already parsed arguments.
 pub trait ConnectionHandlerAsserts<C: Connection> {
-    fn assert_pass_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_nick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_user_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_oper_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_privmsg_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_notice_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_join_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_part_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_invite_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_names_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_list_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_who_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_whois_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_away_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_topic_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_kick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_mode_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_quit_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_server_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_squit_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
-    fn assert_ctcp_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply>;
+    fn assert_pass_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        pass: &Pass,
+    ) -> Result<(), ErrorReply>;
+    fn assert_nick_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        nick: &Nick,
+    ) -> Result<(), ErrorReply>;
+    fn assert_user_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        user: &User,
+    ) -> Result<(), ErrorReply>;
+    fn assert_oper_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        oper: &Oper,
+    ) -> Result<(), ErrorReply>;
+    fn assert_privmsg_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        privmsg: &Privmsg,
+    ) -> Result<(), ErrorReply>;
+    fn assert_notice_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        notice: &Notice,
+    ) -> Result<(), ErrorReply>;
+    fn assert_join_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        join: &Join,
+    ) -> Result<(), ErrorReply>;
+    fn assert_part_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        part: &Part,
+    ) -> Result<(), ErrorReply>;
+    fn assert_invite_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        invite: &Invite,
+    ) -> Result<(), ErrorReply>;
+    fn assert_names_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        names: &Names,
+    ) -> Result<(), ErrorReply>;
+    fn assert_list_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        list: &List,
+    ) -> Result<(), ErrorReply>;
+    fn assert_who_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        who: &Who,
+    ) -> Result<(), ErrorReply>;
+    fn assert_whois_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        whois: &Whois,
+    ) -> Result<(), ErrorReply>;
+    fn assert_away_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        away: &Away,
+    ) -> Result<(), ErrorReply>;
+    fn assert_topic_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        topic: &Topic,
+    ) -> Result<(), ErrorReply>;
+    fn assert_kick_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        kick: &Kick,
+    ) -> Result<(), ErrorReply>;
+    fn assert_mode_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        mode: &Mode,
+    ) -> Result<(), ErrorReply>;
+    fn assert_quit_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        quit: &Quit,
+    ) -> Result<(), ErrorReply>;
+    fn assert_server_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        server: &Server,
+    ) -> Result<(), ErrorReply>;
+    fn assert_squit_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        squit: &Squit,
+    ) -> Result<(), ErrorReply>;
+    fn assert_ctcp_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        ctcp: &Ctcp,
+    ) -> Result<(), ErrorReply>;
+    fn assert_ping_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        ping: &Ping,
+    ) -> Result<(), ErrorReply>;
+    fn assert_pong_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        pong: &Pong,
+    ) -> Result<(), ErrorReply>;
+    fn assert_motd_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        motd: &Motd,
+    ) -> Result<(), ErrorReply>;
+    fn assert_rehash_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        rehash: &Rehash,
+    ) -> Result<(), ErrorReply>;
+    fn assert_kill_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        kill: &Kill,
+    ) -> Result<(), ErrorReply>;
+    fn assert_cap_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        cap: &Cap,
+    ) -> Result<(), ErrorReply>;
+    fn assert_authenticate_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        authenticate: &Authenticate,
+    ) -> Result<(), ErrorReply>;
+    fn assert_account_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        account: &Account,
+    ) -> Result<(), ErrorReply>;
 }
@@ -1,32 +1,35 @@
-use crate::macros::ok_or_return;
+use crate::message::command::names::ACCOUNT_COMMAND;
+use crate::message::command::Account;
+use crate::message::command::*;
 use crate::server::connection::Connection;
-use crate::server::connection_handler::CommandArgs;
 use crate::server::connection_handler::ConnectionHandlerAsserts;
 use crate::server::consts::channel::*;
 use crate::server::consts::channel_flag::ChannelFlag;
-use crate::server::consts::commands::*;
-use crate::server::consts::modes::*;
 use crate::server::consts::user::INVALID_NICKNAME_CHARACTERS;
 use crate::server::consts::user::INVALID_NICKNAME_PREFIXES;
+use crate::server::consts::user::SERVICE_NICKNAMES;
 use crate::server::data_structures::*;
 use crate::server::responses::ErrorReply;
 
 use super::ClientHandler;
 
 impl<C: Connection> ConnectionHandlerAsserts<C> for ClientHandler<C> {
-    fn assert_pass_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_pass_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pass: &Pass,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::AlreadyRegistered462)
     }
 
-    fn assert_nick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        if params.is_empty() {
-            return Err(ErrorReply::NoNicknameGiven431);
-        }
-
-        let nickname = &params[0];
+    fn assert_nick_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        nick: &Nick,
+    ) -> Result<(), ErrorReply> {
+        let nickname = &nick.nickname;
 
-        if nickname.len() > 9
+        if nickname.len() > self.database.get_limits().nickname_length
             || nickname.contains(INVALID_NICKNAME_CHARACTERS)
             || nickname.starts_with(INVALID_NICKNAME_PREFIXES)
         {
@@ -34,66 +37,72 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ClientHandler<C> {
                 nickname: nickname.to_string(),
             });
         }
+        if irc_eq(nickname, &self.nickname) {
+            return Ok(());
+        }
         self.assert_nickname_not_in_use(nickname)
     }
 
-    fn assert_user_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_user_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _user: &User,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::AlreadyRegistered462)
     }
 
-    fn assert_oper_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(1), OPER_COMMAND)?;
-
-        let username = &params[0];
-        let password = &params[1];
-        self.assert_are_credentials_valid(username, password)
-    }
-
-    fn assert_privmsg_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
-        if params.is_empty() {
-            let command = PRIVMSG_COMMAND.to_string();
-            return Err(ErrorReply::NoRecipient411 { command });
-        }
-
-        if trail.is_none() {
-            return Err(ErrorReply::NoTextToSend412 {});
+    fn assert_oper_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _oper: &Oper,
+    ) -> Result<(), ErrorReply> {
+        let address = self.stream.peer_address().map(|address| address.ip());
+        if address.is_ok_and(|address| self.database.oper_attempts_exceeded(address)) {
+            return Err(ErrorReply::PasswordMismatch464);
         }
 
         Ok(())
     }
 
-    fn assert_notice_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
-        if params.is_empty() {
-            let command = NOTICE_COMMAND.to_string();
-            return Err(ErrorReply::NoRecipient411 { command });
-        }
-
-        if trail.is_none() {
-            return Err(ErrorReply::NoTextToSend412 {});
-        }
-
+    fn assert_privmsg_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _privmsg: &Privmsg,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_join_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(0), JOIN_COMMAND)
+    fn assert_notice_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _notice: &Notice,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
     }
 
-    fn assert_part_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(0), PART_COMMAND)
+    fn assert_join_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _join: &Join,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
     }
 
-    fn assert_invite_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(1), INVITE_COMMAND)?;
+    fn assert_part_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _part: &Part,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
 
-        let invited_client = &params[0];
-        let channel = &params[1];
+    fn assert_invite_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        invite: &Invite,
+    ) -> Result<(), ErrorReply> {
+        let invited_client = &invite.nickname;
+        let channel = &invite.channel;
 
         self.assert_exists_client(invited_client)?;
         self.assert_exists_channel(channel)?;
@@ -110,43 +119,55 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ClientHandler<C> {
         Ok(())
     }
 
-    fn assert_names_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_names_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _names: &Names,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_list_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_list_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _list: &List,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_who_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_who_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _who: &Who,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_whois_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        if params.is_empty() {
-            return Err(ErrorReply::NoNicknameGiven431);
-        }
-        if params.len() > 1 {
-            let server = &params[0];
-            if !self.database.contains_server(server) {
-                return Err(ErrorReply::NoSuchServer402 {
-                    server: server.to_string(),
-                });
-            }
+    fn assert_whois_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        whois: &Whois,
+    ) -> Result<(), ErrorReply> {
+        if let Some(server) = &whois.server {
+            self.assert_exists_server(server)?;
         }
         Ok(())
     }
 
-    fn assert_away_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_away_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _away: &Away,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_topic_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(0), TOPIC_COMMAND)?;
-
-        let channel = &params[0];
+    fn assert_topic_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        topic: &Topic,
+    ) -> Result<(), ErrorReply> {
+        let channel = &topic.channel;
 
         self.assert_is_in_channel(channel)?;
 
@@ -160,83 +181,146 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ClientHandler<C> {
         Ok(())
     }
 
-    fn assert_kick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(1), KICK_COMMAND)?;
-
+    fn assert_kick_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _kick: &Kick,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_mode_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(0), MODE_COMMAND)?;
-
-        let target = &params[0];
+    fn assert_mode_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        mode: &Mode,
+    ) -> Result<(), ErrorReply> {
+        let target = &mode.target;
 
         if self.is_channel(target) {
             self.assert_exists_channel(target)?;
             self.assert_is_in_channel(target)?;
-        } else if target != &self.nickname {
+        } else if !irc_eq(target, &self.nickname) {
             return Err(ErrorReply::UsersDontMatch502);
         }
-        if params.len() > 1 {
-            self.assert_modes_starts_correctly(&params[1])?;
-        }
-        if params.len() > 1 && self.is_channel(target) {
+        if mode.changes.is_some() && self.is_channel(target) {
             self.assert_is_channel_operator(target)?;
         }
 
         Ok(())
     }
 
-    fn assert_quit_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_quit_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _quit: &Quit,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_server_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_server_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _server: &Server,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::AlreadyRegistered462)
     }
 
-    fn assert_squit_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-        self.assert_has_enough_params(&params.get(0), SQUIT_COMMAND)?;
-        self.assert_is_server_operator()?;
+    fn assert_squit_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        squit: &Squit,
+    ) -> Result<(), ErrorReply> {
+        self.assert_has_operator_privilege(OperatorPrivilege::Squit)?;
+        self.assert_exists_server(&squit.servername)
+    }
+
+    fn assert_ctcp_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        ctcp: &Ctcp,
+    ) -> Result<(), ErrorReply> {
+        self.assert_target_is_valid(&ctcp.target)
+    }
+
+    fn assert_ping_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _ping: &Ping,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
 
-        let server = &params[0];
+    fn assert_pong_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pong: &Pong,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
 
-        self.assert_exists_server(server)?;
+    fn assert_motd_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _motd: &Motd,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_ctcp_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
-        self.assert_has_enough_params(&params.get(0), CTCP_COMMAND)?;
+    fn assert_rehash_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _rehash: &Rehash,
+    ) -> Result<(), ErrorReply> {
+        self.assert_has_operator_privilege(OperatorPrivilege::Rehash)
+    }
 
-        let target = &params[0];
-        self.assert_target_is_valid(target)?;
+    fn assert_kill_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        kill: &Kill,
+    ) -> Result<(), ErrorReply> {
+        self.assert_has_operator_privilege(OperatorPrivilege::Kill)?;
 
-        if trail.is_none() {
-            return Err(ErrorReply::NoTextToSend412 {});
+        let victim = &kill.nickname;
+        if self.database.contains_server(victim) {
+            return Err(ErrorReply::CantKillServer483);
+        }
+        if !self.database.contains_client(victim) {
+            let nickname = victim.to_string();
+            return Err(ErrorReply::NoSuchNickname401 { nickname });
         }
 
         Ok(())
     }
-}
 
-impl<C: Connection> ClientHandler<C> {
-    pub fn assert_has_enough_params<T>(
+    fn assert_cap_command_is_valid(
         &self,
-        param: &Option<T>,
-        command: &str,
+        _prefix: &Option<String>,
+        _cap: &Cap,
     ) -> Result<(), ErrorReply> {
-        if param.is_none() {
-            return Err(ErrorReply::NeedMoreParameters461 {
-                command: command.to_string(),
-            });
-        }
         Ok(())
     }
 
+    fn assert_authenticate_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _authenticate: &Authenticate,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::AlreadyRegistered462)
+    }
+
+    /// Only servers tell which account a client is logged into.
+    fn assert_account_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _account: &Account,
+    ) -> Result<(), ErrorReply> {
+        let command = ACCOUNT_COMMAND.to_string();
+        Err(ErrorReply::UnknownCommand421 { command })
+    }
+}
+
+impl<C: Connection> ClientHandler<C> {
     pub fn assert_is_channel_operator(&self, channel: &str) -> Result<(), ErrorReply> {
         let channel = channel.to_string();
 
@@ -247,10 +331,17 @@ impl<C: Connection> ClientHandler<C> {
         Ok(())
     }
 
-    fn assert_is_server_operator(&self) -> Result<(), ErrorReply> {
-        if !self.database.is_server_operator(&self.nickname) {
+    fn assert_has_operator_privilege(
+        &self,
+        privilege: OperatorPrivilege,
+    ) -> Result<(), ErrorReply> {
+        if !self
+            .database
+            .has_operator_privilege(&self.nickname, privilege)
+        {
             return Err(ErrorReply::NoPrivileges481);
         }
+
         Ok(())
     }
 
@@ -325,25 +416,17 @@ impl<C: Connection> ClientHandler<C> {
     pub fn assert_nickname_not_in_use(&self, nickname: &str) -> Result<(), ErrorReply> {
         let nickname = nickname.to_string();
 
-        if self.database.contains_client(&nickname) {
+        if self.database.contains_client(&nickname)
+            || SERVICE_NICKNAMES
+                .iter()
+                .any(|service| irc_eq(&nickname, service))
+        {
             return Err(ErrorReply::NicknameInUse433 { nickname });
         }
 
         Ok(())
     }
 
-    pub fn assert_are_credentials_valid(
-        &self,
-        username: &str,
-        password: &str,
-    ) -> Result<(), ErrorReply> {
-        if !self.database.are_credentials_valid(username, password) {
-            return Err(ErrorReply::PasswordMismatch464);
-        }
-
-        Ok(())
-    }
-
     pub fn assert_target_is_valid(&self, target: &str) -> Result<(), ErrorReply> {
         self.assert_target_exists(target)?;
 
@@ -388,48 +471,6 @@ impl<C: Connection> ClientHandler<C> {
         Ok(())
     }
 
-    pub fn assert_can_join_channel(
-        &self,
-        channel: &str,
-        key: &Option<String>,
-    ) -> Result<(), ErrorReply> {
-        let channels_for_client = self
-            .database
-            .get_channels_for_client(&self.nickname)
-            .expect("Client should exist");
-
-        if channels_for_client.len() == MAX_CHANNELS {
-            let channel = channel.to_string();
-            return Err(ErrorReply::TooManyChannels405 { channel });
-        }
-
-        self.assert_channel_name_is_valid(channel)?;
-        self.assert_client_not_on_channel(&self.nickname, channel)?;
-
-        self.assert_is_valid_key(channel, key)?;
-
-        self.assert_channel_is_not_full(channel)?;
-
-        self.assert_is_not_banned_from_channel(channel)?;
-
-        self.assert_is_invited_to_invite_only_channel(channel)
-    }
-
-    fn assert_is_invited_to_invite_only_channel(&self, channel: &str) -> Result<(), ErrorReply> {
-        let is_invite_only = self
-            .database
-            .channel_has_flag(channel, ChannelFlag::InviteOnly);
-        let is_invited = self.database.channel_has_invite(channel, &self.nickname);
-
-        if is_invite_only && !is_invited {
-            return Err(ErrorReply::InviteOnlyChannel473 {
-                channel: channel.to_string(),
-            });
-        }
-
-        Ok(())
-    }
-
     pub fn assert_can_kick_from_channel(&self, channel: &str) -> Result<(), ErrorReply> {
         self.assert_exists_channel(channel)?;
 
@@ -476,57 +517,9 @@ impl<C: Connection> ClientHandler<C> {
         Ok(())
     }
 
-    pub fn assert_modes_starts_correctly(&self, modes: &str) -> Result<(), ErrorReply> {
-        if !modes.starts_with([ADD_MODE, REMOVE_MODE]) {
-            return Err(ErrorReply::NoReply);
-        }
-
-        Ok(())
-    }
-
-    pub fn assert_is_valid_key(
-        &self,
-        channel: &str,
-        key: &Option<String>,
-    ) -> Result<(), ErrorReply> {
-        let channel = channel.to_string();
-        if let Ok(channel_key) = self.database.get_channel_key(&channel) {
-            if channel_key != *key {
-                return Err(ErrorReply::BadChannelKey475 { channel });
-            }
-        }
-
-        Ok(())
-    }
-
-    pub fn assert_channel_is_not_full(&self, channel: &str) -> Result<(), ErrorReply> {
-        let channel = channel.to_string();
-
-        if let Ok(Some(limit)) = self.database.get_channel_limit(&channel) {
-            let channel_clients =
-                ok_or_return!(self.database.get_channel_clients(&channel), Ok(()));
-            if channel_clients.len() >= limit {
-                return Err(ErrorReply::ChannelIsFull471 { channel });
-            }
-        }
-        Ok(())
-    }
-
-    pub fn assert_is_not_banned_from_channel(&self, channel: &str) -> Result<(), ErrorReply> {
-        let channel_banmasks = ok_or_return!(self.database.get_channel_banmask(channel), Ok(()));
-        for mask in channel_banmasks {
-            if self.client_matches_banmask(&self.nickname, &mask) {
-                let channel = channel.to_string();
-                return Err(ErrorReply::BannedFromChannel474 { channel });
-            }
-        }
-
-        Ok(())
-    }
-
     pub fn assert_can_send_whois_response(
         &mut self,
-        clients: &Vec<ClientInfo>,
+        clients: &[ClientInfo],
         nickmask: &str,
     ) -> Result<(), ErrorReply> {
         if clients.is_empty() {
@@ -53,12 +53,6 @@ impl<C: Connection> ClientHandler<C> {
         self.database.is_client_in_channel(channel, &self.nickname)
     }
 
-    pub fn client_matches_banmask(&self, nickname: &str, mask: &str) -> bool {
-        let client = ok_or_return!(self.database.get_client_info(nickname), false);
-
-        client.matches_banmask(mask)
-    }
-
     pub fn is_channel(&self, target: &str) -> bool {
         target.starts_with([DISTRIBUTED_CHANNEL, LOCAL_CHANNEL])
     }
@@ -0,0 +1,76 @@
+use std::io;
+
+use crate::server::consts::user::CHANSERV_NICKNAME;
+use crate::server::{
+    connection::Connection,
+    connection_handler::{client_handler::ClientHandler, ConnectionHandlerUtils},
+    responses::Notification,
+};
+
+const CHANSERV_HELP: &str = "Commands: REGISTER <channel>, DROP <channel>, INFO <channel>";
+
+impl<C: Connection> ClientHandler<C> {
+    /// Handles a message sent to ChanServ, answering with notices.
+    pub(super) fn chanserv_request(&mut self, text: &str) -> io::Result<()> {
+        let mut arguments = text.split_whitespace();
+        let command = arguments.next().unwrap_or_default().to_uppercase();
+        let channel = arguments.next();
+
+        match (command.as_str(), channel) {
+            ("REGISTER", Some(channel)) => self.register_channel_request(channel),
+            ("DROP", Some(channel)) => self.drop_channel_request(channel),
+            ("INFO", Some(channel)) => self.channel_info_request(channel),
+            _ => self.send_chanserv_notice(CHANSERV_HELP),
+        }
+    }
+
+    fn register_channel_request(&mut self, channel: &str) -> io::Result<()> {
+        if !self.database.has_channel_store() {
+            return self.send_chanserv_notice("Channel registration is disabled");
+        }
+        if !self.is_in_channel(channel) {
+            return self.send_chanserv_notice(&format!("You are not on {channel}"));
+        }
+        if !self.database.is_channel_operator(channel, &self.nickname) {
+            return self.send_chanserv_notice(&format!("You are not an operator of {channel}"));
+        }
+        let account = match self.logged_in_account() {
+            Some(account) => account,
+            None => return self.send_chanserv_notice("You must identify to NickServ first"),
+        };
+        if let Some(founder) = self.database.get_channel_founder(channel) {
+            return self
+                .send_chanserv_notice(&format!("{channel} is already registered to {founder}"));
+        }
+
+        self.database
+            .set_channel_founder(channel, Some(account.clone()));
+        self.send_chanserv_notice(&format!("{channel} is now registered to {account}"))
+    }
+
+    fn drop_channel_request(&mut self, channel: &str) -> io::Result<()> {
+        let founder = match self.database.get_channel_founder(channel) {
+            Some(founder) => founder,
+            None => return self.send_chanserv_notice(&format!("{channel} is not registered")),
+        };
+        if !(self.is_logged_into(&founder) || self.database.is_server_operator(&self.nickname)) {
+            return self.send_chanserv_notice(&format!("You are not the founder of {channel}"));
+        }
+
+        self.database.set_channel_founder(channel, None);
+        self.send_chanserv_notice(&format!("{channel} has been dropped"))
+    }
+
+    fn channel_info_request(&mut self, channel: &str) -> io::Result<()> {
+        let text = match self.database.get_channel_founder(channel) {
+            Some(founder) => format!("{channel} is registered to {founder}"),
+            None => format!("{channel} is not registered"),
+        };
+        self.send_chanserv_notice(&text)
+    }
+
+    fn send_chanserv_notice(&mut self, text: &str) -> io::Result<()> {
+        let notice = Notification::notice(CHANSERV_NICKNAME, &self.nickname, text);
+        self.send_message(&notice)
+    }
+}
@@ -1,154 +1,234 @@
 use std::io;
 
 use crate::macros::ok_or_return;
+use crate::message::command::*;
+use crate::message::{truncate_at_char_boundary, ToMessage};
 use crate::server::connection::Connection;
-use crate::server::connection_handler::{
-    CommandArgs, ConnectionHandlerLogic, ConnectionHandlerUtils,
-};
+use crate::server::connection_handler::{ConnectionHandlerLogic, ConnectionHandlerUtils};
 
+use crate::message::command::names::*;
+use crate::server::config::rehash;
 use crate::server::connection_handler::mode_requests::{
-    parse_channel_mode_string, parse_user_mode_string,
+    parse_channel_mode_changes, parse_user_mode_changes,
 };
-use crate::server::consts::channel::{DISTRIBUTED_CHANNEL, LOCAL_CHANNEL};
+use crate::server::consts::channel::{DISTRIBUTED_CHANNEL, LOCAL_CHANNEL, MAX_TOPIC_LENGTH};
+use crate::server::consts::user::{CHANSERV_NICKNAME, NICKSERV_NICKNAME};
 use crate::server::data_structures::*;
-use crate::server::responses::{CommandResponse, Notification};
+use crate::server::database::{JoinPermit, JoinRefusal};
+use crate::server::responses::{CommandResponse, ErrorReply, Notification};
 
-use super::utils::collect_list;
 use super::ClientHandler;
 
+/// Contains the logic of the requests sent to ChanServ.
+mod chanserv_logic;
 /// Contains the extended logic of the MODE command.
 mod mode_logic;
+/// Contains the logic of the requests sent to NickServ.
+mod nickserv_logic;
 
 pub const SQUIT_MESSAGE: &str = "Net split";
 
 impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
-    fn nick_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, params, _) = arguments;
+    fn nick_logic(&mut self, _prefix: Option<String>, nick: Nick) -> io::Result<bool> {
+        self.change_nickname(nick.nickname);
+        self.protect_nickname()?;
+
+        Ok(true)
+    }
+
+    fn oper_logic(&mut self, _prefix: Option<String>, oper: Oper) -> std::io::Result<bool> {
+        let username = &oper.username;
+        let password = &oper.password;
 
-        let new_nickname = params[0].clone();
-        self.database.update_nickname(&self.nickname, &new_nickname);
+        if !self
+            .database
+            .are_credentials_valid(username, password, &self.nickname)
+        {
+            if let Ok(address) = self.stream.peer_address() {
+                self.database.record_failed_oper(address.ip());
+            }
+            self.send_response(&ErrorReply::PasswordMismatch464)?;
+            self.send_failed_oper_notice(username);
+            return Ok(true);
+        }
 
-        let nick_notification = Notification::nick_update(&self.nickname, &new_nickname);
-        self.send_message_to_all_servers(&nick_notification);
+        self.database.set_server_operator(&self.nickname, username);
+        self.send_response(&CommandResponse::you_are_oper())?;
 
-        self.nickname = new_nickname;
+        self.send_oper_notification();
 
         Ok(true)
     }
 
-    fn oper_logic(&mut self, _arguments: CommandArgs) -> std::io::Result<bool> {
-        self.database.set_server_operator(&self.nickname);
-        self.stream.send(&CommandResponse::you_are_oper())?;
+    fn rehash_logic(&mut self, _prefix: Option<String>, _rehash: Rehash) -> io::Result<bool> {
+        let file = self
+            .database
+            .get_config()
+            .and_then(|config| config.file)
+            .map(|file| file.display().to_string())
+            .unwrap_or_default();
+
+        let restart_required = match rehash(&self.database) {
+            Ok(restart_required) => restart_required,
+            Err(error) => {
+                self.send_notice(&format!("Rehash failed: {error}"))?;
+                return Ok(true);
+            }
+        };
 
-        self.send_oper_notification();
+        self.send_response(&CommandResponse::rehashing(&file))?;
+        for setting in restart_required {
+            self.send_notice(&format!(
+                "Setting {setting} changed, it takes effect on restart"
+            ))?;
+        }
+
+        let notice = format!("{} is rehashing server config file", self.nickname);
+        self.send_server_notice(&notice);
 
         Ok(true)
     }
 
-    fn privmsg_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, trail) = arguments;
-        let content = trail.expect("Verified in assert");
-        let targets = params.remove(0);
+    fn kill_logic(&mut self, _prefix: Option<String>, kill: Kill) -> io::Result<bool> {
+        let Kill {
+            nickname: victim,
+            comment,
+        } = kill;
+        let killer = self.nickname.clone();
 
-        for target in targets.split(',') {
-            if let Err(error) = self.assert_target_is_valid(target) {
-                self.stream.send(&error)?;
-                continue;
-            }
+        self.send_message_to_all_servers(&Notification::kill(&killer, &victim, &comment));
+        self.kill_client(&killer, &victim, &comment);
+
+        let notice = format!("Received KILL message for {victim}. From {killer} ({comment})");
+        self.send_server_notice(&notice);
+
+        Ok(!irc_eq(&victim, &killer))
+    }
+
+    /// Once registered, CAP END has no effect.
+    fn cap_logic(&mut self, _prefix: Option<String>, cap: Cap) -> io::Result<bool> {
+        let mut capabilities = self.database.get_client_capabilities(&self.nickname);
 
-            self.send_privmsg_to_target(target, &content)?;
+        match cap.subcommand {
+            CapSubcommand::Ls => {
+                self.send_cap_reply(CAP_LS, &Capability::names(&Capability::all()))?
+            }
+            CapSubcommand::List => {
+                self.send_cap_reply(CAP_LIST, &Capability::names(&capabilities))?
+            }
+            CapSubcommand::Req => {
+                let request = cap.arguments.unwrap_or_default();
+                let reply = match Capability::apply_request(&mut capabilities, &request) {
+                    true => CAP_ACK,
+                    false => CAP_NAK,
+                };
+                self.database
+                    .set_client_capabilities(&self.nickname, capabilities);
+                self.send_cap_reply(reply, &request)?;
+            }
+            CapSubcommand::End => {}
         }
 
         Ok(true)
     }
 
-    fn notice_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, trail) = arguments;
-        let content = trail.expect("Verified in assert");
-        let targets = params.pop().expect("Verified in assert");
+    fn privmsg_logic(&mut self, _prefix: Option<String>, privmsg: Privmsg) -> io::Result<bool> {
+        for target in privmsg.targets {
+            if irc_eq(&target, CHANSERV_NICKNAME) {
+                self.chanserv_request(&privmsg.text)?;
+                continue;
+            }
+            if irc_eq(&target, NICKSERV_NICKNAME) {
+                self.nickserv_request(&privmsg.text)?;
+                continue;
+            }
 
-        for target in targets.split(',') {
-            if let Err(error) = self.assert_target_is_valid(target) {
-                self.stream.send(&error)?;
+            if let Err(error) = self.assert_target_is_valid(&target) {
+                self.send_response(&error)?;
                 continue;
             }
 
-            self.send_notice_to_target(target, &content);
+            let text = self.fit_privmsg_text(&target, &privmsg.text)?;
+            self.send_privmsg_to_target(&target, &text)?;
         }
 
         Ok(true)
     }
 
-    fn join_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, params, _) = arguments;
-        let channels = params[0].split(',');
-
-        let mut keys = collect_list(params.get(1)).into_iter();
-
-        for channel in channels {
-            let key = keys.next();
-
-            if let Err(error) = self.assert_can_join_channel(channel, &key) {
-                self.stream.send(&error)?;
+    fn notice_logic(&mut self, _prefix: Option<String>, notice: Notice) -> io::Result<bool> {
+        for target in notice.targets {
+            if let Err(error) = self.assert_target_is_valid(&target) {
+                self.send_response(&error)?;
                 continue;
             }
 
-            self.send_join_notification(channel);
+            self.send_notice_to_target(&target, &notice.text);
+        }
 
-            let creating_channel = !self.database.contains_channel(channel);
+        Ok(true)
+    }
 
-            self.database.add_client_to_channel(channel, &self.nickname);
+    fn join_logic(&mut self, _prefix: Option<String>, join: Join) -> io::Result<bool> {
+        let mut keys = join.keys.into_iter();
+
+        for channel in join.channels {
+            let key = keys.next();
+
+            let permit = match self.join_channel(&channel, &key) {
+                Ok(permit) => permit,
+                Err(error) => {
+                    self.send_response(&error)?;
+                    continue;
+                }
+            };
 
-            if creating_channel {
-                self.database.add_channel_operator(channel, &self.nickname);
-                self.send_channel_operator_notification(channel, &self.nickname);
+            self.send_join_notification(&channel, permit.recipients);
+            if permit.operator {
+                self.send_channel_operator_notification(&channel, &self.nickname);
             }
 
-            self.send_join_response(channel)?;
+            self.send_join_response(&channel)?;
         }
 
         Ok(true)
     }
 
-    fn part_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-        let channels = params.pop().expect("Verified in assert");
-
-        for channel in channels.split(',') {
-            if let Err(error) = self.assert_can_part_channel(channel) {
-                self.stream.send(&error)?;
+    fn part_logic(&mut self, _prefix: Option<String>, part: Part) -> io::Result<bool> {
+        for channel in part.channels {
+            if let Err(error) = self.assert_can_part_channel(&channel) {
+                self.send_response(&error)?;
                 continue;
             }
 
-            self.send_part_notification(channel);
+            self.send_part_notification(&channel);
 
             self.database
-                .remove_client_from_channel(channel, &self.nickname);
+                .remove_client_from_channel(&channel, &self.nickname);
         }
 
         Ok(true)
     }
 
-    fn invite_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-        let channel = params.pop().expect("Verified in assert");
-        let invited_client = params.pop().expect("Verified in assert");
+    fn invite_logic(&mut self, _prefix: Option<String>, invite: Invite) -> io::Result<bool> {
+        let Invite {
+            nickname: invited_client,
+            channel,
+        } = invite;
         let inviting_client = self.nickname.clone();
 
         self.send_invite_notification(&invited_client, &channel)
             .ok();
 
-        self.stream
-            .send(&CommandResponse::inviting(&inviting_client, &channel))?;
+        self.send_response(&CommandResponse::inviting(&inviting_client, &channel))?;
 
         self.database.add_channel_invite(&channel, &invited_client);
 
         Ok(true)
     }
 
-    fn names_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, params, _) = arguments;
-        let channels = self.channels_to_list(params.get(0));
+    fn names_logic(&mut self, _prefix: Option<String>, names: Names) -> io::Result<bool> {
+        let listed_all = names.channels.is_none();
+        let channels = self.channels_to_list(names.channels);
 
         for channel in channels {
             if !self.can_name_channel(&channel) {
@@ -157,23 +237,22 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
 
             self.send_names_response(&channel)?;
 
-            if !params.is_empty() {
-                self.stream.send(&CommandResponse::end_of_names(&channel))?;
+            if !listed_all {
+                self.send_response(&CommandResponse::end_of_names(&channel))?;
             }
         }
 
-        if params.is_empty() {
+        if listed_all {
             self.send_name_response_for_remaining_clients()?;
         }
 
         Ok(true)
     }
 
-    fn list_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, params, _) = arguments;
-        let channels = self.channels_to_list(params.get(0));
+    fn list_logic(&mut self, _prefix: Option<String>, list: List) -> io::Result<bool> {
+        let channels = self.channels_to_list(list.channels);
 
-        self.stream.send(&CommandResponse::list_start())?;
+        self.send_response(&CommandResponse::list_start())?;
 
         for channel in channels {
             if !self.can_list_channel(&channel) {
@@ -181,14 +260,13 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
             }
             self.send_list_response(channel)?;
         }
-        self.stream.send(&CommandResponse::list_end())?;
+        self.send_response(&CommandResponse::list_end())?;
 
         Ok(true)
     }
 
-    fn who_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-        let mask = params.pop();
+    fn who_logic(&mut self, _prefix: Option<String>, who: Who) -> io::Result<bool> {
+        let mask = who.mask;
 
         let clients = match &mask {
             Some(mask) => self.get_clients_for_mask(mask),
@@ -199,25 +277,23 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
             self.send_whoreply_response(client_info)?;
         }
 
-        self.stream.send(&CommandResponse::end_of_who(mask))?;
+        self.send_response(&CommandResponse::end_of_who(mask))?;
 
         Ok(true)
     }
 
-    fn whois_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-        let nickmasks = params.pop().expect("Verified in assert");
-        let server = params.get(0);
+    fn whois_logic(&mut self, _prefix: Option<String>, whois: Whois) -> io::Result<bool> {
+        let Whois { server, nickmasks } = whois;
 
-        for nickmask in nickmasks.split(',') {
-            let clients: Vec<ClientInfo> = self.get_clients_for_nickmask(nickmask);
+        for nickmask in nickmasks {
+            let clients: Vec<ClientInfo> = self.get_clients_for_nickmask(&nickmask);
 
-            if let Err(error) = self.assert_can_send_whois_response(&clients, nickmask) {
-                self.stream.send(&error)?;
+            if let Err(error) = self.assert_can_send_whois_response(&clients, &nickmask) {
+                self.send_response(&error)?;
                 continue;
             }
             for client in clients {
-                if let Some(server) = server {
+                if let Some(server) = &server {
                     if &client.servername != server {
                         continue;
                     }
@@ -229,8 +305,8 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
         Ok(true)
     }
 
-    fn away_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, _, trail) = arguments;
+    fn away_logic(&mut self, _prefix: Option<String>, away: Away) -> io::Result<bool> {
+        let trail = away.message;
         self.database
             .set_away_message(&self.nickname, trail.clone());
 
@@ -241,16 +317,20 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
         };
 
         self.send_message_to_all_servers(&away_notification);
-        self.stream.send(&reply)?;
+        self.send_away_notify(&self.nickname, &trail);
+        self.send_response(&reply)?;
 
         Ok(true)
     }
 
-    fn topic_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-        let channel = params.remove(0);
+    fn topic_logic(&mut self, _prefix: Option<String>, topic: Topic) -> io::Result<bool> {
+        let Topic { channel, topic } = topic;
 
-        if let Some(topic) = params.pop() {
+        if let Some(mut topic) = topic {
+            if topic.len() > MAX_TOPIC_LENGTH {
+                truncate_at_char_boundary(&mut topic, MAX_TOPIC_LENGTH);
+                self.send_response(&ErrorReply::InputTooLong417)?;
+            }
             self.database.set_channel_topic(&channel, &topic);
             self.send_topic_notification(&channel, &topic);
         } else {
@@ -260,55 +340,50 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
         Ok(true)
     }
 
-    fn kick_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, params, trail) = arguments;
-        let channel = params[0].split(',');
-        let nickname = params[1].split(',');
-
-        for (channel, nickname) in channel.zip(nickname) {
+    fn kick_logic(&mut self, _prefix: Option<String>, kick: Kick) -> io::Result<bool> {
+        for (channel, nickname) in kick.channels.iter().zip(&kick.nicknames) {
             if let Err(error) = self.assert_can_kick_from_channel(channel) {
-                self.stream.send(&error)?;
+                self.send_response(&error)?;
             } else {
-                self.kick_client_from_channel(nickname, channel, &trail);
+                self.kick_client_from_channel(nickname, channel, &kick.comment);
             }
         }
 
         Ok(true)
     }
 
-    fn mode_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, _) = arguments;
-
-        let target = params.remove(0);
+    fn mode_logic(&mut self, _prefix: Option<String>, mode: Mode) -> io::Result<bool> {
+        let Mode {
+            target,
+            changes,
+            arguments,
+        } = mode;
 
         if target.starts_with([DISTRIBUTED_CHANNEL, LOCAL_CHANNEL]) {
-            self.mode_command_for_channel(target, params)?;
+            self.mode_command_for_channel(target, changes, arguments)?;
         } else {
-            self.mode_command_for_user(target, params)?;
+            self.mode_command_for_user(target, changes)?;
         }
 
         Ok(true)
     }
 
-    fn quit_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, _, trail) = arguments;
-        let message = trail.unwrap_or_else(|| self.nickname.clone());
+    fn quit_logic(&mut self, _prefix: Option<String>, quit: Quit) -> io::Result<bool> {
+        let message = quit.message.unwrap_or_else(|| self.nickname.clone());
 
         let nickname = self.nickname.clone();
-        self.database.disconnect_client(&nickname);
-
         self.send_quit_notification(&nickname, &message);
 
-        self.stream.send(&CommandResponse::quit(&message))?;
+        self.send_message(&CommandResponse::quit(&message))?;
+
+        self.database.disconnect_client(&nickname);
 
         Ok(false)
     }
 
-    fn squit_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, params, trail) = arguments;
-
-        let servername = &params[0];
-        let comment = trail;
+    fn squit_logic(&mut self, _prefix: Option<String>, squit: Squit) -> io::Result<bool> {
+        let servername = &squit.servername;
+        let comment = squit.comment;
 
         self.send_squit_notification(servername, comment);
 
@@ -326,19 +401,19 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
                 .collect();
 
             for client in server_clients {
-                self.database.disconnect_client(&client.nickname);
                 self.send_quit_notification(&client.nickname, SQUIT_MESSAGE);
+                self.database.disconnect_client(&client.nickname);
             }
         }
 
         Ok(true)
     }
 
-    fn ctcp_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, mut params, trail) = arguments;
-
-        let target = params.remove(0);
-        let mut content = trail.expect("Verified in assert");
+    fn ctcp_logic(&mut self, _prefix: Option<String>, ctcp: Ctcp) -> io::Result<bool> {
+        let Ctcp {
+            target,
+            text: mut content,
+        } = ctcp;
 
         content.insert(0, 1 as char);
         content.push(1 as char);
@@ -347,14 +422,72 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ClientHandler<C> {
 
         Ok(true)
     }
+
+    fn ping_logic(&mut self, _prefix: Option<String>, ping: Ping) -> io::Result<bool> {
+        let servername = self.database.get_server_name();
+
+        self.send_message(&Notification::pong(&servername, &ping.token))?;
+
+        Ok(true)
+    }
+
+    fn motd_logic(&mut self, _prefix: Option<String>, _motd: Motd) -> io::Result<bool> {
+        self.send_motd()?;
+
+        Ok(true)
+    }
 }
 
 impl<C: Connection> ClientHandler<C> {
+    /// Joins the client to the channel in a single database request, so no other client
+    /// can change the channel between the checks and the join.
+    fn join_channel(
+        &mut self,
+        channel: &str,
+        key: &Option<String>,
+    ) -> Result<JoinPermit<C>, ErrorReply> {
+        self.assert_channel_name_is_valid(channel)?;
+
+        let channel = channel.to_string();
+        let refusal = match self.database.join_channel(&self.nickname, &channel, key) {
+            Ok(permit) => return Ok(permit),
+            Err(refusal) => refusal,
+        };
+
+        Err(match refusal {
+            JoinRefusal::TooManyChannels => ErrorReply::TooManyChannels405 { channel },
+            JoinRefusal::AlreadyOnChannel => ErrorReply::UserOnChannel443 {
+                nickname: self.nickname.clone(),
+                channel,
+            },
+            JoinRefusal::BadKey => ErrorReply::BadChannelKey475 { channel },
+            JoinRefusal::ChannelIsFull => ErrorReply::ChannelIsFull471 { channel },
+            JoinRefusal::Banned => ErrorReply::BannedFromChannel474 { channel },
+            JoinRefusal::InviteOnly => ErrorReply::InviteOnlyChannel473 { channel },
+        })
+    }
+
+    /// Returns the text as it fits in the PRIVMSG relayed to target, telling the sender if it was cut.
+    fn fit_privmsg_text(&mut self, target: &str, text: &str) -> io::Result<String> {
+        let notification = Notification::privmsg(&self.nickname, target, text);
+        let relayed = notification
+            .to_message()
+            .ok()
+            .and_then(|message| message.get_trailing().clone())
+            .unwrap_or_else(|| text.to_string());
+
+        if relayed != text {
+            self.send_response(&ErrorReply::InputTooLong417)?;
+        }
+
+        Ok(relayed)
+    }
+
     fn send_privmsg_to_target(&mut self, target: &str, content: &str) -> io::Result<()> {
         self.send_privmsg_notification(target, content);
 
         if let Ok(Some(message)) = self.database.get_away_message(target) {
-            self.stream.send(&CommandResponse::away(target, &message))?;
+            self.send_response(&CommandResponse::away(target, &message))?;
         }
 
         Ok(())
@@ -377,18 +510,17 @@ impl<C: Connection> ClientHandler<C> {
     fn mode_command_for_channel(
         &mut self,
         channel: String,
-        mut args: Vec<String>,
+        changes: Option<Vec<ModeChange>>,
+        mut arguments: Vec<String>,
     ) -> Result<(), io::Error> {
-        if args.is_empty() {
-            self.send_channel_mode_is_response(&channel)?;
-            return Ok(());
-        }
+        let changes = match changes {
+            Some(changes) => changes,
+            None => return self.send_channel_mode_is_response(&channel),
+        };
 
-        let mode_string = args.remove(0);
-        let mut mode_arguments = args;
-        mode_arguments.reverse();
+        arguments.reverse();
 
-        let mode_requests = parse_channel_mode_string(mode_string, mode_arguments);
+        let mode_requests = parse_channel_mode_changes(changes, arguments);
 
         for request in mode_requests {
             self.handle_channel_mode_request(&channel, request)?;
@@ -397,15 +529,17 @@ impl<C: Connection> ClientHandler<C> {
         Ok(())
     }
 
-    fn mode_command_for_user(&mut self, user: String, mut args: Vec<String>) -> io::Result<()> {
-        if args.is_empty() {
-            self.send_user_mode_is_response(&user)?;
-            return Ok(());
-        }
-
-        let mode_string = args.remove(0);
+    fn mode_command_for_user(
+        &mut self,
+        user: String,
+        changes: Option<Vec<ModeChange>>,
+    ) -> io::Result<()> {
+        let changes = match changes {
+            Some(changes) => changes,
+            None => return self.send_user_mode_is_response(&user),
+        };
 
-        let mode_requests = parse_user_mode_string(mode_string);
+        let mode_requests = parse_user_mode_changes(changes);
         for request in mode_requests {
             self.handle_user_mode_request(&user, request)?;
         }
@@ -4,13 +4,14 @@ use crate::server::consts::channel_flag::ChannelFlag;
 use crate::server::consts::user_flag::UserFlag;
 use crate::{
     macros::ok_or_return,
+    message::command::names::MODE_COMMAND,
     server::{
         connection::Connection,
         connection_handler::{
             client_handler::ClientHandler,
             mode_requests::{ChannelModeRequest, UserModeRequest},
+            ConnectionHandlerUtils,
         },
-        consts::commands::MODE_COMMAND,
         responses::ErrorReply,
     },
 };
@@ -67,7 +68,7 @@ impl<C: Connection> ClientHandler<C> {
 
     fn add_operator_request(&mut self, channel: &str, operator: String) -> io::Result<()> {
         if let Err(error) = self.assert_is_client_in_channel(channel, &operator) {
-            return self.stream.send(&error);
+            return self.send_response(&error);
         }
 
         self.database.add_channel_operator(channel, &operator);
@@ -80,7 +81,7 @@ impl<C: Connection> ClientHandler<C> {
 
     fn add_speaker_request(&mut self, channel: &str, speaker: String) -> io::Result<()> {
         if let Err(error) = self.assert_is_client_in_channel(channel, &speaker) {
-            return self.stream.send(&error);
+            return self.send_response(&error);
         }
         self.database.add_channel_speaker(channel, &speaker);
 
@@ -102,7 +103,7 @@ impl<C: Connection> ClientHandler<C> {
     }
     fn remove_operator_request(&mut self, channel: &str, operator: String) -> io::Result<()> {
         if let Err(error) = self.assert_is_client_in_channel(channel, &operator) {
-            return self.stream.send(&error);
+            return self.send_response(&error);
         }
         self.database.remove_channel_operator(channel, &operator);
 
@@ -112,7 +113,7 @@ impl<C: Connection> ClientHandler<C> {
     }
     fn remove_speaker_request(&mut self, channel: &str, speaker: String) -> io::Result<()> {
         if let Err(error) = self.assert_is_client_in_channel(channel, &speaker) {
-            return self.stream.send(&error);
+            return self.send_response(&error);
         }
         self.database.remove_channel_speaker(channel, &speaker);
 
@@ -129,7 +130,7 @@ impl<C: Connection> ClientHandler<C> {
     }
     fn set_key_request(&mut self, channel: &str, key: String) -> io::Result<()> {
         if let Err(error) = self.assert_can_set_key(channel) {
-            return self.stream.send(&error);
+            return self.send_response(&error);
         }
         self.database.set_channel_key(channel, Some(key.clone()));
 
@@ -169,11 +170,10 @@ impl<C: Connection> ClientHandler<C> {
         Ok(())
     }
     fn unknown_channel_mode_request(&mut self, character: char) -> io::Result<()> {
-        self.stream
-            .send(&ErrorReply::UnknownMode472 { mode: character })
+        self.send_response(&ErrorReply::UnknownMode472 { mode: character })
     }
     fn need_argument_request(&mut self, _character: char) -> io::Result<()> {
-        self.stream.send(&ErrorReply::NeedMoreParameters461 {
+        self.send_response(&ErrorReply::NeedMoreParameters461 {
             command: MODE_COMMAND.to_string(),
         })
     }
@@ -223,6 +223,6 @@ impl<C: Connection> ClientHandler<C> {
     }
     fn unknown_user_mode_request(&mut self, _character: char) -> io::Result<()> {
         let error = ErrorReply::UserModeUnknownFlag501;
-        self.stream.send(&error)
+        self.send_response(&error)
     }
 }
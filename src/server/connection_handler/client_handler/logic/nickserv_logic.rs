@@ -0,0 +1,80 @@
+use std::io;
+
+use crate::macros::{ok_or_return, some_or_return};
+use crate::server::data_structures::{irc_eq, unix_time, Account};
+use crate::server::{
+    connection::Connection,
+    connection_handler::{client_handler::ClientHandler, ConnectionHandlerUtils},
+    responses::{CommandResponse, Notification},
+};
+
+const NICKSERV_HELP: &str = "Commands: REGISTER <password>, IDENTIFY [<account>] <password>";
+
+impl<C: Connection> ClientHandler<C> {
+    /// Handles a message sent to NickServ, answering with notices.
+    pub(super) fn nickserv_request(&mut self, text: &str) -> io::Result<()> {
+        let mut arguments = text.split_whitespace();
+        let command = arguments.next().unwrap_or_default().to_uppercase();
+        let arguments: Vec<&str> = arguments.collect();
+
+        match (command.as_str(), arguments.as_slice()) {
+            ("REGISTER", [password]) => self.register_nickname_request(password),
+            ("IDENTIFY", [password]) => {
+                let nickname = self.nickname.clone();
+                self.identify_request(&nickname, password)
+            }
+            ("IDENTIFY", [account, password]) => self.identify_request(account, password),
+            _ => self.send_nickserv_notice(NICKSERV_HELP),
+        }
+    }
+
+    fn register_nickname_request(&mut self, password: &str) -> io::Result<()> {
+        if !self.database.has_account_store() {
+            return self.send_nickserv_notice("Nickname registration is disabled");
+        }
+
+        let account = Account::new(&self.nickname, password, unix_time());
+        if !self.database.add_account(account) {
+            return self.send_nickserv_notice(&format!("{} is already registered", self.nickname));
+        }
+
+        self.send_nickserv_notice(&format!("{} is now registered", self.nickname))?;
+        let nickname = self.nickname.clone();
+        self.log_in(&nickname)
+    }
+
+    fn identify_request(&mut self, name: &str, password: &str) -> io::Result<()> {
+        if self.login_attempts.exceeded() {
+            return self.send_nickserv_notice("Too many failed attempts, try again later");
+        }
+
+        let account = some_or_return!(
+            self.database.get_account(name),
+            self.send_nickserv_notice(&format!("{name} is not registered"))
+        );
+        if !account.verify(password) {
+            self.login_attempts.failed();
+            return self.send_nickserv_notice("Invalid password");
+        }
+
+        self.log_in(&account.name)
+    }
+
+    /// Logs the client into account, letting the rest of the network know.
+    fn log_in(&mut self, account: &str) -> io::Result<()> {
+        self.database
+            .set_client_account(&self.nickname, Some(account.to_string()));
+        let notification = Notification::account(&self.nickname, &Some(account.to_string()));
+        self.send_message_to_all_servers(&notification);
+
+        let info = ok_or_return!(self.database.get_client_info(&self.nickname), Ok(()));
+        let mask = format!("{}!{}@{}", info.nickname, info.username, info.hostname);
+        self.send_response(&CommandResponse::logged_in(&mask, account))?;
+
+        if irc_eq(&self.nickname, account) {
+            self.protect_nickname()?;
+        }
+
+        Ok(())
+    }
+}
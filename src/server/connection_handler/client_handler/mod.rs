@@ -1,10 +1,22 @@
+use std::io;
 use std::sync::{atomic::AtomicBool, Arc};
+use std::time::Duration;
 
-use crate::server::{connection::Connection, database::DatabaseHandle};
+use crate::message::command::names::{PASS_COMMAND, SERVER_COMMAND, USER_COMMAND};
+use crate::message::{CommandError, CreationError, LineReader, Message, ToMessage};
+use crate::server::{
+    connection::Connection,
+    data_structures::{OutputQueue, SENDQ_EXCEEDED_MESSAGE},
+    database::DatabaseHandle,
+    reactor::{Progress, Task, Waker},
+    responses::{CommandResponse, ErrorReply, Notification},
+    NICK_PROTECTION_SECS,
+};
 
 use super::{
-    ConnectionHandler, ConnectionHandlerCommands, ConnectionHandlerGetters,
-    ConnectionHandlerStructure,
+    closing_reason, ConnectionHandler, ConnectionHandlerCommands, ConnectionHandlerGetters,
+    ConnectionHandlerStructure, ConnectionHandlerUtils, FailedAttempts, Keepalive,
+    PING_TIMEOUT_MESSAGE,
 };
 /// Asserts to ensure the commands the client sends are valid.
 mod asserts;
@@ -12,11 +24,15 @@ mod asserts;
 mod booleans;
 /// Logic for the commands a client may send.
 mod logic;
+/// Renaming of clients that keep a registered nickname without identifying.
+mod nick_protection;
 /// Responses generated by the commands.
 mod responses;
 /// Extra functions that help with the command's logic.
 mod utils;
 
+use nick_protection::NickProtection;
+
 /// Unit tests for each command.
 #[cfg(test)]
 mod tests;
@@ -24,12 +40,32 @@ mod tests;
 /// A Client Handler handles the connection with an already registered client.
 pub struct ClientHandler<C: Connection> {
     stream: C,
+    output: OutputQueue<C>,
     database: DatabaseHandle<C>,
     nickname: String,
     online: Arc<AtomicBool>,
+    keepalive: Keepalive,
+    /// failed attempts at logging into an account, carried over from registration
+    login_attempts: FailedAttempts,
+    nick_protection: NickProtection,
+    reader: LineReader,
 }
 impl<C: Connection> ConnectionHandler<C> for ClientHandler<C> {}
 
+impl<C: Connection> Task for ClientHandler<C> {
+    fn poll(&mut self) -> Progress {
+        self.poll_connection()
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        self.output.set_waker(waker);
+    }
+
+    fn abort(&mut self) {
+        self.abort_connection();
+    }
+}
+
 impl<C: Connection> ClientHandler<C> {
     /// Starts a [`ClientHandler`] with the received information.
     pub fn from_connection(
@@ -38,12 +74,35 @@ impl<C: Connection> ClientHandler<C> {
         database: DatabaseHandle<C>,
         online: Arc<AtomicBool>,
     ) -> std::io::Result<Self> {
-        Ok(Self {
+        let (interval, timeout) = database.get_keepalive_settings();
+        let output = database.get_local_queue(&nickname)?;
+
+        let mut handler = Self {
             stream,
+            output,
             database,
             online,
             nickname,
-        })
+            keepalive: Keepalive::new(interval, timeout),
+            login_attempts: Default::default(),
+            nick_protection: NickProtection::new(Duration::from_secs(NICK_PROTECTION_SECS)),
+            reader: LineReader::new(),
+        };
+        handler.protect_nickname()?;
+
+        Ok(handler)
+    }
+
+    /// Continues reading with the given reader, keeping the bytes it already buffered.
+    pub fn with_reader(mut self, reader: LineReader) -> Self {
+        self.reader = reader;
+        self
+    }
+
+    /// Keeps counting the failed logins made while registering.
+    pub fn with_login_attempts(mut self, attempts: FailedAttempts) -> Self {
+        self.login_attempts = attempts;
+        self
     }
 }
 
@@ -59,15 +118,112 @@ impl<C: Connection> ConnectionHandlerGetters<C> for ClientHandler<C> {
     fn database(&self) -> &DatabaseHandle<C> {
         &self.database
     }
+
+    fn reply_target(&self) -> String {
+        self.nickname.clone()
+    }
 }
 
 impl<C: Connection> ConnectionHandlerStructure<C> for ClientHandler<C> {
-    fn on_try_handle_error(&mut self) {
-        eprintln!("Connection with [{}] ended unexpectedly", self.nickname)
+    fn read_message(&mut self) -> Result<Message, CreationError> {
+        self.reader.read_message(&mut self.stream)
+    }
+
+    /// Client left without QUIT, it is disconnected as if it had sent it.
+    fn on_try_handle_error(&mut self, error: io::Error) {
+        eprintln!("Connection with [{}] ended unexpectedly", self.nickname);
+
+        if !self.output.hung_up() {
+            self.close_link(&closing_reason(&error)).ok();
+        }
     }
     fn on_try_handle_success(&mut self) {
         eprintln!("Closing conection with [{}]", self.nickname)
     }
+
+    fn timeout(&mut self) -> bool {
+        self.keepalive.timed_out()
+    }
+
+    /// Client did not answer to ping, it is disconnected as if it had sent QUIT.
+    fn on_timeout(&mut self) -> io::Result<()> {
+        self.close_link(PING_TIMEOUT_MESSAGE)
+    }
+
+    fn sendq_exceeded(&mut self) -> bool {
+        self.output.exceeded()
+    }
+
+    /// Client is not reading what is sent to it, it is disconnected as if it had sent QUIT.
+    fn on_sendq_exceeded(&mut self) -> io::Result<()> {
+        self.close_link(SENDQ_EXCEEDED_MESSAGE)
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    fn close_output(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.close(message)
+    }
+
+    fn closing(&mut self) -> bool {
+        self.output.closing()
+    }
+
+    fn hung_up(&mut self) -> bool {
+        self.output.hung_up()
+    }
+
+    fn queued_output(&mut self) -> usize {
+        self.output.queued()
+    }
+
+    /// Client kept a registered nickname without identifying, it is renamed.
+    fn on_poll(&mut self) -> io::Result<()> {
+        if self.nick_protection.expired() {
+            self.rename_to_guest()?;
+        }
+        Ok(())
+    }
+
+    fn idle(&mut self) -> bool {
+        self.keepalive.should_ping()
+    }
+
+    fn on_idle(&mut self) -> io::Result<()> {
+        let servername = self.database.get_server_name();
+        self.send_message(&Notification::ping(&servername))?;
+        self.keepalive.ping_sent();
+        Ok(())
+    }
+
+    fn on_activity(&mut self) {
+        self.keepalive.refresh();
+    }
 }
 
-impl<C: Connection> ConnectionHandlerCommands<C> for ClientHandler<C> {}
+impl<C: Connection> ClientHandler<C> {
+    /// Disconnects the client as if it had sent QUIT, telling it why if it still listens.
+    fn close_link(&mut self, reason: &str) -> io::Result<()> {
+        let nickname = self.nickname.clone();
+        self.send_quit_notification(&nickname, reason);
+
+        let message = format!("Closing Link: {nickname} ({reason})");
+        self.send_message(&CommandResponse::error(&message)).ok();
+        self.database.disconnect_client(&nickname);
+        self.flush_output().ok();
+        self.stream.shutdown()
+    }
+}
+
+impl<C: Connection> ConnectionHandlerCommands<C> for ClientHandler<C> {
+    /// Registration commands are refused once registered, whatever their arguments are.
+    fn on_command_error(&mut self, command: &str, error: CommandError) -> io::Result<()> {
+        if [PASS_COMMAND, USER_COMMAND, SERVER_COMMAND].contains(&command) {
+            return self.send_response(&ErrorReply::AlreadyRegistered462);
+        }
+
+        self.send_response(&ErrorReply::from(error))
+    }
+}
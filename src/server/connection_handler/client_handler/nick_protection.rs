@@ -0,0 +1,34 @@
+use std::time::{Duration, Instant};
+
+/// Keeps track of how long a client may keep a registered nickname
+/// before identifying, after which it is renamed.
+pub struct NickProtection {
+    delay: Duration,
+    deadline: Option<Instant>,
+}
+
+impl NickProtection {
+    /// Creates an inactive [`NickProtection`] that gives clients `delay` to identify.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: None,
+        }
+    }
+
+    /// Starts counting down from the full delay.
+    pub fn start(&mut self) {
+        self.deadline = Some(Instant::now() + self.delay);
+    }
+
+    /// Stops the countdown, the client may keep its nickname.
+    pub fn stop(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Returns true if a countdown was started and has run out.
+    pub fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
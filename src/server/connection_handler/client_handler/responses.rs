@@ -3,7 +3,7 @@ use std::io;
 use crate::macros::ok_or_return;
 use crate::server::connection_handler::client_handler::booleans::is_distributed_channel;
 use crate::server::connection_handler::mode_requests::{ChannelModeRequest, UserModeRequest};
-use crate::server::consts::channel::{NO_CHANNEL_NAME, WILDCARD_CHANNEL};
+use crate::server::consts::channel::WILDCARD_CHANNEL;
 use crate::server::consts::channel_flag::ChannelFlag;
 use crate::server::consts::modes::{
     ADD_OPERATOR, NO_TOPIC, SET_BANMASK, SET_KEY, SET_OPERATOR, SET_SPEAKER, SET_USER_LIMIT,
@@ -23,8 +23,7 @@ impl<C: Connection> ClientHandler<C> {
 
         let clients = ok_or_return!(self.database.get_channel_clients(channel), Ok(()));
 
-        self.stream
-            .send(&CommandResponse::name_reply(channel, &clients))
+        self.send_response(&CommandResponse::name_reply(channel, &clients))
     }
 
     pub fn send_oper_notification(&mut self) {
@@ -32,9 +31,26 @@ impl<C: Connection> ClientHandler<C> {
         self.send_message_to_all_servers(&notification);
     }
 
+    /// Sends notice from the server to the client.
+    pub(super) fn send_notice(&mut self, message: &str) -> io::Result<()> {
+        let servername = self.database.get_server_name();
+        let message = format!("*** {message}");
+        let notice = Notification::notice(&servername, &self.nickname, &message);
+        self.send_message(&notice)
+    }
+
+    /// Reports a failed OPER attempt to operators receiving server notices.
+    pub fn send_failed_oper_notice(&mut self, username: &str) {
+        let client = ok_or_return!(self.database.get_client_info(&self.nickname));
+        let notice = format!(
+            "Failed OPER attempt by {} ({}@{}) as {username}",
+            client.nickname, client.username, client.hostname
+        );
+        self.send_server_notice(&notice);
+    }
+
     pub(super) fn send_whois_response(&mut self, client_info: ClientInfo) -> io::Result<()> {
-        self.stream
-            .send(&CommandResponse::whois_user(&client_info))?;
+        self.send_response(&CommandResponse::whois_user(&client_info))?;
 
         let nickname = &client_info.nickname;
         let servername = &client_info.servername;
@@ -45,7 +61,7 @@ impl<C: Connection> ClientHandler<C> {
             self.database.get_own_server_info()
         };
 
-        self.stream.send(&CommandResponse::whois_server(
+        self.send_response(&CommandResponse::whois_server(
             nickname,
             servername,
             &serverinfo,
@@ -54,12 +70,17 @@ impl<C: Connection> ClientHandler<C> {
         self.send_whois_operator_response(nickname)?;
         self.send_whois_channels_response(nickname)?;
 
+        if let Some(account) = &client_info.account {
+            self.send_response(&CommandResponse::whois_account(nickname, account))?;
+        }
+
         if let Some(message) = client_info.away {
-            self.stream
-                .send(&CommandResponse::away(nickname, &message))?;
+            self.send_response(&CommandResponse::away(nickname, &message))?;
         }
 
-        self.stream.send(&CommandResponse::end_of_whois(nickname))?;
+        self.send_whois_sendq_response(nickname)?;
+
+        self.send_response(&CommandResponse::end_of_whois(nickname))?;
 
         Ok(())
     }
@@ -68,12 +89,10 @@ impl<C: Connection> ClientHandler<C> {
         let mut channels = ok_or_return!(self.database.get_channels_for_client(nickname), Ok(()));
         if !channels.is_empty() {
             for channel in &mut channels {
-                if let Some(role) = self.get_client_role_in_channel(channel, nickname) {
-                    channel.insert(0, role);
-                }
+                let roles = self.get_client_roles_in_channel(channel, nickname);
+                channel.insert_str(0, &roles);
             }
-            self.stream
-                .send(&CommandResponse::whois_channel(nickname, &channels))?;
+            self.send_response(&CommandResponse::whois_channel(nickname, &channels))?;
         };
 
         Ok(())
@@ -81,27 +100,36 @@ impl<C: Connection> ClientHandler<C> {
 
     fn send_whois_operator_response(&mut self, nickname: &str) -> Result<(), io::Error> {
         if self.database.is_server_operator(nickname) {
-            self.stream
-                .send(&CommandResponse::whois_operator(nickname))?;
+            self.send_response(&CommandResponse::whois_operator(nickname))?;
         };
         Ok(())
     }
 
+    /// Operators are shown how much output is waiting to be written to local clients.
+    fn send_whois_sendq_response(&mut self, nickname: &str) -> Result<(), io::Error> {
+        if !self.database.is_server_operator(&self.nickname) {
+            return Ok(());
+        }
+
+        let queue = ok_or_return!(self.database.get_local_queue(nickname), Ok(()));
+        let response = CommandResponse::whois_sendq(nickname, queue.queued(), queue.limit());
+        self.send_response(&response)
+    }
+
     pub(super) fn send_banlist_response(&mut self, channel: &str) -> io::Result<()> {
         let banmasks = ok_or_return!(self.database.get_channel_banmask(channel), Ok(()));
         for banmask in banmasks {
-            self.stream
-                .send(&CommandResponse::banlist(channel, &banmask))?;
+            self.send_response(&CommandResponse::banlist(channel, &banmask))?;
         }
 
-        self.stream.send(&CommandResponse::end_of_banlist(channel))
+        self.send_response(&CommandResponse::end_of_banlist(channel))
     }
 
     pub(super) fn send_topic_response(&mut self, channel: &str) -> io::Result<()> {
         let topic = ok_or_return!(self.database.get_channel_topic(channel), Ok(()));
         match &topic {
-            Some(topic) => self.stream.send(&CommandResponse::topic(channel, topic)),
-            None => self.stream.send(&CommandResponse::no_topic(channel)),
+            Some(topic) => self.send_response(&CommandResponse::topic(channel, topic)),
+            None => self.send_response(&CommandResponse::no_topic(channel)),
         }
     }
 
@@ -113,8 +141,7 @@ impl<C: Connection> ClientHandler<C> {
         .get(0)
         .map(|string| string.to_owned());
 
-        self.stream
-            .send(&CommandResponse::whoreply(&channel, &client_info))
+        self.send_response(&CommandResponse::whoreply(&channel, &client_info))
     }
 
     pub(super) fn send_list_response(&mut self, channel: String) -> io::Result<()> {
@@ -126,8 +153,7 @@ impl<C: Connection> ClientHandler<C> {
             .channel_has_flag(&channel, ChannelFlag::Private)
             && !self.is_in_channel(&channel);
 
-        self.stream
-            .send(&CommandResponse::list(channel, topic, prv))
+        self.send_response(&CommandResponse::list(channel, topic, prv))
     }
 
     pub(super) fn send_name_response_for_remaining_clients(&mut self) -> Result<(), io::Error> {
@@ -138,31 +164,33 @@ impl<C: Connection> ClientHandler<C> {
             .collect();
 
         if !remaining_clients.is_empty() {
-            self.stream.send(&CommandResponse::name_reply(
+            self.send_response(&CommandResponse::name_reply(
                 WILDCARD_CHANNEL,
                 &remaining_clients,
             ))?;
         }
 
-        self.stream
-            .send(&CommandResponse::end_of_names(NO_CHANNEL_NAME))?;
+        self.send_response(&CommandResponse::end_of_names(WILDCARD_CHANNEL))?;
         Ok(())
     }
 
     pub(super) fn send_names_response(&mut self, channel: &str) -> Result<(), io::Error> {
         let mut clients = ok_or_return!(self.database.get_channel_clients(channel), Ok(()));
         for client in &mut clients {
-            if let Some(role) = self.get_client_role_in_channel(channel, client) {
-                client.insert(0, role);
-            }
+            let roles = self.get_client_roles_in_channel(channel, client);
+            client.insert_str(0, &roles);
         }
-        self.stream
-            .send(&CommandResponse::name_reply(channel, &clients))
+        self.send_response(&CommandResponse::name_reply(channel, &clients))
     }
 
-    pub(super) fn send_join_notification(&mut self, channel: &str) {
+    /// Notifies the members that were on the channel before the client joined.
+    pub(super) fn send_join_notification(
+        &mut self,
+        channel: &str,
+        recipients: ChannelRecipients<C>,
+    ) {
         let notification = Notification::join(&self.nickname, channel);
-        self.send_message_to_local_clients_on_channel(&notification, channel);
+        self.deliver_to_recipients(&notification, recipients);
 
         if is_distributed_channel(channel) {
             self.send_message_to_all_servers(&notification);
@@ -200,7 +228,16 @@ impl<C: Connection> ClientHandler<C> {
         let quit_notification = Notification::quit(nickname, message);
         let channels = ok_or_return!(self.database.get_channels_for_client(nickname));
         for channel in channels {
-            self.send_message_to_local_clients_on_channel(&quit_notification, &channel);
+            let clients = self
+                .database
+                .get_channel_clients(&channel)
+                .unwrap_or_default();
+            for client in clients {
+                if !irc_eq(&client, nickname) && self.database.is_local_client(&client) {
+                    self.send_message_to_client(&quit_notification, &client)
+                        .ok();
+                }
+            }
         }
         self.send_message_to_all_servers(&quit_notification);
     }
@@ -261,7 +298,7 @@ impl<C: Connection> ClientHandler<C> {
     ) -> Result<(), io::Error> {
         if !speakers.is_empty() {
             let reply = CommandResponse::channel_mode_is(channel, SET_SPEAKER, Some(speakers));
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         };
         Ok(())
     }
@@ -273,7 +310,7 @@ impl<C: Connection> ClientHandler<C> {
     ) -> Result<(), io::Error> {
         if !banmasks.is_empty() {
             let reply = CommandResponse::channel_mode_is(channel, SET_BANMASK, Some(banmasks));
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         };
         Ok(())
     }
@@ -285,7 +322,7 @@ impl<C: Connection> ClientHandler<C> {
     ) -> Result<(), io::Error> {
         if !operators.is_empty() {
             let reply = CommandResponse::channel_mode_is(channel, SET_OPERATOR, Some(operators));
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         };
         Ok(())
     }
@@ -295,10 +332,10 @@ impl<C: Connection> ClientHandler<C> {
         key: Option<String>,
         channel: &str,
     ) -> Result<(), io::Error> {
-        if key.is_some() {
-            let params = vec![key.expect("Verified in if condition")];
+        if let Some(key) = key {
+            let params = vec![key];
             let reply = CommandResponse::channel_mode_is(channel, SET_KEY, Some(params));
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         };
         Ok(())
     }
@@ -308,10 +345,10 @@ impl<C: Connection> ClientHandler<C> {
         limit: Option<usize>,
         channel: &str,
     ) -> Result<(), io::Error> {
-        if limit.is_some() {
-            let params = vec![limit.expect("Verified in if condition").to_string()];
+        if let Some(limit) = limit {
+            let params = vec![limit.to_string()];
             let reply = CommandResponse::channel_mode_is(channel, SET_USER_LIMIT, Some(params));
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         };
         Ok(())
     }
@@ -324,7 +361,7 @@ impl<C: Connection> ClientHandler<C> {
         for flag in flags {
             let mode = flag.to_char();
             let reply = CommandResponse::channel_mode_is(channel, mode, None);
-            self.stream.send(&reply)?;
+            self.send_response(&reply)?;
         }
         Ok(())
     }
@@ -334,7 +371,7 @@ impl<C: Connection> ClientHandler<C> {
         let user_modes = client_info.flags.keys().map(UserFlag::to_char).collect();
 
         let response = CommandResponse::UserModeIs221 { user_modes };
-        self.stream.send(&response)
+        self.send_response(&response)
     }
 
     pub(super) fn send_channel_mode_request_notification(
@@ -7,10 +7,12 @@ fn can_set_away_message_for_client() {
     let mut handler = dummy_client_handler();
 
     let trailing = Some("away message!".to_string());
-    handler.away_command((None, vec![], trailing)).unwrap();
+    handler
+        .raw_command("AWAY", (None, vec![], trailing))
+        .unwrap();
 
     assert_eq!(
-        "306 :You have been marked as being away\r\n",
+        ":servername 306 nickname :You have been marked as being away\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -25,12 +27,14 @@ fn can_unset_away_message_for_client() {
     let mut handler = dummy_client_handler();
 
     let trailing = Some("away message!".to_string());
-    handler.away_command((None, vec![], trailing)).unwrap();
+    handler
+        .raw_command("AWAY", (None, vec![], trailing))
+        .unwrap();
     handler.stream.clear();
-    handler.away_command((None, vec![], None)).unwrap();
+    handler.raw_command("AWAY", (None, vec![], None)).unwrap();
 
     assert_eq!(
-        "305 :You are no longer marked as being away\r\n",
+        ":servername 305 nickname :You are no longer marked as being away\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -49,23 +53,21 @@ fn aways_are_relayed_to_all_servers() {
         .add_immediate_server(dummy_server("servername2"));
 
     let trail = Some("message".to_string());
-    handler.away_command((None, vec![], trail)).unwrap();
-    handler.away_command((None, vec![], None)).unwrap();
+    handler.raw_command("AWAY", (None, vec![], trail)).unwrap();
+    handler.raw_command("AWAY", (None, vec![], None)).unwrap();
 
     assert_eq!(
         ":nickname AWAY :message\r\n:nickname AWAY\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname AWAY :message\r\n:nickname AWAY\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
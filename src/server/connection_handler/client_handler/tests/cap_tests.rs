@@ -0,0 +1,206 @@
+use crate::server::data_structures::Capability;
+use crate::server::testing::dummy_server;
+
+use super::*;
+
+#[test]
+fn cap_fails_with_empty_parameters() {
+    let mut handler = dummy_client_handler();
+
+    handler.raw_command("CAP", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 461 nickname CAP :Not enough parameters\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_fails_with_unknown_subcommand() {
+    let mut handler = dummy_client_handler();
+
+    let parameters = vec!["FOO".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 410 nickname FOO :Invalid CAP command\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_req_after_registration_enables_capabilities() {
+    let mut handler = dummy_client_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("multi-prefix".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP nickname ACK :multi-prefix\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        vec![Capability::MultiPrefix],
+        handler.database.get_client_capabilities("nickname")
+    );
+}
+
+#[test]
+fn cap_list_returns_enabled_capabilities() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_client_capabilities("nickname", vec![Capability::AwayNotify]);
+
+    let parameters = vec!["LIST".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP nickname LIST :away-notify\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_end_after_registration_is_ignored() {
+    let mut handler = dummy_client_handler();
+
+    let parameters = vec!["END".to_string()];
+    assert!(handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap());
+
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+}
+
+#[test]
+fn names_with_multi_prefix_returns_all_roles() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_client_capabilities("nickname", vec![Capability::MultiPrefix]);
+
+    handler.database.add_client_to_channel("#hola", "nickname");
+    handler.database.add_channel_operator("#hola", "nickname");
+    handler
+        .database
+        .set_channel_flag("#hola", ChannelFlag::Moderated);
+    handler.database.add_channel_speaker("#hola", "nickname");
+
+    handler
+        .raw_command("NAMES", (None, vec!["#hola".to_string()], None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(":servername 353 nickname #hola :@+nickname", responses[0]);
+}
+
+#[test]
+fn names_without_multi_prefix_returns_highest_role() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_client_to_channel("#hola", "nickname");
+    handler.database.add_channel_operator("#hola", "nickname");
+    handler
+        .database
+        .set_channel_flag("#hola", ChannelFlag::Moderated);
+    handler.database.add_channel_speaker("#hola", "nickname");
+
+    handler
+        .raw_command("NAMES", (None, vec!["#hola".to_string()], None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(":servername 353 nickname #hola :@nickname", responses[0]);
+}
+
+#[test]
+fn away_is_sent_to_channel_peers_with_away_notify() {
+    let mut handler = dummy_client_handler();
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_local_client(dummy_client("nick3"));
+    handler
+        .database
+        .set_client_capabilities("nick2", vec![Capability::AwayNotify]);
+    handler.database.add_client_to_channel("#hola", "nickname");
+    handler.database.add_client_to_channel("#hola", "nick2");
+    handler.database.add_client_to_channel("#hola", "nick3");
+    handler.database.add_client_to_channel("#chau", "nickname");
+    handler.database.add_client_to_channel("#chau", "nick2");
+
+    let trailing = Some("lunch".to_string());
+    handler
+        .raw_command("AWAY", (None, vec![], trailing))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname AWAY :lunch\r\n",
+        handler
+            .database
+            .flushed_local_stream("nick2")
+            .read_wbuf_to_string()
+    );
+    assert_eq!(
+        "",
+        handler
+            .database
+            .flushed_local_stream("nick3")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn server_time_tags_messages_delivered_to_client() {
+    let mut handler = dummy_client_handler();
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler
+        .database
+        .set_client_capabilities("nick2", vec![Capability::ServerTime]);
+
+    let parameters = vec!["nick2".to_string()];
+    let trailing = Some("hello".to_string());
+    handler
+        .raw_command("PRIVMSG", (None, parameters, trailing))
+        .unwrap();
+
+    let message = handler
+        .database
+        .flushed_local_stream("nick2")
+        .read_wbuf_to_string();
+
+    assert!(message.starts_with("@time="));
+    assert!(message.ends_with("Z :nickname PRIVMSG nick2 :hello\r\n"));
+}
+
+#[test]
+fn server_time_is_not_sent_to_servers() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .set_client_capabilities("nickname", vec![Capability::ServerTime]);
+
+    let trailing = Some("lunch".to_string());
+    handler
+        .raw_command("AWAY", (None, vec![], trailing))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname AWAY :lunch\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+}
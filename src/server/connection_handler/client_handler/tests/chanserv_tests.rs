@@ -0,0 +1,199 @@
+use std::{env, fs, path::PathBuf, process};
+
+use super::*;
+
+fn channel_store_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{name}-{}.db", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn chanserv_request(handler: &mut ClientHandler<MockTcpStream>, text: &str) {
+    let parameters = vec!["ChanServ".to_string()];
+    let trailing = Some(text.to_string());
+    handler
+        .raw_command("PRIVMSG", (None, parameters, trailing))
+        .unwrap();
+}
+
+#[test]
+fn register_fails_without_channel_store() {
+    let mut handler = dummy_client_handler();
+
+    handler
+        .raw_command("JOIN", (None, vec!["#channel".to_string()], None))
+        .unwrap();
+    handler.stream.clear();
+
+    chanserv_request(&mut handler, "REGISTER #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :Channel registration is disabled\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_channel_founder("#channel"));
+}
+
+#[test]
+fn channel_operator_can_register_channel() {
+    let path = channel_store_path("registered-by-operator");
+    let mut handler = dummy_client_handler();
+    handler.database.set_channel_store(path.clone());
+    handler
+        .database
+        .set_client_account("nickname", Some("account".to_string()));
+
+    handler
+        .raw_command("JOIN", (None, vec!["#channel".to_string()], None))
+        .unwrap();
+    handler.stream.clear();
+
+    chanserv_request(&mut handler, "REGISTER #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :#channel is now registered to account\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        Some("account".to_string()),
+        handler.database.get_channel_founder("#channel")
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn register_fails_without_being_identified() {
+    let path = channel_store_path("registered-without-account");
+    let mut handler = dummy_client_handler();
+    handler.database.set_channel_store(path.clone());
+
+    handler
+        .raw_command("JOIN", (None, vec!["#channel".to_string()], None))
+        .unwrap();
+    handler.stream.clear();
+
+    chanserv_request(&mut handler, "REGISTER #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :You must identify to NickServ first\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_channel_founder("#channel"));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn register_fails_without_being_channel_operator() {
+    let path = channel_store_path("registered-by-member");
+    let mut handler = dummy_client_handler();
+    handler.database.set_channel_store(path.clone());
+
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_client_to_channel("#channel", "nick2");
+    handler
+        .raw_command("JOIN", (None, vec!["#channel".to_string()], None))
+        .unwrap();
+    handler.stream.clear();
+
+    chanserv_request(&mut handler, "REGISTER #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :You are not an operator of #channel\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_channel_founder("#channel"));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn founder_regains_operator_on_join() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_client_to_channel("#channel", "nick2");
+    handler
+        .database
+        .set_channel_founder("#channel", Some("account".to_string()));
+    handler
+        .database
+        .set_client_account("nickname", Some("account".to_string()));
+
+    handler
+        .raw_command("JOIN", (None, vec!["#channel".to_string()], None))
+        .unwrap();
+
+    assert!(handler.database.is_channel_operator("#channel", "nickname"));
+}
+
+#[test]
+fn only_founder_can_drop_channel() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_client_to_channel("#channel", "nick2");
+    handler
+        .database
+        .set_channel_founder("#channel", Some("nick2".to_string()));
+
+    chanserv_request(&mut handler, "DROP #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :You are not the founder of #channel\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        Some("nick2".to_string()),
+        handler.database.get_channel_founder("#channel")
+    );
+}
+
+#[test]
+fn client_identified_to_founder_account_can_drop_channel() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_client_to_channel("#channel", "nick2");
+    handler
+        .database
+        .set_channel_founder("#channel", Some("nick2".to_string()));
+    handler
+        .database
+        .set_client_account("nickname", Some("nick2".to_string()));
+
+    chanserv_request(&mut handler, "DROP #channel");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :#channel has been dropped\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_channel_founder("#channel"));
+}
+
+#[test]
+fn unknown_request_is_answered_with_help() {
+    let mut handler = dummy_client_handler();
+
+    chanserv_request(&mut handler, "hello");
+
+    assert_eq!(
+        ":ChanServ NOTICE nickname :Commands: REGISTER <channel>, DROP <channel>, INFO <channel>\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn chanserv_nickname_is_reserved() {
+    let mut handler = dummy_client_handler();
+
+    handler
+        .raw_command("NICK", (None, vec!["chanserv".to_string()], None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 433 nickname chanserv :Nickname is already in use\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{client_handler::tests::dummy_client_handler, ConnectionHandlerCommands},
+    connection_handler::client_handler::tests::dummy_client_handler,
     consts::channel_flag::ChannelFlag,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
@@ -9,10 +10,12 @@ fn ctcp_fails_with_empty_params() {
     let mut handler = dummy_client_handler();
     let parameters = vec![];
 
-    handler.ctcp_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 CTCP :Not enough parameters\r\n",
+        ":servername 461 nickname CTCP :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -23,11 +26,16 @@ fn ctcp_fails_with_invalid_target() {
 
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("401 nick1 :No such nick/channel", responses[0]);
+    assert_eq!(
+        ":servername 401 nickname nick1 :No such nick/channel",
+        responses[0]
+    );
 }
 
 #[test]
@@ -36,11 +44,16 @@ fn ctcp_fails_with_invalid_channel_target() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("401 #channel :No such nick/channel", responses[0]);
+    assert_eq!(
+        ":servername 401 nickname #channel :No such nick/channel",
+        responses[0]
+    );
 }
 
 #[test]
@@ -58,18 +71,22 @@ fn ctcp_fails_with_not_on_channel_with_flag_n() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -77,8 +94,7 @@ fn ctcp_fails_with_not_on_channel_with_flag_n() {
         "",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -99,18 +115,22 @@ fn ctcp_fails_if_not_speaker_on_channel_with_flag_m() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -122,10 +142,12 @@ fn ctcp_fails_with_no_text() {
 
     let parameters = vec!["nick1".to_string()];
     let trailing = None;
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
-        "412 :No text to send\r\n",
+        ":servername 412 nickname :No text to send\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -138,14 +160,15 @@ fn ctcp_works_with_valid_target_client() {
 
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
         ":nickname PRIVMSG nick1 :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -164,7 +187,9 @@ fn ctcp_works_with_valid_target_channel() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -174,8 +199,7 @@ fn ctcp_works_with_valid_target_channel() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -183,8 +207,7 @@ fn ctcp_works_with_valid_target_channel() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -200,10 +223,12 @@ fn privmsg_with_away_client_returns_away_message() {
 
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
-        "301 nick1 :away message!\r\n",
+        ":servername 301 nickname nick1 :away message!\r\n",
         handler.stream.read_wbuf_to_string(),
     );
 }
@@ -226,7 +251,9 @@ fn privmsg_works_on_channel_with_flag_n() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -236,8 +263,7 @@ fn privmsg_works_on_channel_with_flag_n() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -245,8 +271,7 @@ fn privmsg_works_on_channel_with_flag_n() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -268,7 +293,9 @@ fn privmsg_works_on_channel_with_flag_m() {
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -278,8 +305,7 @@ fn privmsg_works_on_channel_with_flag_m() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -311,22 +337,22 @@ fn privmsg_to_distributed_channel_is_only_relayed_to_each_neccesary_server_once(
 
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
-    handler.ctcp_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("CTCP", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
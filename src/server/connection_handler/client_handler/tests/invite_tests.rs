@@ -8,18 +8,22 @@ fn invite_fails_with_less_than_two_parameters() {
 
     let parameters = vec![];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 INVITE :Not enough parameters\r\n",
+        ":servername 461 nickname INVITE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nick2".to_string()];
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 INVITE :Not enough parameters\r\n",
+        ":servername 461 nickname INVITE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -30,10 +34,12 @@ fn invite_fails_with_invalid_nickname() {
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "401 nick2 :No such nick/channel\r\n",
+        ":servername 401 nickname nick2 :No such nick/channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -48,10 +54,12 @@ fn invite_fails_with_user_already_on_channel() {
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "443 nick2 #hola :Is already on channel\r\n",
+        ":servername 443 nickname nick2 #hola :Is already on channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -63,10 +71,12 @@ fn invite_fails_with_no_such_channel() {
     handler.database.add_local_client(dummy_client("nick2"));
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "403 #hola :No such channel\r\n",
+        ":servername 403 nickname #hola :No such channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -80,10 +90,12 @@ fn can_invite_one_user() {
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "341 #hola nickname\r\n",
+        ":servername 341 nickname #hola nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -91,8 +103,7 @@ fn can_invite_one_user() {
         ":nickname INVITE nick2 #hola\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -113,10 +124,12 @@ fn invite_fails_with_not_channop_on_moderated_channel() {
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "482 #hola :You're not channel operator\r\n",
+        ":servername 482 nickname #hola :You're not channel operator\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -124,8 +137,7 @@ fn invite_fails_with_not_channop_on_moderated_channel() {
         "",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -144,10 +156,12 @@ fn can_invite_user_in_moderated_channel_if_channop() {
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
 
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "341 #hola nickname\r\n",
+        ":servername 341 nickname #hola nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -155,8 +169,7 @@ fn can_invite_user_in_moderated_channel_if_channop() {
         ":nickname INVITE nick2 #hola\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -174,14 +187,15 @@ fn invite_is_relayed_to_neccesary_server() {
     handler.database.add_client_to_channel("#hola", "nickname");
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname INVITE nick2 #hola\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
@@ -197,7 +211,9 @@ fn invite_is_registered_in_channel() {
     handler.database.add_client_to_channel("#hola", "nickname");
 
     let parameters = vec!["nick2".to_string(), "#hola".to_string()];
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert!(handler.database.channel_has_invite("#hola", "nick2"));
 }
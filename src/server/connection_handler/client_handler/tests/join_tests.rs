@@ -1,4 +1,5 @@
 use crate::server::testing::dummy_server;
+use crate::server::Limits;
 
 use super::*;
 
@@ -8,10 +9,12 @@ fn join_fails_with_empty_params() {
     let parameters = vec![];
 
     let channels: Vec<String> = vec![];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 JOIN :Not enough parameters\r\n",
+        ":servername 461 nickname JOIN :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert_eq!(handler.database.get_all_channels(), channels);
@@ -23,12 +26,20 @@ fn join_fails_with_invalid_channel_name() {
 
     let parameters = vec!["hola,#ho'la".to_string()];
 
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("403 hola :No such channel", responses[0]);
-    assert_eq!("403 #ho'la :No such channel", responses[1]);
+    assert_eq!(
+        ":servername 403 nickname hola :No such channel",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 403 nickname #ho'la :No such channel",
+        responses[1]
+    );
 }
 
 #[test]
@@ -37,33 +48,61 @@ fn join_fails_with_user_in_too_many_channels() {
 
     let parameters =
         vec!["#uno,#dos,#tres,&cuatro,&cinco,&seis,#siete,#ocho,#nueve,&diez".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     handler.stream.clear();
 
     let parameters = vec!["#once".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "405 #once :You have joined too many channels\r\n",
+        ":servername 405 nickname #once :You have joined too many channels\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
 
+#[test]
+fn channel_limit_is_configurable() {
+    let mut handler = dummy_client_handler();
+    let limits = Limits {
+        max_channels: 1,
+        ..Default::default()
+    };
+    handler.database.set_limits(limits);
+
+    let parameters = vec!["#uno,#dos".to_string()];
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 405 nickname #dos :You have joined too many channels",
+        handler.stream.get_responses().last().unwrap()
+    );
+}
+
 #[test]
 fn join_fails_if_user_already_in_channel() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     handler.stream.clear();
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "443 nickname #hola :Is already on channel\r\n",
+        ":servername 443 nickname nickname #hola :Is already on channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -76,14 +115,19 @@ fn can_join_one_channel() {
 
     assert!(handler.database.get_all_channels().is_empty());
 
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let channels = vec!["#channel".to_string()];
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #channel :No topic is set", responses[0]);
-    assert_eq!("353 #channel :nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #channel :No topic is set",
+        responses[0]
+    );
+    assert_eq!(":servername 353 nickname #channel :nickname", responses[1]);
     assert_eq!(
         handler
             .database
@@ -98,16 +142,27 @@ fn can_join_multiple_channels() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["#channel1,#channel2,#channel3".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #channel1 :No topic is set", responses[0]);
-    assert_eq!("353 #channel1 :nickname", responses[1]);
-    assert_eq!("331 #channel2 :No topic is set", responses[2]);
-    assert_eq!("353 #channel2 :nickname", responses[3]);
-    assert_eq!("331 #channel3 :No topic is set", responses[4]);
-    assert_eq!("353 #channel3 :nickname", responses[5]);
+    assert_eq!(
+        ":servername 331 nickname #channel1 :No topic is set",
+        responses[0]
+    );
+    assert_eq!(":servername 353 nickname #channel1 :nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #channel2 :No topic is set",
+        responses[2]
+    );
+    assert_eq!(":servername 353 nickname #channel2 :nickname", responses[3]);
+    assert_eq!(
+        ":servername 331 nickname #channel3 :No topic is set",
+        responses[4]
+    );
+    assert_eq!(":servername 353 nickname #channel3 :nickname", responses[5]);
 
     let mut channels = vec![
         "#channel1".to_string(),
@@ -135,12 +190,20 @@ fn can_join_existing_channel() {
 
     let channels = vec!["#channel".to_string()];
 
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #channel :No topic is set", responses[0]);
-    assert_eq!("353 #channel :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #channel :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #channel :nick2 nickname",
+        responses[1]
+    );
 
     assert_eq!(
         handler
@@ -169,12 +232,20 @@ fn can_join_channel_with_topic() {
 
     let channels = vec!["#channel".to_string()];
 
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("332 #channel :topic for channel", responses[0]);
-    assert_eq!("353 #channel :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 332 nickname #channel :topic for channel",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #channel :nick2 nickname",
+        responses[1]
+    );
 
     assert_eq!(
         handler
@@ -189,6 +260,28 @@ fn can_join_channel_with_topic() {
     );
 }
 
+#[test]
+fn topic_too_long_for_reply_is_cut_without_closing_connection() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler.database.add_client_to_channel("#channel", "nick2");
+    handler
+        .database
+        .set_channel_topic("#channel", &"a".repeat(600));
+
+    let parameters = vec!["#channel".to_string()];
+    let keep_open = handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert!(keep_open);
+    assert_eq!(510, responses[0].len());
+    assert!(responses[0].starts_with(":servername 332 nickname #channel :aaa"));
+}
+
 #[test]
 fn join_notifies_users_in_channel() {
     let mut handler = dummy_client_handler();
@@ -197,14 +290,15 @@ fn join_notifies_users_in_channel() {
     handler.database.add_client_to_channel("#channel", "nick2");
 
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname JOIN #channel\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -221,10 +315,12 @@ fn join_fails_with_incorrect_key() {
         .set_channel_key("#hola", Some("key".to_string()));
 
     let parameters = vec!["#hola".to_string(), "wrong_key".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "475 #hola :Cannot join channel (+k)\r\n",
+        ":servername 475 nickname #hola :Cannot join channel (+k)\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -243,12 +339,20 @@ fn can_join_channel_with_key() {
         .set_channel_key("#hola", Some("key".to_string()));
 
     let parameters = vec!["#hola".to_string(), "key".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #hola :No topic is set", responses[0]);
-    assert_eq!("353 #hola :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #hola :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #hola :nick2 nickname",
+        responses[1]
+    );
 
     assert!(handler.database.is_client_in_channel("#hola", "nickname"))
 }
@@ -274,16 +378,33 @@ fn can_join_multiple_channels_with_keys() {
         "#channel1,#channel2,#channel3".to_string(),
         "key1,key2".to_string(),
     ];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #channel1 :No topic is set", responses[0]);
-    assert_eq!("353 #channel1 :nick2 nickname", responses[1]);
-    assert_eq!("331 #channel2 :No topic is set", responses[2]);
-    assert_eq!("353 #channel2 :nick3 nickname", responses[3]);
-    assert_eq!("331 #channel3 :No topic is set", responses[4]);
-    assert_eq!("353 #channel3 :nickname", responses[5]);
+    assert_eq!(
+        ":servername 331 nickname #channel1 :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #channel1 :nick2 nickname",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 331 nickname #channel2 :No topic is set",
+        responses[2]
+    );
+    assert_eq!(
+        ":servername 353 nickname #channel2 :nick3 nickname",
+        responses[3]
+    );
+    assert_eq!(
+        ":servername 331 nickname #channel3 :No topic is set",
+        responses[4]
+    );
+    assert_eq!(":servername 353 nickname #channel3 :nickname", responses[5]);
 
     assert!(handler
         .database
@@ -306,10 +427,12 @@ fn join_fails_with_user_limit_reached_on_limited_channel() {
     handler.database.set_channel_limit("#hola", Some(1));
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "471 #hola :Cannot join channel (+l)\r\n",
+        ":servername 471 nickname #hola :Cannot join channel (+l)\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -326,12 +449,20 @@ fn can_join_limited_channel_if_limit_not_reached() {
     handler.database.set_channel_limit("#hola", Some(4));
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #hola :No topic is set", responses[0]);
-    assert_eq!("353 #hola :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #hola :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #hola :nick2 nickname",
+        responses[1]
+    );
 
     assert!(handler.database.is_client_in_channel("#hola", "nickname"))
 }
@@ -346,10 +477,12 @@ fn join_fails_with_banmask() {
     handler.database.add_channel_banmask("#channel", "nickname");
 
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "474 #channel :Cannot join channel (+b)\r\n",
+        ":servername 474 nickname #channel :Cannot join channel (+b)\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -368,12 +501,20 @@ fn can_join_channel_with_banmask() {
     handler.database.add_channel_banmask("#channel", "user");
 
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #channel :No topic is set", responses[0]);
-    assert_eq!("353 #channel :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #channel :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #channel :nick2 nickname",
+        responses[1]
+    );
 
     assert!(handler
         .database
@@ -388,14 +529,15 @@ fn joins_notifies_user_in_channel() {
     handler.database.add_client_to_channel("#channel", "nick2");
 
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname JOIN #channel\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -412,22 +554,22 @@ fn distributed_channels_joins_are_relayed_to_all_servers() {
         .add_immediate_server(dummy_server("servername2"));
 
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname JOIN #channel\r\n:nickname MODE #channel +o nickname\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname JOIN #channel\r\n:nickname MODE #channel +o nickname\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -444,10 +586,12 @@ fn join_fails_with_invite_only_channel() {
         .set_channel_flag("#hola", ChannelFlag::InviteOnly);
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "473 #hola :Cannot join channel (+i)\r\n",
+        ":servername 473 nickname #hola :Cannot join channel (+i)\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -468,12 +612,20 @@ fn can_join_invite_only_channel_with_invite() {
     handler.database.add_channel_invite("#hola", "nickname");
 
     let parameters = vec!["#hola".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #hola :No topic is set", responses[0]);
-    assert_eq!("353 #hola :nick2 nickname", responses[1]);
+    assert_eq!(
+        ":servername 331 nickname #hola :No topic is set",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 353 nickname #hola :nick2 nickname",
+        responses[1]
+    );
 
     assert!(handler.database.is_client_in_channel("#hola", "nickname"))
 }
@@ -6,10 +6,10 @@ use super::*;
 fn kick_fails_with_empty_params() {
     let mut handler = dummy_client_handler();
 
-    handler.kick_command((None, vec![], None)).unwrap();
+    handler.raw_command("KICK", (None, vec![], None)).unwrap();
 
     assert_eq!(
-        "461 KICK :Not enough parameters\r\n",
+        ":servername 461 nickname KICK :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -25,10 +25,12 @@ fn kick_fails_when_not_on_channel() {
     handler.database.add_client_to_channel("#channel1", "nick1");
 
     let parameters = vec!["#channel1".to_string(), "nick1".to_string()];
-    handler.kick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #channel1 :You're not on that channel\r\n",
+        ":servername 442 nickname #channel1 :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -44,10 +46,12 @@ fn kick_fails_when_not_operator() {
         .add_client_to_channel("#channel1", "nickname");
 
     let parameters = vec!["#channel1".to_string(), "nickname".to_string()];
-    handler.kick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "482 #channel1 :You're not channel operator\r\n",
+        ":servername 482 nickname #channel1 :You're not channel operator\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -57,10 +61,12 @@ fn kick_fails_when_channel_does_not_exist() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["#channel1".to_string(), "nickname".to_string()];
-    handler.kick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "403 #channel1 :No such channel\r\n",
+        ":servername 403 nickname #channel1 :No such channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -79,7 +85,9 @@ fn can_kick_user_from_channel() {
     handler.database.add_client_to_channel("#channel", "nick2");
 
     let parameters = vec!["#channel".to_string(), "nick2".to_string()];
-    handler.kick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.database.is_client_in_channel("#channel", "nick2"));
 
@@ -87,8 +95,7 @@ fn can_kick_user_from_channel() {
         ":nickname KICK #channel nick2\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -108,7 +115,9 @@ fn can_kick_user_from_channel_with_comment() {
 
     let parameters = vec!["#channel".to_string(), "nick2".to_string()];
     let trailing = Some("no lollygagging".to_string());
-    handler.kick_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, trailing))
+        .unwrap();
 
     assert!(!handler.database.is_client_in_channel("#channel", "nick2"));
 
@@ -116,8 +125,7 @@ fn can_kick_user_from_channel_with_comment() {
         ":nickname KICK #channel nick2 :no lollygagging\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -139,7 +147,9 @@ fn can_kick_multiple_user() {
 
     let parameters = vec!["#channel,#channel".to_string(), "nick2,nick3".to_string()];
     let trailing = Some("no lollygagging".to_string());
-    handler.kick_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, trailing))
+        .unwrap();
 
     assert!(!handler.database.is_client_in_channel("#channel", "nick2"));
     assert!(!handler.database.is_client_in_channel("#channel", "nick3"));
@@ -162,12 +172,13 @@ fn kick_notifies_users_in_channel() {
 
     let parameters = vec!["#channel".to_string(), "nick2".to_string()];
     let trailing = Some("no lollygagging".to_string());
-    handler.kick_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, trailing))
+        .unwrap();
 
     let responses = handler
         .database
-        .get_local_stream("nick3")
-        .unwrap()
+        .flushed_local_stream("nick3")
         .get_responses();
 
     assert_eq!(
@@ -203,22 +214,20 @@ fn on_distributed_channels_kick_is_relayed_to_all_servers() {
 
     let params = vec!["#channel".to_string(), "nickname1".to_string()];
     let trail = Some("message".to_string());
-    handler.kick_command((None, params, trail)).unwrap();
+    handler.raw_command("KICK", (None, params, trail)).unwrap();
 
     assert_eq!(
         ":nickname KICK #channel nickname1 :message\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname KICK #channel nickname1 :message\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -0,0 +1,203 @@
+use crate::server::consts::user_flag::UserFlag;
+use crate::server::testing::{dummy_external_client, dummy_operator, dummy_server, flushed_stream};
+
+use super::*;
+
+fn operator_client_handler() -> ClientHandler<MockTcpStream> {
+    let handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
+    handler.database.set_server_operator("nickname", "admin");
+    handler
+}
+
+#[test]
+fn kill_fails_with_insufficient_parameters() {
+    let mut handler = operator_client_handler();
+
+    handler.raw_command("KILL", (None, vec![], None)).unwrap();
+    let parameters = vec!["nickname2".to_string()];
+    handler
+        .raw_command("KILL", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 461 nickname KILL :Not enough parameters\r\n\
+        :servername 461 nickname KILL :Not enough parameters\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_fails_for_non_operators() {
+    let mut handler = dummy_client_handler();
+    handler.database.add_local_client(dummy_client("nickname2"));
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 481 nickname :Permission Denied- You're not an IRC operator\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(handler.database.contains_client("nickname2"));
+}
+
+#[test]
+fn kill_fails_with_unknown_nickname() {
+    let mut handler = operator_client_handler();
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 401 nickname nickname2 :No such nick/channel\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_fails_with_server_as_target() {
+    let mut handler = operator_client_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    let parameters = vec!["servername2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 483 nickname :You can't kill a server!\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_disconnects_local_client() {
+    let mut handler = operator_client_handler();
+    handler.database.add_local_client(dummy_client("nickname2"));
+    let queue = handler.database.get_local_queue("nickname2").unwrap();
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    assert!(handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap());
+
+    assert_eq!(
+        ":nickname KILL nickname2 :spam\r\n\
+        ERROR :Closing Link: nickname2 (Killed (nickname (spam)))\r\n",
+        flushed_stream(&queue).read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname2"));
+}
+
+#[test]
+fn kill_sends_quit_to_local_clients_on_shared_channels() {
+    let mut handler = operator_client_handler();
+    handler.database.add_local_client(dummy_client("nickname2"));
+    handler.database.add_local_client(dummy_client("nickname3"));
+    handler.database.add_local_client(dummy_client("nickname4"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname2");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname3");
+    handler
+        .database
+        .add_client_to_channel("#channel2", "nickname2");
+    handler
+        .database
+        .add_client_to_channel("#channel2", "nickname3");
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname2 QUIT :Killed (nickname (spam))\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname3")
+            .read_wbuf_to_string()
+    );
+    assert_eq!(
+        "",
+        handler
+            .database
+            .flushed_local_stream("nickname4")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_is_relayed_to_all_servers() {
+    let mut handler = operator_client_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname2", "servername2"));
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname KILL nickname2 :spam\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname2"));
+}
+
+#[test]
+fn kill_notifies_operators_receiving_server_notices() {
+    let mut handler = operator_client_handler();
+    handler.database.add_local_client(dummy_client("nickname2"));
+    handler
+        .database
+        .set_user_flag("nickname", UserFlag::ReceiveServerNotices);
+
+    let parameters = vec!["nickname2".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":servername NOTICE nickname :*** Notice -- Received KILL message for nickname2. From nickname (spam)\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_on_self_ends_connection() {
+    let mut handler = operator_client_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    let trail = Some("bye".to_string());
+    assert!(!handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap());
+
+    assert!(!handler.database.contains_client("nickname"));
+}
@@ -6,12 +6,17 @@ fn list_with_no_channels_prints_start_and_end() {
 
     let parameters = vec![];
 
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("323 :End of /LIST", responses[1]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[1]);
 }
 
 #[test]
@@ -28,7 +33,9 @@ fn list_with_no_parameters_prints_all_channels() {
     handler.database.add_local_client(dummy_client("nick2"));
     handler.database.add_client_to_channel("#canal", "nick2");
 
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -36,11 +43,17 @@ fn list_with_no_parameters_prints_all_channels() {
 
     channels.sort();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #canal :No topic set", channels[0]);
-    assert_eq!("322 #chau :No topic set", channels[1]);
-    assert_eq!("322 #hola :topic for #hola", channels[2]);
-    assert_eq!("323 :End of /LIST", responses[1]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #canal :No topic set", channels[0]);
+    assert_eq!(":servername 322 nickname #chau :No topic set", channels[1]);
+    assert_eq!(
+        ":servername 322 nickname #hola :topic for #hola",
+        channels[2]
+    );
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[1]);
 }
 
 #[test]
@@ -52,14 +65,19 @@ fn list_with_parameters_prints_requested_channels() {
     handler.database.add_client_to_channel("#hola", "nickname");
     handler.database.add_client_to_channel("#chau", "nickname");
 
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #hola :No topic set", responses[1]);
-    assert_eq!("322 #chau :No topic set", responses[2]);
-    assert_eq!("323 :End of /LIST", responses[3]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #hola :No topic set", responses[1]);
+    assert_eq!(":servername 322 nickname #chau :No topic set", responses[2]);
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[3]);
 }
 
 #[test]
@@ -71,14 +89,19 @@ fn list_ignores_invalid_channels() {
     handler.database.add_client_to_channel("#hola", "nickname");
     handler.database.add_client_to_channel("#chau", "nickname");
 
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #hola :No topic set", responses[1]);
-    assert_eq!("322 #chau :No topic set", responses[2]);
-    assert_eq!("323 :End of /LIST", responses[3]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #hola :No topic set", responses[1]);
+    assert_eq!(":servername 322 nickname #chau :No topic set", responses[2]);
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[3]);
 }
 
 #[test]
@@ -96,7 +119,9 @@ fn list_ignores_secret_channels() {
         .set_channel_flag("#secreto", ChannelFlag::Secret);
 
     let parameters = vec![];
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -104,10 +129,13 @@ fn list_ignores_secret_channels() {
 
     channels.sort();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #chau :No topic set", channels[0]);
-    assert_eq!("322 #hola :No topic set", channels[1]);
-    assert_eq!("323 :End of /LIST", responses[1]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #chau :No topic set", channels[0]);
+    assert_eq!(":servername 322 nickname #hola :No topic set", channels[1]);
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[1]);
 }
 
 #[test]
@@ -125,15 +153,20 @@ fn list_prints_priv_channels_as_priv() {
         .set_channel_flag("#privado", ChannelFlag::Private);
 
     let parameters = vec!["#hola,#privado,#chau".to_string()];
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #hola :No topic set", responses[1]);
-    assert_eq!("322 #privado Prv", responses[2]);
-    assert_eq!("322 #chau :No topic set", responses[3]);
-    assert_eq!("323 :End of /LIST", responses[4]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #hola :No topic set", responses[1]);
+    assert_eq!(":servername 322 nickname #privado Prv", responses[2]);
+    assert_eq!(":servername 322 nickname #chau :No topic set", responses[3]);
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[4]);
 }
 
 #[test]
@@ -151,7 +184,9 @@ fn list_prints_secret_channel_if_client_is_in_it() {
         .set_channel_flag("#secreto", ChannelFlag::Secret);
 
     let parameters = vec![];
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -159,11 +194,17 @@ fn list_prints_secret_channel_if_client_is_in_it() {
 
     channels.sort();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #chau :No topic set", channels[0]);
-    assert_eq!("322 #hola :No topic set", channels[1]);
-    assert_eq!("322 #secreto :No topic set", channels[2]);
-    assert_eq!("323 :End of /LIST", responses[1]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #chau :No topic set", channels[0]);
+    assert_eq!(":servername 322 nickname #hola :No topic set", channels[1]);
+    assert_eq!(
+        ":servername 322 nickname #secreto :No topic set",
+        channels[2]
+    );
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[1]);
 }
 
 #[test]
@@ -181,13 +222,21 @@ fn list_prints_private_channel_if_client_is_in_it() {
         .set_channel_flag("#privado", ChannelFlag::Private);
 
     let parameters = vec!["#hola,#privado,#chau".to_string()];
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("321 :Channel :Users Name", responses[0]);
-    assert_eq!("322 #hola :No topic set", responses[1]);
-    assert_eq!("322 #privado :No topic set", responses[2]);
-    assert_eq!("322 #chau :No topic set", responses[3]);
-    assert_eq!("323 :End of /LIST", responses[4]);
+    assert_eq!(
+        ":servername 321 nickname :Channel :Users Name",
+        responses[0]
+    );
+    assert_eq!(":servername 322 nickname #hola :No topic set", responses[1]);
+    assert_eq!(
+        ":servername 322 nickname #privado :No topic set",
+        responses[2]
+    );
+    assert_eq!(":servername 322 nickname #chau :No topic set", responses[3]);
+    assert_eq!(":servername 323 nickname :End of /LIST", responses[4]);
 }
@@ -1,21 +1,31 @@
 use super::*;
 use crate::server::consts::channel_flag::ChannelFlag;
-use crate::server::testing::{dummy_client, dummy_database, MockTcpStream};
+use crate::server::testing::{
+    dummy_client, dummy_database, HandleRawCommand, MockTcpStream, ReadQueuedOutput,
+};
 
 mod away_tests;
+mod cap_tests;
+mod chanserv_tests;
 mod ctcp_tests;
 mod invite_tests;
 mod join_tests;
 mod kick_tests;
+mod kill_tests;
 mod list_tests;
 mod mode_tests;
+mod motd_tests;
 mod names_tests;
 mod nick_tests;
+mod nickserv_tests;
 mod notice_tests;
 mod oper_tests;
 mod part_tests;
+mod ping_tests;
 mod privmsg_tests;
 mod quit_tests;
+mod rehash_tests;
+mod shutdown_tests;
 mod squit_tests;
 mod topic_tests;
 mod who_tests;
@@ -27,7 +37,7 @@ fn dummy_client_handler() -> ClientHandler<MockTcpStream> {
     let online = Arc::new(AtomicBool::new(true));
 
     let client = dummy_client(&nickname);
-    let connection = client.stream().unwrap().try_clone().unwrap();
+    let connection = client.queue().unwrap().try_clone_stream().unwrap();
 
     database.add_local_client(client);
 
@@ -7,10 +7,12 @@ fn mode_fails_with_not_enough_parameters() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec![];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -20,10 +22,12 @@ fn mode_fails_with_nonexistent_channel() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["#channel".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "403 #channel :No such channel\r\n",
+        ":servername 403 nickname #channel :No such channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -36,10 +40,12 @@ fn mode_fails_with_user_not_on_channel() {
     handler.database.add_client_to_channel("#channel", "dummy");
 
     let parameters = vec!["#channel".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #channel :You're not on that channel\r\n",
+        ":servername 442 nickname #channel :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -55,10 +61,12 @@ fn mode_fails_with_user_not_operator() {
         .add_client_to_channel("#channel", "nickname");
 
     let parameters = vec!["#channel".to_string(), "+i".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "482 #channel :You're not channel operator\r\n",
+        ":servername 482 nickname #channel :You're not channel operator\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -83,7 +91,9 @@ fn mode_adds_channop() {
         "+o".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +o nick2\r\n",
@@ -113,7 +123,9 @@ fn mode_removes_channop() {
         "-o".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -o nick2\r\n",
@@ -139,10 +151,12 @@ fn mode_fails_with_no_oper_parameter() {
     assert!(!handler.database.is_channel_operator("#channel", "nick2"));
 
     let parameters = vec!["#channel".to_string(), "+o".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_operator("#channel", "nick2"));
@@ -163,10 +177,12 @@ fn mode_fails_with_nonexistent_oper() {
         "+o".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "401 nick2 :No such nick/channel\r\n",
+        ":servername 401 nickname nick2 :No such nick/channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_operator("#channel", "nick2"));
@@ -189,10 +205,12 @@ fn mode_oper_fails_with_nick_not_on_channel() {
         "+o".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #channel :You're not on that channel\r\n",
+        ":servername 442 nickname #channel :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_operator("#channel", "nick2"));
@@ -215,7 +233,9 @@ fn mode_sets_limit_to_channel() {
         .is_none());
 
     let parameters = vec!["#channel".to_string(), "+l".to_string(), "5".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +l 5\r\n",
@@ -245,7 +265,9 @@ fn mode_unsets_channel_limit() {
     );
 
     let parameters = vec!["#channel".to_string(), "-l".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -l\r\n",
@@ -275,10 +297,12 @@ fn mode_fails_with_no_limit_parameter() {
         .is_none());
 
     let parameters = vec!["#channel".to_string(), "+l".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(handler
@@ -309,7 +333,9 @@ fn mode_sets_banmask() {
         "+b".to_string(),
         "banmask".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +b banmask\r\n",
@@ -347,7 +373,9 @@ fn mode_unsets_banmask() {
         "-b".to_string(),
         "banmask".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -b banmask\r\n",
@@ -366,7 +394,9 @@ fn mode_unsets_banmask() {
         "-b".to_string(),
         "banmask2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -396,14 +426,19 @@ fn mode_returns_ban_list_with_no_parameters() {
     handler.database.add_channel_banmask("#channel", "banmask3");
 
     let parameters = vec!["#channel".to_string(), "+b".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("367 #channel banmask1", responses[0]);
-    assert_eq!("367 #channel banmask2", responses[1]);
-    assert_eq!("367 #channel banmask3", responses[2]);
-    assert_eq!("368 #channel :End of channel ban list", responses[3]);
+    assert_eq!(":servername 367 nickname #channel banmask1", responses[0]);
+    assert_eq!(":servername 367 nickname #channel banmask2", responses[1]);
+    assert_eq!(":servername 367 nickname #channel banmask3", responses[2]);
+    assert_eq!(
+        ":servername 368 nickname #channel :End of channel ban list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -424,10 +459,12 @@ fn mode_fails_with_no_banmask_parameter() {
         .is_empty());
 
     let parameters = vec!["#channel".to_string(), "-b".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -447,7 +484,9 @@ fn mode_ignores_unknown_banmask_when_unsetting() {
         "-b".to_string(),
         "banmask".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -477,7 +516,9 @@ fn mode_adds_speaker_to_channel() {
         "+v".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +v nick2\r\n",
@@ -507,7 +548,9 @@ fn mode_removes_speakers_from_channel() {
         "-v".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -v nick2\r\n",
@@ -532,10 +575,12 @@ fn mode_fails_with_no_speaker_parameter() {
     assert!(!handler.database.is_channel_speaker("#channel", "nick2"));
 
     let parameters = vec!["#channel".to_string(), "+v".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_speaker("#channel", "nick2"));
@@ -556,10 +601,12 @@ fn mode_fails_with_nonexistent_speaker() {
         "+v".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "401 nick2 :No such nick/channel\r\n",
+        ":servername 401 nickname nick2 :No such nick/channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_speaker("#channel", "nick2"));
@@ -582,10 +629,12 @@ fn mode_speaker_fails_with_nick_not_on_channel() {
         "+v".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #channel :You're not on that channel\r\n",
+        ":servername 442 nickname #channel :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_channel_speaker("#channel", "nick2"));
@@ -608,7 +657,9 @@ fn mode_sets_channel_key() {
         .is_none());
 
     let parameters = vec!["#channel".to_string(), "+k".to_string(), "key".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +k key\r\n",
@@ -640,7 +691,9 @@ fn mode_unsets_channel_key() {
     );
 
     let parameters = vec!["#channel".to_string(), "-k".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -k\r\n",
@@ -670,10 +723,12 @@ fn mode_fails_with_no_key_parameter() {
         .is_none());
 
     let parameters = vec!["#channel".to_string(), "+k".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 MODE :Not enough parameters\r\n",
+        ":servername 461 nickname MODE :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(handler
@@ -707,10 +762,12 @@ fn mode_fails_with_key_already_set() {
         "+k".to_string(),
         "new_key".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "467 #channel :Channel key already set\r\n",
+        ":servername 467 nickname #channel :Channel key already set\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert_eq!(
@@ -734,7 +791,9 @@ fn mode_sets_and_unsets_private_flag() {
         .channel_has_flag("#channel", ChannelFlag::Private));
 
     let mut parameters = vec!["#channel".to_string(), "+p".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +p\r\n",
@@ -746,7 +805,9 @@ fn mode_sets_and_unsets_private_flag() {
 
     parameters = vec!["#channel".to_string(), "-p".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -p\r\n",
@@ -772,7 +833,9 @@ fn mode_sets_and_unsets_secret_flag() {
         .channel_has_flag("#channel", ChannelFlag::Secret));
 
     let mut parameters = vec!["#channel".to_string(), "+s".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +s\r\n",
@@ -784,7 +847,9 @@ fn mode_sets_and_unsets_secret_flag() {
 
     parameters = vec!["#channel".to_string(), "-s".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -s\r\n",
@@ -810,7 +875,9 @@ fn mode_sets_and_unsets_invite_only_flag() {
         .channel_has_flag("#channel", ChannelFlag::InviteOnly));
 
     let mut parameters = vec!["#channel".to_string(), "+i".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +i\r\n",
@@ -822,7 +889,9 @@ fn mode_sets_and_unsets_invite_only_flag() {
 
     parameters = vec!["#channel".to_string(), "-i".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -i\r\n",
@@ -848,7 +917,9 @@ fn mode_sets_and_unsets_topic_flag() {
         .channel_has_flag("#channel", ChannelFlag::TopicByOperatorOnly));
 
     let mut parameters = vec!["#channel".to_string(), "+t".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +t\r\n",
@@ -860,7 +931,9 @@ fn mode_sets_and_unsets_topic_flag() {
 
     parameters = vec!["#channel".to_string(), "-t".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -t\r\n",
@@ -885,7 +958,9 @@ fn mode_sets_and_unsets_no_outside_messages_flag() {
         .channel_has_flag("#channel", ChannelFlag::NoOutsideMessages));
 
     let mut parameters = vec!["#channel".to_string(), "+n".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +n\r\n",
@@ -897,7 +972,9 @@ fn mode_sets_and_unsets_no_outside_messages_flag() {
 
     parameters = vec!["#channel".to_string(), "-n".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -n\r\n",
@@ -923,7 +1000,9 @@ fn mode_sets_and_unsets_moderated_flag() {
         .channel_has_flag("#channel", ChannelFlag::Moderated));
 
     let mut parameters = vec!["#channel".to_string(), "+m".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel +m\r\n",
@@ -935,7 +1014,9 @@ fn mode_sets_and_unsets_moderated_flag() {
 
     parameters = vec!["#channel".to_string(), "-m".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE #channel -m\r\n",
@@ -957,10 +1038,12 @@ fn mode_fails_when_setting_unknown_mode() {
         .database
         .add_channel_operator("#channel", "nickname");
     let parameters = vec!["#channel".to_string(), "+w".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "472 w :Is unknown mode char to me\r\n",
+        ":servername 472 nickname w :Is unknown mode char to me\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -976,10 +1059,12 @@ fn mode_fails_when_unsetting_unknown_mode() {
         .database
         .add_channel_operator("#channel", "nickname");
     let parameters = vec!["#channel".to_string(), "-w".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "472 w :Is unknown mode char to me\r\n",
+        ":servername 472 nickname w :Is unknown mode char to me\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -1010,7 +1095,9 @@ fn mode_sets_multiple_flags() {
         "+ois".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -1052,7 +1139,9 @@ fn mode_unsets_multiple_flags() {
         "-ois".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -1094,7 +1183,9 @@ fn mode_sets_and_unsets_multiple_flags() {
         "-os+p-m+i".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -1141,11 +1232,16 @@ fn mode_sets_and_unsets_multiple_valid_flags() {
         "-os+p-m+i".to_string(),
         "nick2".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("401 nick2 :No such nick/channel", responses[0]);
+    assert_eq!(
+        ":servername 401 nickname nick2 :No such nick/channel",
+        responses[0]
+    );
     assert_eq!(":nickname MODE #channel -s", responses[1]);
     assert_eq!(":nickname MODE #channel +p", responses[2]);
     assert_eq!(":nickname MODE #channel -m", responses[3]);
@@ -1184,13 +1280,15 @@ fn mode_with_no_parameters_returns_channel_mode() {
 
     let parameters = vec!["#channel".to_string()];
 
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("324 #channel m", responses[0]);
-    assert_eq!("324 #channel k key", responses[1]);
-    assert_eq!("324 #channel o nickname", responses[2]);
+    assert_eq!(":servername 324 nickname #channel m", responses[0]);
+    assert_eq!(":servername 324 nickname #channel k key", responses[1]);
+    assert_eq!(":servername 324 nickname #channel o nickname", responses[2]);
 }
 
 #[test]
@@ -1219,7 +1317,9 @@ fn mode_works_with_multiples_arguments() {
         "banmask".to_string(),
         "32".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -1266,7 +1366,9 @@ fn mode_works_with_multiples_arguments_in_disorder() {
         "nickname2".to_string(),
         "32".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
@@ -1292,10 +1394,12 @@ fn mode_fails_with_users_dont_match_if_target_is_not_not_own_nickname() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname2".to_string(), "+i".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "502 :Cant change mode for other users\r\n",
+        ":servername 502 nickname :Cant change mode for other users\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -1308,10 +1412,12 @@ fn mode_fails_when_setting_unknown_user_mode() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname".to_string(), "+a".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "501 :Unknown MODE flag\r\n",
+        ":servername 501 nickname :Unknown MODE flag\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -1321,7 +1427,9 @@ fn mode_sets_user_flag_invisible() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname".to_string(), "+i".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(info.flags.contains_key(&UserFlag::Invisible));
@@ -1336,7 +1444,9 @@ fn mode_unsets_user_flag_invisible() {
         .set_user_flag("nickname", UserFlag::Invisible);
 
     let parameters = vec!["nickname".to_string(), "-i".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(!info.flags.contains_key(&UserFlag::Invisible));
@@ -1347,7 +1457,9 @@ fn mode_cannot_set_oper_flag_to_self() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname".to_string(), "+o".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(!info.flags.contains_key(&UserFlag::Operator));
@@ -1358,13 +1470,15 @@ fn mode_cannot_set_oper_flag_to_self() {
 fn mode_can_unset_oper_flag() {
     let mut handler = dummy_client_handler();
 
-    handler.database.set_server_operator("nickname");
+    handler.database.set_server_operator("nickname", "admin");
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(info.flags.contains_key(&UserFlag::Operator));
     assert!(handler.database.is_server_operator("nickname"));
 
     let parameters = vec!["nickname".to_string(), "-o".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 }
 
 #[test]
@@ -1372,7 +1486,9 @@ fn mode_sets_user_flag_receives_server_notices() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname".to_string(), "+s".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(info.flags.contains_key(&UserFlag::ReceiveServerNotices));
@@ -1387,7 +1503,9 @@ fn mode_unsets_user_flag_receives_server_notices() {
         .set_user_flag("nickname", UserFlag::ReceiveServerNotices);
 
     let parameters = vec!["nickname".to_string(), "-s".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(!info.flags.contains_key(&UserFlag::ReceiveServerNotices));
@@ -1398,7 +1516,9 @@ fn mode_sets_user_flag_receives_wallops() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nickname".to_string(), "+w".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(info.flags.contains_key(&UserFlag::ReceivesWallops));
@@ -1413,7 +1533,9 @@ fn mode_unsets_user_flag_receives_wallops() {
         .set_user_flag("nickname", UserFlag::ReceivesWallops);
 
     let parameters = vec!["nickname".to_string(), "-w".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let info = handler.database.get_client_info("nickname").unwrap();
     assert!(!info.flags.contains_key(&UserFlag::ReceivesWallops));
@@ -1437,17 +1559,19 @@ fn mode_without_parameters_returns_umode_response() {
         .set_user_flag("nickname", UserFlag::Operator);
 
     let parameters = vec!["nickname".to_string()];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let mut response: Vec<String> = handler.stream.get_responses()[0]
         .split(' ')
         .map(|s| s.to_string())
         .collect();
-    let mut mode_string: Vec<char> = response[1].chars().collect();
+    let mut mode_string: Vec<char> = response[3].chars().collect();
     mode_string.sort();
-    response[1] = mode_string.iter().collect();
+    response[3] = mode_string.iter().collect();
 
-    let response = [response[0].clone(), response[1].clone()].join(" ");
+    let response = response.join(" ");
 
-    assert_eq!("221 iosw", response);
+    assert_eq!(":servername 221 nickname iosw", response);
 }
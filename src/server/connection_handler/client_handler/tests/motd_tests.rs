@@ -0,0 +1,51 @@
+use crate::server::testing::dummy_motd_file;
+use crate::server::testing::HandleRawCommand;
+
+use super::dummy_client_handler;
+
+#[test]
+fn motd_without_file_returns_no_motd() {
+    let mut handler = dummy_client_handler();
+
+    handler.raw_command("MOTD", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 422 nickname :MOTD File is missing\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn motd_with_unreadable_file_returns_no_motd() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_motd_file("this/file/does/not/exist.motd".into());
+
+    handler.raw_command("MOTD", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 422 nickname :MOTD File is missing\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn motd_returns_every_line_of_file() {
+    let mut handler = dummy_client_handler();
+    let path = dummy_motd_file("motd_returns_every_line_of_file", "welcome\n\nhave fun");
+    handler.database.set_motd_file(path);
+
+    handler.raw_command("MOTD", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        vec![
+            ":servername 375 nickname :- servername Message of the day -",
+            ":servername 372 nickname :- welcome",
+            ":servername 372 nickname :- ",
+            ":servername 372 nickname :- have fun",
+            ":servername 376 nickname :End of /MOTD command",
+        ],
+        handler.stream.get_responses()
+    )
+}
@@ -6,7 +6,9 @@ fn names_with_no_valid_channels_does_not_return() {
 
     let parameters = vec!["no_channel".to_string()];
 
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string())
 }
@@ -22,7 +24,9 @@ fn names_with_no_parameters_prints_all_channels() {
     handler.database.add_client_to_channel("#canal", "nick2");
 
     let parameters = vec![];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -30,10 +34,13 @@ fn names_with_no_parameters_prints_all_channels() {
 
     channels.sort();
 
-    assert_eq!("353 #canal :nick2", channels[0]);
-    assert_eq!("353 #chau :nickname", channels[1]);
-    assert_eq!("353 #hola :nickname", channels[2]);
-    assert_eq!("366 :End of /NAMES list", responses[0]);
+    assert_eq!(":servername 353 nickname #canal :nick2", channels[0]);
+    assert_eq!(":servername 353 nickname #chau :nickname", channels[1]);
+    assert_eq!(":servername 353 nickname #hola :nickname", channels[2]);
+    assert_eq!(
+        ":servername 366 nickname * :End of /NAMES list",
+        responses[0]
+    );
 }
 
 #[test]
@@ -44,14 +51,22 @@ fn names_with_parameters_prints_requested_channels() {
     handler.database.add_client_to_channel("#chau", "nickname");
 
     let parameters = vec!["#hola,#chau".to_string()];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #chau :nickname", responses[2]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[3]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #chau :nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -63,14 +78,22 @@ fn names_ignores_invalid_channels() {
     handler.database.add_client_to_channel("#hola", "nickname");
     handler.database.add_client_to_channel("#chau", "nickname");
 
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #chau :nickname", responses[2]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[3]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #chau :nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -88,14 +111,22 @@ fn name_ignores_secret_channels() {
         .set_channel_flag("#secreto", ChannelFlag::Secret);
 
     let parameters = vec!["#hola,#secreto,#chau".to_string()];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #chau :nickname", responses[2]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[3]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #chau :nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -115,14 +146,22 @@ fn name_ignores_private_channels() {
         .set_channel_flag("#privado", ChannelFlag::Private);
 
     let parameters = vec!["#hola,#privado,#chau".to_string()];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :@nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #chau :@nickname", responses[2]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[3]);
+    assert_eq!(":servername 353 nickname #hola :@nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #chau :@nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -140,16 +179,27 @@ fn name_prints_secret_channel_if_client_is_in_it() {
         .set_channel_flag("#secreto", ChannelFlag::Secret);
 
     let parameters = vec!["#hola,#secreto,#chau".to_string()];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #secreto :nickname", responses[2]);
-    assert_eq!("366 #secreto :End of /NAMES list", responses[3]);
-    assert_eq!("353 #chau :nickname", responses[4]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[5]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #secreto :nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #secreto :End of /NAMES list",
+        responses[3]
+    );
+    assert_eq!(":servername 353 nickname #chau :nickname", responses[4]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[5]
+    );
 }
 
 #[test]
@@ -169,16 +219,27 @@ fn name_prints_private_channel_if_client_is_in_it() {
         .set_channel_flag("#privado", ChannelFlag::Private);
 
     let parameters = vec!["#hola,#privado,#chau".to_string()];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :@nickname", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
-    assert_eq!("353 #privado :nickname", responses[2]);
-    assert_eq!("366 #privado :End of /NAMES list", responses[3]);
-    assert_eq!("353 #chau :nickname", responses[4]);
-    assert_eq!("366 #chau :End of /NAMES list", responses[5]);
+    assert_eq!(":servername 353 nickname #hola :@nickname", responses[0]);
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
+    assert_eq!(":servername 353 nickname #privado :nickname", responses[2]);
+    assert_eq!(
+        ":servername 366 nickname #privado :End of /NAMES list",
+        responses[3]
+    );
+    assert_eq!(":servername 353 nickname #chau :nickname", responses[4]);
+    assert_eq!(
+        ":servername 366 nickname #chau :End of /NAMES list",
+        responses[5]
+    );
 }
 
 #[test]
@@ -188,13 +249,16 @@ fn names_returns_clients_in_no_channels_as_being_on_wildcard_channel() {
     handler.database.add_local_client(dummy_client("nick2"));
     handler.database.add_client_to_channel("#hola", "nickname");
 
-    handler.names_command((None, vec![], None)).unwrap();
+    handler.raw_command("NAMES", (None, vec![], None)).unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("353 * :nick2", responses[1]);
-    assert_eq!("366 :End of /NAMES list", responses[2]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(":servername 353 nickname * :nick2", responses[1]);
+    assert_eq!(
+        ":servername 366 nickname * :End of /NAMES list",
+        responses[2]
+    );
 }
 
 #[test]
@@ -209,13 +273,16 @@ fn names_returns_clients_in_not_visible_private_channel_as_being_on_wildcard_cha
         .database
         .set_channel_flag("#hola2", ChannelFlag::Private);
 
-    handler.names_command((None, vec![], None)).unwrap();
+    handler.raw_command("NAMES", (None, vec![], None)).unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("353 * :nick2", responses[1]);
-    assert_eq!("366 :End of /NAMES list", responses[2]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(":servername 353 nickname * :nick2", responses[1]);
+    assert_eq!(
+        ":servername 366 nickname * :End of /NAMES list",
+        responses[2]
+    );
 }
 
 #[test]
@@ -230,13 +297,16 @@ fn names_returns_clients_in_not_visible_secret_channel_as_being_on_wildcard_chan
         .database
         .set_channel_flag("#hola2", ChannelFlag::Secret);
 
-    handler.names_command((None, vec![], None)).unwrap();
+    handler.raw_command("NAMES", (None, vec![], None)).unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname", responses[0]);
-    assert_eq!("353 * :nick2", responses[1]);
-    assert_eq!("366 :End of /NAMES list", responses[2]);
+    assert_eq!(":servername 353 nickname #hola :nickname", responses[0]);
+    assert_eq!(":servername 353 nickname * :nick2", responses[1]);
+    assert_eq!(
+        ":servername 366 nickname * :End of /NAMES list",
+        responses[2]
+    );
 }
 
 #[test]
@@ -248,13 +318,19 @@ fn names_returns_all_channel_clients() {
     handler.database.add_client_to_channel("#hola", "nick2");
 
     handler
-        .names_command((None, vec!["#hola".to_string()], None))
+        .raw_command("NAMES", (None, vec!["#hola".to_string()], None))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :nickname nick2", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
+    assert_eq!(
+        ":servername 353 nickname #hola :nickname nick2",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
 }
 
 #[test]
@@ -275,11 +351,17 @@ fn names_returns_client_role() {
     handler.database.add_channel_speaker("#hola", "nick2");
 
     handler
-        .names_command((None, vec!["#hola".to_string()], None))
+        .raw_command("NAMES", (None, vec!["#hola".to_string()], None))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("353 #hola :@nickname +nick2 nick3", responses[0]);
-    assert_eq!("366 #hola :End of /NAMES list", responses[1]);
+    assert_eq!(
+        ":servername 353 nickname #hola :@nickname +nick2 nick3",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 366 nickname #hola :End of /NAMES list",
+        responses[1]
+    );
 }
@@ -7,10 +7,12 @@ fn nick_fails_with_no_nickname_given() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec![];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "431 :No nickname given\r\n",
+        ":servername 431 nickname :No nickname given\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -22,20 +24,68 @@ fn changing_nick_fails_with_nickname_in_use() {
     handler.database.add_local_client(dummy_client("nick2"));
 
     let parameters = vec!["nick2".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 433 nickname nick2 :Nickname is already in use\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn changing_nick_fails_with_nickname_in_use_with_different_case() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("Nick[2]"));
+
+    let parameters = vec!["nick{2}".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "433 nick2 :Nickname is already in use\r\n",
+        ":servername 433 nickname nick{2} :Nickname is already in use\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
+#[test]
+fn can_change_case_of_own_nickname() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname");
+
+    let parameters = vec!["NickName".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!("NickName", handler.nickname);
+    assert_eq!(
+        "NickName",
+        handler
+            .database
+            .get_client_info("nickname")
+            .unwrap()
+            .nickname
+    );
+    assert_eq!(
+        vec!["NickName".to_string()],
+        handler.database.get_channel_clients("#channel").unwrap()
+    );
+}
+
 #[test]
 fn can_set_nickname() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nick".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 
@@ -47,7 +97,9 @@ fn can_update_nickname() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nick2".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("nick2", handler.nickname);
 
@@ -64,7 +116,9 @@ fn after_nick_update_channel_info_is_updated() {
         .add_client_to_channel("#channel", "nickname");
 
     let parameters = vec!["nick2".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         vec!["nick2".to_string()],
@@ -83,22 +137,22 @@ fn nick_update_is_relayed_to_all_servers() {
         .add_immediate_server(dummy_server("servername2"));
 
     let parameters = vec!["nick2".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname NICK nick2\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname NICK nick2\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -108,10 +162,12 @@ fn nick_fails_with_long_nickname() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nicknamenickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nicknamenickname :Erroneous nickname\r\n",
+        ":servername 432 nickname nicknamenickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -121,34 +177,42 @@ fn nick_fails_with_invalid_prefix() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["#nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 #nickname :Erroneous nickname\r\n",
+        ":servername 432 nickname #nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["&nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 &nickname :Erroneous nickname\r\n",
+        ":servername 432 nickname &nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["$nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 $nickname :Erroneous nickname\r\n",
+        ":servername 432 nickname $nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
-    let parameters = vec![":nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    let trailing = Some(":nickname".to_string());
+    handler
+        .raw_command("NICK", (None, vec![], trailing))
+        .unwrap();
 
     assert_eq!(
-        "432 :nickname :Erroneous nickname\r\n",
+        ":servername 431 nickname :No nickname given\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -158,50 +222,62 @@ fn nick_fails_with_invalid_character() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["nick.name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nick.name :Erroneous nickname\r\n",
+        ":servername 432 nickname nick.name :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nickname!".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nickname! :Erroneous nickname\r\n",
+        ":servername 432 nickname nickname! :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["ni,ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 ni,ckname :Erroneous nickname\r\n",
+        ":servername 432 nickname ni,ckname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nick*name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nick*name :Erroneous nickname\r\n",
+        ":servername 432 nickname nick*name :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["ni?ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 ni?ckname :Erroneous nickname\r\n",
+        ":servername 432 nickname ni?ckname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nickname@".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nickname@ :Erroneous nickname\r\n",
+        ":servername 432 nickname nickname@ :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
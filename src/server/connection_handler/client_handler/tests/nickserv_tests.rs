@@ -0,0 +1,234 @@
+use std::time::Duration;
+use std::{env, fs, path::PathBuf, process};
+
+use crate::server::connection_handler::ConnectionHandlerStructure;
+use crate::server::data_structures::Account;
+use crate::server::testing::dummy_server;
+
+use super::super::nick_protection::NickProtection;
+use super::*;
+
+fn account_store_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{name}-{}.db", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn nickserv_request(handler: &mut ClientHandler<MockTcpStream>, text: &str) {
+    let parameters = vec!["NickServ".to_string()];
+    let trailing = Some(text.to_string());
+    handler
+        .raw_command("PRIVMSG", (None, parameters, trailing))
+        .unwrap();
+}
+
+fn logged_into(handler: &ClientHandler<MockTcpStream>, nickname: &str) -> Option<String> {
+    handler.database.get_client_info(nickname).unwrap().account
+}
+
+#[test]
+fn register_fails_without_account_store() {
+    let mut handler = dummy_client_handler();
+
+    nickserv_request(&mut handler, "REGISTER password");
+
+    assert_eq!(
+        ":NickServ NOTICE nickname :Nickname registration is disabled\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_account("nickname"));
+}
+
+#[test]
+fn register_saves_account_and_logs_in() {
+    let path = account_store_path("registered-nickname");
+    let mut handler = dummy_client_handler();
+    handler.database.set_account_store(path.clone());
+
+    nickserv_request(&mut handler, "REGISTER password");
+
+    assert_eq!(
+        ":NickServ NOTICE nickname :nickname is now registered\r\n\
+         :servername 900 nickname nickname!username@127.0.0.1 nickname :You are now logged in as nickname\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(handler
+        .database
+        .get_account("nickname")
+        .unwrap()
+        .verify("password"));
+    assert_eq!(
+        Some("nickname".to_string()),
+        logged_into(&handler, "nickname")
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn registered_nickname_can_not_be_registered_again() {
+    let path = account_store_path("registered-twice");
+    let mut handler = dummy_client_handler();
+    handler.database.set_account_store(path.clone());
+    handler
+        .database
+        .add_account(Account::new("nickname", "secret", 1669831542));
+    handler.stream.clear();
+
+    nickserv_request(&mut handler, "REGISTER password");
+
+    assert_eq!(
+        ":NickServ NOTICE nickname :nickname is already registered\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, logged_into(&handler, "nickname"));
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn identify_logs_into_account() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("account", "password", 1669831542));
+
+    nickserv_request(&mut handler, "IDENTIFY account password");
+
+    assert_eq!(
+        ":servername 900 nickname nickname!username@127.0.0.1 account :You are now logged in as account\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        Some("account".to_string()),
+        logged_into(&handler, "nickname")
+    );
+}
+
+#[test]
+fn identify_fails_with_wrong_password() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("account", "password", 1669831542));
+
+    nickserv_request(&mut handler, "IDENTIFY account wrong");
+
+    assert_eq!(
+        ":NickServ NOTICE nickname :Invalid password\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, logged_into(&handler, "nickname"));
+}
+
+#[test]
+fn identify_is_refused_after_too_many_failures() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("account", "password", 1669831542));
+
+    for _ in 0..3 {
+        nickserv_request(&mut handler, "IDENTIFY account wrong");
+    }
+    handler.stream.clear();
+    nickserv_request(&mut handler, "IDENTIFY account password");
+
+    assert_eq!(
+        ":NickServ NOTICE nickname :Too many failed attempts, try again later\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, logged_into(&handler, "nickname"));
+}
+
+#[test]
+fn identify_is_relayed_to_all_servers() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .add_account(Account::new("account", "password", 1669831542));
+
+    nickserv_request(&mut handler, "IDENTIFY account password");
+
+    assert_eq!(
+        ":nickname ACCOUNT account\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn taking_registered_nickname_asks_to_identify() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("nick2", "password", 1669831542));
+
+    handler
+        .raw_command("NICK", (None, vec!["nick2".to_string()], None))
+        .unwrap();
+
+    assert_eq!(
+        ":NickServ NOTICE nick2 :nick2 is registered, identify within 60 seconds or your nickname will be changed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn client_that_does_not_identify_is_renamed() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("nick2", "password", 1669831542));
+    handler.nick_protection = NickProtection::new(Duration::ZERO);
+
+    handler
+        .raw_command("NICK", (None, vec!["nick2".to_string()], None))
+        .unwrap();
+    handler.stream.clear();
+    handler.on_poll().unwrap();
+    handler.flush_output().unwrap();
+
+    assert!(handler.nickname.starts_with("Guest"));
+    assert!(!handler.database.contains_client("nick2"));
+    assert_eq!(
+        format!(":nick2 NICK {}\r\n", handler.nickname),
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn client_that_identifies_keeps_nickname() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .add_account(Account::new("nick2", "password", 1669831542));
+    handler.nick_protection = NickProtection::new(Duration::ZERO);
+
+    handler
+        .raw_command("NICK", (None, vec!["nick2".to_string()], None))
+        .unwrap();
+    nickserv_request(&mut handler, "IDENTIFY password");
+    handler.on_poll().unwrap();
+
+    assert_eq!("nick2", handler.nickname);
+}
+
+#[test]
+fn nickserv_nickname_is_reserved() {
+    let mut handler = dummy_client_handler();
+
+    handler
+        .raw_command("NICK", (None, vec!["nickserv".to_string()], None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 433 nickname nickserv :Nickname is already in use\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
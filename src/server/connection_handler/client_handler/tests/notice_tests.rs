@@ -9,15 +9,14 @@ fn notice_works_with_valid_target_client() {
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
         ":nickname NOTICE nick1 :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -34,7 +33,7 @@ fn notice_with_away_client_does_not_return_away_message() {
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     assert!(handler.stream.read_wbuf_to_string().is_empty());
@@ -56,19 +55,21 @@ fn notice_fails_with_not_on_channel_with_flag_n() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -76,8 +77,7 @@ fn notice_fails_with_not_on_channel_with_flag_n() {
         "",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -99,19 +99,21 @@ fn notice_fails_if_not_speaker_on_channel_with_flag_m() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -135,7 +137,7 @@ fn notice_works_on_channel_with_flag_n() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
@@ -146,8 +148,7 @@ fn notice_works_on_channel_with_flag_n() {
         ":nickname NOTICE #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -155,8 +156,7 @@ fn notice_works_on_channel_with_flag_n() {
         ":nickname NOTICE #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -179,7 +179,7 @@ fn notice_works_on_channel_with_flag_m() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
@@ -190,8 +190,7 @@ fn notice_works_on_channel_with_flag_m() {
         ":nickname NOTICE #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -1,4 +1,5 @@
-use crate::server::testing::dummy_server;
+use crate::server::consts::user_flag::UserFlag;
+use crate::server::testing::{dummy_operator, dummy_server};
 
 use super::*;
 
@@ -7,10 +8,12 @@ fn oper_fails_with_less_than_two_parameters() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec![];
-    handler.oper_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 OPER :Not enough parameters\r\n",
+        ":servername 461 nickname OPER :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -20,10 +23,12 @@ fn oper_fails_with_incorrect_credentials() {
     let mut handler = dummy_client_handler();
 
     let parameters = vec!["user".to_string(), "user".to_string()];
-    handler.oper_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "464 :Password incorrect\r\n",
+        ":servername 464 nickname :Password incorrect\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert!(!handler.database.is_server_operator("nickname"));
@@ -32,12 +37,17 @@ fn oper_fails_with_incorrect_credentials() {
 #[test]
 fn can_register_as_operator() {
     let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
 
     let parameters = vec!["admin".to_string(), "admin".to_string()];
-    handler.oper_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "381 :You are now an IRC operator\r\n",
+        ":servername 381 nickname :You are now an IRC operator\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
@@ -47,6 +57,9 @@ fn can_register_as_operator() {
 #[test]
 fn oper_is_relayed_as_mode_to_all_servers() {
     let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
 
     handler
         .database
@@ -56,14 +69,15 @@ fn oper_is_relayed_as_mode_to_all_servers() {
         .add_immediate_server(dummy_server("servername3"));
 
     let parameters = vec!["admin".to_string(), "admin".to_string()];
-    handler.oper_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname MODE nickname +o\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 
@@ -71,8 +85,122 @@ fn oper_is_relayed_as_mode_to_all_servers() {
         ":nickname MODE nickname +o\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn oper_fails_outside_host_mask() {
+    let mut handler = dummy_client_handler();
+    let mut operator = dummy_operator("admin", "admin");
+    operator.host_mask = Some("*@10.0.0.*".to_string());
+    handler.database.set_operators(vec![operator]);
+
+    let parameters = vec!["admin".to_string(), "admin".to_string()];
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 464 nickname :Password incorrect\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(!handler.database.is_server_operator("nickname"));
+}
+
+#[test]
+fn oper_is_rejected_after_too_many_failures() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
+
+    for _ in 0..3 {
+        let parameters = vec!["admin".to_string(), "wrong".to_string()];
+        handler
+            .raw_command("OPER", (None, parameters, None))
+            .unwrap();
+    }
+    handler.stream.clear();
+
+    let parameters = vec!["admin".to_string(), "admin".to_string()];
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 464 nickname :Password incorrect\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(!handler.database.is_server_operator("nickname"));
+}
+
+#[test]
+fn oper_failures_count_against_the_address_across_connections() {
+    let mut handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
+
+    for _ in 0..3 {
+        let parameters = vec!["admin".to_string(), "wrong".to_string()];
+        handler
+            .raw_command("OPER", (None, parameters, None))
+            .unwrap();
+    }
+
+    let client = dummy_client("other");
+    let connection = client.queue().unwrap().try_clone_stream().unwrap();
+    handler.database.add_local_client(client);
+    let online = Arc::new(AtomicBool::new(true));
+    let mut other = ClientHandler::from_connection(
+        connection,
+        "other".to_string(),
+        handler.database.clone(),
+        online,
+    )
+    .unwrap();
+
+    let parameters = vec!["admin".to_string(), "admin".to_string()];
+    other.raw_command("OPER", (None, parameters, None)).unwrap();
+
+    assert_eq!(
+        ":servername 464 other :Password incorrect\r\n",
+        other.stream.read_wbuf_to_string()
+    );
+    assert!(!other.database.is_server_operator("other"));
+}
+
+#[test]
+fn failed_oper_is_reported_to_operators_receiving_server_notices() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("oper"));
+    handler.database.set_server_operator("oper", "admin");
+    handler
+        .database
+        .set_user_flag("oper", UserFlag::ReceiveServerNotices);
+    handler.database.add_local_client(dummy_client("deaf"));
+    handler.database.set_server_operator("deaf", "admin");
+
+    let parameters = vec!["admin".to_string(), "wrong".to_string()];
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername NOTICE oper :*** Notice -- Failed OPER attempt by nickname (username@127.0.0.1) as admin\r\n",
+        handler
+            .database
+            .flushed_local_stream("oper")
+            .read_wbuf_to_string()
+    );
+    assert_eq!(
+        "",
+        handler
+            .database
+            .flushed_local_stream("deaf")
             .read_wbuf_to_string()
     );
 }
@@ -8,10 +8,12 @@ fn part_fails_with_empty_params() {
     let parameters = vec![];
     let channels: Vec<String> = vec![];
 
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 PART :Not enough parameters\r\n",
+        ":servername 461 nickname PART :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
     assert_eq!(handler.database.get_all_channels(), channels);
@@ -23,13 +25,24 @@ fn part_fails_with_invalid_channel_name() {
 
     let parameters = vec!["hola,#ho'la,#hola".to_string()];
 
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("403 hola :No such channel", responses[0]);
-    assert_eq!("403 #ho'la :No such channel", responses[1]);
-    assert_eq!("403 #hola :No such channel", responses[2]);
+    assert_eq!(
+        ":servername 403 nickname hola :No such channel",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 403 nickname #ho'la :No such channel",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 403 nickname #hola :No such channel",
+        responses[2]
+    );
 }
 
 #[test]
@@ -45,10 +58,12 @@ fn part_fails_with_user_not_on_channel() {
         .database
         .add_client_to_channel("#hola", "newnickname");
 
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #hola :You're not on that channel\r\n",
+        ":servername 442 nickname #hola :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -59,12 +74,14 @@ fn can_part_one_channel() {
 
     let parameters = vec!["#hola".to_string()];
     handler
-        .join_command((None, parameters.clone(), None))
+        .raw_command("JOIN", (None, parameters.clone(), None))
         .unwrap();
 
     handler.stream.clear();
 
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert!(handler.database.get_all_channels().is_empty());
 }
@@ -81,12 +98,14 @@ fn can_part_existing_channels() {
     handler.database.add_client_to_channel("nick", "#chau");
 
     handler
-        .join_command((None, parameters.clone(), None))
+        .raw_command("JOIN", (None, parameters.clone(), None))
         .unwrap();
 
     handler.stream.clear();
 
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.database.get_all_channels().is_empty())
 }
@@ -102,14 +121,15 @@ fn part_notifies_users_in_channel() {
     handler.database.add_client_to_channel("#channel", "nick2");
 
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname PART #channel\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 
@@ -117,8 +137,7 @@ fn part_notifies_users_in_channel() {
         ":nickname PART #channel\r\n",
         handler
             .database
-            .get_local_stream("nickname")
-            .unwrap()
+            .flushed_local_stream("nickname")
             .read_wbuf_to_string()
     );
 }
@@ -134,14 +153,15 @@ fn part_notifies_user_in_channel() {
         .add_client_to_channel("#channel", "nickname");
 
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname PART #channel\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -162,22 +182,22 @@ fn distributed_channels_parts_are_relayed_to_all_servers() {
         .add_client_to_channel("#channel", "nickname");
 
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname PART #channel\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname PART #channel\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -0,0 +1,153 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
+use std::time::Duration;
+
+use crate::server::{
+    connection_handler::{ConnectionHandlerStructure, ConnectionHandlerUtils, Keepalive},
+    responses::Notification,
+    testing::{dummy_client, dummy_server},
+};
+
+use super::dummy_client_handler;
+
+#[test]
+fn ping_without_origin_returns_no_origin() {
+    let mut handler = dummy_client_handler();
+
+    handler.raw_command("PING", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 409 nickname :No origin specified\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn ping_is_answered_with_pong() {
+    let mut handler = dummy_client_handler();
+
+    let parameters = vec!["token".to_string()];
+    handler
+        .raw_command("PING", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername PONG servername :token\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn pong_is_accepted_silently() {
+    let mut handler = dummy_client_handler();
+
+    let parameters = vec!["servername".to_string()];
+    handler
+        .raw_command("PONG", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!("", handler.stream.read_wbuf_to_string())
+}
+
+#[test]
+fn idle_client_is_pinged() {
+    let mut handler = dummy_client_handler();
+    handler.keepalive = Keepalive::new(Duration::ZERO, Duration::from_secs(60));
+
+    assert!(handler.idle());
+    handler.on_idle().unwrap();
+    handler.flush_output().unwrap();
+
+    assert_eq!("PING :servername\r\n", handler.stream.read_wbuf_to_string());
+    assert!(!handler.idle());
+    assert!(!handler.timeout());
+}
+
+#[test]
+fn activity_clears_pending_ping() {
+    let mut handler = dummy_client_handler();
+    handler.keepalive = Keepalive::new(Duration::ZERO, Duration::ZERO);
+
+    handler.on_idle().unwrap();
+    handler.on_activity();
+
+    assert!(!handler.timeout());
+}
+
+#[test]
+fn unanswered_ping_disconnects_client() {
+    let mut handler = dummy_client_handler();
+    handler.keepalive = Keepalive::new(Duration::ZERO, Duration::ZERO);
+
+    handler.database.add_local_client(dummy_client("nickname1"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname1");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname");
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername1"));
+
+    handler.on_idle().unwrap();
+    handler.flush_output().unwrap();
+    handler.stream.clear();
+
+    assert!(handler.timeout());
+    handler.on_timeout().unwrap();
+
+    assert_eq!(
+        "ERROR :Closing Link: nickname (Ping timeout)\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        ":nickname QUIT :Ping timeout\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname1")
+            .read_wbuf_to_string()
+    );
+    assert_eq!(
+        ":nickname QUIT :Ping timeout\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername1")
+            .read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(
+        vec!["nickname1".to_string()],
+        handler.database.get_channel_clients("#channel").unwrap()
+    );
+}
+
+#[test]
+fn exceeding_sendq_disconnects_client() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nickname1"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname1");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname");
+
+    handler.output.set_limit(8);
+    let notification = Notification::ping("servername");
+    assert!(handler
+        .send_message_to_client(&notification, "nickname")
+        .is_err());
+
+    assert!(handler.sendq_exceeded());
+    handler.on_sendq_exceeded().unwrap();
+
+    assert_eq!(
+        ":nickname QUIT :SendQ exceeded\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname1")
+            .read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname"));
+}
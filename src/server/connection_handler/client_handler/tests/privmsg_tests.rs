@@ -9,11 +9,11 @@ fn privmsg_fails_with_no_recipient() {
     let parameters = vec![];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
-        "411 :No recipient given (PRIVMSG)\r\n",
+        ":servername 411 nickname :No recipient given (PRIVMSG)\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -25,11 +25,11 @@ fn privmsg_fails_with_no_text() {
     let parameters = vec!["nick1".to_string()];
     let trailing = None;
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
-        "412 :No text to send\r\n",
+        ":servername 412 nickname :No text to send\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -41,12 +41,15 @@ fn privmsg_fails_with_invalid_target() {
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("401 nick1 :No such nick/channel", responses[0]);
+    assert_eq!(
+        ":servername 401 nickname nick1 :No such nick/channel",
+        responses[0]
+    );
 }
 
 #[test]
@@ -58,15 +61,14 @@ fn privmsg_works_with_valid_target_client() {
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
         ":nickname PRIVMSG nick1 :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -86,7 +88,7 @@ fn privmsg_works_with_valid_target_channel() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
@@ -97,8 +99,7 @@ fn privmsg_works_with_valid_target_channel() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -106,8 +107,7 @@ fn privmsg_works_with_valid_target_channel() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -122,15 +122,14 @@ fn privmsg_works_with_multiple_targets() {
     let parameters = vec!["nick1,nick2".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
         ":nickname PRIVMSG nick1 :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -138,8 +137,7 @@ fn privmsg_works_with_multiple_targets() {
         ":nickname PRIVMSG nick2 :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -156,11 +154,11 @@ fn privmsg_with_away_client_returns_away_message() {
     let parameters = vec!["nick1".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
-        "301 nick1 :away message!\r\n",
+        ":servername 301 nickname nick1 :away message!\r\n",
         handler.stream.read_wbuf_to_string(),
     );
 }
@@ -181,19 +179,21 @@ fn privmsg_fails_with_not_on_channel_with_flag_n() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -201,8 +201,7 @@ fn privmsg_fails_with_not_on_channel_with_flag_n() {
         "",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -224,19 +223,21 @@ fn privmsg_fails_if_not_speaker_on_channel_with_flag_m() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("404 #channel :Cannot send to channel", responses[0]);
+    assert_eq!(
+        ":servername 404 nickname #channel :Cannot send to channel",
+        responses[0]
+    );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -260,7 +261,7 @@ fn privmsg_works_on_channel_with_flag_n() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
@@ -271,8 +272,7 @@ fn privmsg_works_on_channel_with_flag_n() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 
@@ -280,8 +280,7 @@ fn privmsg_works_on_channel_with_flag_n() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick2")
-            .unwrap()
+            .flushed_local_stream("nick2")
             .read_wbuf_to_string()
     );
 }
@@ -304,7 +303,7 @@ fn privmsg_works_on_channel_with_flag_m() {
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     let responses = handler.stream.get_responses();
@@ -315,8 +314,7 @@ fn privmsg_works_on_channel_with_flag_m() {
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_local_stream("nick1")
-            .unwrap()
+            .flushed_local_stream("nick1")
             .read_wbuf_to_string()
     );
 }
@@ -349,23 +347,46 @@ fn privmsg_to_distributed_channel_is_only_relayed_to_each_neccesary_server_once(
     let parameters = vec!["#channel".to_string()];
     let trailing = Some("message!".to_string());
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
         ":nickname PRIVMSG #channel :message!\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
+
+#[test]
+fn privmsg_too_long_to_relay_is_cut_and_sender_is_told() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick1"));
+
+    let parameters = vec!["nick1".to_string()];
+    let trailing = Some("a".repeat(495));
+    handler
+        .raw_command("PRIVMSG", (None, parameters, trailing))
+        .unwrap();
+
+    assert_eq!(
+        format!(":nickname PRIVMSG nick1 :{}\r\n", "a".repeat(485)),
+        handler
+            .database
+            .flushed_local_stream("nick1")
+            .read_wbuf_to_string()
+    );
+    assert_eq!(
+        ":servername 417 nickname :Input line was too long\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
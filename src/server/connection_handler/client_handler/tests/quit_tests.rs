@@ -1,7 +1,7 @@
-use crate::server::{
-    connection_handler::ConnectionHandlerCommands,
-    testing::{dummy_client, dummy_external_client, dummy_server},
-};
+use crate::server::connection_handler::ConnectionHandler;
+use crate::server::reactor::{Progress, Task};
+use crate::server::testing::{dummy_client, dummy_external_client, dummy_server};
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 
 use super::dummy_client_handler;
 
@@ -9,7 +9,7 @@ use super::dummy_client_handler;
 fn quit_without_message_returns_nickname() {
     let mut handler = dummy_client_handler();
 
-    handler.quit_command((None, vec![], None)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], None)).unwrap();
 
     assert_eq!("QUIT :nickname\r\n", handler.stream.read_wbuf_to_string())
 }
@@ -19,7 +19,7 @@ fn quit_with_message_returns_message() {
     let mut handler = dummy_client_handler();
 
     let trail = Some("message".to_string());
-    handler.quit_command((None, vec![], trail)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], trail)).unwrap();
 
     assert_eq!("QUIT :message\r\n", handler.stream.read_wbuf_to_string())
 }
@@ -37,15 +37,14 @@ fn quit_notifies_all_users_in_clients_channels() {
         .add_client_to_channel("#channel", "nickname");
 
     let trail = Some("message".to_string());
-    handler.quit_command((None, vec![], trail)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], trail)).unwrap();
 
     assert_eq!("QUIT :message\r\n", handler.stream.read_wbuf_to_string());
     assert_eq!(
         ":nickname QUIT :message\r\n",
         handler
             .database
-            .get_local_stream("nickname1")
-            .unwrap()
+            .flushed_local_stream("nickname1")
             .read_wbuf_to_string()
     )
 }
@@ -62,22 +61,20 @@ fn quit_is_sent_to_all_servers() {
         .add_immediate_server(dummy_server("servername2"));
 
     let trail = Some("message".to_string());
-    handler.quit_command((None, vec![], trail)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], trail)).unwrap();
 
     assert_eq!(
         ":nickname QUIT :message\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname QUIT :message\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -95,14 +92,65 @@ fn quit_is_sent_to_servers_only_once() {
         .add_external_client(dummy_external_client("nickname1", "servername1"));
 
     let trail = Some("message".to_string());
-    handler.quit_command((None, vec![], trail)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], trail)).unwrap();
 
     assert_eq!(
         ":nickname QUIT :message\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn lost_connection_is_handled_as_quit() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nickname1"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname1");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname");
+
+    assert!(matches!(handler.poll_connection(), Progress::Closed));
+
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(
+        vec!["nickname1"],
+        handler.database.get_channel_clients("#channel").unwrap()
+    );
+    assert_eq!(
+        ":nickname QUIT :Remote host closed the connection\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname1")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn aborted_handler_quits_client() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nickname1"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname1");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname");
+
+    handler.abort();
+
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(
+        ":nickname QUIT :Internal error\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname1")
             .read_wbuf_to_string()
     );
 }
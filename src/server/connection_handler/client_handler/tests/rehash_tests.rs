@@ -0,0 +1,168 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::server::config::ServerConfig;
+use crate::server::consts::user_flag::UserFlag;
+use crate::server::data_structures::{OperatorPrivilege, PasswordHash};
+use crate::server::testing::{dummy_config_file, dummy_operator};
+
+use super::*;
+
+fn config_content(server: &str, privileges: &str) -> String {
+    let hash = PasswordHash::with_salt("admin", "salt");
+    format!(
+        "[server]
+name = servername
+info = serverinfo
+listen = 127.0.0.1:9000
+{server}
+[operator admin]
+password = {hash}
+privileges = {privileges}
+"
+    )
+}
+
+fn configured_client_handler(
+    name: &str,
+    privileges: &str,
+) -> (ClientHandler<MockTcpStream>, PathBuf) {
+    let handler = dummy_client_handler();
+    let path = dummy_config_file(name, &config_content("", privileges));
+
+    let config = ServerConfig::from_file(&path).unwrap();
+    handler.database.configure(config);
+    handler.database.set_server_operator("nickname", "admin");
+
+    (handler, path)
+}
+
+#[test]
+fn rehash_fails_for_non_operators() {
+    let mut handler = dummy_client_handler();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 481 nickname :Permission Denied- You're not an IRC operator\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn rehash_fails_without_rehash_privilege() {
+    let (mut handler, _) = configured_client_handler("rehash_privilege", "kill");
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 481 nickname :Permission Denied- You're not an IRC operator\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn rehash_applies_new_settings() {
+    let (mut handler, path) = configured_client_handler("rehash_applies", "rehash");
+    let server = "password = secret\n[limits]\nnickname_length = 20\n";
+    fs::write(&path, config_content(server, "rehash")).unwrap();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        format!(":servername 382 nickname {} :Rehashing\r\n", path.display()),
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        Some("secret".to_string()),
+        handler.database.get_server_password()
+    );
+    assert_eq!(20, handler.database.get_limits().nickname_length);
+    assert!(handler.database.is_server_operator("nickname"));
+}
+
+#[test]
+fn rehash_reports_settings_that_require_restart() {
+    let (mut handler, path) = configured_client_handler("rehash_restart", "rehash");
+    let content = config_content("listen = 127.0.0.1:9001\n", "rehash")
+        .replace("name = servername", "name = newname");
+    fs::write(&path, content).unwrap();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    let responses = handler.stream.get_responses();
+    assert_eq!(
+        ":servername NOTICE nickname :*** Setting name changed, it takes effect on restart",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername NOTICE nickname :*** Setting listen changed, it takes effect on restart",
+        responses[2]
+    );
+    assert_eq!(
+        "servername",
+        handler.database.get_config().unwrap().servername
+    );
+}
+
+#[test]
+fn rehash_reports_ping_settings_without_applying_them() {
+    let (mut handler, path) = configured_client_handler("rehash_ping", "rehash");
+    let server = "[limits]\nping_interval = 5\n";
+    fs::write(&path, config_content(server, "rehash")).unwrap();
+    let (interval, _) = handler.database.get_keepalive_settings();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    let responses = handler.stream.get_responses();
+    assert_eq!(
+        ":servername NOTICE nickname :*** Setting ping_interval changed, it takes effect on restart",
+        responses[1]
+    );
+    assert_eq!(interval, handler.database.get_keepalive_settings().0);
+}
+
+#[test]
+fn rehash_keeps_configuration_if_file_is_invalid() {
+    let (mut handler, path) = configured_client_handler("rehash_invalid", "rehash");
+    fs::write(&path, "[server]\npassword = secret\nport = 9000\n").unwrap();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername NOTICE nickname :*** Rehash failed: line 3: unknown key port\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.database.get_server_password());
+}
+
+#[test]
+fn rehash_fails_if_server_was_not_configured_from_file() {
+    let mut handler = dummy_client_handler();
+    let mut operator = dummy_operator("admin", "admin");
+    operator.privileges = vec![OperatorPrivilege::Rehash];
+    handler.database.set_operators(vec![operator]);
+    handler.database.set_server_operator("nickname", "admin");
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername NOTICE nickname :*** Rehash failed: server was not configured from a file\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn rehash_is_reported_to_operators_receiving_server_notices() {
+    let (mut handler, _) = configured_client_handler("rehash_notice", "rehash");
+    handler
+        .database
+        .set_user_flag("nickname", UserFlag::ReceiveServerNotices);
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername NOTICE nickname :*** Notice -- nickname is rehashing server config file",
+        handler.stream.get_responses()[1]
+    );
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::Ordering;
+
+use crate::server::connection_handler::{ConnectionHandlerStructure, ConnectionHandlerUtils};
+use crate::server::responses::Notification;
+
+use super::dummy_client_handler;
+
+#[test]
+fn client_is_told_link_is_closing_on_shutdown() {
+    let mut handler = dummy_client_handler();
+    handler.online.store(false, Ordering::Relaxed);
+
+    assert!(!handler.try_handle().unwrap());
+
+    assert_eq!(
+        "ERROR :Closing Link: nickname (Server shutting down)\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn messages_after_shutdown_are_discarded() {
+    let mut handler = dummy_client_handler();
+    handler.online.store(false, Ordering::Relaxed);
+
+    handler.try_handle().unwrap();
+    handler.stream.clear();
+
+    let notification = Notification::ping("servername");
+    handler
+        .send_message_to_client(&notification, "nickname")
+        .unwrap();
+
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+}
+
+#[test]
+fn disconnected_client_is_sent_what_was_pending_before_closing() {
+    let mut handler = dummy_client_handler();
+    let queue = handler.database.get_local_queue("nickname").unwrap();
+
+    queue.send(&Notification::ping("servername")).unwrap();
+    handler.database.disconnect_client("nickname");
+    assert!(!handler.database.contains_client("nickname"));
+
+    assert!(!handler.try_handle().unwrap());
+    assert_eq!("PING :servername\r\n", handler.stream.read_wbuf_to_string());
+}
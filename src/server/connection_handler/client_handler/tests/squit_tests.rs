@@ -1,9 +1,19 @@
-use crate::server::{
-    connection_handler::ConnectionHandlerCommands,
-    testing::{dummy_client, dummy_distant_server, dummy_external_client, dummy_server},
+use crate::server::data_structures::OperatorPrivilege;
+use crate::server::testing::{
+    dummy_client, dummy_distant_server, dummy_external_client, dummy_operator, dummy_server,
 };
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 
-use super::dummy_client_handler;
+use super::{dummy_client_handler, ClientHandler, MockTcpStream};
+
+fn operator_client_handler() -> ClientHandler<MockTcpStream> {
+    let handler = dummy_client_handler();
+    handler
+        .database
+        .set_operators(vec![dummy_operator("admin", "admin")]);
+    handler.database.set_server_operator("nickname", "admin");
+    handler
+}
 
 #[test]
 fn squit_fails_without_oper() {
@@ -13,32 +23,51 @@ fn squit_fails_without_oper() {
         .add_immediate_server(dummy_server("servername2"));
 
     let params = vec!["servername2".to_string()];
-    handler.squit_command((None, params, None)).unwrap();
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
 
     assert_eq!(
-        "481 :Permission Denied- You're not an IRC operator\r\n",
+        ":servername 481 nickname :Permission Denied- You're not an IRC operator\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
 #[test]
-fn squit_fails_without_valid_server() {
+fn squit_fails_without_squit_privilege() {
     let mut handler = dummy_client_handler();
-    handler.database.set_server_operator("nickname");
+    let mut operator = dummy_operator("admin", "admin");
+    operator.privileges = vec![OperatorPrivilege::Kill];
+    handler.database.set_operators(vec![operator]);
+    handler.database.set_server_operator("nickname", "admin");
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    let params = vec!["servername2".to_string()];
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
+
+    assert_eq!(
+        ":servername 481 nickname :Permission Denied- You're not an IRC operator\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(handler.database.contains_server("servername2"));
+}
+
+#[test]
+fn squit_fails_without_valid_server() {
+    let mut handler = operator_client_handler();
 
     let params = vec!["servername2".to_string()];
-    handler.squit_command((None, params, None)).unwrap();
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
 
     assert_eq!(
-        "402 servername2 :No such server\r\n",
+        ":servername 402 nickname servername2 :No such server\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
 #[test]
 fn squit_is_relayed_to_all_servers() {
-    let mut handler = dummy_client_handler();
-    handler.database.set_server_operator("nickname");
+    let mut handler = operator_client_handler();
 
     handler
         .database
@@ -51,44 +80,40 @@ fn squit_is_relayed_to_all_servers() {
         .add_distant_server(dummy_distant_server("servername5"));
 
     let params = vec!["servername5".to_string()];
-    handler.squit_command((None, params, None)).unwrap();
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
 
     assert_eq!(
         ":nickname SQUIT servername5\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname SQUIT servername5\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
 
 #[test]
 fn squit_to_immediate_server_removes_it_from_database() {
-    let mut handler = dummy_client_handler();
-    handler.database.set_server_operator("nickname");
+    let mut handler = operator_client_handler();
     handler
         .database
         .add_immediate_server(dummy_server("servername2"));
 
     let params = vec!["servername2".to_string()];
-    handler.squit_command((None, params, None)).unwrap();
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
 
     assert!(!handler.database.contains_server("servername2"));
 }
 
 #[test]
 fn squit_to_immediate_server_notifies_quit_for_every_client() {
-    let mut handler = dummy_client_handler();
-    handler.database.set_server_operator("nickname");
+    let mut handler = operator_client_handler();
     handler
         .database
         .add_immediate_server(dummy_server("servername2"));
@@ -109,22 +134,20 @@ fn squit_to_immediate_server_notifies_quit_for_every_client() {
         .add_client_to_channel("#channel", "nickname1");
 
     let params = vec!["servername2".to_string()];
-    handler.squit_command((None, params, None)).unwrap();
+    handler.raw_command("SQUIT", (None, params, None)).unwrap();
 
     assert_eq!(
         ":nickname1 QUIT :Net split\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname SQUIT servername2\r\n:nickname1 QUIT :Net split\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
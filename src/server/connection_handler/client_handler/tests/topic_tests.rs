@@ -7,10 +7,12 @@ fn topic_fails_with_empty_params() {
     let mut handler = dummy_client_handler();
     let parameters = vec![];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 TOPIC :Not enough parameters\r\n",
+        ":servername 461 nickname TOPIC :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -24,10 +26,12 @@ fn cannot_modify_topic_if_not_in_channel() {
 
     let parameters = vec!["#canal".to_string(), "topic".to_string()];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #canal :You're not on that channel\r\n",
+        ":servername 442 nickname #canal :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -38,10 +42,12 @@ fn topic_ignores_nonexistent_channels() {
 
     let parameters = vec!["#canal1".to_string(), "topic1".to_string()];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "442 #canal1 :You're not on that channel\r\n",
+        ":servername 442 nickname #canal1 :You're not on that channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -57,19 +63,26 @@ fn topic_sets_and_gets_channel_topic() {
     let parameters1 = vec!["#canal".to_string()];
 
     handler
-        .topic_command((None, parameters1.clone(), None))
+        .raw_command("TOPIC", (None, parameters1.clone(), None))
         .unwrap();
 
     let parameters2 = vec!["#canal".to_string(), "topic".to_string()];
 
-    handler.topic_command((None, parameters2, None)).unwrap();
-    handler.topic_command((None, parameters1, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters2, None))
+        .unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters1, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("331 #canal :No topic is set", responses[0]);
-    assert_eq!(":nickname TOPIC #canal topic", responses[1]);
-    assert_eq!("332 #canal :topic", responses[2]);
+    assert_eq!(
+        ":servername 331 nickname #canal :No topic is set",
+        responses[0]
+    );
+    assert_eq!(":nickname TOPIC #canal :topic", responses[1]);
+    assert_eq!(":servername 332 nickname #canal :topic", responses[2]);
 }
 #[test]
 fn topic_fails_with_not_channop_on_channel_with_topic_flag() {
@@ -87,10 +100,12 @@ fn topic_fails_with_not_channop_on_channel_with_topic_flag() {
 
     let parameters = vec!["#hola".to_string(), "topic".to_string()];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "482 #hola :You're not channel operator\r\n",
+        ":servername 482 nickname #hola :You're not channel operator\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -109,18 +124,25 @@ fn can_modify_topic_if_channop_on_channel_with_topic_flag() {
 
     let mut parameters = vec!["#hola".to_string(), "topic".to_string()];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        ":nickname TOPIC #hola topic\r\n",
+        ":nickname TOPIC #hola :topic\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     parameters = vec!["#hola".to_string()];
 
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
-    assert_eq!("332 #hola :topic\r\n", handler.stream.read_wbuf_to_string());
+    assert_eq!(
+        ":servername 332 nickname #hola :topic\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
 }
 
 #[test]
@@ -139,23 +161,49 @@ fn distributed_channel_topics_are_relayed_to_all_servers() {
         .add_client_to_channel("#channel", "nickname");
 
     let params = vec!["#channel".to_string(), "topic".to_string()];
-    handler.topic_command((None, params, None)).unwrap();
+    handler.raw_command("TOPIC", (None, params, None)).unwrap();
 
     assert_eq!(
-        ":nickname TOPIC #channel topic\r\n",
+        ":nickname TOPIC #channel :topic\r\n",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 
     assert_eq!(
-        ":nickname TOPIC #channel topic\r\n",
+        ":nickname TOPIC #channel :topic\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
+
+#[test]
+fn long_topic_is_cut_and_sender_is_told() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_client_to_channel("#canal", "nickname");
+
+    let parameters = vec!["#canal".to_string()];
+    let trailing = Some("a".repeat(400));
+    handler
+        .raw_command("TOPIC", (None, parameters, trailing))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(
+        ":servername 417 nickname :Input line was too long",
+        responses[0]
+    );
+    assert_eq!(
+        format!(":nickname TOPIC #canal :{}", "a".repeat(307)),
+        responses[1]
+    );
+    assert_eq!(
+        Some("a".repeat(307)),
+        handler.database.get_channel_topic("#canal").unwrap()
+    );
+}
@@ -18,7 +18,9 @@ fn who_with_no_parameters_returns_all_public_clients_with_no_common_channels() {
 
     let parameters = vec![];
 
-    handler.who_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHO", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -27,14 +29,14 @@ fn who_with_no_parameters_returns_all_public_clients_with_no_common_channels() {
     channels.sort();
 
     assert_eq!(
-        "352 #channel2 username 127.0.0.1 servername nick1 :0 realname",
+        ":servername 352 nickname #channel2 username 127.0.0.1 servername nick1 :0 realname",
         channels[0]
     );
     assert_eq!(
-        "352 * username 127.0.0.1 servername nick2 :0 realname",
+        ":servername 352 nickname * username 127.0.0.1 servername nick2 :0 realname",
         channels[1]
     );
-    assert_eq!("315 :End of /WHO list", responses[0]);
+    assert_eq!(":servername 315 nickname :End of /WHO list", responses[0]);
 }
 
 #[test]
@@ -45,15 +47,20 @@ fn who_with_mask_returns_all_public_clients_matching_mask() {
 
     let parameters = vec!["*k1*".to_string()];
 
-    handler.who_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHO", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
     assert_eq!(
-        "352 * username 127.0.0.1 servername nick1name :0 realname",
+        ":servername 352 nickname * username 127.0.0.1 servername nick1name :0 realname",
         responses[0]
     );
-    assert_eq!("315 *k1* :End of /WHO list", responses[1]);
+    assert_eq!(
+        ":servername 315 nickname *k1* :End of /WHO list",
+        responses[1]
+    );
 }
 
 #[test]
@@ -66,15 +73,20 @@ fn who_with_external_clients_returns_client_correct_hopcount() {
 
     let parameters = vec!["*k1*".to_string()];
 
-    handler.who_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHO", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
     assert_eq!(
-        "352 * username 127.0.0.1 servername1 nick1name :1 realname",
+        ":servername 352 nickname * username 127.0.0.1 servername1 nick1name :1 realname",
         responses[0]
     );
-    assert_eq!("315 *k1* :End of /WHO list", responses[1]);
+    assert_eq!(
+        ":servername 315 nickname *k1* :End of /WHO list",
+        responses[1]
+    );
 }
 
 #[test]
@@ -92,19 +104,24 @@ fn who_returns_client_masks() {
         .database
         .set_user_flag("nick1name", UserFlag::Operator);
 
-    handler.who_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHO", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
     let mut first_response: Vec<String> = responses[0].split(' ').map(String::from).collect();
-    let mut flags = first_response[6].chars().collect::<Vec<char>>();
+    let mut flags = first_response[8].chars().collect::<Vec<char>>();
     flags.sort();
-    first_response[6] = flags.iter().collect();
+    first_response[8] = flags.iter().collect();
     let first_response = first_response.join(" ");
 
     assert_eq!(
-        "352 * username 127.0.0.1 servername nick1name io :0 realname",
+        ":servername 352 nickname * username 127.0.0.1 servername nick1name io :0 realname",
         first_response
     );
-    assert_eq!("315 *k1* :End of /WHO list", responses[1]);
+    assert_eq!(
+        ":servername 315 nickname *k1* :End of /WHO list",
+        responses[1]
+    );
 }
@@ -7,10 +7,12 @@ fn whois_fails_with_empty_params() {
     let mut handler = dummy_client_handler();
     let parameters = vec![];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "431 :No nickname given\r\n",
+        ":servername 431 nickname :No nickname given\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -21,10 +23,12 @@ fn whois_fails_with_nonexistent_nickname() {
 
     let parameters: Vec<String> = vec!["nick2".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "401 nick2 :No such nick/channel\r\n",
+        ":servername 401 nickname nick2 :No such nick/channel\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -35,31 +39,60 @@ fn whois_returns_nick_info() {
 
     let parameters = vec!["nickname".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname servername :serverinfo", responses[1]);
-    assert_eq!("318 nickname :End of /WHOIS list", responses[2]);
+    assert_eq!(
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname servername :serverinfo",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[2]
+    );
 }
 
 #[test]
 fn whois_returns_nick_info_if_oper() {
     let mut handler = dummy_client_handler();
 
-    handler.database.set_server_operator("nickname");
+    handler.database.set_server_operator("nickname", "admin");
 
     let parameters = vec!["nickname".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname servername :serverinfo", responses[1]);
-    assert_eq!("313 nickname :Is an IRC operator", responses[2]);
-    assert_eq!("318 nickname :End of /WHOIS list", responses[3]);
+    assert_eq!(
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname servername :serverinfo",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 313 nickname nickname :Is an IRC operator",
+        responses[2]
+    );
+    assert_eq!(
+        ":servername 320 nickname nickname :SendQ 179/262144 bytes",
+        responses[3]
+    );
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[4]
+    );
 }
 
 #[test]
@@ -70,14 +103,25 @@ fn whois_returns_nick_info_with_channels() {
 
     handler.database.add_client_to_channel("#hola", "nickname");
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname servername :serverinfo", responses[1]);
-    assert_eq!("319 nickname : #hola", responses[2]);
-    assert_eq!("318 nickname :End of /WHOIS list", responses[3]);
+    assert_eq!(
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname servername :serverinfo",
+        responses[1]
+    );
+    assert_eq!(":servername 319 nickname nickname : #hola", responses[2]);
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -90,14 +134,25 @@ fn whois_returns_nick_away_info() {
 
     let parameters = vec!["nickname".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname servername :serverinfo", responses[1]);
-    assert_eq!("301 nickname :away", responses[2]);
-    assert_eq!("318 nickname :End of /WHOIS list", responses[3]);
+    assert_eq!(
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname servername :serverinfo",
+        responses[1]
+    );
+    assert_eq!(":servername 301 nickname nickname :away", responses[2]);
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[3]
+    );
 }
 
 #[test]
@@ -107,21 +162,80 @@ fn whois_returns_complete_nick_info() {
     let parameters = vec!["nickname".to_string()];
 
     handler.database.add_client_to_channel("#hola", "nickname");
-    handler.database.set_server_operator("nickname");
+    handler.database.set_server_operator("nickname", "admin");
     handler
         .database
         .set_away_message("nickname", Some("away message".to_string()));
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname servername :serverinfo",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 313 nickname nickname :Is an IRC operator",
+        responses[2]
+    );
+    assert_eq!(":servername 319 nickname nickname : #hola", responses[3]);
+    assert_eq!(
+        ":servername 301 nickname nickname :away message",
+        responses[4]
+    );
+    assert_eq!(
+        ":servername 320 nickname nickname :SendQ 271/262144 bytes",
+        responses[5]
+    );
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[6]
+    );
+}
+
+#[test]
+fn whois_shows_sendq_of_local_clients_to_operators() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.set_server_operator("nickname", "admin");
+    handler.database.add_local_client(dummy_client("nick2"));
+    handler
+        .database
+        .get_local_queue("nick2")
+        .unwrap()
+        .set_limit(1024);
+
+    let parameters = vec!["nick2".to_string()];
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert!(responses.contains(&":servername 320 nickname nick2 :SendQ 0/1024 bytes".to_string()));
+}
+
+#[test]
+fn whois_hides_sendq_from_non_operators() {
+    let mut handler = dummy_client_handler();
+
+    handler.database.add_local_client(dummy_client("nick2"));
+
+    let parameters = vec!["nick2".to_string()];
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname servername :serverinfo", responses[1]);
-    assert_eq!("313 nickname :Is an IRC operator", responses[2]);
-    assert_eq!("319 nickname : #hola", responses[3]);
-    assert_eq!("301 nickname :away message", responses[4]);
-    assert_eq!("318 nickname :End of /WHOIS list", responses[5]);
+    assert!(!responses.iter().any(|response| response.contains(" 320 ")));
 }
 
 #[test]
@@ -133,7 +247,9 @@ fn whois_works_with_nickmask() {
     handler.database.add_local_client(dummy_client("nickname2"));
     handler.database.add_local_client(dummy_client("nickname3"));
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let mut responses = handler.stream.get_responses();
 
@@ -146,34 +262,43 @@ fn whois_works_with_nickmask() {
     sorted_responses.sort();
 
     assert_eq!(
-        "311 nickname username 127.0.0.1 *: realname",
+        ":servername 311 nickname nickname username 127.0.0.1 * :realname",
         sorted_responses[0][0]
     );
     assert_eq!(
-        "312 nickname servername :serverinfo",
+        ":servername 312 nickname nickname servername :serverinfo",
         sorted_responses[0][1]
     );
-    assert_eq!("318 nickname :End of /WHOIS list", sorted_responses[0][2]);
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        sorted_responses[0][2]
+    );
 
     assert_eq!(
-        "311 nickname2 username 127.0.0.1 *: realname",
+        ":servername 311 nickname nickname2 username 127.0.0.1 * :realname",
         sorted_responses[1][0]
     );
     assert_eq!(
-        "312 nickname2 servername :serverinfo",
+        ":servername 312 nickname nickname2 servername :serverinfo",
         sorted_responses[1][1]
     );
-    assert_eq!("318 nickname2 :End of /WHOIS list", sorted_responses[1][2]);
+    assert_eq!(
+        ":servername 318 nickname nickname2 :End of /WHOIS list",
+        sorted_responses[1][2]
+    );
 
     assert_eq!(
-        "311 nickname3 username 127.0.0.1 *: realname",
+        ":servername 311 nickname nickname3 username 127.0.0.1 * :realname",
         sorted_responses[2][0]
     );
     assert_eq!(
-        "312 nickname3 servername :serverinfo",
+        ":servername 312 nickname nickname3 servername :serverinfo",
         sorted_responses[2][1]
     );
-    assert_eq!("318 nickname3 :End of /WHOIS list", sorted_responses[2][2]);
+    assert_eq!(
+        ":servername 318 nickname nickname3 :End of /WHOIS list",
+        sorted_responses[2][2]
+    );
 }
 
 #[test]
@@ -181,10 +306,12 @@ fn whois_fails_with_unknown_server() {
     let mut handler = dummy_client_handler();
     let parameters = vec!["server1".to_string(), "nickname".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "402 server1 :No such server\r\n",
+        ":servername 402 nickname server1 :No such server\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -201,11 +328,48 @@ fn whois_with_server_param_only_sends_response_for_clients_in_server() {
 
     let parameters = vec!["servername2".to_string(), "nickna*".to_string()];
 
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
 
-    assert_eq!("311 nickname2 username 127.0.0.1 *: realname", responses[0]);
-    assert_eq!("312 nickname2 servername2 :serverinfo", responses[1]);
-    assert_eq!("318 nickname2 :End of /WHOIS list", responses[2]);
+    assert_eq!(
+        ":servername 311 nickname nickname2 username 127.0.0.1 * :realname",
+        responses[0]
+    );
+    assert_eq!(
+        ":servername 312 nickname nickname2 servername2 :serverinfo",
+        responses[1]
+    );
+    assert_eq!(
+        ":servername 318 nickname nickname2 :End of /WHOIS list",
+        responses[2]
+    );
+}
+
+#[test]
+fn whois_returns_account_if_logged_in() {
+    let mut handler = dummy_client_handler();
+
+    handler
+        .database
+        .set_client_account("nickname", Some("account".to_string()));
+
+    let parameters = vec!["nickname".to_string()];
+
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(
+        ":servername 330 nickname nickname account :is logged in as",
+        responses[2]
+    );
+    assert_eq!(
+        ":servername 318 nickname nickname :End of /WHOIS list",
+        responses[3]
+    );
 }
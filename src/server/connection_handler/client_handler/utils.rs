@@ -1,23 +1,29 @@
-use crate::macros::ok_or_return;
+use std::io;
+
+use crate::macros::{ok_or_return, some_or_return};
+use crate::message::ToMessage;
 use crate::server::connection::Connection;
 use crate::server::connection_handler::ConnectionHandlerUtils;
 use crate::server::consts::channel_flag::ChannelFlag;
+use crate::server::consts::user::{GUEST_NICKNAME_PREFIX, NICKSERV_NICKNAME};
+use crate::server::responses::Notification;
 use crate::server::{
     consts::modes::{OPERATOR_SYMBOL, SPEAKER_SYMBOL},
-    data_structures::ClientInfo,
+    data_structures::{irc_eq, unix_time, Capability, ClientInfo},
+    NICK_PROTECTION_SECS,
 };
 
 use super::ClientHandler;
 
-impl<C: Connection> ConnectionHandlerUtils<C> for ClientHandler<C> {}
+impl<C: Connection> ConnectionHandlerUtils<C> for ClientHandler<C> {
+    fn send_message(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.send(message)
+    }
+}
 
 impl<C: Connection> ClientHandler<C> {
-    pub(super) fn channels_to_list(&mut self, channels: Option<&String>) -> Vec<String> {
-        if channels.is_none() {
-            return self.database.get_all_channels();
-        }
-
-        collect_list(channels)
+    pub(super) fn channels_to_list(&mut self, channels: Option<Vec<String>>) -> Vec<String> {
+        channels.unwrap_or_else(|| self.database.get_all_channels())
     }
 
     pub(super) fn clients_for_default_who(&self) -> Vec<ClientInfo> {
@@ -28,18 +34,28 @@ impl<C: Connection> ClientHandler<C> {
             .collect()
     }
 
-    pub fn get_client_role_in_channel(&self, channel: &str, nickname: &str) -> Option<char> {
+    /// Returns the symbols of the client's roles in channel, highest first.
+    /// Only the highest one is returned, unless multi-prefix was enabled.
+    pub fn get_client_roles_in_channel(&self, channel: &str, nickname: &str) -> String {
+        let mut roles = String::new();
+
         if self.database.is_channel_operator(channel, nickname) {
-            return Some(OPERATOR_SYMBOL);
+            roles.push(OPERATOR_SYMBOL);
         }
         if self
             .database
             .channel_has_flag(channel, ChannelFlag::Moderated)
             && self.database.is_channel_speaker(channel, nickname)
         {
-            return Some(SPEAKER_SYMBOL);
+            roles.push(SPEAKER_SYMBOL);
+        }
+
+        let capabilities = self.database.get_client_capabilities(&self.nickname);
+        if !capabilities.contains(&Capability::MultiPrefix) {
+            roles.truncate(1);
         }
-        None
+
+        roles
     }
 
     pub(super) fn get_clients_for_mask(&self, mask: &str) -> Vec<ClientInfo> {
@@ -80,11 +96,65 @@ impl<C: Connection> ClientHandler<C> {
 
         all_clients
     }
-}
 
-pub fn collect_list(parameters: Option<&String>) -> Vec<String> {
-    match parameters {
-        Some(parameters) => parameters.split(',').map(|s| s.to_string()).collect(),
-        None => vec![],
+    /// Renames the client, letting the rest of the network know.
+    pub(super) fn change_nickname(&mut self, new_nickname: String) {
+        self.database.update_nickname(&self.nickname, &new_nickname);
+
+        let nick_notification = Notification::nick_update(&self.nickname, &new_nickname);
+        self.send_message_to_all_servers(&nick_notification);
+
+        self.nickname = new_nickname;
+    }
+
+    /// Starts the nick protection countdown if the client's nickname is
+    /// registered to an account it is not logged into, stops it otherwise.
+    pub(super) fn protect_nickname(&mut self) -> io::Result<()> {
+        self.nick_protection.stop();
+
+        let account = some_or_return!(self.database.get_account(&self.nickname), Ok(()));
+        if self.is_logged_into(&account.name) {
+            return Ok(());
+        }
+
+        self.nick_protection.start();
+        self.send_nickserv_notice(&format!(
+            "{} is registered, identify within {NICK_PROTECTION_SECS} seconds or your nickname will be changed",
+            self.nickname
+        ))
+    }
+
+    /// Renames the client to a free guest nickname, once it ran out of time to identify.
+    pub(super) fn rename_to_guest(&mut self) -> io::Result<()> {
+        self.nick_protection.stop();
+
+        let mut number = unix_time() % 10000;
+        let guest = loop {
+            let guest = format!("{GUEST_NICKNAME_PREFIX}{number:04}");
+            if !self.database.contains_client(&guest) {
+                break guest;
+            }
+            number = (number + 1) % 10000;
+        };
+
+        self.send_message(&Notification::nick_update(&self.nickname, &guest))?;
+        self.change_nickname(guest);
+
+        Ok(())
+    }
+
+    pub(super) fn is_logged_into(&self, account: &str) -> bool {
+        self.logged_in_account()
+            .is_some_and(|logged_into| irc_eq(&logged_into, account))
+    }
+
+    /// Returns the account the client is logged into, if any.
+    pub(super) fn logged_in_account(&self) -> Option<String> {
+        self.database.get_client_info(&self.nickname).ok()?.account
+    }
+
+    pub(super) fn send_nickserv_notice(&mut self, text: &str) -> io::Result<()> {
+        let notice = Notification::notice(NICKSERV_NICKNAME, &self.nickname, text);
+        self.send_message(&notice)
     }
 }
@@ -1,3 +1,4 @@
+use crate::message::command::*;
 use crate::server::{connection::Connection, responses::ErrorReply};
 use std::io;
 
@@ -9,185 +10,369 @@ use super::{
 pub trait ConnectionHandlerCommands<C: Connection>:
     ConnectionHandlerAsserts<C> + ConnectionHandlerLogic<C> + ConnectionHandlerUtils<C>
 {
-    fn pass_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_pass_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    /// Handles a command already checked and typed, sent from prefix.
+    fn handle_command(&mut self, prefix: Option<String>, command: Command) -> io::Result<bool> {
+        match command {
+            Command::Pass(pass) => self.pass_command((prefix, pass)),
+            Command::Nick(nick) => self.nick_command((prefix, nick)),
+            Command::User(user) => self.user_command((prefix, user)),
+            Command::Oper(oper) => self.oper_command((prefix, oper)),
+            Command::Privmsg(privmsg) => self.privmsg_command((prefix, privmsg)),
+            Command::Notice(notice) => self.notice_command((prefix, notice)),
+            Command::Join(join) => self.join_command((prefix, join)),
+            Command::Part(part) => self.part_command((prefix, part)),
+            Command::Invite(invite) => self.invite_command((prefix, invite)),
+            Command::Names(names) => self.names_command((prefix, names)),
+            Command::List(list) => self.list_command((prefix, list)),
+            Command::Who(who) => self.who_command((prefix, who)),
+            Command::Whois(whois) => self.whois_command((prefix, whois)),
+            Command::Away(away) => self.away_command((prefix, away)),
+            Command::Topic(topic) => self.topic_command((prefix, topic)),
+            Command::Kick(kick) => self.kick_command((prefix, kick)),
+            Command::Mode(mode) => self.mode_command((prefix, mode)),
+            Command::Quit(quit) => self.quit_command((prefix, quit)),
+            Command::Server(server) => self.server_command((prefix, server)),
+            Command::Squit(squit) => self.squit_command((prefix, squit)),
+            Command::Ctcp(ctcp) => self.ctcp_command((prefix, ctcp)),
+            Command::Ping(ping) => self.ping_command((prefix, ping)),
+            Command::Pong(pong) => self.pong_command((prefix, pong)),
+            Command::Motd(motd) => self.motd_command((prefix, motd)),
+            Command::Rehash(rehash) => self.rehash_command((prefix, rehash)),
+            Command::Kill(kill) => self.kill_command((prefix, kill)),
+            Command::Cap(cap) => self.cap_command((prefix, cap)),
+            Command::Authenticate(authenticate) => {
+                self.authenticate_command((prefix, authenticate))
+            }
+            Command::Account(account) => self.account_command((prefix, account)),
+        }
+    }
+
+    fn pass_command(&mut self, arguments: CommandArgs<Pass>) -> io::Result<bool> {
+        let (prefix, pass) = arguments;
+
+        if let Err(error) = self.assert_pass_command_is_valid(&prefix, &pass) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.pass_logic(arguments)
+        self.pass_logic(prefix, pass)
     }
-    fn nick_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_nick_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn nick_command(&mut self, arguments: CommandArgs<Nick>) -> io::Result<bool> {
+        let (prefix, nick) = arguments;
+
+        if let Err(error) = self.assert_nick_command_is_valid(&prefix, &nick) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.nick_logic(arguments)
+        self.nick_logic(prefix, nick)
     }
-    fn user_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_user_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn user_command(&mut self, arguments: CommandArgs<User>) -> io::Result<bool> {
+        let (prefix, user) = arguments;
+
+        if let Err(error) = self.assert_user_command_is_valid(&prefix, &user) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.user_logic(arguments)
+        self.user_logic(prefix, user)
     }
-    fn oper_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_oper_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn oper_command(&mut self, arguments: CommandArgs<Oper>) -> io::Result<bool> {
+        let (prefix, oper) = arguments;
+
+        if let Err(error) = self.assert_oper_command_is_valid(&prefix, &oper) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.oper_logic(arguments)
+        self.oper_logic(prefix, oper)
     }
 
-    fn privmsg_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_privmsg_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn privmsg_command(&mut self, arguments: CommandArgs<Privmsg>) -> io::Result<bool> {
+        let (prefix, privmsg) = arguments;
+
+        if let Err(error) = self.assert_privmsg_command_is_valid(&prefix, &privmsg) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.privmsg_logic(arguments)
+        self.privmsg_logic(prefix, privmsg)
     }
-    fn notice_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_notice_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn notice_command(&mut self, arguments: CommandArgs<Notice>) -> io::Result<bool> {
+        let (prefix, notice) = arguments;
+
+        if let Err(error) = self.assert_notice_command_is_valid(&prefix, &notice) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.notice_logic(arguments)
+        self.notice_logic(prefix, notice)
     }
 
-    fn join_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_join_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn join_command(&mut self, arguments: CommandArgs<Join>) -> io::Result<bool> {
+        let (prefix, join) = arguments;
+
+        if let Err(error) = self.assert_join_command_is_valid(&prefix, &join) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.join_logic(arguments)
+        self.join_logic(prefix, join)
     }
-    fn part_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_part_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn part_command(&mut self, arguments: CommandArgs<Part>) -> io::Result<bool> {
+        let (prefix, part) = arguments;
+
+        if let Err(error) = self.assert_part_command_is_valid(&prefix, &part) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.part_logic(arguments)
+        self.part_logic(prefix, part)
     }
-    fn invite_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_invite_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn invite_command(&mut self, arguments: CommandArgs<Invite>) -> io::Result<bool> {
+        let (prefix, invite) = arguments;
+
+        if let Err(error) = self.assert_invite_command_is_valid(&prefix, &invite) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.invite_logic(arguments)
+        self.invite_logic(prefix, invite)
     }
-    fn names_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_names_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn names_command(&mut self, arguments: CommandArgs<Names>) -> io::Result<bool> {
+        let (prefix, names) = arguments;
+
+        if let Err(error) = self.assert_names_command_is_valid(&prefix, &names) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.names_logic(arguments)
+        self.names_logic(prefix, names)
     }
-    fn list_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_list_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn list_command(&mut self, arguments: CommandArgs<List>) -> io::Result<bool> {
+        let (prefix, list) = arguments;
+
+        if let Err(error) = self.assert_list_command_is_valid(&prefix, &list) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.list_logic(arguments)
+        self.list_logic(prefix, list)
     }
-    fn who_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_who_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn who_command(&mut self, arguments: CommandArgs<Who>) -> io::Result<bool> {
+        let (prefix, who) = arguments;
+
+        if let Err(error) = self.assert_who_command_is_valid(&prefix, &who) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.who_logic(arguments)
+        self.who_logic(prefix, who)
     }
-    fn whois_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_whois_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn whois_command(&mut self, arguments: CommandArgs<Whois>) -> io::Result<bool> {
+        let (prefix, whois) = arguments;
+
+        if let Err(error) = self.assert_whois_command_is_valid(&prefix, &whois) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.whois_logic(arguments)
+        self.whois_logic(prefix, whois)
     }
-    fn away_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_away_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn away_command(&mut self, arguments: CommandArgs<Away>) -> io::Result<bool> {
+        let (prefix, away) = arguments;
+
+        if let Err(error) = self.assert_away_command_is_valid(&prefix, &away) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.away_logic(arguments)
+        self.away_logic(prefix, away)
     }
-    fn topic_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_topic_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn topic_command(&mut self, arguments: CommandArgs<Topic>) -> io::Result<bool> {
+        let (prefix, topic) = arguments;
+
+        if let Err(error) = self.assert_topic_command_is_valid(&prefix, &topic) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.topic_logic(arguments)
+        self.topic_logic(prefix, topic)
     }
-    fn kick_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_kick_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+
+    fn kick_command(&mut self, arguments: CommandArgs<Kick>) -> io::Result<bool> {
+        let (prefix, kick) = arguments;
+
+        if let Err(error) = self.assert_kick_command_is_valid(&prefix, &kick) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.kick_logic(arguments)
+        self.kick_logic(prefix, kick)
     }
 
-    fn mode_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_mode_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn mode_command(&mut self, arguments: CommandArgs<Mode>) -> io::Result<bool> {
+        let (prefix, mode) = arguments;
+
+        if let Err(error) = self.assert_mode_command_is_valid(&prefix, &mode) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.mode_logic(arguments)
+        self.mode_logic(prefix, mode)
     }
 
-    fn quit_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_quit_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn quit_command(&mut self, arguments: CommandArgs<Quit>) -> io::Result<bool> {
+        let (prefix, quit) = arguments;
+
+        if let Err(error) = self.assert_quit_command_is_valid(&prefix, &quit) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.quit_logic(arguments)
+        self.quit_logic(prefix, quit)
     }
 
-    fn server_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_server_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn server_command(&mut self, arguments: CommandArgs<Server>) -> io::Result<bool> {
+        let (prefix, server) = arguments;
+
+        if let Err(error) = self.assert_server_command_is_valid(&prefix, &server) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.server_logic(arguments)
+        self.server_logic(prefix, server)
     }
 
-    fn squit_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_squit_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn squit_command(&mut self, arguments: CommandArgs<Squit>) -> io::Result<bool> {
+        let (prefix, squit) = arguments;
+
+        if let Err(error) = self.assert_squit_command_is_valid(&prefix, &squit) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.squit_logic(arguments)
+        self.squit_logic(prefix, squit)
     }
 
     fn on_unknown_command(&mut self, command: String) -> io::Result<bool> {
-        self.stream()
-            .send(&ErrorReply::UnknownCommand421 { command })?;
+        self.send_response(&ErrorReply::UnknownCommand421 { command })?;
         Ok(true)
     }
 
-    fn ctcp_command(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        if let Err(error) = self.assert_ctcp_command_is_valid(&arguments) {
-            self.stream().send(&error)?;
+    fn ctcp_command(&mut self, arguments: CommandArgs<Ctcp>) -> io::Result<bool> {
+        let (prefix, ctcp) = arguments;
+
+        if let Err(error) = self.assert_ctcp_command_is_valid(&prefix, &ctcp) {
+            self.send_response(&error)?;
             return Ok(true);
         }
 
-        self.ctcp_logic(arguments)
+        self.ctcp_logic(prefix, ctcp)
+    }
+
+    fn ping_command(&mut self, arguments: CommandArgs<Ping>) -> io::Result<bool> {
+        let (prefix, ping) = arguments;
+
+        if let Err(error) = self.assert_ping_command_is_valid(&prefix, &ping) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.ping_logic(prefix, ping)
+    }
+
+    fn pong_command(&mut self, arguments: CommandArgs<Pong>) -> io::Result<bool> {
+        let (prefix, pong) = arguments;
+
+        if let Err(error) = self.assert_pong_command_is_valid(&prefix, &pong) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.pong_logic(prefix, pong)
+    }
+
+    fn motd_command(&mut self, arguments: CommandArgs<Motd>) -> io::Result<bool> {
+        let (prefix, motd) = arguments;
+
+        if let Err(error) = self.assert_motd_command_is_valid(&prefix, &motd) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.motd_logic(prefix, motd)
+    }
+
+    fn rehash_command(&mut self, arguments: CommandArgs<Rehash>) -> io::Result<bool> {
+        let (prefix, rehash) = arguments;
+
+        if let Err(error) = self.assert_rehash_command_is_valid(&prefix, &rehash) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.rehash_logic(prefix, rehash)
+    }
+
+    fn kill_command(&mut self, arguments: CommandArgs<Kill>) -> io::Result<bool> {
+        let (prefix, kill) = arguments;
+
+        if let Err(error) = self.assert_kill_command_is_valid(&prefix, &kill) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.kill_logic(prefix, kill)
+    }
+
+    fn cap_command(&mut self, arguments: CommandArgs<Cap>) -> io::Result<bool> {
+        let (prefix, cap) = arguments;
+
+        if let Err(error) = self.assert_cap_command_is_valid(&prefix, &cap) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.cap_logic(prefix, cap)
+    }
+
+    fn authenticate_command(&mut self, arguments: CommandArgs<Authenticate>) -> io::Result<bool> {
+        let (prefix, authenticate) = arguments;
+
+        if let Err(error) = self.assert_authenticate_command_is_valid(&prefix, &authenticate) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.authenticate_logic(prefix, authenticate)
+    }
+
+    fn account_command(&mut self, arguments: CommandArgs<Account>) -> io::Result<bool> {
+        let (prefix, account) = arguments;
+
+        if let Err(error) = self.assert_account_command_is_valid(&prefix, &account) {
+            self.send_response(&error)?;
+            return Ok(true);
+        }
+
+        self.account_logic(prefix, account)
+    }
+
+    /// Called when a command's arguments could not be built.
+    fn on_command_error(&mut self, _command: &str, error: CommandError) -> io::Result<()> {
+        self.send_response(&ErrorReply::from(error))
     }
 }
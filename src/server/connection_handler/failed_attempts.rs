@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+use crate::server::{FAILED_ATTEMPTS_WINDOW_SECS, MAX_FAILED_ATTEMPTS};
+
+/// Keeps track of failed password attempts, so that
+/// passwords can not be guessed by brute force.
+pub struct FailedAttempts {
+    max_failures: usize,
+    window: Duration,
+    failures: Vec<Instant>,
+}
+
+impl FailedAttempts {
+    /// Creates [`FailedAttempts`] that allows up to `max_failures` failed attempts every `window`.
+    pub fn new(max_failures: usize, window: Duration) -> Self {
+        Self {
+            max_failures,
+            window,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Registers a failed attempt.
+    pub fn failed(&mut self) {
+        self.failures.push(Instant::now());
+    }
+
+    /// Returns true if too many attempts have failed recently.
+    pub fn exceeded(&self) -> bool {
+        self.recent() >= self.max_failures
+    }
+
+    /// Returns true once no failure is recent anymore, so the attempts can be forgotten.
+    pub fn is_expired(&self) -> bool {
+        self.recent() == 0
+    }
+
+    fn recent(&self) -> usize {
+        self.failures
+            .iter()
+            .filter(|failure| failure.elapsed() < self.window)
+            .count()
+    }
+}
+
+impl Default for FailedAttempts {
+    fn default() -> Self {
+        Self::new(
+            MAX_FAILED_ATTEMPTS,
+            Duration::from_secs(FAILED_ATTEMPTS_WINDOW_SECS),
+        )
+    }
+}
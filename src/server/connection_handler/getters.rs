@@ -8,4 +8,6 @@ pub trait ConnectionHandlerGetters<C: Connection> {
     fn online(&self) -> &Arc<AtomicBool>;
     fn stream(&mut self) -> &mut C;
     fn database(&self) -> &DatabaseHandle<C>;
+    /// Name placed as first parameter of numeric replies sent through this connection.
+    fn reply_target(&self) -> String;
 }
@@ -0,0 +1,47 @@
+use std::time::{Duration, Instant};
+
+/// Keeps track of a connection's activity, deciding when it should be
+/// probed with a PING and when it should be considered dead.
+pub struct Keepalive {
+    interval: Duration,
+    timeout: Duration,
+    last_activity: Instant,
+    ping_sent: Option<Instant>,
+}
+
+impl Keepalive {
+    /// Creates a [`Keepalive`] that pings after `interval` without activity,
+    ///  and times out if no answer arrives within `timeout`.
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            timeout,
+            last_activity: Instant::now(),
+            ping_sent: None,
+        }
+    }
+
+    /// Registers activity on the connection, clearing any pending ping.
+    pub fn refresh(&mut self) {
+        self.last_activity = Instant::now();
+        self.ping_sent = None;
+    }
+
+    /// Returns true if the connection has been idle and no ping is pending.
+    pub fn should_ping(&self) -> bool {
+        self.ping_sent.is_none() && self.last_activity.elapsed() >= self.interval
+    }
+
+    /// Registers that a ping was sent.
+    pub fn ping_sent(&mut self) {
+        self.ping_sent = Some(Instant::now());
+    }
+
+    /// Returns true if a ping was sent and was not answered in time.
+    pub fn timed_out(&self) -> bool {
+        match self.ping_sent {
+            Some(ping_sent) => ping_sent.elapsed() >= self.timeout,
+            None => false,
+        }
+    }
+}
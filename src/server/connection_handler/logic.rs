@@ -1,70 +1,97 @@
+use crate::message::command::*;
 use crate::server::connection::Connection;
 use std::io;
 
-use super::structure::CommandArgs;
-
 pub trait ConnectionHandlerLogic<C: Connection> {
-    fn pass_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn pass_logic(&mut self, _prefix: Option<String>, _pass: Pass) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn nick_logic(&mut self, _prefix: Option<String>, _nick: Nick) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn user_logic(&mut self, _prefix: Option<String>, _user: User) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn oper_logic(&mut self, _prefix: Option<String>, _oper: Oper) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn privmsg_logic(&mut self, _prefix: Option<String>, _privmsg: Privmsg) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn notice_logic(&mut self, _prefix: Option<String>, _notice: Notice) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn join_logic(&mut self, _prefix: Option<String>, _join: Join) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn part_logic(&mut self, _prefix: Option<String>, _part: Part) -> io::Result<bool> {
+        Ok(true)
+    }
+    fn invite_logic(&mut self, _prefix: Option<String>, _invite: Invite) -> io::Result<bool> {
         Ok(true)
     }
-    fn nick_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn names_logic(&mut self, _prefix: Option<String>, _names: Names) -> io::Result<bool> {
         Ok(true)
     }
-    fn user_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn list_logic(&mut self, _prefix: Option<String>, _list: List) -> io::Result<bool> {
         Ok(true)
     }
-    fn oper_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn who_logic(&mut self, _prefix: Option<String>, _who: Who) -> io::Result<bool> {
         Ok(true)
     }
-    fn privmsg_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn whois_logic(&mut self, _prefix: Option<String>, _whois: Whois) -> io::Result<bool> {
         Ok(true)
     }
-    fn notice_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn away_logic(&mut self, _prefix: Option<String>, _away: Away) -> io::Result<bool> {
         Ok(true)
     }
-    fn join_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn topic_logic(&mut self, _prefix: Option<String>, _topic: Topic) -> io::Result<bool> {
         Ok(true)
     }
-    fn part_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn kick_logic(&mut self, _prefix: Option<String>, _kick: Kick) -> io::Result<bool> {
         Ok(true)
     }
-    fn invite_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn mode_logic(&mut self, _prefix: Option<String>, _mode: Mode) -> io::Result<bool> {
         Ok(true)
     }
-    fn names_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn quit_logic(&mut self, _prefix: Option<String>, _quit: Quit) -> io::Result<bool> {
         Ok(true)
     }
-    fn list_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn server_logic(&mut self, _prefix: Option<String>, _server: Server) -> io::Result<bool> {
         Ok(true)
     }
-    fn who_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn squit_logic(&mut self, _prefix: Option<String>, _squit: Squit) -> io::Result<bool> {
         Ok(true)
     }
-    fn whois_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn ctcp_logic(&mut self, _prefix: Option<String>, _ctcp: Ctcp) -> io::Result<bool> {
         Ok(true)
     }
-    fn away_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn ping_logic(&mut self, _prefix: Option<String>, _ping: Ping) -> io::Result<bool> {
         Ok(true)
     }
-    fn topic_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn pong_logic(&mut self, _prefix: Option<String>, _pong: Pong) -> io::Result<bool> {
         Ok(true)
     }
-    fn kick_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn motd_logic(&mut self, _prefix: Option<String>, _motd: Motd) -> io::Result<bool> {
         Ok(true)
     }
-    fn mode_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn rehash_logic(&mut self, _prefix: Option<String>, _rehash: Rehash) -> io::Result<bool> {
         Ok(true)
     }
-    fn quit_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn kill_logic(&mut self, _prefix: Option<String>, _kill: Kill) -> io::Result<bool> {
         Ok(true)
     }
-    fn server_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn cap_logic(&mut self, _prefix: Option<String>, _cap: Cap) -> io::Result<bool> {
         Ok(true)
     }
-    fn squit_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn authenticate_logic(
+        &mut self,
+        _prefix: Option<String>,
+        _authenticate: Authenticate,
+    ) -> io::Result<bool> {
         Ok(true)
     }
-    fn ctcp_logic(&mut self, _arguments: CommandArgs) -> io::Result<bool> {
+    fn account_logic(&mut self, _prefix: Option<String>, _account: Account) -> io::Result<bool> {
         Ok(true)
     }
 }
@@ -16,7 +16,10 @@ mod server_handler;
 pub use registration_handler::RegistrationHandler;
 pub use server_handler::ServerHandler;
 
+use std::io;
+
 use crate::server::connection::Connection;
+use crate::server::reactor::{Progress, Task};
 
 /// Contains asserts for every command.
 /// Each handler must implement the asserts it needs.
@@ -31,6 +34,10 @@ mod getters;
 /// Each handler must implement the asserts it needs.
 mod logic;
 
+/// Contains the rate limiting of failed password attempts.
+mod failed_attempts;
+/// Contains the keepalive tracker used to detect dead connections.
+mod keepalive;
 /// Contains the structure and main functionalities all handlers share.
 mod structure;
 /// Contains auxiliars all handlers use.
@@ -41,19 +48,55 @@ pub use commands::ConnectionHandlerCommands;
 pub use getters::ConnectionHandlerGetters;
 pub use logic::ConnectionHandlerLogic;
 
+pub use failed_attempts::FailedAttempts;
+pub use keepalive::Keepalive;
 pub use structure::CommandArgs;
 pub use structure::ConnectionHandlerStructure;
 pub use utils::ConnectionHandlerUtils;
 
-const READ_FROM_STREAM_TIMEOUT_MS: u64 = 100;
+pub const PING_TIMEOUT_MESSAGE: &str = "Ping timeout";
+pub const SERVER_SHUTDOWN_MESSAGE: &str = "Server shutting down";
+pub const CONNECTION_CLOSED_MESSAGE: &str = "Remote host closed the connection";
+pub const CONNECTION_RESET_MESSAGE: &str = "Connection reset by peer";
+pub const INTERNAL_ERROR_MESSAGE: &str = "Internal error";
 
 pub trait ConnectionHandler<C: Connection>:
     Sized + ConnectionHandlerStructure<C> + ConnectionHandlerGetters<C> + ConnectionHandlerCommands<C>
 {
-    fn handle(mut self) {
+    /// Handles whatever the connection has ready, returning what becomes of it.
+    fn poll_connection(&mut self) -> Progress {
         match self.try_handle() {
-            Ok(()) => self.on_try_handle_success(),
-            Err(_) => self.on_try_handle_error(),
+            Ok(true) => Progress::Pending,
+            Ok(false) => {
+                self.on_try_handle_success();
+                match self.successor() {
+                    Some(task) => Progress::Handover(task),
+                    None => Progress::Closed,
+                }
+            }
+            Err(error) => {
+                self.on_try_handle_error(error);
+                Progress::Closed
+            }
         }
     }
+
+    /// Releases what the connection holds after handling it panicked.
+    fn abort_connection(&mut self) {
+        self.on_try_handle_error(io::Error::other(INTERNAL_ERROR_MESSAGE));
+    }
+
+    /// Returns the handler taking the connection over once this one is done with it.
+    fn successor(&mut self) -> Option<Box<dyn Task>> {
+        None
+    }
+}
+
+/// Returns the reason given to others when a connection ends with error.
+pub fn closing_reason(error: &io::Error) -> String {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => CONNECTION_CLOSED_MESSAGE.to_string(),
+        io::ErrorKind::ConnectionReset => CONNECTION_RESET_MESSAGE.to_string(),
+        _ => error.to_string(),
+    }
 }
@@ -1,37 +1,35 @@
+use crate::message::command::*;
 use crate::server::connection::Connection;
-use crate::server::connection_handler::{CommandArgs, ConnectionHandlerAsserts};
-use crate::server::consts::commands::*;
-use crate::server::consts::user::{INVALID_NICKNAME_CHARACTERS, INVALID_NICKNAME_PREFIXES};
+use crate::server::connection_handler::ConnectionHandlerAsserts;
+use crate::server::consts::user::{
+    INVALID_NICKNAME_CHARACTERS, INVALID_NICKNAME_PREFIXES, SERVICE_NICKNAMES,
+};
+use crate::server::data_structures::{irc_eq, Capability};
 use crate::server::responses::ErrorReply;
 
 use super::RegistrationHandler;
 
 impl<C: Connection> ConnectionHandlerAsserts<C> for RegistrationHandler<C> {
-    fn assert_pass_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-
-        if params.is_empty() {
-            let command = PASS_COMMAND.to_string();
-            return Err(ErrorReply::NeedMoreParameters461 { command });
-        }
-
-        if self.attributes.get("nickname").is_some() {
+    fn assert_pass_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pass: &Pass,
+    ) -> Result<(), ErrorReply> {
+        if self.attributes.contains_key("nickname") {
             return Err(ErrorReply::AlreadyRegistered462);
         }
 
         Ok(())
     }
 
-    fn assert_nick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, _) = arguments;
-
-        if params.is_empty() {
-            return Err(ErrorReply::NoNicknameGiven431);
-        }
+    fn assert_nick_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        nick: &Nick,
+    ) -> Result<(), ErrorReply> {
+        let nickname = &nick.nickname;
 
-        let nickname = &params[0];
-
-        if nickname.len() > 9
+        if nickname.len() > self.database.get_limits().nickname_length
             || nickname.contains(INVALID_NICKNAME_CHARACTERS)
             || nickname.starts_with(INVALID_NICKNAME_PREFIXES)
         {
@@ -43,103 +41,230 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for RegistrationHandler<C> {
         self.assert_nickname_collision(nickname)
     }
 
-    fn assert_user_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
-
-        if params.is_empty() || trail.is_none() {
-            let command = USER_COMMAND.to_string();
-            return Err(ErrorReply::NeedMoreParameters461 { command });
-        }
-
-        if self.attributes.get("nickname").is_none() {
+    fn assert_user_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _user: &User,
+    ) -> Result<(), ErrorReply> {
+        if !self.attributes.contains_key("nickname") {
             return Err(ErrorReply::NoNickname);
         }
 
         Ok(())
     }
 
-    fn assert_oper_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_oper_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _oper: &Oper,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_privmsg_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_privmsg_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _privmsg: &Privmsg,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_notice_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_notice_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _notice: &Notice,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_join_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_join_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _join: &Join,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_part_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_part_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _part: &Part,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_invite_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_invite_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _invite: &Invite,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_names_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_names_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _names: &Names,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_list_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_list_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _list: &List,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_who_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_who_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _who: &Who,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_whois_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_whois_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _whois: &Whois,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_away_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_away_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _away: &Away,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_topic_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_topic_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _topic: &Topic,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_kick_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_kick_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _kick: &Kick,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_mode_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_mode_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _mode: &Mode,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_quit_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_quit_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _quit: &Quit,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_server_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
+    fn assert_server_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _server: &Server,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
 
-        if params.len() < 2 || trail.is_none() {
-            let command = SERVER_COMMAND.to_string();
-            return Err(ErrorReply::NeedMoreParameters461 { command });
-        }
+    fn assert_squit_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _squit: &Squit,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NotRegistered451)
+    }
 
-        if params[1].parse::<usize>().is_err() {
-            let command = SERVER_COMMAND.to_string();
-            let message = "Hopcount is not numeric".to_string();
-            return Err(ErrorReply::UnknownError400 { command, message });
-        }
+    fn assert_ctcp_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _ctcp: &Ctcp,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NotRegistered451)
+    }
+
+    fn assert_ping_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _ping: &Ping,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
 
+    fn assert_pong_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pong: &Pong,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_squit_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_motd_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _motd: &Motd,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NotRegistered451)
+    }
+
+    fn assert_rehash_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _rehash: &Rehash,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 
-    fn assert_ctcp_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_kill_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _kill: &Kill,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NotRegistered451)
+    }
+
+    fn assert_cap_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _cap: &Cap,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
+
+    fn assert_authenticate_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _authenticate: &Authenticate,
+    ) -> Result<(), ErrorReply> {
+        if !self.capabilities.contains(&Capability::Sasl) {
+            return Err(ErrorReply::SaslFail904);
+        }
+        if self.attributes.contains_key("account") {
+            return Err(ErrorReply::SaslAlready907);
+        }
+
+        Ok(())
+    }
+
+    fn assert_account_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _account: &Account,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NotRegistered451)
     }
 }
@@ -148,7 +273,11 @@ impl<C: Connection> RegistrationHandler<C> {
     pub fn assert_nickname_collision(&self, nickname: &str) -> Result<(), ErrorReply> {
         let nickname = nickname.to_string();
 
-        if self.database.contains_client(&nickname) {
+        if self.database.contains_client(&nickname)
+            || SERVICE_NICKNAMES
+                .iter()
+                .any(|service| irc_eq(&nickname, service))
+        {
             return Err(ErrorReply::NickCollision436 { nickname });
         }
 
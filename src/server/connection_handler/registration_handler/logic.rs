@@ -1,39 +1,39 @@
 use std::io;
 
+use crate::message::command::names::*;
+use crate::message::command::{
+    Authenticate, Cap, CapSubcommand, Nick, Pass, Ping, Quit, Server, User,
+};
 use crate::server::connection::Connection;
-use crate::server::connection_handler::{CommandArgs, ConnectionHandlerLogic};
-use crate::server::responses::CommandResponse;
+use crate::server::connection_handler::{ConnectionHandlerLogic, ConnectionHandlerUtils};
+use crate::server::data_structures::Capability;
+use crate::server::responses::{CommandResponse, Notification};
 use crate::server::server_connection_setup::ServerConnectionSetup;
 
 use super::connection_type::ConnectionType;
 use super::RegistrationHandler;
 
 impl<C: Connection> ConnectionHandlerLogic<C> for RegistrationHandler<C> {
-    fn pass_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, mut params, _) = arguments;
-
-        let password = params.remove(0);
-
-        self.attributes.insert("password", password);
+    fn pass_logic(&mut self, _prefix: Option<String>, pass: Pass) -> io::Result<bool> {
+        self.attributes.insert("password", pass.password);
 
         Ok(true)
     }
 
-    fn nick_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, mut params, _) = arguments;
-
-        let nickname = params.remove(0);
+    fn nick_logic(&mut self, _prefix: Option<String>, nick: Nick) -> io::Result<bool> {
+        self.attributes.insert("nickname", nick.nickname);
 
-        self.attributes.insert("nickname", nickname);
+        if self.is_ready_to_register() {
+            return self.complete_registration();
+        }
 
         Ok(true)
     }
 
-    fn user_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (_, mut params, trail) = arguments;
-
-        let realname = trail.expect("Verified in assert");
-        let username = params.remove(0);
+    fn user_logic(&mut self, _prefix: Option<String>, user: User) -> std::io::Result<bool> {
+        let User {
+            username, realname, ..
+        } = user;
         let servername = self.database.get_server_name();
         let hostname = self.stream.peer_address()?.ip().to_string();
 
@@ -42,36 +42,30 @@ impl<C: Connection> ConnectionHandlerLogic<C> for RegistrationHandler<C> {
         self.attributes.insert("servername", servername);
         self.attributes.insert("realname", realname);
 
-        let client = self
-            .build_client()
-            .expect("Client's information should be complete to build");
-        let client_info = client.get_info();
-        self.send_new_client_notification(&client_info);
-        self.database.add_local_client(client);
-
-        self.connection_type = ConnectionType::Client;
-
-        self.send_welcome_response(client_info)?;
+        if self.negotiating {
+            return Ok(true);
+        }
 
-        Ok(false)
+        self.complete_registration()
     }
 
-    fn server_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, mut params, trail) = arguments;
-
-        let hopcount = params
-            .remove(1)
-            .parse::<usize>()
-            .expect("Verified in assert");
-        let servername = params.remove(0);
-        let serverinfo = trail.expect("Verified in assert");
+    fn server_logic(&mut self, _prefix: Option<String>, server: Server) -> io::Result<bool> {
+        let Server {
+            servername,
+            hopcount,
+            info: serverinfo,
+        } = server;
+        let password = self.attributes.remove("password");
+
+        let mut registerer = ServerConnectionSetup::new(
+            self.stream.try_clone()?,
+            self.output.clone(),
+            self.database.clone(),
+        );
+        registerer.register_incoming(servername.clone(), hopcount, serverinfo.clone(), password)?;
 
         self.send_server_notification(&servername, hopcount, &serverinfo);
 
-        let mut registerer =
-            ServerConnectionSetup::new(self.stream.try_clone()?, self.database.clone());
-        registerer.register_incoming(servername, hopcount, serverinfo)?;
-
         self.connection_type = ConnectionType::Server;
         self.attributes
             .insert("servername", registerer.servername());
@@ -79,14 +73,61 @@ impl<C: Connection> ConnectionHandlerLogic<C> for RegistrationHandler<C> {
         Ok(false)
     }
 
-    fn quit_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, _, trail) = arguments;
+    fn quit_logic(&mut self, _prefix: Option<String>, quit: Quit) -> io::Result<bool> {
+        let message = quit
+            .message
+            .unwrap_or_else(|| self.attributes.remove("nickname").unwrap_or_default());
 
-        let message =
-            trail.unwrap_or_else(|| self.attributes.remove("nickname").unwrap_or_default());
-
-        self.stream.send(&CommandResponse::quit(&message))?;
+        self.send_message(&CommandResponse::quit(&message))?;
 
         Ok(false)
     }
+
+    fn cap_logic(&mut self, _prefix: Option<String>, cap: Cap) -> io::Result<bool> {
+        match cap.subcommand {
+            CapSubcommand::Ls => {
+                self.negotiating = true;
+                self.send_cap_reply(CAP_LS, &Capability::names(&Capability::all()))?;
+            }
+            CapSubcommand::List => {
+                let capabilities = Capability::names(&self.capabilities);
+                self.send_cap_reply(CAP_LIST, &capabilities)?;
+            }
+            CapSubcommand::Req => {
+                self.negotiating = true;
+                let request = cap.arguments.unwrap_or_default();
+                let reply = match Capability::apply_request(&mut self.capabilities, &request) {
+                    true => CAP_ACK,
+                    false => CAP_NAK,
+                };
+                self.send_cap_reply(reply, &request)?;
+            }
+            CapSubcommand::End => {
+                self.negotiating = false;
+                if self.is_ready_to_register() {
+                    return self.complete_registration();
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn authenticate_logic(
+        &mut self,
+        _prefix: Option<String>,
+        authenticate: Authenticate,
+    ) -> io::Result<bool> {
+        self.sasl_request(authenticate.data)?;
+
+        Ok(true)
+    }
+
+    fn ping_logic(&mut self, _prefix: Option<String>, ping: Ping) -> io::Result<bool> {
+        let servername = self.database.get_server_name();
+
+        self.send_message(&Notification::pong(&servername, &ping.token))?;
+
+        Ok(true)
+    }
 }
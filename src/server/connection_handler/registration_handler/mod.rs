@@ -2,16 +2,25 @@ use std::{
     collections::HashMap,
     io,
     sync::{atomic::AtomicBool, Arc},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use crate::server::{connection::Connection, database::DatabaseHandle};
+use crate::message::command::names::*;
+use crate::message::{CommandError, CreationError, LineReader, Message, ToMessage};
+use crate::server::responses::{CommandResponse, ErrorReply};
+use crate::server::{
+    connection::Connection,
+    data_structures::{Capability, OutputQueue},
+    database::DatabaseHandle,
+    reactor::{Progress, Task, Waker},
+};
 
 use self::connection_type::ConnectionType;
 
 use super::{
     client_handler::ClientHandler, ConnectionHandler, ConnectionHandlerCommands,
-    ConnectionHandlerGetters, ConnectionHandlerStructure, ServerHandler,
+    ConnectionHandlerGetters, ConnectionHandlerStructure, ConnectionHandlerUtils, FailedAttempts,
+    ServerHandler,
 };
 /// Asserts to ensure the commands received are valid.
 mod asserts;
@@ -19,6 +28,8 @@ mod asserts;
 mod connection_type;
 /// Logic for the commands a connection may send.
 mod logic;
+/// Logging into an account with SASL before registering.
+mod sasl;
 /// Extra functions that help with the command's logic.
 mod utils;
 
@@ -26,21 +37,80 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
-const REGISTRATION_TIMELIMIT_SECS: u64 = 60;
+const UNREGISTERED_TARGET: &str = "*";
+const REGISTRATION_TIMEOUT_MESSAGE: &str = "Registration timeout";
+/// Commands that can be handled before registering.
+const REGISTRATION_COMMANDS: [&str; 9] = [
+    PASS_COMMAND,
+    NICK_COMMAND,
+    USER_COMMAND,
+    SERVER_COMMAND,
+    QUIT_COMMAND,
+    PING_COMMAND,
+    PONG_COMMAND,
+    CAP_COMMAND,
+    AUTHENTICATE_COMMAND,
+];
 
 /// A Registration Handler handles a new connection.
 /// It must save all new information in order to start corresponding handler later.
 pub struct RegistrationHandler<C: Connection> {
     stream: C,
-    stream_for_database: Option<C>,
+    /// handed to the registered client or server, so nothing queued is lost
+    output: OutputQueue<C>,
     database: DatabaseHandle<C>,
     online: Arc<AtomicBool>,
     attributes: HashMap<&'static str, String>,
     timestamp: Instant,
+    timelimit: Duration,
     connection_type: ConnectionType,
+    /// capabilities requested with CAP, given to the client once registered
+    capabilities: Vec<Capability>,
+    /// while true, registration is held until CAP END is received
+    negotiating: bool,
+    /// payload received so far while logging in with SASL, none when not logging in
+    sasl: Option<String>,
+    login_attempts: FailedAttempts,
+    /// handed to the spawned handler, so lines already buffered are not lost
+    reader: LineReader,
+}
+
+impl<C: Connection> ConnectionHandler<C> for RegistrationHandler<C> {
+    /// Once registered, the connection is taken over by a client or server handler.
+    fn successor(&mut self) -> Option<Box<dyn Task>> {
+        let handler = match self.connection_type {
+            ConnectionType::Undefined => return None,
+            ConnectionType::Server => self
+                .build_server_handler()
+                .map(|handler| Box::new(handler) as Box<dyn Task>),
+            ConnectionType::Client => self
+                .build_client_handler()
+                .map(|handler| Box::new(handler) as Box<dyn Task>),
+        };
+
+        match handler {
+            Ok(handler) => Some(handler),
+            Err(error) => {
+                eprintln!("Could not initiate handler, {error:?}");
+                None
+            }
+        }
+    }
 }
 
-impl<C: Connection> ConnectionHandler<C> for RegistrationHandler<C> {}
+impl<C: Connection> Task for RegistrationHandler<C> {
+    fn poll(&mut self) -> Progress {
+        self.poll_connection()
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        self.output.set_waker(waker);
+    }
+
+    fn abort(&mut self) {
+        self.abort_connection();
+    }
+}
 
 impl<C: Connection> RegistrationHandler<C> {
     /// Starts a [`RegistrationHandler`] with the received information.
@@ -49,28 +119,26 @@ impl<C: Connection> RegistrationHandler<C> {
         database: DatabaseHandle<C>,
         online: Arc<AtomicBool>,
     ) -> std::io::Result<Self> {
-        let stream_for_database = Some(stream.try_clone()?);
+        let limits = database.get_limits();
+        let output = OutputQueue::new(stream.try_clone()?, limits.sendq);
 
         Ok(Self {
             stream,
-            stream_for_database,
+            output,
             database,
             online,
             attributes: HashMap::new(),
             timestamp: Instant::now(),
+            timelimit: limits.registration_timeout,
             connection_type: ConnectionType::Undefined,
+            capabilities: Vec::new(),
+            negotiating: false,
+            sasl: None,
+            login_attempts: Default::default(),
+            reader: LineReader::new(),
         })
     }
 
-    fn spawn_client_handler(&mut self) {
-        let client_handler = match self.build_client_handler() {
-            Ok(client_handler) => client_handler,
-            Err(error) => return eprintln!("Could not initiate client handler, {error:?}"),
-        };
-
-        client_handler.handle();
-    }
-
     fn build_client_handler(&mut self) -> io::Result<ClientHandler<C>> {
         ClientHandler::from_connection(
             self.stream().try_clone()?,
@@ -80,14 +148,11 @@ impl<C: Connection> RegistrationHandler<C> {
             self.database().clone(),
             Arc::clone(self.online()),
         )
-    }
-
-    fn spawn_server_handler(&mut self) {
-        let server_handler = match self.build_server_handler() {
-            Ok(server_handler) => server_handler,
-            Err(error) => return eprintln!("Could not initiate server handler, {error:?}"),
-        };
-        server_handler.handle();
+        .map(|handler| {
+            handler
+                .with_reader(std::mem::take(&mut self.reader))
+                .with_login_attempts(std::mem::take(&mut self.login_attempts))
+        })
     }
 
     fn build_server_handler(&mut self) -> io::Result<ServerHandler<C>> {
@@ -99,6 +164,7 @@ impl<C: Connection> RegistrationHandler<C> {
             self.database().clone(),
             Arc::clone(self.online()),
         )
+        .map(|handler| handler.with_reader(std::mem::take(&mut self.reader)))
     }
 }
 
@@ -114,31 +180,66 @@ impl<C: Connection> ConnectionHandlerGetters<C> for RegistrationHandler<C> {
     fn database(&self) -> &DatabaseHandle<C> {
         &self.database
     }
+
+    /// Before a nickname is given, replies are addressed to `*`.
+    fn reply_target(&self) -> String {
+        self.attributes
+            .get("nickname")
+            .cloned()
+            .unwrap_or_else(|| UNREGISTERED_TARGET.to_string())
+    }
 }
 
 impl<C: Connection> ConnectionHandlerStructure<C> for RegistrationHandler<C> {
-    fn on_try_handle_error(&mut self) {
+    fn read_message(&mut self) -> Result<Message, CreationError> {
+        self.reader.read_message(&mut self.stream)
+    }
+
+    fn on_try_handle_error(&mut self, _error: io::Error) {
         println!("Connection with unregistered client ended unexpectedly")
     }
-    /// Spawns corresponding handler according to Connection Type.
     fn on_try_handle_success(&mut self) {
-        match self.connection_type {
-            ConnectionType::Undefined => println!("Closing connection with unregistered client"),
-            ConnectionType::Server => self.spawn_server_handler(),
-            ConnectionType::Client => self.spawn_client_handler(),
+        if let ConnectionType::Undefined = self.connection_type {
+            println!("Closing connection with unregistered client")
         }
     }
 
     fn timeout(&mut self) -> bool {
-        let elapsed_time = Instant::now().duration_since(self.timestamp);
-        let elapsed_time_secs = elapsed_time.as_secs();
-
-        elapsed_time_secs > REGISTRATION_TIMELIMIT_SECS
+        self.timestamp.elapsed() > self.timelimit
     }
 
     fn on_timeout(&mut self) -> io::Result<()> {
-        self.stream.send(&"Registration timeout")
+        self.send_message(&CommandResponse::error(REGISTRATION_TIMEOUT_MESSAGE))
+    }
+
+    fn sendq_exceeded(&mut self) -> bool {
+        self.output.exceeded()
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    fn close_output(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.close(message)
+    }
+
+    fn closing(&mut self) -> bool {
+        self.output.closing()
+    }
+
+    fn queued_output(&mut self) -> usize {
+        self.output.queued()
     }
 }
 
-impl<C: Connection> ConnectionHandlerCommands<C> for RegistrationHandler<C> {}
+impl<C: Connection> ConnectionHandlerCommands<C> for RegistrationHandler<C> {
+    /// Other commands need registration first, whatever their arguments are.
+    fn on_command_error(&mut self, command: &str, error: CommandError) -> io::Result<()> {
+        if !REGISTRATION_COMMANDS.contains(&command) {
+            return self.send_response(&ErrorReply::NotRegistered451);
+        }
+
+        self.send_response(&ErrorReply::from(error))
+    }
+}
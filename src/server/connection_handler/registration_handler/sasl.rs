@@ -0,0 +1,119 @@
+use std::io;
+
+use crate::macros::some_or_return;
+use crate::message::command::names::{AUTHENTICATE_ABORT, AUTHENTICATE_EMPTY};
+use crate::server::connection::Connection;
+use crate::server::connection_handler::{ConnectionHandlerGetters, ConnectionHandlerUtils};
+use crate::server::data_structures::irc_eq;
+use crate::server::responses::{CommandResponse, ErrorReply, Notification};
+
+use super::RegistrationHandler;
+
+/// The only SASL mechanism supported, credentials are sent as `authzid\0authcid\0password`.
+const SASL_MECHANISM: &str = "PLAIN";
+/// Payloads longer than this are split in several AUTHENTICATE messages.
+const SASL_CHUNK_LENGTH: usize = 400;
+/// Longest payload accepted, PLAIN credentials fit well within it.
+const SASL_MAX_PAYLOAD: usize = 4096;
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl<C: Connection> RegistrationHandler<C> {
+    /// Handles each step of a SASL exchange: the mechanism first, then the payload in chunks.
+    pub(super) fn sasl_request(&mut self, data: String) -> io::Result<()> {
+        if data == AUTHENTICATE_ABORT {
+            self.sasl = None;
+            return self.send_response(&ErrorReply::SaslAborted906);
+        }
+
+        let mut payload = some_or_return!(self.sasl.take(), self.start_sasl(&data));
+        if data != AUTHENTICATE_EMPTY {
+            payload.push_str(&data);
+        }
+        if payload.len() > SASL_MAX_PAYLOAD {
+            return self.send_response(&ErrorReply::SaslFail904);
+        }
+        if data.len() == SASL_CHUNK_LENGTH {
+            self.sasl = Some(payload);
+            return Ok(());
+        }
+
+        self.finish_sasl(&payload)
+    }
+
+    fn start_sasl(&mut self, mechanism: &str) -> io::Result<()> {
+        if self.login_attempts.exceeded() {
+            return self.send_response(&ErrorReply::SaslFail904);
+        }
+        if !mechanism.eq_ignore_ascii_case(SASL_MECHANISM) {
+            self.send_response(&CommandResponse::sasl_mechanisms(SASL_MECHANISM))?;
+            return self.send_response(&ErrorReply::SaslFail904);
+        }
+
+        self.sasl = Some(String::new());
+        self.send_message(&Notification::authenticate(AUTHENTICATE_EMPTY))
+    }
+
+    fn finish_sasl(&mut self, payload: &str) -> io::Result<()> {
+        let account = match self.plain_account(payload) {
+            Some(account) => account,
+            None => {
+                self.login_attempts.failed();
+                return self.send_response(&ErrorReply::SaslFail904);
+            }
+        };
+
+        let mask = format!(
+            "{}!{}@{}",
+            self.reply_target(),
+            self.attributes.get("username").map_or("*", String::as_str),
+            self.attributes.get("hostname").map_or("*", String::as_str),
+        );
+        self.send_response(&CommandResponse::logged_in(&mask, &account))?;
+        self.attributes.insert("account", account);
+
+        self.send_response(&CommandResponse::sasl_success())
+    }
+
+    /// Returns the account PLAIN credentials log into, if the password is right.
+    /// The authorization identity must be empty or the account itself.
+    fn plain_account(&self, payload: &str) -> Option<String> {
+        let credentials = String::from_utf8(decode_base64(payload)?).ok()?;
+        let mut credentials = credentials.split('\0');
+
+        let authorization = credentials.next()?;
+        let name = credentials.next()?;
+        let password = credentials.next()?;
+        if credentials.next().is_some()
+            || !(authorization.is_empty() || irc_eq(authorization, name))
+        {
+            return None;
+        }
+
+        let account = self.database.get_account(name)?;
+        account.verify(password).then_some(account.name)
+    }
+}
+
+/// Decodes standard base64, padded or not. Returns None if data is not valid base64.
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for character in data.trim_end_matches('=').bytes() {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&symbol| symbol == character)?;
+
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
@@ -0,0 +1,197 @@
+use crate::server::data_structures::Account;
+
+use super::*;
+
+fn request_sasl(handler: &mut RegistrationHandler<MockTcpStream>) {
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("sasl".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    handler
+        .database
+        .add_account(Account::new("nickname", "password", 1669831542));
+    handler.stream.clear();
+}
+
+fn authenticate(handler: &mut RegistrationHandler<MockTcpStream>, data: &str) {
+    let parameters = vec![data.to_string()];
+    handler
+        .raw_command("AUTHENTICATE", (None, parameters, None))
+        .unwrap();
+}
+
+#[test]
+fn authenticate_fails_without_requesting_sasl() {
+    let mut handler = dummy_registration_handler();
+
+    authenticate(&mut handler, "PLAIN");
+
+    assert_eq!(
+        ":servername 904 * :SASL authentication failed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn authenticate_plain_logs_into_account() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    assert_eq!("AUTHENTICATE +\r\n", handler.stream.read_wbuf_to_string());
+    handler.stream.clear();
+
+    authenticate(&mut handler, "AG5pY2tuYW1lAHBhc3N3b3Jk");
+
+    assert_eq!(
+        ":servername 900 nickname nickname!*@* nickname :You are now logged in as nickname\r\n\
+         :servername 903 nickname :SASL authentication successful\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        Some(&"nickname".to_string()),
+        handler.attributes.get("account")
+    );
+}
+
+#[test]
+fn authenticate_fails_with_wrong_password() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    handler.stream.clear();
+    authenticate(&mut handler, "AG5pY2tuYW1lAHdyb25n");
+
+    assert_eq!(
+        ":servername 904 nickname :SASL authentication failed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.attributes.get("account"));
+}
+
+#[test]
+fn authenticate_fails_for_other_authorization_identity() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    handler.stream.clear();
+    authenticate(&mut handler, "b3RoZXIAbmlja25hbWUAcGFzc3dvcmQ=");
+
+    assert_eq!(
+        ":servername 904 nickname :SASL authentication failed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn authenticate_with_unknown_mechanism_lists_supported_ones() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "EXTERNAL");
+
+    assert_eq!(
+        ":servername 908 nickname PLAIN :are available SASL mechanisms\r\n\
+         :servername 904 nickname :SASL authentication failed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn authenticate_can_be_aborted() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    handler.stream.clear();
+    authenticate(&mut handler, "*");
+
+    assert_eq!(
+        ":servername 906 nickname :SASL authentication aborted\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(None, handler.sasl);
+}
+
+#[test]
+fn authenticate_fails_once_logged_in() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    authenticate(&mut handler, "AG5pY2tuYW1lAHBhc3N3b3Jk");
+    handler.stream.clear();
+    authenticate(&mut handler, "PLAIN");
+
+    assert_eq!(
+        ":servername 907 nickname :You have already authenticated using SASL\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn logged_in_client_registers_with_account() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    authenticate(&mut handler, "AG5pY2tuYW1lAHBhc3N3b3Jk");
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+    let parameters = vec!["END".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    let info = handler.database.get_client_info("nickname").unwrap();
+    assert_eq!(Some("nickname".to_string()), info.account);
+}
+
+#[test]
+fn authenticate_fails_when_payload_is_too_long() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    authenticate(&mut handler, "PLAIN");
+    let chunk = "A".repeat(400);
+    for _ in 0..11 {
+        authenticate(&mut handler, &chunk);
+    }
+
+    assert!(handler
+        .stream
+        .read_wbuf_to_string()
+        .ends_with(":servername 904 nickname :SASL authentication failed\r\n"));
+    assert_eq!(None, handler.sasl);
+}
+
+#[test]
+fn authenticate_is_refused_after_too_many_failures() {
+    let mut handler = dummy_registration_handler();
+    request_sasl(&mut handler);
+
+    for _ in 0..3 {
+        authenticate(&mut handler, "PLAIN");
+        authenticate(&mut handler, "AG5pY2tuYW1lAHdyb25n");
+    }
+    handler.stream.clear();
+    authenticate(&mut handler, "PLAIN");
+
+    assert_eq!(
+        ":servername 904 nickname :SASL authentication failed\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
@@ -0,0 +1,224 @@
+use crate::server::data_structures::Capability;
+
+use super::*;
+
+fn register(handler: &mut RegistrationHandler<MockTcpStream>) -> bool {
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap()
+}
+
+#[test]
+fn cap_fails_with_empty_parameters() {
+    let mut handler = dummy_registration_handler();
+
+    handler.raw_command("CAP", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 461 * CAP :Not enough parameters\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_fails_with_unknown_subcommand() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["FOO".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 410 * FOO :Invalid CAP command\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_ls_lists_supported_capabilities() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["LS".to_string(), "302".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP * LS :server-time multi-prefix away-notify sasl\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_req_acknowledges_supported_capabilities() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("server-time multi-prefix".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP * ACK :server-time multi-prefix\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_req_with_unknown_capability_is_rejected_entirely() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("server-time unknown".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+    handler.stream.clear();
+
+    let parameters = vec!["LIST".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP * LIST :\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_req_nak_echoes_request() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("server-time unknown".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP * NAK :server-time unknown\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_req_can_disable_capabilities() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("server-time away-notify".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("-server-time".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+    handler.stream.clear();
+
+    let parameters = vec!["LIST".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP * LIST :away-notify\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_replies_are_addressed_to_nickname_once_given() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["LIST".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername CAP nickname LIST :\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn cap_ls_holds_registration_until_cap_end() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["LS".to_string(), "302".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert!(register(&mut handler));
+    assert!(!handler.database.contains_client("nickname"));
+
+    let parameters = vec!["END".to_string()];
+    assert!(!handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap());
+    assert!(handler.database.contains_client("nickname"));
+}
+
+#[test]
+fn cap_end_before_user_does_not_register() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["LS".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+    let parameters = vec!["END".to_string()];
+    assert!(handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap());
+
+    assert!(!register(&mut handler));
+    assert!(handler.database.contains_client("nickname"));
+}
+
+#[test]
+fn registration_without_cap_is_not_held() {
+    let mut handler = dummy_registration_handler();
+
+    assert!(!register(&mut handler));
+    assert!(handler.database.contains_client("nickname"));
+}
+
+#[test]
+fn registered_client_keeps_requested_capabilities() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["REQ".to_string()];
+    let trailing = Some("server-time".to_string());
+    handler
+        .raw_command("CAP", (None, parameters, trailing))
+        .unwrap();
+    register(&mut handler);
+    let parameters = vec!["END".to_string()];
+    handler
+        .raw_command("CAP", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        vec![Capability::ServerTime],
+        handler.database.get_client_capabilities("nickname")
+    );
+}
@@ -1,7 +1,9 @@
-use crate::server::testing::{dummy_database, MockTcpStream};
+use crate::server::testing::{dummy_database, HandleRawCommand, MockTcpStream, ReadQueuedOutput};
 
 use super::*;
 
+mod authenticate_tests;
+mod cap_tests;
 mod nick_tests;
 mod non_registration_tests;
 mod pass_tests;
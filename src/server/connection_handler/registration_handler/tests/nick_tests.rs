@@ -1,4 +1,5 @@
 use crate::server::testing::dummy_client;
+use crate::server::Limits;
 
 use super::*;
 
@@ -7,10 +8,12 @@ fn nick_fails_with_no_nickname_given() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "431 :No nickname given\r\n",
+        ":servername 431 * :No nickname given\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -21,10 +24,12 @@ fn nick_fails_with_nickname_collision() {
     handler.database.add_local_client(dummy_client("nickname"));
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "436 nickname :Nickname collision KILL\r\n",
+        ":servername 436 * nickname :Nickname collision KILL\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -34,7 +39,9 @@ fn can_set_nickname() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 
@@ -46,47 +53,74 @@ fn nick_fails_with_long_nickname() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nicknamenickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nicknamenickname :Erroneous nickname\r\n",
+        ":servername 432 * nicknamenickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
+#[test]
+fn nickname_length_is_configurable() {
+    let mut handler = dummy_registration_handler();
+    let limits = Limits {
+        nickname_length: 16,
+        ..Default::default()
+    };
+    handler.database.set_limits(limits);
+
+    let parameters = vec!["nicknamenickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+}
+
 #[test]
 fn nick_fails_with_invalid_prefix() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["#nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 #nickname :Erroneous nickname\r\n",
+        ":servername 432 * #nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["&nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 &nickname :Erroneous nickname\r\n",
+        ":servername 432 * &nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["$nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 $nickname :Erroneous nickname\r\n",
+        ":servername 432 * $nickname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
-    let parameters = vec![":nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    let trailing = Some(":nickname".to_string());
+    handler
+        .raw_command("NICK", (None, vec![], trailing))
+        .unwrap();
 
     assert_eq!(
-        "432 :nickname :Erroneous nickname\r\n",
+        ":servername 431 * :No nickname given\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -96,50 +130,62 @@ fn nick_fails_with_invalid_character() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nick.name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nick.name :Erroneous nickname\r\n",
+        ":servername 432 * nick.name :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nickname!".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nickname! :Erroneous nickname\r\n",
+        ":servername 432 * nickname! :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["ni,ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 ni,ckname :Erroneous nickname\r\n",
+        ":servername 432 * ni,ckname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nick*name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nick*name :Erroneous nickname\r\n",
+        ":servername 432 * nick*name :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["ni?ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 ni?ckname :Erroneous nickname\r\n",
+        ":servername 432 * ni?ckname :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 
     let parameters = vec!["nickname@".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "432 nickname@ :Erroneous nickname\r\n",
+        ":servername 432 * nickname@ :Erroneous nickname\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -5,10 +5,12 @@ fn away_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let trailing = None;
-    handler.away_command((None, vec![], trailing)).unwrap();
+    handler
+        .raw_command("AWAY", (None, vec![], trailing))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -18,10 +20,12 @@ fn invite_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.invite_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("INVITE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -31,10 +35,12 @@ fn join_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.join_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -45,10 +51,12 @@ fn kick_fails_with_unregistered_client() {
 
     let parameters = vec![];
     let trailing = None;
-    handler.kick_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("KICK", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -58,10 +66,12 @@ fn list_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.list_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("LIST", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -71,10 +81,12 @@ fn mode_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -84,10 +96,12 @@ fn names_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.names_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NAMES", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -99,11 +113,11 @@ fn notice_fails_with_unregistered_client() {
     let parameters = vec![];
     let trailing = None;
     handler
-        .notice_command((None, parameters, trailing))
+        .raw_command("NOTICE", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -113,10 +127,12 @@ fn oper_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.oper_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("OPER", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -126,10 +142,12 @@ fn part_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.part_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -141,11 +159,11 @@ fn privmsg_fails_with_unregistered_client() {
     let parameters = vec![];
     let trailing = None;
     handler
-        .privmsg_command((None, parameters, trailing))
+        .raw_command("PRIVMSG", (None, parameters, trailing))
         .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -155,10 +173,12 @@ fn topic_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.topic_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -168,10 +188,12 @@ fn who_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.who_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHO", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
@@ -181,10 +203,66 @@ fn who_is_fails_with_unregistered_client() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.whois_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("WHOIS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "451 :You have not registered\r\n",
+        ":servername 451 * :You have not registered\r\n",
         handler.stream.read_wbuf_to_string()
     )
 }
+
+#[test]
+fn motd_fails_with_unregistered_client() {
+    let mut handler = dummy_registration_handler();
+
+    handler.raw_command("MOTD", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 451 * :You have not registered\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn rehash_fails_with_unregistered_client() {
+    let mut handler = dummy_registration_handler();
+
+    handler.raw_command("REHASH", (None, vec![], None)).unwrap();
+
+    assert_eq!(
+        ":servername 451 * :You have not registered\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn kill_fails_with_unregistered_client() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":servername 451 * :You have not registered\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn client_that_does_not_register_in_time_is_told_before_closing() {
+    let mut handler = dummy_registration_handler();
+    handler.timelimit = Duration::ZERO;
+    handler.timestamp = Instant::now() - Duration::from_secs(1);
+
+    assert!(!handler.try_handle().unwrap());
+
+    assert_eq!(
+        "ERROR :Registration timeout\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+}
@@ -5,10 +5,12 @@ fn pass_fails_with_empty_params() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec![];
-    handler.pass_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "461 PASS :Not enough parameters\r\n",
+        ":servername 461 * PASS :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -18,10 +20,14 @@ fn pass_sets_connection_password() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["pass".to_string()];
-    handler.pass_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
 
     let parameters = vec!["pass2".to_string()];
-    handler.pass_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
 
     assert_eq!("pass2", handler.attributes.get("password").unwrap())
 }
@@ -31,11 +37,15 @@ fn pass_fails_after_nick() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nick".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     let parameters = vec!["pass".to_string()];
-    handler.pass_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
-    assert_eq!("462 :You may not reregister", responses[0]);
+    assert_eq!(":servername 462 nick :You may not reregister", responses[0]);
 }
@@ -2,14 +2,28 @@ use crate::server::testing::{dummy_client, dummy_server};
 
 use super::*;
 
+fn dummy_linked_registration_handler() -> RegistrationHandler<MockTcpStream> {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .set_link_password("servername1", "linkpassword");
+
+    let parameters = vec!["linkpassword".to_string()];
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
+
+    handler
+}
+
 #[test]
 fn server_fails_with_not_enough_parameters() {
     let mut handler = dummy_registration_handler();
 
-    handler.server_command((None, vec![], None)).unwrap();
+    handler.raw_command("SERVER", (None, vec![], None)).unwrap();
 
     assert_eq!(
-        "461 SERVER :Not enough parameters\r\n",
+        ":servername 461 * SERVER :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -20,80 +34,88 @@ fn server_fails_with_no_numeric_hopcount() {
 
     let parameters = vec!["servername1".to_string(), "nonumeric".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
-        "400 SERVER :Hopcount is not numeric\r\n",
+        ":servername 400 * SERVER :Hopcount is not numeric\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
 #[test]
 fn server_fails_when_servername_already_registered() {
-    let mut handler = dummy_registration_handler();
+    let mut handler = dummy_linked_registration_handler();
     handler
         .database
         .add_immediate_server(dummy_server("servername1"));
 
     let parameters = vec!["servername1".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    let result = handler.server_command((None, parameters, trail));
+    let result = handler.raw_command("SERVER", (None, parameters, trail));
 
     assert!(result.is_err());
 
     assert_eq!(
-        "400 SERVER :Servername already registered\r\n",
+        ":servername 400 servername1 SERVER :Servername already registered\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
 #[test]
 fn server_sets_connection_type() {
-    let mut handler = dummy_registration_handler();
+    let mut handler = dummy_linked_registration_handler();
 
     let parameters = vec!["servername1".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert!(matches!(handler.connection_type, ConnectionType::Server));
 }
 
 #[test]
 fn server_sends_back_server() {
-    let mut handler = dummy_registration_handler();
+    let mut handler = dummy_linked_registration_handler();
 
     let parameters = vec!["servername1".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
-        "SERVER servername 1 :serverinfo\r\n",
+        "PASS linkpassword\r\nSERVER servername 1 :serverinfo\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
 
 #[test]
 fn server_sends_back_client_info() {
-    let mut handler = dummy_registration_handler();
+    let mut handler = dummy_linked_registration_handler();
 
     handler.database.add_local_client(dummy_client("nickname1"));
 
     let parameters = vec!["servername1".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     let responses = handler.stream.get_responses();
-    assert_eq!("SERVER servername 1 :serverinfo", responses[0]);
-    assert_eq!("NICK nickname1 1", responses[1]);
+    assert_eq!("SERVER servername 1 :serverinfo", responses[1]);
+    assert_eq!("NICK nickname1 1 1000000000", responses[2]);
     assert_eq!(
         ":nickname1 USER username 127.0.0.1 servername :realname",
-        responses[2]
+        responses[3]
     );
 }
 
 #[test]
 fn server_is_relayed_to_all_other_servers() {
-    let mut handler = dummy_registration_handler();
+    let mut handler = dummy_linked_registration_handler();
 
     handler.database.add_local_client(dummy_client("nickname1"));
     handler
@@ -105,14 +127,15 @@ fn server_is_relayed_to_all_other_servers() {
 
     let parameters = vec!["servername1".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "SERVER servername1 1 :serverinfo\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 
@@ -120,8 +143,72 @@ fn server_is_relayed_to_all_other_servers() {
         "SERVER servername1 1 :serverinfo\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn server_without_link_password_is_rejected() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["servername1".to_string(), "1".to_string()];
+    let trail = Some("serverinfo".to_string());
+    let result = handler.raw_command("SERVER", (None, parameters, trail));
+
+    assert!(result.is_err());
+    assert!(!handler.database.contains_server("servername1"));
+    assert_eq!(
+        vec![
+            ":servername 464 servername1 :Password incorrect",
+            "ERROR :Closing Link: servername1 (Bad password)"
+        ],
+        handler.stream.get_responses()
+    );
+}
+
+#[test]
+fn server_with_wrong_link_password_is_rejected() {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .set_link_password("servername1", "linkpassword");
+
+    let parameters = vec!["wrongpassword".to_string()];
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["servername1".to_string(), "1".to_string()];
+    let trail = Some("serverinfo".to_string());
+    let result = handler.raw_command("SERVER", (None, parameters, trail));
+
+    assert!(result.is_err());
+    assert!(!handler.database.contains_server("servername1"));
+    assert_eq!(
+        ":servername 464 servername1 :Password incorrect",
+        handler.stream.get_responses()[0]
+    );
+}
+
+#[test]
+fn rejected_server_is_not_relayed() {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    let parameters = vec!["servername1".to_string(), "1".to_string()];
+    let trail = Some("serverinfo".to_string());
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .ok();
+
+    assert_eq!(
+        "",
+        handler
+            .database
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -1,4 +1,5 @@
-use crate::server::testing::dummy_server;
+use crate::server::testing::{dummy_client, dummy_motd_file, dummy_server};
+use crate::server::{Limits, SERVER_VERSION};
 
 use super::*;
 
@@ -8,10 +9,12 @@ fn user_fails_with_no_nickname_registered() {
 
     let parameters = vec!["username".to_string()];
     let trailing = Some("realname".to_string());
-    handler.user_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
-        "200 :No nickname registered\r\n",
+        ":servername 200 * :No nickname registered\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -21,15 +24,19 @@ fn user_fails_with_empty_parameters() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
     handler.stream.clear();
 
     let parameters = vec![];
     let trailing = None;
-    handler.user_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
 
     assert_eq!(
-        "461 USER :Not enough parameters\r\n",
+        ":servername 461 nickname USER :Not enough parameters\r\n",
         handler.stream.read_wbuf_to_string()
     );
 }
@@ -39,15 +46,53 @@ fn user_registers_client_correctly() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     let parameters = vec!["username".to_string()];
     let trailing = Some("realname".to_string());
-    handler.user_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
 
     assert!(handler.database.contains_client("nickname"));
 }
 
+#[test]
+fn nickname_taken_before_registration_completes_is_refused() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+    handler.database.add_local_client(dummy_client("nickname"));
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    let keep_registering = handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert!(keep_registering);
+    assert_eq!(
+        ":servername 433 * nickname :Nickname is already in use\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(handler.database.get_local_queue("nickname").is_ok());
+    handler.stream.clear();
+
+    let parameters = vec!["other".to_string()];
+    let keep_registering = handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert!(!keep_registering);
+    assert!(handler.database.contains_client("other"));
+    assert!(handler.stream.get_responses()[0].starts_with(":servername 001 other "));
+}
+
 #[test]
 fn user_notifies_all_servers() {
     let mut handler = dummy_registration_handler();
@@ -59,26 +104,32 @@ fn user_notifies_all_servers() {
         .add_immediate_server(dummy_server("servername2"));
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     let parameters = vec!["username".to_string()];
     let trailing = Some("realname".to_string());
-    handler.user_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
 
+    let signon = handler.database.get_client_info("nickname").unwrap().signon;
+    let expected = format!(
+        "NICK nickname 1 {signon}\r\n:nickname USER username 127.0.0.1 servername :realname\r\n"
+    );
     assert_eq!(
-        "NICK nickname 1\r\n:nickname USER username 127.0.0.1 servername :realname\r\n",
+        expected,
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
     assert_eq!(
-        "NICK nickname 1\r\n:nickname USER username 127.0.0.1 servername :realname\r\n",
+        expected,
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -88,16 +139,269 @@ fn user_sends_welcome_after_registering() {
     let mut handler = dummy_registration_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     let parameters = vec!["username".to_string()];
     let trailing = Some("realname".to_string());
-    handler.user_command((None, parameters, trailing)).unwrap();
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
 
     assert!(handler.database.contains_client("nickname"));
 
     assert_eq!(
-        "001 realname :Welcome to servername Network, nickname !username @127.0.0.1\r\n",
-        handler.stream.read_wbuf_to_string()
+        ":servername 001 nickname :Welcome to the Internet Relay Network nickname!username@127.0.0.1",
+        handler.stream.get_responses()[0]
     )
 }
+
+#[test]
+fn user_sends_server_information_after_welcome() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+    let version = SERVER_VERSION;
+
+    assert_eq!(
+        format!(":servername 002 nickname :Your host is servername, running version {version}"),
+        responses[1]
+    );
+    assert!(responses[2].starts_with(":servername 003 nickname :This server was created "));
+    assert_eq!(
+        format!(":servername 004 nickname servername {version} iosw opsitnmlbvk"),
+        responses[3]
+    );
+    assert_eq!(
+        ":servername 005 nickname CHANTYPES=#& PREFIX=(ov)@+ CHANMODES=b,k,l,imnpst CHANLIMIT=#&:10 NICKLEN=9 TOPICLEN=307 CASEMAPPING=rfc1459 :are supported by this server",
+        responses[4]
+    );
+}
+
+#[test]
+fn isupport_reflects_configured_limits() {
+    let mut handler = dummy_registration_handler();
+    let limits = Limits {
+        max_channels: 3,
+        nickname_length: 30,
+        ..Default::default()
+    };
+    handler.database.set_limits(limits);
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+    assert!(responses[4].contains(" CHANLIMIT=#&:3 NICKLEN=30 "));
+}
+
+#[test]
+fn user_without_motd_file_receives_no_motd() {
+    let mut handler = dummy_registration_handler();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(6, responses.len());
+    assert_eq!(
+        ":servername 422 nickname :MOTD File is missing",
+        responses[5]
+    );
+}
+
+#[test]
+fn user_receives_motd_after_registering() {
+    let mut handler = dummy_registration_handler();
+    let path = dummy_motd_file(
+        "user_receives_motd_after_registering",
+        "first line\nsecond line",
+    );
+    handler.database.set_motd_file(path);
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    let responses = handler.stream.get_responses();
+
+    assert_eq!(
+        vec![
+            ":servername 375 nickname :- servername Message of the day -",
+            ":servername 372 nickname :- first line",
+            ":servername 372 nickname :- second line",
+            ":servername 376 nickname :End of /MOTD command",
+        ],
+        responses[5..]
+    );
+}
+
+#[test]
+fn user_without_password_is_rejected_when_server_has_one() {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .set_server_password(Some("password".to_string()));
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    let keep_connection = handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert!(!keep_connection);
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(
+        vec![
+            ":servername 464 nickname :Password incorrect",
+            "ERROR :Closing Link: nickname (Bad password)"
+        ],
+        handler.stream.get_responses()
+    );
+}
+
+#[test]
+fn user_with_wrong_password_is_rejected() {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .set_server_password(Some("password".to_string()));
+
+    let parameters = vec!["wrongpassword".to_string()];
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert!(!handler.database.contains_client("nickname"));
+    assert!(matches!(handler.connection_type, ConnectionType::Undefined));
+}
+
+#[test]
+fn user_with_matching_password_registers() {
+    let mut handler = dummy_registration_handler();
+    handler
+        .database
+        .set_server_password(Some("password".to_string()));
+
+    let parameters = vec!["password".to_string()];
+    handler
+        .raw_command("PASS", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert!(handler.database.contains_client("nickname"));
+}
+
+fn configure_bans(handler: &RegistrationHandler<MockTcpStream>, bans: &str) {
+    let content =
+        format!("[server]\nname = servername\ninfo = serverinfo\nlisten = 127.0.0.1:9000\n{bans}");
+    handler.database.configure(content.parse().unwrap());
+}
+
+#[test]
+fn banned_user_is_rejected() {
+    let mut handler = dummy_registration_handler();
+    configure_bans(&handler, "[ban *@127.0.0.*]\nreason = Spamming\n");
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert_eq!(
+        vec![
+            ":servername 465 nickname :You are banned from this server",
+            "ERROR :Closing Link: nickname (Spamming)"
+        ],
+        handler.stream.get_responses()
+    );
+    assert!(!handler.database.contains_client("nickname"));
+    assert!(matches!(handler.connection_type, ConnectionType::Undefined));
+}
+
+#[test]
+fn user_not_matching_ban_registers() {
+    let mut handler = dummy_registration_handler();
+    configure_bans(&handler, "[ban otheruser@*]\n");
+
+    let parameters = vec!["nickname".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    let parameters = vec!["username".to_string()];
+    let trailing = Some("realname".to_string());
+    handler
+        .raw_command("USER", (None, parameters, trailing))
+        .unwrap();
+
+    assert!(handler.database.contains_client("nickname"));
+}
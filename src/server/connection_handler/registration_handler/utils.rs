@@ -1,12 +1,24 @@
+use std::io;
+
+use crate::message::ToMessage;
+use crate::server::config::{BanBlock, Limits};
 use crate::server::connection::Connection;
-use crate::server::connection_handler::ConnectionHandlerUtils;
+use crate::server::connection_handler::{ConnectionHandlerGetters, ConnectionHandlerUtils};
+use crate::server::consts::channel::{DISTRIBUTED_CHANNEL, LOCAL_CHANNEL, MAX_TOPIC_LENGTH};
+use crate::server::consts::modes::*;
+use crate::server::SERVER_VERSION;
 
 use crate::server::data_structures::*;
-use crate::server::responses::{CommandResponse, Notification};
+use crate::server::responses::{CommandResponse, ErrorReply, Notification};
 
+use super::connection_type::ConnectionType;
 use super::RegistrationHandler;
 
-impl<C: Connection> ConnectionHandlerUtils<C> for RegistrationHandler<C> {}
+impl<C: Connection> ConnectionHandlerUtils<C> for RegistrationHandler<C> {
+    fn send_message(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.send(message)
+    }
+}
 
 impl<C: Connection> RegistrationHandler<C> {
     pub fn build_client(&mut self) -> Option<LocalClient<C>> {
@@ -17,10 +29,53 @@ impl<C: Connection> RegistrationHandler<C> {
             .servername(self.attributes.get("servername")?)
             .realname(self.attributes.get("realname")?)
             .password(self.attributes.get("password"))
-            .stream(self.stream_for_database.take()?)
+            .account(self.attributes.get("account"))
+            .queue(self.output.clone())
             .build_local_client()
     }
 
+    /// Registers client with the received information, unless the password is wrong or it is banned.
+    /// Returns false once the registration handler is done, or true if the nickname was taken
+    /// in the meantime, so the client can pick another one.
+    pub fn complete_registration(&mut self) -> io::Result<bool> {
+        if !self.is_password_valid() {
+            self.send_response(&ErrorReply::PasswordMismatch464)?;
+            let nickname = self.reply_target();
+            let message = format!("Closing Link: {nickname} (Bad password)");
+            self.send_message(&CommandResponse::error(&message))?;
+            return Ok(false);
+        }
+
+        let mut client = self
+            .build_client()
+            .expect("Client's information should be complete to build");
+        client.set_capabilities(self.capabilities.clone());
+        let client_info = client.get_info();
+
+        if let Some(ban) = self.find_server_ban(&client_info) {
+            self.send_response(&ErrorReply::YoureBannedCreep465)?;
+            let reason = ban.reason.unwrap_or_else(|| "Banned".to_string());
+            let message = format!("Closing Link: {} ({reason})", client_info.nickname);
+            self.send_message(&CommandResponse::error(&message))?;
+            return Ok(false);
+        }
+
+        if !self.database.add_local_client(client) {
+            self.attributes.remove("nickname");
+            let nickname = client_info.nickname;
+            self.send_response(&ErrorReply::NicknameInUse433 { nickname })?;
+            return Ok(true);
+        }
+
+        self.send_new_client_notification(&client_info);
+
+        self.connection_type = ConnectionType::Client;
+
+        self.send_registration_burst(client_info)?;
+
+        Ok(false)
+    }
+
     pub fn send_server_notification(
         &mut self,
         servername: &str,
@@ -28,20 +83,101 @@ impl<C: Connection> RegistrationHandler<C> {
         serverinfo: &str,
     ) {
         let notification = Notification::server(servername, hopcount, serverinfo);
-        self.send_message_to_all_servers(&notification)
+        let other_servers = self
+            .database
+            .get_all_servers()
+            .into_iter()
+            .filter(|server| server != servername)
+            .collect();
+        self.send_message_to_servers(other_servers, &notification)
     }
 
     pub fn send_new_client_notification(&mut self, info: &ClientInfo) {
         let nickname = &info.nickname;
-        let nick_notification = Notification::nick(nickname, 1);
+        let nick_notification = Notification::nick(nickname, 1, info.signon);
         self.send_message_to_all_servers(&nick_notification);
 
         let user_notification = Notification::user(info);
         self.send_message_to_all_servers(&user_notification);
+
+        if info.account.is_some() {
+            let account_notification = Notification::account(nickname, &info.account);
+            self.send_message_to_all_servers(&account_notification);
+        }
+    }
+
+    /// Returns true if the client sent both NICK and USER, and is not negotiating capabilities.
+    pub fn is_ready_to_register(&self) -> bool {
+        !self.negotiating
+            && self.attributes.contains_key("nickname")
+            && self.attributes.contains_key("username")
     }
 
-    pub fn send_welcome_response(&mut self, client_info: ClientInfo) -> std::io::Result<()> {
-        let response = CommandResponse::welcome(client_info);
-        self.stream.send(&response)
+    /// Returns true if the server has no password, or the one sent with PASS matches it.
+    pub fn is_password_valid(&self) -> bool {
+        match self.database.get_server_password() {
+            Some(password) => self.attributes.get("password") == Some(&password),
+            None => true,
+        }
     }
+
+    /// Returns the configured ban the client matches, if any.
+    pub fn find_server_ban(&self, client_info: &ClientInfo) -> Option<BanBlock> {
+        self.database
+            .get_server_bans()
+            .into_iter()
+            .find(|ban| client_info.matches_usermask(&ban.mask))
+    }
+
+    /// Sends everything a client expects after registering: welcome, server information and MOTD.
+    pub fn send_registration_burst(&mut self, client_info: ClientInfo) -> io::Result<()> {
+        let servername = self.database.get_server_name();
+        let created = self.database.get_creation_time();
+        let user_modes: String = VALID_USER_MODES.iter().collect();
+        let channel_modes: String = VALID_CHANNEL_MODES.iter().collect();
+
+        self.send_response(&CommandResponse::welcome(client_info))?;
+        self.send_response(&CommandResponse::your_host(&servername, SERVER_VERSION))?;
+        self.send_response(&CommandResponse::created(created))?;
+        self.send_response(&CommandResponse::my_info(
+            &servername,
+            SERVER_VERSION,
+            &user_modes,
+            &channel_modes,
+        ))?;
+        self.send_response(&CommandResponse::isupport(&isupport_tokens(
+            &self.database.get_limits(),
+        )))?;
+        self.send_motd()
+    }
+}
+
+/// Features advertised to clients in RPL_ISUPPORT.
+fn isupport_tokens(limits: &Limits) -> Vec<String> {
+    let list_modes = SET_BANMASK;
+    let parameter_modes = SET_KEY;
+    let set_parameter_modes = SET_USER_LIMIT;
+    let flag_modes: String = [
+        INVITE_ONLY,
+        MODERATED,
+        NO_OUTSIDE_MESSAGES,
+        PRIVATE,
+        SECRET,
+        TOPIC_SETTABLE,
+    ]
+    .iter()
+    .collect();
+
+    vec![
+        format!("CHANTYPES={DISTRIBUTED_CHANNEL}{LOCAL_CHANNEL}"),
+        format!("PREFIX=({SET_OPERATOR}{SET_SPEAKER}){OPERATOR_SYMBOL}{SPEAKER_SYMBOL}"),
+        format!("CHANMODES={list_modes},{parameter_modes},{set_parameter_modes},{flag_modes}"),
+        format!(
+            "CHANLIMIT={DISTRIBUTED_CHANNEL}{LOCAL_CHANNEL}:{}",
+            limits.max_channels
+        ),
+        format!("NICKLEN={}", limits.nickname_length),
+        format!("TOPICLEN={MAX_TOPIC_LENGTH}"),
+        format!("CASEMAPPING={CASEMAPPING}"),
+    ]
 }
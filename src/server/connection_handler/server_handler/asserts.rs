@@ -1,99 +1,108 @@
+use crate::message::command::*;
 use crate::server::connection::Connection;
-use crate::server::connection_handler::{CommandArgs, ConnectionHandlerAsserts};
+use crate::server::connection_handler::ConnectionHandlerAsserts;
 
-use crate::server::consts::modes::{ADD_MODE, REMOVE_MODE, VALID_CHANNEL_MODES, VALID_USER_MODES};
+use crate::server::consts::modes::{VALID_CHANNEL_MODES, VALID_USER_MODES};
 use crate::server::consts::user::{INVALID_NICKNAME_CHARACTERS, INVALID_NICKNAME_PREFIXES};
 use crate::server::responses::ErrorReply;
 
 use super::ServerHandler;
 
 impl<C: Connection> ConnectionHandlerAsserts<C> for ServerHandler<C> {
-    fn assert_pass_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_pass_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pass: &Pass,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::AlreadyRegistered462)
     }
 
-    fn assert_nick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.len() < 2 && prefix.is_none() {
+    fn assert_nick_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        nick: &Nick,
+    ) -> Result<(), ErrorReply> {
+        if nick.hopcount.is_none() && prefix.is_none() {
             return Err(ErrorReply::NoReply);
         }
-        if params.is_empty() && prefix.is_some() {
-            return Err(ErrorReply::NoReply);
-        }
-
-        if let Some(hopcount) = params.get(1) {
-            if hopcount.parse::<usize>().is_err() {
-                return Err(ErrorReply::NoReply);
-            }
-        }
 
-        let nickname = &params[0];
+        let nickname = &nick.nickname;
 
-        if nickname.len() > 9
+        if nickname.len() > self.database.get_limits().nickname_length
             || nickname.contains(INVALID_NICKNAME_CHARACTERS)
             || nickname.starts_with(INVALID_NICKNAME_PREFIXES)
         {
             return Err(ErrorReply::NoReply);
         }
 
-        self.assert_nickname_not_in_use(nickname)
+        Ok(())
     }
 
-    fn assert_user_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, trail) = arguments;
-
-        if params.len() < 3 || trail.is_none() || prefix.is_none() {
+    fn assert_user_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        user: &User,
+    ) -> Result<(), ErrorReply> {
+        if user.servername.is_none() {
             return Err(ErrorReply::NoReply);
         }
 
-        let nickname = prefix.as_ref().expect("Prefix should be Some");
+        let nickname = some_or_no_reply(prefix)?;
 
-        if self.hopcounts.get(nickname).is_none() {
+        if !self.hopcounts.contains_key(nickname) {
             return Err(ErrorReply::NoReply);
         }
 
         Ok(())
     }
 
-    fn assert_oper_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        todo!()
+    fn assert_oper_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _oper: &Oper,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NoReply)
     }
 
-    fn assert_privmsg_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, trail) = arguments;
-        if params.is_empty() || prefix.is_none() || trail.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_privmsg_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        privmsg: &Privmsg,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
 
-        let target = &params[0];
-        if !self.database.contains_channel(target) && !self.database.contains_client(target) {
-            return Err(ErrorReply::NoReply);
+        for target in &privmsg.targets {
+            if !self.database.contains_channel(target) && !self.database.contains_client(target) {
+                return Err(ErrorReply::NoReply);
+            }
         }
 
         Ok(())
     }
 
-    fn assert_notice_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, trail) = arguments;
-        if params.is_empty() || prefix.is_none() || trail.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_notice_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        notice: &Notice,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
 
-        let target = &params[0];
-        if !self.database.contains_channel(target) || !self.database.contains_client(target) {
-            return Err(ErrorReply::NoReply);
+        for target in &notice.targets {
+            if !self.database.contains_channel(target) || !self.database.contains_client(target) {
+                return Err(ErrorReply::NoReply);
+            }
         }
 
         Ok(())
     }
 
-    fn assert_join_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.is_empty() || prefix.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_join_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        _join: &Join,
+    ) -> Result<(), ErrorReply> {
+        let nickname = some_or_no_reply(prefix)?;
 
-        let nickname = prefix.as_ref().expect("Prefix should be Some");
         if !self.database.contains_client(nickname) {
             return Err(ErrorReply::NoReply);
         }
@@ -101,61 +110,83 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ServerHandler<C> {
         Ok(())
     }
 
-    fn assert_part_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.is_empty() || prefix.is_none() {
+    fn assert_part_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        part: &Part,
+    ) -> Result<(), ErrorReply> {
+        let nickname = some_or_no_reply(prefix)?;
+
+        if !self.database.contains_client(nickname) {
             return Err(ErrorReply::NoReply);
         }
 
-        let nickname = prefix.as_ref().expect("Prefix should be Some");
-        let channel = &params[0];
-        if !self.database.contains_client(nickname)
-            || !self.database.contains_channel(channel)
-            || !self.database.is_client_in_channel(channel, nickname)
-        {
-            return Err(ErrorReply::NoReply);
+        for channel in &part.channels {
+            if !self.database.contains_channel(channel)
+                || !self.database.is_client_in_channel(channel, nickname)
+            {
+                return Err(ErrorReply::NoReply);
+            }
         }
+
         Ok(())
     }
 
-    fn assert_invite_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.len() < 2 || prefix.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_invite_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        invite: &Invite,
+    ) -> Result<(), ErrorReply> {
+        let inviting = some_or_no_reply(prefix)?;
 
-        let inviting = prefix.as_ref().expect("Prefix should be Some");
-        let invited = &params[0];
-        if !self.database.contains_client(inviting) || !self.database.contains_client(invited) {
+        if !self.database.contains_client(inviting)
+            || !self.database.contains_client(&invite.nickname)
+        {
             return Err(ErrorReply::NoReply);
         }
 
         Ok(())
     }
 
-    fn assert_names_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_names_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _names: &Names,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NoReply)
     }
 
-    fn assert_list_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_list_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _list: &List,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NoReply)
     }
 
-    fn assert_who_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_who_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _who: &Who,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NoReply)
     }
 
-    fn assert_whois_command_is_valid(&self, _arguments: &CommandArgs) -> Result<(), ErrorReply> {
+    fn assert_whois_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _whois: &Whois,
+    ) -> Result<(), ErrorReply> {
         Err(ErrorReply::NoReply)
     }
 
-    fn assert_away_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, _, _) = arguments;
-        if prefix.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_away_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        _away: &Away,
+    ) -> Result<(), ErrorReply> {
+        let nickname = some_or_no_reply(prefix)?;
 
-        let nickname = prefix.as_ref().expect("Prefix should be Some");
         if !self.database.contains_client(nickname) {
             return Err(ErrorReply::NoReply);
         }
@@ -163,54 +194,53 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ServerHandler<C> {
         Ok(())
     }
 
-    fn assert_topic_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
+    fn assert_topic_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        topic: &Topic,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
+        some_or_no_reply(&topic.topic)?;
 
-        if prefix.is_none() || params.len() < 2 {
-            return Err(ErrorReply::NoReply);
-        }
-
-        let channel = &params[0];
-        if !self.database.contains_channel(channel) {
+        if !self.database.contains_channel(&topic.channel) {
             return Err(ErrorReply::NoReply);
         }
 
         Ok(())
     }
 
-    fn assert_kick_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-
-        if prefix.is_none() || params.len() < 2 {
-            return Err(ErrorReply::NoReply);
-        }
-
-        let kicked = &params[1];
-        let channel = &params[0];
-
-        if !self.database.contains_client(kicked)
-            || !self.database.contains_channel(channel)
-            || !self.database.is_client_in_channel(channel, kicked)
-        {
-            return Err(ErrorReply::NoReply);
+    fn assert_kick_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        kick: &Kick,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
+
+        for (channel, kicked) in kick.channels.iter().zip(&kick.nicknames) {
+            if !self.database.contains_client(kicked)
+                || !self.database.contains_channel(channel)
+                || !self.database.is_client_in_channel(channel, kicked)
+            {
+                return Err(ErrorReply::NoReply);
+            }
         }
 
         Ok(())
     }
 
-    fn assert_mode_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.len() < 2 || prefix.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_mode_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        mode: &Mode,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
+        let changes = some_or_no_reply(&mode.changes)?;
 
-        let mode = &params[1];
-        if mode.len() != 2 {
+        let [change] = changes.as_slice() else {
             return Err(ErrorReply::NoReply);
-        }
+        };
 
-        if !mode.starts_with([ADD_MODE, REMOVE_MODE])
-            || !(mode.ends_with(VALID_CHANNEL_MODES) || mode.ends_with(VALID_USER_MODES))
+        if !(VALID_CHANNEL_MODES.contains(&change.mode) || VALID_USER_MODES.contains(&change.mode))
         {
             return Err(ErrorReply::NoReply);
         }
@@ -218,39 +248,36 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ServerHandler<C> {
         Ok(())
     }
 
-    fn assert_quit_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, _, trail) = arguments;
-        if prefix.is_none() || trail.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
-        let nickname = prefix.as_ref().expect("Prefix should be Some");
+    fn assert_quit_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        quit: &Quit,
+    ) -> Result<(), ErrorReply> {
+        let nickname = some_or_no_reply(prefix)?;
+        some_or_no_reply(&quit.message)?;
+
         if !self.database.contains_client(nickname) {
             return Err(ErrorReply::NoReply);
         }
         Ok(())
     }
 
-    fn assert_server_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (_, params, trail) = arguments;
-        if params.len() < 2 || trail.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
-
-        let hopcount = &params[1];
-        if hopcount.parse::<usize>().is_err() {
-            return Err(ErrorReply::NoReply);
-        }
-
+    fn assert_server_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _server: &Server,
+    ) -> Result<(), ErrorReply> {
         Ok(())
     }
 
-    fn assert_squit_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, _) = arguments;
-        if params.is_empty() || prefix.is_none() {
-            return Err(ErrorReply::NoReply);
-        }
+    fn assert_squit_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        squit: &Squit,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
 
-        let servername = &params[0];
+        let servername = &squit.servername;
 
         if !self.database.contains_server(servername)
             && servername != &self.database.get_server_name()
@@ -260,28 +287,99 @@ impl<C: Connection> ConnectionHandlerAsserts<C> for ServerHandler<C> {
         Ok(())
     }
 
-    fn assert_ctcp_command_is_valid(&self, arguments: &CommandArgs) -> Result<(), ErrorReply> {
-        let (prefix, params, trail) = arguments;
-        if params.is_empty() || prefix.is_none() || trail.is_none() {
+    fn assert_ctcp_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        ctcp: &Ctcp,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
+
+        let target = &ctcp.target;
+        if !self.database.contains_channel(target) && !self.database.contains_client(target) {
             return Err(ErrorReply::NoReply);
         }
 
-        let target = &params[0];
-        if !self.database.contains_channel(target) && !self.database.contains_client(target) {
+        Ok(())
+    }
+
+    fn assert_ping_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _ping: &Ping,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
+
+    fn assert_pong_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _pong: &Pong,
+    ) -> Result<(), ErrorReply> {
+        Ok(())
+    }
+
+    fn assert_motd_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _motd: &Motd,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NoReply)
+    }
+
+    fn assert_rehash_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _rehash: &Rehash,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NoReply)
+    }
+
+    fn assert_kill_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        kill: &Kill,
+    ) -> Result<(), ErrorReply> {
+        some_or_no_reply(prefix)?;
+
+        if !self.database.contains_client(&kill.nickname) {
             return Err(ErrorReply::NoReply);
         }
 
         Ok(())
     }
-}
 
-impl<C: Connection> ServerHandler<C> {
-    pub fn assert_nickname_not_in_use(&self, nickname: &str) -> Result<(), ErrorReply> {
-        let nickname = nickname.to_string();
+    fn assert_cap_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _cap: &Cap,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NoReply)
+    }
+
+    fn assert_authenticate_command_is_valid(
+        &self,
+        _prefix: &Option<String>,
+        _authenticate: &Authenticate,
+    ) -> Result<(), ErrorReply> {
+        Err(ErrorReply::NoReply)
+    }
+
+    fn assert_account_command_is_valid(
+        &self,
+        prefix: &Option<String>,
+        _account: &Account,
+    ) -> Result<(), ErrorReply> {
+        let nickname = some_or_no_reply(prefix)?;
 
-        if self.database.contains_client(&nickname) {
-            return Err(ErrorReply::NickCollision436 { nickname });
+        if !self.database.contains_client(nickname) {
+            return Err(ErrorReply::NoReply);
         }
+
         Ok(())
     }
 }
+
+/// Servers are not answered, a missing argument just drops the message.
+fn some_or_no_reply<T>(argument: &Option<T>) -> Result<&T, ErrorReply> {
+    argument.as_ref().ok_or(ErrorReply::NoReply)
+}
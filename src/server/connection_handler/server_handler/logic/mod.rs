@@ -1,14 +1,20 @@
+use std::cmp::Ordering;
 use std::io;
 
-use crate::macros::ok_or_return;
+use crate::macros::{ok_or_return, some_or_return};
+use crate::message::command::{
+    format_mode_changes, Account, Away, Ctcp, Invite, Join, Kick, Kill, Mode, ModeChange, Nick,
+    Notice, Part, Ping, Privmsg, Quit, Server, Squit, Topic, User,
+};
 use crate::server::connection::Connection;
-use crate::server::connection_handler::{CommandArgs, ConnectionHandlerLogic};
+use crate::server::connection_handler::{ConnectionHandlerLogic, ConnectionHandlerUtils};
 
 use crate::server::connection_handler::mode_requests::{
-    parse_channel_mode_string, parse_user_mode_string,
+    parse_channel_mode_changes, parse_user_mode_changes,
 };
 use crate::server::consts::channel::DISTRIBUTED_CHANNEL;
 use crate::server::data_structures::*;
+use crate::server::responses::Notification;
 
 use super::ServerHandler;
 
@@ -16,41 +22,63 @@ use super::ServerHandler;
 mod mode_logic;
 
 pub const SQUIT_MESSAGE: &str = "Net split";
+pub const NICK_COLLISION_MESSAGE: &str = "Nick collision";
 
 impl<C: Connection> ConnectionHandlerLogic<C> for ServerHandler<C> {
-    fn nick_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (prefix, mut params, _) = arguments;
-
-        let nickname = params.remove(0);
+    fn nick_logic(&mut self, prefix: Option<String>, nick: Nick) -> std::io::Result<bool> {
+        let Nick {
+            nickname,
+            hopcount,
+            signon,
+        } = nick;
 
         if let Some(old_nickname) = prefix {
+            if self.database.contains_client(&nickname) {
+                self.kill_colliding_client(&nickname, true);
+                self.kill_colliding_client(&old_nickname, true);
+                return Ok(true);
+            }
             self.database.update_nickname(&old_nickname, &nickname);
             self.send_nick_update_notification(&old_nickname, &nickname);
             return Ok(true);
         }
 
-        let hopcount = params[0].parse::<usize>().expect("Verified in assert");
-        self.send_nick_notification(&nickname, hopcount);
-        self.hopcounts.insert(nickname, hopcount);
+        let hopcount = some_or_return!(hopcount, Ok(true));
+
+        if self.database.contains_client(&nickname)
+            && !self.resolve_nick_collision(&nickname, signon)
+        {
+            return Ok(true);
+        }
+
+        let signon = signon.unwrap_or_else(unix_time);
+        self.send_nick_notification(&nickname, hopcount, signon);
+        self.hopcounts.insert(nickname.clone(), hopcount);
+        self.signons.insert(nickname, signon);
 
         Ok(true)
     }
 
-    fn user_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (prefix, params, trail) = arguments;
-
-        let nickname = &prefix.expect("Verified in assert");
+    fn user_logic(&mut self, prefix: Option<String>, user: User) -> std::io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+        let hostname = some_or_return!(user.hostname, Ok(true));
+        let servername = some_or_return!(user.servername, Ok(true));
         let client = ClientBuilder::<C>::new()
-            .nickname(nickname)
+            .nickname(&nickname)
             .hopcount(
                 self.hopcounts
-                    .remove(nickname)
+                    .remove(&nickname)
                     .expect("Hopcount value should be saved in hopcounts"),
             )
-            .username(params.get(0).expect("Verified in assert"))
-            .hostname(params.get(1).expect("Verified in assert"))
-            .servername(params.get(2).expect("Verified in assert"))
-            .realname(&trail.expect("Verified in assert"))
+            .username(&user.username)
+            .hostname(&hostname)
+            .servername(&servername)
+            .realname(&user.realname)
+            .signon(
+                self.signons
+                    .remove(&nickname)
+                    .expect("Signon value should be saved in signons"),
+            )
             .immediate(&self.servername)
             .build_external_client()
             .expect("Client's information should be stored and available");
@@ -61,144 +89,151 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ServerHandler<C> {
         Ok(true)
     }
 
-    fn privmsg_logic(&mut self, arguments: CommandArgs) -> std::io::Result<bool> {
-        let (prefix, mut params, trail) = arguments;
-
-        let sender = prefix.expect("Verified in assert");
-        let target = params.remove(0);
-        let content = trail.expect("Verified in assert");
+    fn privmsg_logic(&mut self, prefix: Option<String>, privmsg: Privmsg) -> std::io::Result<bool> {
+        let sender = some_or_return!(prefix, Ok(true));
 
-        self.send_privmsg_notification(&sender, &target, &content);
+        for target in privmsg.targets {
+            self.send_privmsg_notification(&sender, &target, &privmsg.text);
+        }
 
         Ok(true)
     }
 
-    fn notice_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, trail) = arguments;
-
-        let sender = prefix.expect("Verified in assert");
-        let target = params.remove(0);
-        let content = trail.expect("Verified in assert");
+    fn notice_logic(&mut self, prefix: Option<String>, notice: Notice) -> io::Result<bool> {
+        let sender = some_or_return!(prefix, Ok(true));
 
-        self.send_notice_notification(&sender, &target, &content);
+        for target in notice.targets {
+            self.send_notice_notification(&sender, &target, &notice.text);
+        }
 
         Ok(true)
     }
 
-    fn join_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, _) = arguments;
-
-        let nickname = prefix.expect("Verified in assert");
-        let channel = params.remove(0);
+    fn join_logic(&mut self, prefix: Option<String>, join: Join) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
 
-        self.database.add_client_to_channel(&channel, &nickname);
+        for channel in join.channels {
+            self.database.add_client_to_channel(&channel, &nickname);
+            self.send_join_notification(&nickname, &channel);
+        }
 
-        self.send_join_notification(&nickname, &channel);
         Ok(true)
     }
 
-    fn part_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, _) = arguments;
+    fn part_logic(&mut self, prefix: Option<String>, part: Part) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+
+        for channel in part.channels {
+            self.database
+                .remove_client_from_channel(&channel, &nickname);
+            self.send_part_notification(&nickname, &channel);
+        }
 
-        let nickname = prefix.expect("Verified in assert");
-        let channel = params.remove(0);
-        self.database
-            .remove_client_from_channel(&channel, &nickname);
-        self.send_part_notification(&nickname, &channel);
         Ok(true)
     }
 
-    fn invite_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, params, _) = arguments;
-
-        let inviting = &prefix.expect("Verified in assert");
-        let invited = &params[0];
-        let channel = &params[1];
+    fn invite_logic(&mut self, prefix: Option<String>, invite: Invite) -> io::Result<bool> {
+        let inviting = some_or_return!(prefix, Ok(true));
 
-        self.send_invite_notification(inviting, invited, channel);
+        self.send_invite_notification(&inviting, &invite.nickname, &invite.channel);
         Ok(true)
     }
 
-    fn away_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, _, trail) = arguments;
-        self.database
-            .set_away_message(prefix.as_ref().expect("Verified in assert"), trail.clone());
+    fn away_logic(&mut self, prefix: Option<String>, away: Away) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+        let message = away.message;
 
-        let nickname = prefix.expect("Verified in assert");
-        let message = trail;
+        self.database.set_away_message(&nickname, message.clone());
 
         self.send_away_notification(&nickname, &message);
+        self.send_away_notify(&nickname, &message);
         Ok(true)
     }
 
-    fn topic_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, params, _) = arguments;
+    fn account_logic(&mut self, prefix: Option<String>, account: Account) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+        let account = account.account;
 
-        let nickname = &prefix.expect("Verified in assert");
-        let channel = &params[0];
-        let topic = &params[1];
-        self.database.set_channel_topic(channel, topic);
+        self.database.set_client_account(&nickname, account.clone());
 
-        self.send_topic_notification(nickname, channel, topic);
+        self.send_account_notification(&nickname, &account);
         Ok(true)
     }
 
-    fn kick_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, trail) = arguments;
+    fn topic_logic(&mut self, prefix: Option<String>, topic: Topic) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+        let channel = &topic.channel;
+        let topic = some_or_return!(topic.topic, Ok(true));
+        self.database.set_channel_topic(channel, &topic);
 
-        let kicker = prefix.expect("Verified in assert");
-        let kicked = params.remove(1);
-        let channel = params.remove(0);
-        let message = trail;
+        self.send_topic_notification(&nickname, channel, &topic);
+        Ok(true)
+    }
 
-        self.send_kick_notification(&kicker, &channel, &kicked, &message);
-        self.database.remove_client_from_channel(&channel, &kicked);
+    fn kick_logic(&mut self, prefix: Option<String>, kick: Kick) -> io::Result<bool> {
+        let kicker = some_or_return!(prefix, Ok(true));
+        let message = kick.comment;
+
+        for (channel, kicked) in kick.channels.iter().zip(&kick.nicknames) {
+            self.send_kick_notification(&kicker, channel, kicked, &message);
+            self.database.remove_client_from_channel(channel, kicked);
+        }
 
         Ok(true)
     }
 
-    fn mode_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, _) = arguments;
-
-        let sender = prefix.expect("Verified in assert");
-        let target = params.remove(0);
+    fn mode_logic(&mut self, prefix: Option<String>, mode: Mode) -> io::Result<bool> {
+        let sender = some_or_return!(prefix, Ok(true));
+        let Mode {
+            target,
+            changes,
+            arguments,
+        } = mode;
 
-        let mode = params.get(0).expect("Verified in assert").to_string();
-        let argument = params.get(1).map(|s| s.to_string()).unwrap_or_default();
-        let request = format!("{mode} {argument}");
+        let changes = some_or_return!(changes, Ok(true));
+        let argument = arguments.first().cloned().unwrap_or_default();
+        let request = format!("{} {argument}", format_mode_changes(&changes));
 
         self.send_mode_notification(&sender, &target, &request);
 
         if self.is_channel(&target) {
-            self.mode_command_for_channel(target, params);
+            self.mode_command_for_channel(target, changes, arguments);
         } else {
-            self.mode_command_for_user(target, params);
+            self.mode_command_for_user(target, changes);
         }
 
         Ok(true)
     }
 
-    fn quit_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, _, trail) = arguments;
-
-        let nickname = prefix.expect("Verified in assert");
-        let message = trail.expect("Verified in assert");
+    fn quit_logic(&mut self, prefix: Option<String>, quit: Quit) -> io::Result<bool> {
+        let nickname = some_or_return!(prefix, Ok(true));
+        let message = some_or_return!(quit.message, Ok(true));
 
+        self.send_quit_notification(nickname.clone(), message);
         self.database.disconnect_client(&nickname);
-        self.send_quit_notification(nickname, message);
 
         Ok(true)
     }
 
-    fn server_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (_, mut params, trail) = arguments;
+    fn kill_logic(&mut self, prefix: Option<String>, kill: Kill) -> io::Result<bool> {
+        let killer = some_or_return!(prefix, Ok(true));
+        let Kill {
+            nickname: victim,
+            comment,
+        } = kill;
+
+        self.send_message_to_all_other_servers(&Notification::kill(&killer, &victim, &comment));
+        self.kill_client(&killer, &victim, &comment);
+
+        Ok(true)
+    }
 
-        let hopcount = params
-            .remove(1)
-            .parse::<usize>()
-            .expect("Verified in assert");
-        let servername = params.remove(0);
-        let serverinfo = trail.expect("Verified in assert");
+    fn server_logic(&mut self, _prefix: Option<String>, server: Server) -> io::Result<bool> {
+        let Server {
+            servername,
+            hopcount,
+            info: serverinfo,
+        } = server;
 
         self.send_server_notification(&servername, hopcount + 1, &serverinfo);
 
@@ -207,12 +242,10 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ServerHandler<C> {
         Ok(true)
     }
 
-    fn squit_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, params, trail) = arguments;
-
-        let sender = prefix.expect("Verified in assert");
-        let servername = &params[0];
-        let comment = trail;
+    fn squit_logic(&mut self, prefix: Option<String>, squit: Squit) -> io::Result<bool> {
+        let sender = some_or_return!(prefix, Ok(true));
+        let servername = &squit.servername;
+        let comment = squit.comment;
 
         self.send_squit_notification(&sender, servername, comment);
 
@@ -228,14 +261,18 @@ impl<C: Connection> ConnectionHandlerLogic<C> for ServerHandler<C> {
         Ok(true)
     }
 
-    fn ctcp_logic(&mut self, arguments: CommandArgs) -> io::Result<bool> {
-        let (prefix, mut params, trail) = arguments;
+    fn ctcp_logic(&mut self, prefix: Option<String>, ctcp: Ctcp) -> io::Result<bool> {
+        let sender = some_or_return!(prefix, Ok(true));
 
-        let sender = prefix.expect("Verified in assert");
-        let target = params.remove(0);
-        let content = trail.expect("Verified in assert");
+        self.send_privmsg_notification(&sender, &ctcp.target, &ctcp.text);
 
-        self.send_privmsg_notification(&sender, &target, &content);
+        Ok(true)
+    }
+
+    fn ping_logic(&mut self, _prefix: Option<String>, ping: Ping) -> io::Result<bool> {
+        let servername = self.database.get_server_name();
+
+        self.send_message(&Notification::pong(&servername, &ping.token))?;
 
         Ok(true)
     }
@@ -246,33 +283,71 @@ impl<C: Connection> ServerHandler<C> {
         target.starts_with(DISTRIBUTED_CHANNEL)
     }
 
+    /// Resolves a collision between a known client and one being introduced by the sender.
+    /// The older client is kept, on ties or unknown signon both are killed.
+    /// Both sides of the link reach the same verdict, so each one only kills its own copy.
+    ///
+    /// Returns true if the introduced client should be accepted.
+    fn resolve_nick_collision(&mut self, nickname: &str, signon: Option<u64>) -> bool {
+        let existing = ok_or_return!(self.database.get_client_info(nickname), true);
+
+        let notice = format!("Nick collision on {nickname} from {}", self.servername);
+        self.send_server_notice(&notice);
+
+        match signon.map(|signon| signon.cmp(&existing.signon)) {
+            Some(Ordering::Greater) => false,
+            Some(Ordering::Less) => {
+                self.kill_colliding_client(nickname, false);
+                true
+            }
+            _ => {
+                self.kill_colliding_client(nickname, true);
+                false
+            }
+        }
+    }
+
+    /// Kills a client that lost a nick collision, the sender is only told when it has to kill its own.
+    fn kill_colliding_client(&mut self, nickname: &str, notify_sender: bool) {
+        let servername = self.database.get_server_name();
+        let kill = Notification::kill(&servername, nickname, NICK_COLLISION_MESSAGE);
+
+        if notify_sender {
+            self.send_message_to_all_servers(&kill);
+        } else {
+            self.send_message_to_all_other_servers(&kill);
+        }
+        self.kill_client(&servername, nickname, NICK_COLLISION_MESSAGE);
+    }
+
     fn add_server(&mut self, servername: String, serverinfo: String, hopcount: usize) {
         let server = ServerInfo::new(servername, serverinfo, hopcount);
         self.database.add_distant_server(server);
     }
 
-    fn mode_command_for_channel(&mut self, channel: String, mut args: Vec<String>) {
-        let mode_string = args.remove(0);
-        let mut mode_arguments = args;
-        mode_arguments.reverse();
+    fn mode_command_for_channel(
+        &mut self,
+        channel: String,
+        changes: Vec<ModeChange>,
+        mut arguments: Vec<String>,
+    ) {
+        arguments.reverse();
 
-        let mut mode_requests = parse_channel_mode_string(mode_string, mode_arguments);
+        let mut mode_requests = parse_channel_mode_changes(changes, arguments);
 
         let request = mode_requests.remove(0);
 
         self.handle_channel_mode_request(&channel, request);
     }
 
-    fn mode_command_for_user(&mut self, user: String, mut args: Vec<String>) {
-        let mode_string = args.remove(0);
-
-        let mut mode_requests = parse_user_mode_string(mode_string);
+    fn mode_command_for_user(&mut self, user: String, changes: Vec<ModeChange>) {
+        let mut mode_requests = parse_user_mode_changes(changes);
         let request = mode_requests.remove(0);
 
         self.handle_user_mode_request(&user, request);
     }
 
-    fn disconnect_server(&mut self, servername: &String) {
+    pub(super) fn disconnect_server(&mut self, servername: &String) {
         self.database.remove_server(servername);
         let all_clients = self.database.get_all_clients();
         let server_clients: Vec<ClientInfo> = all_clients
@@ -285,9 +360,9 @@ impl<C: Connection> ServerHandler<C> {
             .collect();
         for client in server_clients {
             println!("desconectando al cliente: {}", client.nickname);
-            self.database.disconnect_client(&client.nickname);
+            self.send_quit_notification(client.nickname.clone(), SQUIT_MESSAGE.to_string());
 
-            self.send_quit_notification(client.nickname, SQUIT_MESSAGE.to_string());
+            self.database.disconnect_client(&client.nickname);
         }
     }
 }
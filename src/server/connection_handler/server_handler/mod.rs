@@ -1,13 +1,22 @@
 use std::{
     collections::HashMap,
+    io,
     sync::{atomic::AtomicBool, Arc},
 };
 
-use crate::server::{connection::Connection, database::DatabaseHandle};
+use crate::message::{CommandError, CreationError, LineReader, Message, ToMessage};
+use crate::server::{
+    connection::Connection,
+    data_structures::{OutputQueue, SENDQ_EXCEEDED_MESSAGE},
+    database::DatabaseHandle,
+    reactor::{Progress, Task, Waker},
+    responses::{CommandResponse, Notification},
+};
 
 use super::{
-    ConnectionHandler, ConnectionHandlerCommands, ConnectionHandlerGetters,
-    ConnectionHandlerStructure,
+    closing_reason, ConnectionHandler, ConnectionHandlerCommands, ConnectionHandlerGetters,
+    ConnectionHandlerStructure, ConnectionHandlerUtils, Keepalive, PING_TIMEOUT_MESSAGE,
+    SERVER_SHUTDOWN_MESSAGE,
 };
 
 /// Asserts to ensure the commands the server sends are valid.
@@ -26,14 +35,32 @@ mod tests;
 /// A Client Handler handles the connection with an already registered server.
 pub struct ServerHandler<C: Connection> {
     stream: C,
+    output: OutputQueue<C>,
     servername: String,
     database: DatabaseHandle<C>,
     online: Arc<AtomicBool>,
     hopcounts: HashMap<String, usize>,
+    signons: HashMap<String, u64>,
+    keepalive: Keepalive,
+    reader: LineReader,
 }
 
 impl<C: Connection> ConnectionHandler<C> for ServerHandler<C> {}
 
+impl<C: Connection> Task for ServerHandler<C> {
+    fn poll(&mut self) -> Progress {
+        self.poll_connection()
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        self.output.set_waker(waker);
+    }
+
+    fn abort(&mut self) {
+        self.abort_connection();
+    }
+}
+
 impl<C: Connection> ServerHandler<C> {
     /// Starts a [`ServerHandler`] with the received information.
     pub fn from_connection(
@@ -42,14 +69,27 @@ impl<C: Connection> ServerHandler<C> {
         database: DatabaseHandle<C>,
         online: Arc<AtomicBool>,
     ) -> std::io::Result<Self> {
+        let (interval, timeout) = database.get_keepalive_settings();
+        let output = database.get_server_queue(&servername)?;
+
         Ok(Self {
             stream,
+            output,
             servername,
             database,
             online,
             hopcounts: HashMap::new(),
+            signons: HashMap::new(),
+            keepalive: Keepalive::new(interval, timeout),
+            reader: LineReader::new(),
         })
     }
+
+    /// Continues reading with the given reader, keeping the bytes it already buffered.
+    pub fn with_reader(mut self, reader: LineReader) -> Self {
+        self.reader = reader;
+        self
+    }
 }
 
 impl<C: Connection> ConnectionHandlerGetters<C> for ServerHandler<C> {
@@ -64,15 +104,113 @@ impl<C: Connection> ConnectionHandlerGetters<C> for ServerHandler<C> {
     fn database(&self) -> &DatabaseHandle<C> {
         &self.database
     }
+
+    fn reply_target(&self) -> String {
+        self.servername.clone()
+    }
 }
 
 impl<C: Connection> ConnectionHandlerStructure<C> for ServerHandler<C> {
-    fn on_try_handle_error(&mut self) {
-        eprintln!("Connection with [{}] ended unexpectedly", self.servername)
+    fn read_message(&mut self) -> Result<Message, CreationError> {
+        self.reader.read_message(&mut self.stream)
+    }
+
+    /// Link was lost without SQUIT, it is dropped as if it had received it.
+    fn on_try_handle_error(&mut self, error: io::Error) {
+        eprintln!("Connection with [{}] ended unexpectedly", self.servername);
+
+        if self.database.contains_server(&self.servername) {
+            self.close_link(&closing_reason(&error)).ok();
+        }
     }
     fn on_try_handle_success(&mut self) {
         eprintln!("Closing conection with [{}]", self.servername)
     }
+
+    fn timeout(&mut self) -> bool {
+        self.keepalive.timed_out()
+    }
+
+    /// Server did not answer to ping, the link is dropped as if it had received SQUIT.
+    fn on_timeout(&mut self) -> io::Result<()> {
+        self.close_link(PING_TIMEOUT_MESSAGE)
+    }
+
+    /// Peer server is told this server is leaving the network, so it drops everyone behind the link.
+    fn on_server_shutdown(&mut self) -> io::Result<()> {
+        let own_servername = self.database.get_server_name();
+        let comment = Some(SERVER_SHUTDOWN_MESSAGE.to_string());
+        let squit = Notification::squit(&own_servername, &own_servername, comment);
+        self.send_message(&squit).ok();
+
+        let message = format!(
+            "Closing Link: {} ({SERVER_SHUTDOWN_MESSAGE})",
+            self.servername
+        );
+        self.close_output(&CommandResponse::error(&message))
+    }
+
+    fn sendq_exceeded(&mut self) -> bool {
+        self.output.exceeded()
+    }
+
+    /// Server is not reading what is sent to it, the link is dropped as if it had received SQUIT.
+    fn on_sendq_exceeded(&mut self) -> io::Result<()> {
+        self.close_link(SENDQ_EXCEEDED_MESSAGE)
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    fn close_output(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.close(message)
+    }
+
+    fn closing(&mut self) -> bool {
+        self.output.closing()
+    }
+
+    fn queued_output(&mut self) -> usize {
+        self.output.queued()
+    }
+
+    fn idle(&mut self) -> bool {
+        self.keepalive.should_ping()
+    }
+
+    fn on_idle(&mut self) -> io::Result<()> {
+        let servername = self.database.get_server_name();
+        self.send_message(&Notification::ping(&servername))?;
+        self.keepalive.ping_sent();
+        Ok(())
+    }
+
+    fn on_activity(&mut self) {
+        self.keepalive.refresh();
+    }
+}
+
+impl<C: Connection> ServerHandler<C> {
+    /// Drops the link as if it had received SQUIT, telling the server why if it still listens.
+    fn close_link(&mut self, reason: &str) -> io::Result<()> {
+        let own_servername = self.database.get_server_name();
+        let servername = self.servername.clone();
+        let comment = Some(reason.to_string());
+
+        self.send_squit_notification(&own_servername, &servername, comment);
+        self.disconnect_server(&servername);
+
+        let message = format!("Closing Link: {servername} ({reason})");
+        self.send_message(&CommandResponse::error(&message)).ok();
+        self.flush_output().ok();
+        self.stream.shutdown()
+    }
 }
 
-impl<C: Connection> ConnectionHandlerCommands<C> for ServerHandler<C> {}
+impl<C: Connection> ConnectionHandlerCommands<C> for ServerHandler<C> {
+    /// Servers are not answered, malformed commands are dropped.
+    fn on_command_error(&mut self, _command: &str, _error: CommandError) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -33,8 +33,8 @@ impl<C: Connection> ServerHandler<C> {
         self.send_message_to_all_other_servers(&notification);
     }
 
-    pub(super) fn send_nick_notification(&mut self, nickname: &str, hopcount: usize) {
-        let notification = Notification::nick(nickname, hopcount + 1);
+    pub(super) fn send_nick_notification(&mut self, nickname: &str, hopcount: usize, signon: u64) {
+        let notification = Notification::nick(nickname, hopcount + 1, signon);
         self.send_message_to_all_other_servers(&notification);
     }
 
@@ -76,6 +76,11 @@ impl<C: Connection> ServerHandler<C> {
         self.send_message_to_all_other_servers(&notification);
     }
 
+    pub(super) fn send_account_notification(&mut self, nickname: &str, account: &Option<String>) {
+        let notification = Notification::account(nickname, account);
+        self.send_message_to_all_other_servers(&notification);
+    }
+
     pub(super) fn send_topic_notification(&mut self, nickname: &str, channel: &str, topic: &str) {
         let notification = Notification::topic(nickname, channel, topic);
         self.send_message_to_local_clients_on_channel(&notification, channel);
@@ -0,0 +1,91 @@
+use crate::server::testing::{dummy_external_client, dummy_server};
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
+
+use super::dummy_server_handler;
+
+#[test]
+fn account_with_no_client_in_database_is_ignored() {
+    let mut handler = dummy_server_handler();
+
+    let prefix = Some("nickname".to_string());
+    let parameters = vec!["account".to_string()];
+    handler
+        .raw_command("ACCOUNT", (prefix, parameters, None))
+        .unwrap();
+
+    assert!(!handler.database.contains_client("nickname"));
+}
+
+#[test]
+fn account_sets_client_account() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname", "servername1"));
+
+    let prefix = Some("nickname".to_string());
+    let parameters = vec!["account".to_string()];
+    handler
+        .raw_command("ACCOUNT", (prefix, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        Some("account".to_string()),
+        handler
+            .database
+            .get_client_info("nickname")
+            .unwrap()
+            .account
+    );
+}
+
+#[test]
+fn account_star_logs_client_out() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname", "servername1"));
+    handler
+        .database
+        .set_client_account("nickname", Some("account".to_string()));
+
+    let prefix = Some("nickname".to_string());
+    let parameters = vec!["*".to_string()];
+    handler
+        .raw_command("ACCOUNT", (prefix, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        None,
+        handler
+            .database
+            .get_client_info("nickname")
+            .unwrap()
+            .account
+    );
+}
+
+#[test]
+fn account_is_relayed_to_all_other_servers() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname", "servername1"));
+
+    let prefix = Some("nickname".to_string());
+    let parameters = vec!["account".to_string()];
+    handler
+        .raw_command("ACCOUNT", (prefix, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname ACCOUNT account\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+}
@@ -1,7 +1,5 @@
-use crate::server::{
-    connection_handler::ConnectionHandlerCommands,
-    testing::{dummy_external_client, dummy_server},
-};
+use crate::server::testing::{dummy_external_client, dummy_server};
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 
 use super::dummy_server_handler;
 
@@ -13,14 +11,13 @@ fn away_with_no_prefix_is_ignored() {
         .database
         .add_immediate_server(dummy_server("servername2"));
 
-    handler.away_command((None, vec![], None)).unwrap();
+    handler.raw_command("AWAY", (None, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -34,14 +31,13 @@ fn away_with_no_client_in_database_is_ignored() {
         .add_immediate_server(dummy_server("servername2"));
 
     let prefix = Some("nickname".to_string());
-    handler.away_command((prefix, vec![], None)).unwrap();
+    handler.raw_command("AWAY", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -55,7 +51,9 @@ fn away_sets_away_message_for_client() {
 
     let prefix = Some("nickname".to_string());
     let trail = Some("message".to_string());
-    handler.away_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("AWAY", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!(
         Some("message".to_string()),
@@ -79,14 +77,15 @@ fn away_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname".to_string());
     let trail = Some("message".to_string());
-    handler.away_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("AWAY", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!(
         ":nickname AWAY :message\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 
@@ -94,8 +93,7 @@ fn away_is_relayed_to_all_other_servers() {
         ":nickname AWAY :message\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
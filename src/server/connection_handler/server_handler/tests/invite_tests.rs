@@ -1,7 +1,5 @@
-use crate::server::{
-    connection_handler::ConnectionHandlerCommands,
-    testing::{dummy_client, dummy_external_client, dummy_server},
-};
+use crate::server::testing::{dummy_client, dummy_external_client, dummy_server};
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 
 use super::dummy_server_handler;
 
@@ -18,19 +16,22 @@ fn invite_with_invalid_arguments_is_ignored() {
     handler.database.add_local_client(dummy_client("target"));
 
     let params = vec!["target".to_string(), "#channel".to_string()];
-    handler.invite_command((None, params, None)).unwrap();
+    handler.raw_command("INVITE", (None, params, None)).unwrap();
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string()];
-    handler.invite_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, params, None))
+        .unwrap();
     let prefix = Some("sender".to_string());
-    handler.invite_command((prefix, vec![], None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, vec![], None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -45,14 +46,15 @@ fn invite_with_no_inviting_client_in_database_is_ignored() {
 
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string(), "#channel".to_string()];
-    handler.invite_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -69,14 +71,15 @@ fn invite_with_no_invited_client_in_database_is_ignored() {
 
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string(), "#channel".to_string()];
-    handler.invite_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -91,14 +94,15 @@ fn invite_is_sent_to_local_client() {
 
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string(), "#channel".to_string()];
-    handler.invite_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         ":sender INVITE target #channel\r\n",
         handler
             .database
-            .get_local_stream("target")
-            .unwrap()
+            .flushed_local_stream("target")
             .read_wbuf_to_string()
     )
 }
@@ -119,14 +123,15 @@ fn invite_is_relayed_to_all_other_servers() {
 
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string(), "#channel".to_string()];
-    handler.invite_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("INVITE", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         ":sender INVITE target #channel\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 
@@ -134,8 +139,7 @@ fn invite_is_relayed_to_all_other_servers() {
         ":sender INVITE target #channel\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
 
@@ -15,15 +16,16 @@ fn join_with_invalid_arguments_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((None, parameters, None)).unwrap();
-    handler.join_command((prefix, vec![], None)).unwrap();
+    handler
+        .raw_command("JOIN", (None, parameters, None))
+        .unwrap();
+    handler.raw_command("JOIN", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -37,14 +39,15 @@ fn join_with_no_client_in_database_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -58,7 +61,7 @@ fn join_adds_client_to_channel() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string()];
-    handler.join_command((prefix, params, None)).unwrap();
+    handler.raw_command("JOIN", (prefix, params, None)).unwrap();
 
     assert!(handler
         .database
@@ -80,22 +83,22 @@ fn join_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 JOIN #channel\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname1 JOIN #channel\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -109,7 +112,9 @@ fn join_is_never_relayed_to_sending_server() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
@@ -129,22 +134,22 @@ fn join_is_relayed_to_local_clients_on_channel() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.join_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("JOIN", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 JOIN #channel\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
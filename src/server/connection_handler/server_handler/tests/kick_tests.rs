@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
 
@@ -19,20 +20,19 @@ fn kick_with_invalid_arguments_is_ignored() {
     handler.database.add_client_to_channel("kicked", "#channel");
 
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((None, params, None)).unwrap();
+    handler.raw_command("KICK", (None, params, None)).unwrap();
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string()];
     handler
-        .kick_command((prefix.clone(), params, None))
+        .raw_command("KICK", (prefix.clone(), params, None))
         .unwrap();
-    handler.kick_command((prefix, vec![], None)).unwrap();
+    handler.raw_command("KICK", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -49,14 +49,13 @@ fn kick_with_no_client_in_database_is_ignored() {
 
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((prefix, params, None)).unwrap();
+    handler.raw_command("KICK", (prefix, params, None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -77,14 +76,13 @@ fn kick_with_no_client_in_channel_is_ignored() {
 
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((prefix, params, None)).unwrap();
+    handler.raw_command("KICK", (prefix, params, None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -101,14 +99,13 @@ fn kick_with_no_channel_in_database_is_ignored() {
 
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((prefix, params, None)).unwrap();
+    handler.raw_command("KICK", (prefix, params, None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -121,7 +118,7 @@ fn kick_removes_client_from_channel() {
 
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((prefix, params, None)).unwrap();
+    handler.raw_command("KICK", (prefix, params, None)).unwrap();
 
     assert!(!handler.database.is_client_in_channel("#channel", "kicked"));
 }
@@ -142,22 +139,20 @@ fn kick_is_relayed_to_all_other_servers() {
 
     let prefix = Some("kicker".to_string());
     let params = vec!["#channel".to_string(), "kicked".to_string()];
-    handler.kick_command((prefix, params, None)).unwrap();
+    handler.raw_command("KICK", (prefix, params, None)).unwrap();
 
     assert_eq!(
         ":kicker KICK #channel kicked\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":kicker KICK #channel kicked\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -180,7 +175,9 @@ fn kick_is_never_relayed_to_sending_server() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string(), "nickname2".to_string()];
-    handler.kick_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
@@ -207,30 +204,29 @@ fn kick_is_relayed_to_local_clients_on_channel() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string(), "nickname3".to_string()];
-    handler.kick_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("KICK", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 KICK #channel nickname3\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname1 KICK #channel nickname3\r\n",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname4")
-            .unwrap()
+            .flushed_local_stream("nickname4")
             .read_wbuf_to_string()
     );
 }
@@ -0,0 +1,128 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
+use crate::server::{
+    connection_handler::server_handler::tests::dummy_server_handler,
+    testing::{dummy_client, dummy_external_client, dummy_server, flushed_stream},
+};
+
+#[test]
+fn kill_with_invalid_arguments_is_ignored() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname1"));
+
+    let prefix = Some("operator".to_string());
+    let parameters = vec!["nickname1".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (None, parameters.clone(), trail.clone()))
+        .unwrap();
+    handler
+        .raw_command("KILL", (prefix.clone(), vec![], trail))
+        .unwrap();
+    handler
+        .raw_command("KILL", (prefix, parameters, None))
+        .unwrap();
+
+    assert!(handler.database.contains_client("nickname1"));
+}
+
+#[test]
+fn kill_with_unknown_client_is_ignored() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    let prefix = Some("operator".to_string());
+    let parameters = vec!["nickname1".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (prefix, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        "",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+}
+
+#[test]
+fn kill_disconnects_local_client() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname1"));
+    let queue = handler.database.get_local_queue("nickname1").unwrap();
+
+    let prefix = Some("operator".to_string());
+    let parameters = vec!["nickname1".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (prefix, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":operator KILL nickname1 :spam\r\n\
+        ERROR :Closing Link: nickname1 (Killed (operator (spam)))\r\n",
+        flushed_stream(&queue).read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname1"));
+}
+
+#[test]
+fn kill_sends_quit_to_local_clients_on_shared_channels() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname1", "servername1"));
+    handler.database.add_local_client(dummy_client("nickname2"));
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname1");
+    handler
+        .database
+        .add_client_to_channel("#channel", "nickname2");
+
+    let prefix = Some("operator".to_string());
+    let parameters = vec!["nickname1".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (prefix, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":nickname1 QUIT :Killed (operator (spam))\r\n",
+        handler
+            .database
+            .flushed_local_stream("nickname2")
+            .read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname1"));
+}
+
+#[test]
+fn kill_is_relayed_to_all_other_servers() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname1", "servername2"));
+
+    let prefix = Some("operator".to_string());
+    let parameters = vec!["nickname1".to_string()];
+    let trail = Some("spam".to_string());
+    handler
+        .raw_command("KILL", (prefix, parameters, trail))
+        .unwrap();
+
+    assert_eq!(
+        ":operator KILL nickname1 :spam\r\n",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+}
@@ -1,15 +1,20 @@
 use crate::server::testing::{dummy_database, dummy_server, MockTcpStream};
 
+mod account_tests;
 mod away_tests;
 mod invite_tests;
 mod join_tests;
 mod kick_tests;
+mod kill_tests;
 mod mode_tests;
 mod nick_tests;
+mod oper_tests;
 mod part_tests;
+mod ping_tests;
 mod privmsg_tests;
 mod quit_tests;
 mod server_tests;
+mod shutdown_tests;
 mod squit_tests;
 mod topic_tests;
 mod user_tests;
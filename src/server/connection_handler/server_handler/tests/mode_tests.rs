@@ -1,6 +1,7 @@
 use crate::server::consts::channel_flag::ChannelFlag;
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     consts::user_flag::UserFlag,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
@@ -17,7 +18,9 @@ fn mode_with_invalid_arguments_is_invalid() {
         "+b".to_string(),
         "banmask".to_string(),
     ];
-    handler.mode_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (None, parameters, None))
+        .unwrap();
 
     let prefix = Some("sender".to_string());
     let parameters = vec![
@@ -26,7 +29,7 @@ fn mode_with_invalid_arguments_is_invalid() {
         "banmask".to_string(),
     ];
     handler
-        .mode_command((prefix.clone(), parameters, None))
+        .raw_command("MODE", (prefix.clone(), parameters, None))
         .unwrap();
     let parameters = vec![
         "#channel".to_string(),
@@ -34,7 +37,7 @@ fn mode_with_invalid_arguments_is_invalid() {
         "banmask".to_string(),
     ];
     handler
-        .mode_command((prefix.clone(), parameters, None))
+        .raw_command("MODE", (prefix.clone(), parameters, None))
         .unwrap();
 
     let parameters = vec![
@@ -43,22 +46,21 @@ fn mode_with_invalid_arguments_is_invalid() {
         "banmask".to_string(),
     ];
     handler
-        .mode_command((prefix.clone(), parameters, None))
+        .raw_command("MODE", (prefix.clone(), parameters, None))
         .unwrap();
 
     let parameters = vec!["#channel".to_string()];
     handler
-        .mode_command((prefix.clone(), parameters, None))
+        .raw_command("MODE", (prefix.clone(), parameters, None))
         .unwrap();
 
-    handler.mode_command((prefix, vec![], None)).unwrap();
+    handler.raw_command("MODE", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -82,22 +84,22 @@ fn mode_is_relayed_to_all_other_servers() {
         "+b".to_string(),
         "banmask".to_string(),
     ];
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":sender MODE #channel +b banmask\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":sender MODE #channel +b banmask\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -111,7 +113,9 @@ fn mode_is_never_relayed_to_sending_server() {
 
     let parameters = vec!["#channel".to_string(), "+p".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
@@ -131,22 +135,22 @@ fn channel_mode_is_relayed_to_local_clients_on_channel() {
 
     let parameters = vec!["#channel".to_string(), "+s".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":sender MODE #channel +s\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
@@ -170,7 +174,9 @@ fn channel_mode_adds_channop() {
         "nick2".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
     assert!(handler.database.is_channel_operator("#channel", "nick2"));
 }
 
@@ -194,7 +200,9 @@ fn channel_mode_removes_channop() {
         "nick2".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler.database.is_channel_operator("#channel", "nick2"));
 }
@@ -216,7 +224,9 @@ fn channel_mode_sets_limit_to_channel() {
 
     let parameters = vec!["#channel".to_string(), "+l".to_string(), "5".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         handler.database.get_channel_limit("#channel").unwrap(),
@@ -241,7 +251,9 @@ fn channel_mode_unsets_channel_limit() {
 
     let parameters = vec!["#channel".to_string(), "-l".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -271,7 +283,9 @@ fn channel_mode_sets_banmask() {
         "banmask".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     let masks = vec!["banmask".to_string()];
 
@@ -304,7 +318,9 @@ fn channel_mode_unsets_banmask() {
         "banmask".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     let masks = vec!["banmask2".to_string()];
 
@@ -319,7 +335,9 @@ fn channel_mode_unsets_banmask() {
         "banmask2".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -348,7 +366,9 @@ fn channel_mode_adds_speaker_to_channel() {
         "nick2".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler.database.is_channel_speaker("#channel", "nick2"));
 }
@@ -374,7 +394,9 @@ fn channel_mode_removes_speakers_from_channel() {
         "nick2".to_string(),
     ];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler.database.is_channel_speaker("#channel", "nick2"));
 }
@@ -396,7 +418,9 @@ fn channel_mode_sets_channel_key() {
 
     let parameters = vec!["#channel".to_string(), "+k".to_string(), "key".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         handler.database.get_channel_key("#channel").unwrap(),
@@ -423,7 +447,9 @@ fn channel_mode_unsets_channel_key() {
 
     let parameters = vec!["#channel".to_string(), "-k".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -447,7 +473,9 @@ fn channel_mode_sets_and_unsets_private_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+p".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -457,7 +485,9 @@ fn channel_mode_sets_and_unsets_private_flag() {
     parameters = vec!["#channel".to_string(), "-p".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -480,7 +510,9 @@ fn channel_mode_sets_and_unsets_secret_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+s".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -490,7 +522,9 @@ fn channel_mode_sets_and_unsets_secret_flag() {
     parameters = vec!["#channel".to_string(), "-s".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -513,7 +547,9 @@ fn channel_mode_sets_and_unsets_invite_only_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+i".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -523,7 +559,9 @@ fn channel_mode_sets_and_unsets_invite_only_flag() {
     parameters = vec!["#channel".to_string(), "-i".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -546,7 +584,9 @@ fn channel_mode_sets_and_unsets_topic_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+t".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -556,7 +596,9 @@ fn channel_mode_sets_and_unsets_topic_flag() {
     parameters = vec!["#channel".to_string(), "-t".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -578,7 +620,9 @@ fn channel_mode_sets_and_unsets_no_outside_messages_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+n".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -588,7 +632,9 @@ fn channel_mode_sets_and_unsets_no_outside_messages_flag() {
     parameters = vec!["#channel".to_string(), "-n".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -611,7 +657,9 @@ fn channel_mode_sets_and_unsets_moderated_flag() {
 
     let mut parameters = vec!["#channel".to_string(), "+m".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(handler
@@ -621,7 +669,9 @@ fn channel_mode_sets_and_unsets_moderated_flag() {
     parameters = vec!["#channel".to_string(), "-m".to_string()];
 
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert!(!handler
@@ -638,7 +688,9 @@ fn user_mode_sets_and_unsets_invisible_flag() {
 
     let parameters = vec!["nickname".to_string(), "+i".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -649,7 +701,9 @@ fn user_mode_sets_and_unsets_invisible_flag() {
 
     let parameters = vec!["nickname".to_string(), "-i".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler
         .database
@@ -668,7 +722,9 @@ fn user_mode_sets_and_unsets_server_notices_flag() {
 
     let parameters = vec!["nickname".to_string(), "+s".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -679,7 +735,9 @@ fn user_mode_sets_and_unsets_server_notices_flag() {
 
     let parameters = vec!["nickname".to_string(), "-s".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler
         .database
@@ -698,7 +756,9 @@ fn user_mode_sets_and_unsets_server_wallop_flag() {
 
     let parameters = vec!["nickname".to_string(), "+w".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -709,7 +769,9 @@ fn user_mode_sets_and_unsets_server_wallop_flag() {
 
     let parameters = vec!["nickname".to_string(), "-w".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler
         .database
@@ -728,7 +790,9 @@ fn user_mode_sets_and_unsets_operator_flag() {
 
     let parameters = vec!["nickname".to_string(), "+o".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler
         .database
@@ -739,7 +803,9 @@ fn user_mode_sets_and_unsets_operator_flag() {
 
     let parameters = vec!["nickname".to_string(), "-o".to_string()];
     let prefix = Some("sender".to_string());
-    handler.mode_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("MODE", (prefix, parameters, None))
+        .unwrap();
 
     assert!(!handler
         .database
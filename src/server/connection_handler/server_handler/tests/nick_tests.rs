@@ -1,6 +1,7 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
-    testing::{dummy_client, dummy_external_client, dummy_server},
+    connection_handler::server_handler::tests::dummy_server_handler,
+    testing::{dummy_client, dummy_external_client, dummy_server, flushed_stream, DUMMY_SIGNON},
 };
 
 #[test]
@@ -8,7 +9,9 @@ fn nick_is_ignored_without_enough_parameters() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("nickname"));
 }
@@ -18,7 +21,9 @@ fn nick_is_ignored_with_non_numeric_hopcount() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("nickname"));
 }
@@ -28,7 +33,9 @@ fn nick_adds_client_to_hopcounts() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["nickname".to_string(), "1".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(1, handler.hopcounts.remove("nickname").unwrap());
 }
@@ -42,23 +49,150 @@ fn nick_with_prefix_updates_nick() {
 
     let parameters = vec!["nickname2".to_string()];
     let prefix = Some("nickname1".to_string());
-    handler.nick_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (prefix, parameters, None))
+        .unwrap();
 
     handler.database.contains_client("nickname2");
 }
 
 #[test]
-fn nick_with_used_nickname_returns_nick_collision() {
+fn nick_with_used_nickname_kills_both_clients() {
     let mut handler = dummy_server_handler();
     handler.database.add_local_client(dummy_client("nickname"));
+    let queue = handler.database.get_local_queue("nickname").unwrap();
 
     let parameters = vec!["nickname".to_string(), "1".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername KILL nickname :Nick collision\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        ":servername KILL nickname :Nick collision\r\n\
+        ERROR :Closing Link: nickname (Killed (servername (Nick collision)))\r\n",
+        flushed_stream(&queue).read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname"));
+    assert!(!handler.hopcounts.contains_key("nickname"));
+}
+
+#[test]
+fn nick_with_same_signon_kills_both_clients() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname"));
+
+    let signon = DUMMY_SIGNON.to_string();
+    let parameters = vec!["nickname".to_string(), "1".to_string(), signon];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername KILL nickname :Nick collision\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert!(!handler.database.contains_client("nickname"));
+    assert!(!handler.hopcounts.contains_key("nickname"));
+}
+
+#[test]
+fn nick_with_older_signon_kills_existing_client() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname"));
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    let queue = handler.database.get_local_queue("nickname").unwrap();
+
+    let signon = (DUMMY_SIGNON - 1).to_string();
+    let parameters = vec!["nickname".to_string(), "1".to_string(), signon];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername KILL nickname :Nick collision\r\n\
+        ERROR :Closing Link: nickname (Killed (servername (Nick collision)))\r\n",
+        flushed_stream(&queue).read_wbuf_to_string()
+    );
+    assert_eq!(
+        format!(
+            ":servername KILL nickname :Nick collision\r\nNICK nickname 2 {}\r\n",
+            DUMMY_SIGNON - 1
+        ),
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(Some(&1), handler.hopcounts.get("nickname"));
+}
+
+#[test]
+fn nick_with_newer_signon_is_ignored() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname"));
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    let signon = (DUMMY_SIGNON + 1).to_string();
+    let parameters = vec!["nickname".to_string(), "1".to_string(), signon];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
+    assert_eq!("", handler.stream.read_wbuf_to_string());
     assert_eq!(
-        "436 nickname :Nickname collision KILL\r\n",
+        "",
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .read_wbuf_to_string()
+    );
+    assert!(handler.database.is_local_client("nickname"));
+    assert!(!handler.hopcounts.contains_key("nickname"));
+}
+
+#[test]
+fn nick_is_ignored_with_non_numeric_signon() {
+    let mut handler = dummy_server_handler();
+
+    let parameters = vec!["nickname".to_string(), "1".to_string(), "a".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
+
+    assert!(!handler.hopcounts.contains_key("nickname"));
+}
+
+#[test]
+fn nick_update_to_used_nickname_kills_both_clients() {
+    let mut handler = dummy_server_handler();
+    handler.database.add_local_client(dummy_client("nickname1"));
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname2", "servername1"));
+
+    let parameters = vec!["nickname1".to_string()];
+    let prefix = Some("nickname2".to_string());
+    handler
+        .raw_command("NICK", (prefix, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername KILL nickname1 :Nick collision\r\n\
+        :servername KILL nickname2 :Nick collision\r\n",
         handler.stream.read_wbuf_to_string()
     );
+    assert!(!handler.database.contains_client("nickname1"));
+    assert!(!handler.database.contains_client("nickname2"));
 }
 
 #[test]
@@ -71,23 +205,23 @@ fn nick_is_relayed_to_all_other_servers() {
         .database
         .add_immediate_server(dummy_server("servername3"));
 
-    let parameters = vec!["nickname".to_string(), "1".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    let parameters = vec!["nickname".to_string(), "1".to_string(), "1234".to_string()];
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        "NICK nickname 2\r\n",
+        "NICK nickname 2 1234\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
-        "NICK nickname 2\r\n",
+        "NICK nickname 2 1234\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -97,14 +231,15 @@ fn nick_is_not_relayed_to_sending_server() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["nickname".to_string(), "1".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
@@ -124,22 +259,22 @@ fn nick_update_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["nickname2".to_string()];
-    handler.nick_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 NICK nickname2\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname1 NICK nickname2\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -149,22 +284,30 @@ fn nick_fails_with_invalid_prefix() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["#nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("#nickname"));
 
     let parameters = vec!["&nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("&nickname"));
 
     let parameters = vec!["$nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("$nickname"));
 
-    let parameters = vec![":nickname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    let trailing = Some(":nickname".to_string());
+    handler
+        .raw_command("NICK", (None, vec![], trailing))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key(":nickname"));
 }
@@ -174,31 +317,43 @@ fn nick_fails_with_invalid_character() {
     let mut handler = dummy_server_handler();
 
     let parameters = vec!["nick.name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("nick.name"));
 
     let parameters = vec!["nickname!".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("nickname!"));
 
     let parameters = vec!["ni,ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("ni,ckname"));
 
     let parameters = vec!["nick*name".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("nick*name"));
 
     let parameters = vec!["ni?ckname".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
 
     assert!(!handler.hopcounts.contains_key("ni?ckname"));
 
     let parameters = vec!["nickname@".to_string()];
-    handler.nick_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("NICK", (None, parameters, None))
+        .unwrap();
     assert!(!handler.hopcounts.contains_key("nickname@"));
 }
@@ -0,0 +1,21 @@
+use crate::server::testing::HandleRawCommand;
+use crate::server::{
+    connection_handler::server_handler::tests::dummy_server_handler, testing::dummy_external_client,
+};
+
+#[test]
+fn oper_is_ignored() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname", "servername1"));
+
+    let prefix = Some("nickname".to_string());
+    let parameters = vec!["admin".to_string(), "admin".to_string()];
+    handler
+        .raw_command("OPER", (prefix, parameters, None))
+        .unwrap();
+
+    assert!(!handler.database.is_server_operator("nickname"));
+    assert_eq!("", handler.stream.read_wbuf_to_string());
+}
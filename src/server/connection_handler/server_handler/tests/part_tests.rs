@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
 
@@ -19,15 +20,16 @@ fn part_without_enough_parameters_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((None, parameters, None)).unwrap();
-    handler.part_command((prefix, vec![], None)).unwrap();
+    handler
+        .raw_command("PART", (None, parameters, None))
+        .unwrap();
+    handler.raw_command("PART", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -44,14 +46,15 @@ fn part_with_no_client_in_channel_database_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -68,7 +71,9 @@ fn part_with_no_channel_in_database_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     handler
         .database
@@ -78,8 +83,7 @@ fn part_with_no_channel_in_database_is_ignored() {
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -93,14 +97,15 @@ fn part_with_no_client_in_database_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -118,7 +123,7 @@ fn part_removes_client_from_channel() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string()];
-    handler.part_command((prefix, params, None)).unwrap();
+    handler.raw_command("PART", (prefix, params, None)).unwrap();
 
     assert!(!handler
         .database
@@ -144,22 +149,22 @@ fn part_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 PART #channel\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname1 PART #channel\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -177,7 +182,9 @@ fn part_is_never_relayed_to_sending_server() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
@@ -201,22 +208,22 @@ fn part_is_relayed_to_local_clients_on_channel() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string()];
-    handler.part_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("PART", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 PART #channel\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
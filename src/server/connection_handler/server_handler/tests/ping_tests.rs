@@ -0,0 +1,83 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
+use std::time::Duration;
+
+use crate::server::{
+    connection_handler::{
+        server_handler::tests::dummy_server_handler, ConnectionHandlerStructure, Keepalive,
+    },
+    testing::{dummy_external_client, dummy_server},
+};
+
+#[test]
+fn ping_is_answered_with_pong() {
+    let mut handler = dummy_server_handler();
+
+    let parameters = vec!["servername1".to_string()];
+    handler
+        .raw_command("PING", (None, parameters, None))
+        .unwrap();
+
+    assert_eq!(
+        ":servername PONG servername :servername1\r\n",
+        handler.stream.read_wbuf_to_string()
+    )
+}
+
+#[test]
+fn ping_without_origin_is_ignored() {
+    let mut handler = dummy_server_handler();
+
+    handler.raw_command("PING", (None, vec![], None)).unwrap();
+
+    assert_eq!("", handler.stream.read_wbuf_to_string())
+}
+
+#[test]
+fn idle_server_is_pinged() {
+    let mut handler = dummy_server_handler();
+    handler.keepalive = Keepalive::new(Duration::ZERO, Duration::from_secs(60));
+
+    assert!(handler.idle());
+    handler.on_idle().unwrap();
+    handler.flush_output().unwrap();
+
+    assert_eq!("PING :servername\r\n", handler.stream.read_wbuf_to_string());
+    assert!(!handler.timeout());
+}
+
+#[test]
+fn unanswered_ping_disconnects_server_and_its_clients() {
+    let mut handler = dummy_server_handler();
+    handler.keepalive = Keepalive::new(Duration::ZERO, Duration::ZERO);
+
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname1", "servername1"));
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+
+    handler.on_idle().unwrap();
+    handler.flush_output().unwrap();
+    handler.stream.clear();
+
+    assert!(handler.timeout());
+    handler.on_timeout().unwrap();
+
+    assert_eq!(
+        "ERROR :Closing Link: servername1 (Ping timeout)\r\n",
+        handler.stream.read_wbuf_to_string()
+    );
+    assert_eq!(
+        vec![
+            ":servername SQUIT servername1 :Ping timeout",
+            ":nickname1 QUIT :Net split",
+        ],
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .get_responses()
+    );
+    assert!(!handler.database.contains_server("servername1"));
+    assert!(!handler.database.contains_client("nickname1"));
+}
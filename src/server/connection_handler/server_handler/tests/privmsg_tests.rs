@@ -1,7 +1,5 @@
-use crate::server::{
-    connection_handler::ConnectionHandlerCommands,
-    testing::{dummy_client, dummy_external_client, dummy_server},
-};
+use crate::server::testing::{dummy_client, dummy_external_client, dummy_server};
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 
 use super::dummy_server_handler;
 
@@ -13,12 +11,14 @@ fn privmsg_is_ignored_without_enough_parameters() {
     let params = vec!["target".to_string()];
     let trail = Some("message".to_string());
     handler
-        .privmsg_command((None, params.clone(), trail.clone()))
+        .raw_command("PRIVMSG", (None, params.clone(), trail.clone()))
         .unwrap();
     handler
-        .privmsg_command((prefix.clone(), vec![], trail))
+        .raw_command("PRIVMSG", (prefix.clone(), vec![], trail))
+        .unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, None))
         .unwrap();
-    handler.privmsg_command((prefix, params, None)).unwrap();
 }
 
 #[test]
@@ -28,7 +28,9 @@ fn privmsg_is_ignored_with_unknown_target() {
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string()];
     let trail = Some("message".to_string());
-    assert!(handler.privmsg_command((prefix, params, trail)).is_ok());
+    assert!(handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .is_ok());
 }
 
 #[test]
@@ -42,14 +44,15 @@ fn privmsg_is_relayed_to_client() {
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string()];
     let trail = Some("message".to_string());
-    handler.privmsg_command((prefix, params, trail)).unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .unwrap();
 
     assert_eq!(
         ":sender PRIVMSG target :message\r\n",
         handler
             .database
-            .get_local_stream("target")
-            .unwrap()
+            .flushed_local_stream("target")
             .read_wbuf_to_string()
     )
 }
@@ -70,14 +73,15 @@ fn privmsg_to_client_is_relayed_to_necesary_server() {
     let prefix = Some("sender".to_string());
     let params = vec!["target".to_string()];
     let trail = Some("message".to_string());
-    handler.privmsg_command((prefix, params, trail)).unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .unwrap();
 
     assert_eq!(
         ":sender PRIVMSG target :message\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     )
 }
@@ -102,14 +106,15 @@ fn privmsg_to_channel_is_relayed_to_all_local_clients_in_channel() {
     let prefix = Some("sender".to_string());
     let params = vec!["#channel".to_string()];
     let trail = Some("message".to_string());
-    handler.privmsg_command((prefix, params, trail)).unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .unwrap();
 
     assert_eq!(
         ":sender PRIVMSG #channel :message\r\n",
         handler
             .database
-            .get_local_stream("nickname1")
-            .unwrap()
+            .flushed_local_stream("nickname1")
             .read_wbuf_to_string()
     );
 
@@ -117,8 +122,7 @@ fn privmsg_to_channel_is_relayed_to_all_local_clients_in_channel() {
         ":sender PRIVMSG #channel :message\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
 }
@@ -149,14 +153,15 @@ fn privmsg_to_channel_is_relayed_to_each_necesary_server_once() {
     let prefix = Some("sender".to_string());
     let params = vec!["#channel".to_string()];
     let trail = Some("message".to_string());
-    handler.privmsg_command((prefix, params, trail)).unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .unwrap();
 
     assert_eq!(
         ":sender PRIVMSG #channel :message\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -172,15 +177,16 @@ fn privmsg_is_never_relayed_to_sending_server() {
     let prefix = Some("sender".to_string());
     let params = vec!["#channel".to_string()];
     let trail = Some("message".to_string());
-    handler.privmsg_command((prefix, params, trail)).unwrap();
+    handler
+        .raw_command("PRIVMSG", (prefix, params, trail))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
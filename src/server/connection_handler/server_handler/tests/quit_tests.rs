@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
 
@@ -15,15 +16,14 @@ fn quit_with_invalid_arguments_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let trail = Some("going to lunch!".to_string());
-    handler.quit_command((None, vec![], trail)).unwrap();
-    handler.quit_command((prefix, vec![], None)).unwrap();
+    handler.raw_command("QUIT", (None, vec![], trail)).unwrap();
+    handler.raw_command("QUIT", (prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 }
@@ -38,14 +38,15 @@ fn quit_with_unknown_client_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let trail = Some("going to lunch!".to_string());
-    handler.quit_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("QUIT", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 }
@@ -67,22 +68,22 @@ fn quit_is_sent_to_local_clients_on_shared_channels() {
         .add_client_to_channel("#channel", "nickname2");
     let prefix = Some("nickname1".to_string());
     let trail = Some("nickname1".to_string());
-    handler.quit_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("QUIT", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 QUIT :nickname1\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
@@ -102,14 +103,15 @@ fn quit_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname1".to_string());
     let trail = Some("going to lunch!".to_string());
-    handler.quit_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("QUIT", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 QUIT :going to lunch!\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 
@@ -117,8 +119,7 @@ fn quit_is_relayed_to_all_other_servers() {
         ":nickname1 QUIT :going to lunch!\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string(),
     );
 }
@@ -132,7 +133,9 @@ fn quit_is_never_relayed_to_sending_server() {
 
     let prefix = Some("nickname1".to_string());
     let trail = Some("disconnecting".to_string());
-    handler.quit_command((prefix, vec![], trail)).unwrap();
+    handler
+        .raw_command("QUIT", (prefix, vec![], trail))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
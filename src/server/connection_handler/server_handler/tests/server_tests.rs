@@ -1,6 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
-    testing::dummy_server,
+    connection_handler::server_handler::tests::dummy_server_handler, testing::dummy_server,
 };
 
 #[test]
@@ -11,28 +11,31 @@ fn server_with_invalid_arguments_is_ignored() {
         .add_immediate_server(dummy_server("servername2"));
 
     let parameters = vec!["servername4".to_string(), "2".to_string()];
-    handler.server_command((None, parameters, None)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, None))
+        .unwrap();
 
     let trail = Some("serverinfo".to_string());
     let parameters = vec!["servername4".to_string()];
     handler
-        .server_command((None, parameters, trail.clone()))
+        .raw_command("SERVER", (None, parameters, trail.clone()))
         .unwrap();
 
     let parameters = vec![];
     handler
-        .server_command((None, parameters, trail.clone()))
+        .raw_command("SERVER", (None, parameters, trail.clone()))
         .unwrap();
 
     let parameters = vec!["servername4".to_string(), "a".to_string()];
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -43,7 +46,9 @@ fn server_adds_server_to_database() {
 
     let parameters = vec!["servername2".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert!(handler.database.contains_server("servername2"));
 }
@@ -60,22 +65,22 @@ fn server_is_relayed_to_all_other_servers() {
 
     let parameters = vec!["servername4".to_string(), "2".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "SERVER servername4 3 :serverinfo\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "SERVER servername4 3 :serverinfo\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -86,14 +91,15 @@ fn server_is_not_relayed_to_sending_server() {
 
     let parameters = vec!["servername2".to_string(), "1".to_string()];
     let trail = Some("serverinfo".to_string());
-    handler.server_command((None, parameters, trail)).unwrap();
+    handler
+        .raw_command("SERVER", (None, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
@@ -0,0 +1,21 @@
+use std::sync::atomic::Ordering;
+
+use crate::server::connection_handler::{
+    server_handler::tests::dummy_server_handler, ConnectionHandlerStructure,
+};
+
+#[test]
+fn server_is_sent_squit_on_shutdown() {
+    let mut handler = dummy_server_handler();
+    handler.online.store(false, Ordering::Relaxed);
+
+    assert!(!handler.try_handle().unwrap());
+
+    assert_eq!(
+        vec![
+            ":servername SQUIT servername :Server shutting down",
+            "ERROR :Closing Link: servername1 (Server shutting down)",
+        ],
+        handler.stream.get_responses()
+    );
+}
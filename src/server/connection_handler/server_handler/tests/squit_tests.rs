@@ -1,6 +1,11 @@
+use crate::server::connection_handler::ConnectionHandler;
+use crate::server::reactor::Progress;
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
-    testing::{dummy_client, dummy_distant_server, dummy_external_client, dummy_server},
+    connection_handler::server_handler::tests::dummy_server_handler,
+    testing::{
+        dummy_client, dummy_distant_server, dummy_external_client, dummy_server, flushed_stream,
+    },
 };
 
 #[test]
@@ -15,15 +20,18 @@ fn squit_with_invalid_arguments_is_ignored() {
 
     let prefix = Some("oper".to_string());
     let parameters = vec!["servername2".to_string()];
-    handler.squit_command((None, parameters, None)).unwrap();
-    handler.squit_command((prefix, vec![], None)).unwrap();
+    handler
+        .raw_command("SQUIT", (None, parameters, None))
+        .unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, vec![], None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -38,14 +46,15 @@ fn squit_with_unknown_server_is_ignored() {
 
     let prefix = Some("oper".to_string());
     let parameters = vec!["servername2".to_string()];
-    handler.squit_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -62,7 +71,9 @@ fn squit_removes_server_from_database() {
     let trail = Some("Closing connection".to_string());
 
     assert!(handler.database.contains_server("servername2"));
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
     assert!(!handler.database.contains_server("servername2"));
 }
 
@@ -78,7 +89,9 @@ fn squit_disconnects_external_client() {
     let prefix = Some("oper".to_string());
     let parameters = vec!["servername2".to_string()];
     let trail = Some("Closing connection".to_string());
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
 
     assert!(handler.database.get_local_stream("nickname").is_err());
 }
@@ -97,20 +110,21 @@ fn squit_is_relayed_to_all_other_servers() {
     let parameters = vec!["servername2".to_string()];
     let trail = Some("Closing connection".to_string());
 
-    let server2_stream = handler.database.get_server_stream("servername2");
+    let queue2 = handler.database.get_server_queue("servername2").unwrap();
 
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         ":oper SQUIT servername2 :Closing connection\r\n",
-        server2_stream.unwrap().read_wbuf_to_string()
+        flushed_stream(&queue2).read_wbuf_to_string()
     );
     assert_eq!(
         ":oper SQUIT servername2 :Closing connection\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -123,14 +137,15 @@ fn squit_is_not_relayed_to_sending_server() {
     let parameters = vec!["servername2".to_string()];
     let trail = Some("Closing connection".to_string());
 
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
@@ -151,14 +166,16 @@ fn squit_relays_client_quit_to_servers() {
     let parameters = vec!["servername2".to_string()];
     let trail = Some("Closing connection".to_string());
 
-    let stream2 = handler.database.get_server_stream("servername2");
+    let queue2 = handler.database.get_server_queue("servername2").unwrap();
 
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
 
-    let stream3 = handler.database.get_server_stream("servername3");
+    let queue3 = handler.database.get_server_queue("servername3").unwrap();
 
-    let responses2 = stream2.unwrap().get_responses();
-    let responses3 = stream3.unwrap().get_responses();
+    let responses2 = flushed_stream(&queue2).get_responses();
+    let responses3 = flushed_stream(&queue3).get_responses();
 
     assert_eq!(":oper SQUIT servername2 :Closing connection", responses2[0]);
     assert_eq!(":oper SQUIT servername2 :Closing connection", responses3[0]);
@@ -179,21 +196,22 @@ fn squit_does_not_relay_client_quit_to_sending_server() {
     let parameters = vec!["servername2".to_string()];
     let trail = Some("Closing connection".to_string());
 
-    let stream2 = handler.database.get_server_stream("servername2");
+    let queue2 = handler.database.get_server_queue("servername2").unwrap();
 
-    handler.squit_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         ":oper SQUIT servername2 :Closing connection\r\n",
-        stream2.unwrap().read_wbuf_to_string()
+        flushed_stream(&queue2).read_wbuf_to_string()
     );
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
@@ -210,7 +228,9 @@ fn squit_does_not_disconnect_distant_servers() {
 
     assert!(handler.database.contains_server("servername2"));
 
-    handler.squit_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, None))
+        .unwrap();
 
     assert!(handler.database.contains_server("servername2"));
 }
@@ -240,22 +260,48 @@ fn squit_relays_client_quit_to_local_clients_on_channel() {
     let prefix = Some("oper".to_string());
     let parameters = vec!["servername2".to_string()];
 
-    handler.squit_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("SQUIT", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
         ":nickname1 QUIT :Net split\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
+
+#[test]
+fn lost_link_is_handled_as_squit() {
+    let mut handler = dummy_server_handler();
+    handler
+        .database
+        .add_immediate_server(dummy_server("servername2"));
+    handler
+        .database
+        .add_external_client(dummy_external_client("nickname", "servername1"));
+
+    assert!(matches!(handler.poll_connection(), Progress::Closed));
+
+    assert!(!handler.database.contains_server("servername1"));
+    assert!(!handler.database.contains_client("nickname"));
+    assert_eq!(
+        vec![
+            ":servername SQUIT servername1 :Remote host closed the connection",
+            ":nickname QUIT :Net split"
+        ],
+        handler
+            .database
+            .flushed_server_stream("servername2")
+            .get_responses()
+    );
+}
@@ -1,5 +1,6 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
+    connection_handler::server_handler::tests::dummy_server_handler,
     testing::{dummy_client, dummy_external_client, dummy_server},
 };
 
@@ -17,20 +18,21 @@ fn topic_with_invalid_arguments_is_ignored() {
         .add_immediate_server(dummy_server("servername2"));
 
     let params = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((None, params, None)).unwrap();
+    handler.raw_command("TOPIC", (None, params, None)).unwrap();
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string()];
     handler
-        .topic_command((prefix.clone(), params, None))
+        .raw_command("TOPIC", (prefix.clone(), params, None))
+        .unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, vec![], None))
         .unwrap();
-    handler.topic_command((prefix, vec![], None)).unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 }
@@ -47,14 +49,15 @@ fn topic_with_no_channel_is_ignored() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 }
@@ -71,7 +74,9 @@ fn topic_sets_channel_topic() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
         Some("new_topic".to_string()),
@@ -94,22 +99,22 @@ fn topic_is_sent_to_local_clients() {
 
     let prefix = Some("nickname1".to_string());
     let parameters = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((prefix, parameters, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, parameters, None))
+        .unwrap();
 
     assert_eq!(
-        ":nickname1 TOPIC #channel new_topic\r\n",
+        ":nickname1 TOPIC #channel :new_topic\r\n",
         handler
             .database
-            .get_local_stream("nickname2")
-            .unwrap()
+            .flushed_local_stream("nickname2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         "",
         handler
             .database
-            .get_local_stream("nickname3")
-            .unwrap()
+            .flushed_local_stream("nickname3")
             .read_wbuf_to_string()
     );
 }
@@ -132,23 +137,23 @@ fn topic_is_relayed_to_all_other_servers() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, params, None))
+        .unwrap();
 
     assert_eq!(
-        ":nickname1 TOPIC #channel new_topic\r\n",
+        ":nickname1 TOPIC #channel :new_topic\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string(),
     );
 
     assert_eq!(
-        ":nickname1 TOPIC #channel new_topic\r\n",
+        ":nickname1 TOPIC #channel :new_topic\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string(),
     );
 }
@@ -165,7 +170,9 @@ fn topic_is_never_relayed_to_sending_server() {
 
     let prefix = Some("nickname1".to_string());
     let params = vec!["#channel".to_string(), "new_topic".to_string()];
-    handler.topic_command((prefix, params, None)).unwrap();
+    handler
+        .raw_command("TOPIC", (prefix, params, None))
+        .unwrap();
 
     assert_eq!("", handler.stream.read_wbuf_to_string());
 }
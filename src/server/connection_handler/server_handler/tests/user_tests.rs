@@ -1,12 +1,13 @@
+use crate::server::testing::{HandleRawCommand, ReadQueuedOutput};
 use crate::server::{
-    connection_handler::{server_handler::tests::dummy_server_handler, ConnectionHandlerCommands},
-    testing::dummy_server,
+    connection_handler::server_handler::tests::dummy_server_handler, testing::dummy_server,
 };
 
 #[test]
 fn user_is_ignored_without_enough_parameters() {
     let mut handler = dummy_server_handler();
     handler.hopcounts.insert("nickname".to_string(), 1);
+    handler.signons.insert("nickname".to_string(), 1234);
 
     handler
         .database
@@ -19,21 +20,23 @@ fn user_is_ignored_without_enough_parameters() {
     ];
     let trail = Some("realname".to_string());
     handler
-        .user_command((None, parameters.clone(), trail.clone()))
+        .raw_command("USER", (None, parameters.clone(), trail.clone()))
         .unwrap();
     let prefix = Some("nickname".to_string());
     handler
-        .user_command((prefix.clone(), parameters, None))
+        .raw_command("USER", (prefix.clone(), parameters, None))
         .unwrap();
     let parameters = vec!["username".to_string(), "hostname".to_string()];
     handler
-        .user_command((prefix.clone(), parameters, trail.clone()))
+        .raw_command("USER", (prefix.clone(), parameters, trail.clone()))
         .unwrap();
     let parameters = vec!["username".to_string()];
     handler
-        .user_command((prefix.clone(), parameters, trail.clone()))
+        .raw_command("USER", (prefix.clone(), parameters, trail.clone()))
+        .unwrap();
+    handler
+        .raw_command("USER", (prefix, vec![], trail))
         .unwrap();
-    handler.user_command((prefix, vec![], trail)).unwrap();
 
     assert!(!handler.database.contains_client("nickname"));
 
@@ -41,8 +44,7 @@ fn user_is_ignored_without_enough_parameters() {
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -63,7 +65,7 @@ fn user_is_ignored_with_no_previuos_nick() {
     ];
     let trailing = Some("realname".to_string());
     handler
-        .user_command((prefix, parameters, trailing))
+        .raw_command("USER", (prefix, parameters, trailing))
         .unwrap();
 
     assert!(!handler.database.contains_client("nickname"));
@@ -72,8 +74,7 @@ fn user_is_ignored_with_no_previuos_nick() {
         "",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
 }
@@ -82,6 +83,7 @@ fn user_is_ignored_with_no_previuos_nick() {
 fn user_adds_client_to_database() {
     let mut handler = dummy_server_handler();
     handler.hopcounts.insert("nickname".to_string(), 1);
+    handler.signons.insert("nickname".to_string(), 1234);
 
     let prefix = Some("nickname".to_string());
     let parameters = vec![
@@ -91,10 +93,12 @@ fn user_adds_client_to_database() {
     ];
     let trailing = Some("realname".to_string());
     handler
-        .user_command((prefix, parameters, trailing))
+        .raw_command("USER", (prefix, parameters, trailing))
         .unwrap();
 
     assert!(handler.database.contains_client("nickname"));
+    let info = handler.database.get_client_info("nickname").unwrap();
+    assert_eq!(1234, info.signon);
 }
 
 #[test]
@@ -107,6 +111,7 @@ fn user_is_relayed_to_all_other_servers() {
         .database
         .add_immediate_server(dummy_server("servername3"));
     handler.hopcounts.insert("nickname".to_string(), 1);
+    handler.signons.insert("nickname".to_string(), 1234);
 
     let parameters = vec![
         "username".to_string(),
@@ -115,22 +120,22 @@ fn user_is_relayed_to_all_other_servers() {
     ];
     let prefix = Some("nickname".to_string());
     let trail = Some("realname".to_string());
-    handler.user_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("USER", (prefix, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         ":nickname USER username hostname servername :realname\r\n",
         handler
             .database
-            .get_server_stream("servername2")
-            .unwrap()
+            .flushed_server_stream("servername2")
             .read_wbuf_to_string()
     );
     assert_eq!(
         ":nickname USER username hostname servername :realname\r\n",
         handler
             .database
-            .get_server_stream("servername3")
-            .unwrap()
+            .flushed_server_stream("servername3")
             .read_wbuf_to_string()
     );
 }
@@ -146,14 +151,15 @@ fn user_is_not_relayed_to_sending_server() {
     ];
     let prefix = Some("nickname".to_string());
     let trail = Some("realname".to_string());
-    handler.user_command((prefix, parameters, trail)).unwrap();
+    handler
+        .raw_command("USER", (prefix, parameters, trail))
+        .unwrap();
 
     assert_eq!(
         "",
         handler
             .database
-            .get_server_stream("servername1")
-            .unwrap()
+            .flushed_server_stream("servername1")
             .read_wbuf_to_string()
     );
 }
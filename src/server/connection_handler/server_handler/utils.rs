@@ -1,14 +1,17 @@
-use std::fmt::Display;
+use std::io;
 
+use crate::message::ToMessage;
 use crate::server::{connection::Connection, connection_handler::ConnectionHandlerUtils};
 
 use super::ServerHandler;
 
 impl<C: Connection> ConnectionHandlerUtils<C> for ServerHandler<C> {
-    fn send_message_to_channel(&self, message: &dyn Display, channel: &str) {
-        self.send_message_to_local_clients_on_channel(message, channel);
+    fn send_message(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.output.send(message)
+    }
 
-        let mut servers = self.get_channel_immediate_servers(channel);
+    fn send_message_to_channel(&self, message: &dyn ToMessage, channel: &str) {
+        let mut servers = self.deliver_to_channel(message, channel);
 
         servers
             .iter()
@@ -20,7 +23,7 @@ impl<C: Connection> ConnectionHandlerUtils<C> for ServerHandler<C> {
 }
 
 impl<C: Connection> ServerHandler<C> {
-    pub fn send_message_to_all_other_servers(&self, message: &dyn Display) {
+    pub fn send_message_to_all_other_servers(&self, message: &dyn ToMessage) {
         let mut servers = self.database.get_all_servers();
 
         if let Some(index) = servers.iter().position(|x| x == &self.servername) {
@@ -1,44 +1,69 @@
 use std::io;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
 
-use crate::message::{CreationError, Message};
+use crate::message::{Command, CommandError, CreationError, Message, ToMessage};
 use crate::server::connection::Connection;
-use crate::server::consts::commands::*;
-use crate::server::responses::ErrorReply;
+use crate::server::responses::{CommandResponse, ErrorReply};
 
 use super::{
     ConnectionHandlerCommands, ConnectionHandlerGetters, ConnectionHandlerUtils,
-    READ_FROM_STREAM_TIMEOUT_MS,
+    SERVER_SHUTDOWN_MESSAGE,
 };
 
-pub type CommandArgs = (Option<String>, Vec<String>, Option<String>);
-const SERVER_SHUTDOWN_MESSAGE: &str = "Server has shutdown";
+/// Typed arguments of a command, along with the prefix it was sent from.
+pub type CommandArgs<T> = (Option<String>, T);
 
 pub trait ConnectionHandlerStructure<C: Connection>:
     ConnectionHandlerCommands<C> + ConnectionHandlerGetters<C> + ConnectionHandlerUtils<C>
 {
-    fn try_handle(&mut self) -> io::Result<()> {
-        let timeout = Duration::from_millis(READ_FROM_STREAM_TIMEOUT_MS);
-        self.stream()
-            .set_read_timeout(Some(timeout))
-            .expect("Duration should never be zero or None");
+    /// Handles every message the connection has ready, without waiting for more.
+    /// Returns false once the handler is done with the connection,
+    /// after writing as much of what is still queued as the connection accepts.
+    fn try_handle(&mut self) -> io::Result<bool> {
+        let handled = self.handle_ready();
+
+        if !matches!(handled, Ok(true)) {
+            self.flush_output().ok();
+        }
 
-        loop {
-            if self.server_shutdown() {
-                return self.on_server_shutdown();
-            }
+        handled
+    }
 
-            if self.timeout() {
-                return self.on_timeout();
-            }
+    /// Handles every message the connection has ready, returning false when it should be closed.
+    fn handle_ready(&mut self) -> io::Result<bool> {
+        if self.server_shutdown() {
+            return self.shut_down();
+        }
+
+        if self.hung_up() {
+            return self.hang_up();
+        }
+
+        if self.timeout() {
+            self.on_timeout()?;
+            return Ok(false);
+        }
 
-            let message = Message::read_from(self.stream());
+        if self.sendq_exceeded() {
+            self.on_sendq_exceeded()?;
+            return Ok(false);
+        }
+
+        self.flush_output()?;
+        self.on_poll()?;
+
+        if self.idle() {
+            self.on_idle()?;
+        }
 
-            let message = match message {
+        loop {
+            let message = match self.read_message() {
                 Ok(message) => message,
                 Err(CreationError::IoError(error)) => match error.kind() {
-                    io::ErrorKind::WouldBlock => continue,
+                    io::ErrorKind::WouldBlock => {
+                        self.flush_output()?;
+                        return Ok(true);
+                    }
                     _ => return Err(error),
                 },
                 Err(CreationError::ParsingError(_)) => {
@@ -47,43 +72,38 @@ pub trait ConnectionHandlerStructure<C: Connection>:
                 }
             };
 
-            if !self.handle_message(message)? {
-                return Ok(());
+            self.on_activity();
+
+            match self.handle_message(message) {
+                Ok(true) => continue,
+                Ok(false) => return Ok(false),
+                Err(_) if self.sendq_exceeded() => {
+                    self.on_sendq_exceeded()?;
+                    return Ok(false);
+                }
+                Err(error) => return Err(error),
             }
         }
     }
 
+    /// Builds the command the message carries, handling the error if it is unknown or malformed.
     fn handle_message(&mut self, message: Message) -> io::Result<bool> {
-        let (prefix, command, parameters, trailing) = message.unpack();
-        let arguments = (prefix, parameters, trailing);
-
-        match &command[..] {
-            PASS_COMMAND => self.pass_command(arguments),
-            NICK_COMMAND => self.nick_command(arguments),
-            USER_COMMAND => self.user_command(arguments),
-            OPER_COMMAND => self.oper_command(arguments),
-            PRIVMSG_COMMAND => self.privmsg_command(arguments),
-            NOTICE_COMMAND => self.notice_command(arguments),
-            JOIN_COMMAND => self.join_command(arguments),
-            PART_COMMAND => self.part_command(arguments),
-            INVITE_COMMAND => self.invite_command(arguments),
-            NAMES_COMMAND => self.names_command(arguments),
-            LIST_COMMAND => self.list_command(arguments),
-            WHO_COMMAND => self.who_command(arguments),
-            WHOIS_COMMAND => self.whois_command(arguments),
-            AWAY_COMMAND => self.away_command(arguments),
-            TOPIC_COMMAND => self.topic_command(arguments),
-            KICK_COMMAND => self.kick_command(arguments),
-            MODE_COMMAND => self.mode_command(arguments),
-            QUIT_COMMAND => self.quit_command(arguments),
-            SERVER_COMMAND => self.server_command(arguments),
-            SQUIT_COMMAND => self.squit_command(arguments),
-            CTCP_COMMAND => self.ctcp_command(arguments),
-            _ => self.on_unknown_command(command),
+        let (prefix, name, parameters, trailing) = message.unpack();
+
+        match Command::new(&name, parameters, trailing) {
+            Ok(command) => self.handle_command(prefix, command),
+            Err(CommandError::UnknownCommand { command }) => self.on_unknown_command(command),
+            Err(error) => {
+                self.on_command_error(&name, error)?;
+                Ok(true)
+            }
         }
     }
 
-    fn on_try_handle_error(&mut self);
+    /// Reads next message from stream, keeping partial lines buffered between calls.
+    fn read_message(&mut self) -> Result<Message, CreationError>;
+    /// Called when the connection ended with error, to clean up as if it had been closed.
+    fn on_try_handle_error(&mut self, error: io::Error);
     fn on_try_handle_success(&mut self);
 
     fn server_shutdown(&mut self) -> bool {
@@ -94,16 +114,91 @@ pub trait ConnectionHandlerStructure<C: Connection>:
         false
     }
 
+    /// Says goodbye once, then keeps the connection open until everything queued for it was written.
+    /// Returns false once the connection was closed.
+    fn shut_down(&mut self) -> io::Result<bool> {
+        if !self.closing() {
+            self.on_server_shutdown()?;
+        }
+
+        self.flush_output()?;
+        if self.queued_output() > 0 {
+            return Ok(true);
+        }
+
+        self.stream().shutdown()?;
+        Ok(false)
+    }
+
+    /// Writes as much of what is still queued as the connection accepts, then closes it.
+    fn hang_up(&mut self) -> io::Result<bool> {
+        self.flush_output()?;
+        self.stream().shutdown()?;
+        Ok(false)
+    }
+
     fn on_server_shutdown(&mut self) -> io::Result<()> {
-        self.stream().send(&SERVER_SHUTDOWN_MESSAGE)?;
-        self.stream().shutdown()
+        let target = self.reply_target();
+        let message = format!("Closing Link: {target} ({SERVER_SHUTDOWN_MESSAGE})");
+        self.close_output(&CommandResponse::error(&message))
+    }
+
+    /// Sends message as the last one the connection gets.
+    fn close_output(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.send_message(message)
+    }
+
+    /// Returns true once the last message for the connection was sent.
+    fn closing(&mut self) -> bool {
+        false
+    }
+
+    /// Returns true once the connection was hung up from elsewhere, and should be closed.
+    fn hung_up(&mut self) -> bool {
+        false
+    }
+
+    /// Returns how many bytes are still waiting to be written to the connection.
+    fn queued_output(&mut self) -> usize {
+        0
     }
 
     fn on_timeout(&mut self) -> io::Result<()> {
         Ok(())
     }
 
+    /// Returns true once more output was queued for the connection than its sendq allows.
+    fn sendq_exceeded(&mut self) -> bool {
+        false
+    }
+
+    fn on_sendq_exceeded(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Writes whatever output is still queued for the connection, as much as it accepts.
+    fn flush_output(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called every time the connection is polled, before reading from it.
+    fn on_poll(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns true when the connection has been quiet long enough to be probed.
+    fn idle(&mut self) -> bool {
+        false
+    }
+
+    fn on_idle(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called for every message read from the connection.
+    fn on_activity(&mut self) {}
+
     fn on_parsing_error(&mut self) -> io::Result<()> {
-        self.stream().send(&ErrorReply::ParsingError)
+        self.send_response(&ErrorReply::ParsingError)
     }
 }
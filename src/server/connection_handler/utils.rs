@@ -1,16 +1,144 @@
-use std::fmt::Display;
-use std::io;
+use std::time::SystemTime;
+use std::{fs, io};
 
-use crate::macros::ok_or_return;
+use crate::macros::{ok_or_return, some_or_return};
+use crate::message::{Message, ParsingError, ToMessage};
 use crate::server::connection::Connection;
+use crate::server::consts::user_flag::UserFlag;
+use crate::server::data_structures::{irc_eq, Capability, ChannelRecipients};
+use crate::server::responses::{
+    format_server_time, CommandResponse, ErrorReply, Notification, ServerReply,
+};
 
 use super::ConnectionHandlerGetters;
 
 pub trait ConnectionHandlerUtils<C: Connection>: ConnectionHandlerGetters<C> {
-    fn send_message_to_client(&self, message: &dyn Display, nickname: &str) -> io::Result<()> {
+    /// Sends message through own connection.
+    fn send_message(&mut self, message: &dyn ToMessage) -> io::Result<()> {
+        self.stream().send(message)
+    }
+
+    /// Sends response through own connection, framing numeric replies with server prefix and target.
+    fn send_response(&mut self, response: &dyn ToMessage) -> io::Result<()> {
+        let servername = self.database().get_server_name();
+        let target = self.reply_target();
+        let reply = ServerReply::new(&servername, &target, response);
+        self.send_message(&reply)
+    }
+
+    /// Sends the message of the day, read from the file set at startup.
+    fn send_motd(&mut self) -> io::Result<()> {
+        let motd = self
+            .database()
+            .get_motd_file()
+            .and_then(|path| fs::read_to_string(path).ok());
+        let motd = some_or_return!(motd, self.send_response(&ErrorReply::NoMotd422));
+
+        let servername = self.database().get_server_name();
+        self.send_response(&CommandResponse::motd_start(&servername))?;
+        for line in motd.lines() {
+            self.send_response(&CommandResponse::motd(line))?;
+        }
+        self.send_response(&CommandResponse::end_of_motd())
+    }
+
+    /// Sends notice to every local operator receiving server notices.
+    fn send_server_notice(&self, notice: &str) {
+        let servername = self.database().get_server_name();
+        let message = format!("*** Notice -- {notice}");
+
+        for client in self.database().get_all_clients() {
+            let receives_notices = client.flags.contains_key(&UserFlag::ReceiveServerNotices);
+            if !client.is_operator() || !receives_notices {
+                continue;
+            }
+            if self.database().is_local_client(&client.nickname) {
+                let notification = Notification::notice(&servername, &client.nickname, &message);
+                self.send_message_to_client(&notification, &client.nickname)
+                    .ok();
+            }
+        }
+    }
+
+    /// Removes client from the network, informing its channels and closing its link if local.
+    fn kill_client(&self, killer: &str, victim: &str, comment: &str) {
+        let reason = format!("Killed ({killer} ({comment}))");
+        let quit_notification = Notification::quit(victim, &reason);
+
+        for client in self.get_local_channel_peers(victim) {
+            self.send_message_to_client(&quit_notification, &client)
+                .ok();
+        }
+
+        if self.database().is_local_client(victim) {
+            if let Ok(queue) = self.database().get_local_queue(victim) {
+                let message = format!("Closing Link: {victim} ({reason})");
+                queue
+                    .send(&Notification::kill(killer, victim, comment))
+                    .ok();
+                queue.send(&CommandResponse::error(&message)).ok();
+            }
+        }
+
+        self.database().disconnect_client(victim);
+    }
+
+    /// Returns local clients sharing a channel with the client, each one listed once.
+    fn get_local_channel_peers(&self, nickname: &str) -> Vec<String> {
+        let channels = self
+            .database()
+            .get_channels_for_client(nickname)
+            .unwrap_or_default();
+
+        let mut peers: Vec<String> = vec![];
+        for channel in channels {
+            let clients = self
+                .database()
+                .get_channel_clients(&channel)
+                .unwrap_or_default();
+            for client in clients {
+                let is_listed =
+                    irc_eq(&client, nickname) || peers.iter().any(|peer| irc_eq(peer, &client));
+                if !is_listed && self.database().is_local_client(&client) {
+                    peers.push(client);
+                }
+            }
+        }
+
+        peers
+    }
+
+    /// Sends AWAY to local clients sharing a channel with the client, if they enabled away-notify.
+    fn send_away_notify(&self, nickname: &str, message: &Option<String>) {
+        let notification = Notification::away(nickname, message);
+
+        for client in self.get_local_channel_peers(nickname) {
+            let capabilities = self.database().get_client_capabilities(&client);
+            if capabilities.contains(&Capability::AwayNotify) {
+                self.send_message_to_client(&notification, &client).ok();
+            }
+        }
+    }
+
+    /// Sends a CAP reply through own connection.
+    fn send_cap_reply(&mut self, subcommand: &str, capabilities: &str) -> io::Result<()> {
+        let servername = self.database().get_server_name();
+        let target = self.reply_target();
+        let reply = Notification::cap(&servername, &target, subcommand, capabilities);
+        self.send_message(&reply)
+    }
+
+    /// Local clients receive the message adapted to the capabilities they enabled.
+    /// Messages are queued for the client, failing if its queue is over the sendq limit.
+    fn send_message_to_client(&self, message: &dyn ToMessage, nickname: &str) -> io::Result<()> {
         if self.database().is_local_client(nickname) {
-            let mut stream = ok_or_return!(self.database().get_local_stream(nickname), Ok(()));
-            return stream.send(&message);
+            let queue = ok_or_return!(self.database().get_local_queue(nickname), Ok(()));
+            let capabilities = self.database().get_client_capabilities(nickname);
+            if capabilities.contains(&Capability::ServerTime) {
+                let tagged = ok_or_return!(with_server_time(message), Ok(()));
+                return queue.send(&tagged);
+            }
+            return queue.send(message);
         }
 
         let server = ok_or_return!(self.database().get_immediate_server(nickname), Ok(()));
@@ -18,36 +146,56 @@ pub trait ConnectionHandlerUtils<C: Connection>: ConnectionHandlerGetters<C> {
         self.send_message_to_server(message, &server)
     }
 
-    fn send_message_to_channel(&self, message: &dyn Display, channel: &str) {
-        self.send_message_to_local_clients_on_channel(message, channel);
-
-        let servers = self.get_channel_immediate_servers(channel);
+    fn send_message_to_channel(&self, message: &dyn ToMessage, channel: &str) {
+        let servers = self.deliver_to_channel(message, channel);
 
         self.send_message_to_servers(servers, message);
     }
 
-    fn send_message_to_local_clients_on_channel(&self, message: &dyn Display, channel: &str) {
-        let clients = ok_or_return!(self.database().get_channel_clients(channel));
+    fn send_message_to_local_clients_on_channel(&self, message: &dyn ToMessage, channel: &str) {
+        self.deliver_to_channel(message, channel);
+    }
+
+    /// Queues message for the channel's local members, tagging it for those that enabled server-time.
+    /// Returns the immediate servers leading to its remote members, for the caller to relay it to.
+    fn deliver_to_channel(&self, message: &dyn ToMessage, channel: &str) -> Vec<String> {
+        let recipients = ok_or_return!(self.database().get_channel_recipients(channel), vec![]);
 
-        for client in clients {
-            if self.database().is_local_client(&client) {
-                self.send_message_to_client(message, &client).ok();
+        self.deliver_to_recipients(message, recipients)
+    }
+
+    /// Queues message for recipients gathered beforehand, returning the servers to relay it to.
+    fn deliver_to_recipients(
+        &self,
+        message: &dyn ToMessage,
+        recipients: ChannelRecipients<C>,
+    ) -> Vec<String> {
+        for queue in &recipients.untagged {
+            queue.send(message).ok();
+        }
+        if !recipients.tagged.is_empty() {
+            if let Ok(tagged) = with_server_time(message) {
+                for queue in &recipients.tagged {
+                    queue.send(&tagged).ok();
+                }
             }
         }
+
+        recipients.servers
     }
 
-    fn send_message_to_server(&self, message: &dyn Display, server: &str) -> io::Result<()> {
-        let mut stream = ok_or_return!(self.database().get_server_stream(server), Ok(()));
-        stream.send(message)
+    fn send_message_to_server(&self, message: &dyn ToMessage, server: &str) -> io::Result<()> {
+        let queue = ok_or_return!(self.database().get_server_queue(server), Ok(()));
+        queue.send(message)
     }
 
-    fn send_message_to_all_servers(&self, message: &dyn Display) {
+    fn send_message_to_all_servers(&self, message: &dyn ToMessage) {
         let servers = self.database().get_all_servers();
 
         self.send_message_to_servers(servers, message);
     }
 
-    fn send_message_to_target(&self, message: &dyn Display, target: &str) {
+    fn send_message_to_target(&self, message: &dyn ToMessage, target: &str) {
         if self.database().contains_client(target) {
             self.send_message_to_client(message, target).ok();
         } else {
@@ -55,25 +203,17 @@ pub trait ConnectionHandlerUtils<C: Connection>: ConnectionHandlerGetters<C> {
         }
     }
 
-    fn send_message_to_servers(&self, servers: Vec<String>, message: &dyn Display) {
+    fn send_message_to_servers(&self, servers: Vec<String>, message: &dyn ToMessage) {
         for server in servers {
             self.send_message_to_server(message, &server).ok();
         }
     }
+}
 
-    fn get_channel_immediate_servers(&self, channel: &str) -> Vec<String> {
-        let clients = ok_or_return!(self.database().get_channel_clients(channel), vec![]);
-
-        let mut servers = vec![];
-
-        for client in clients {
-            if let Ok(server) = self.database().get_immediate_server(&client) {
-                if !servers.contains(&server) {
-                    servers.push(server);
-                }
-            }
-        }
+/// Tags message with the time it is being sent, as requested by the server-time capability.
+fn with_server_time(message: &dyn ToMessage) -> Result<Message, ParsingError> {
+    let mut message = message.to_message()?;
+    message.set_tag("time", &format_server_time(&SystemTime::now()));
 
-        servers
-    }
+    Ok(message)
 }
@@ -1,7 +1,7 @@
 pub const MAX_CHANNELS: usize = 10;
+/// longest topic kept, so it fits in the replies that carry it
+pub const MAX_TOPIC_LENGTH: usize = 307;
 pub const INVALID_CHARACTER: char = '\'';
 pub const DISTRIBUTED_CHANNEL: char = '#';
 pub const LOCAL_CHANNEL: char = '&';
 pub const WILDCARD_CHANNEL: &str = "*";
-
-pub const NO_CHANNEL_NAME: &str = "";
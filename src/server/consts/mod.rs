@@ -1,6 +1,5 @@
 pub mod channel;
 pub mod channel_flag;
-pub mod commands;
 pub mod modes;
 pub mod user;
 pub mod user_flag;
@@ -34,9 +34,6 @@ pub const VALID_USER_MODES: [char; 4] = [
     RECEIVES_WALLOPS,
 ];
 
-pub const ADD_MODE: char = '+';
-pub const REMOVE_MODE: char = '-';
-
 pub const INVISIBLE: char = 'i';
 pub const OPERATOR: char = 'o';
 pub const RECEIVES_SERVER_NOTICES: char = 's';
@@ -1,5 +1,19 @@
 use super::channel::{DISTRIBUTED_CHANNEL, LOCAL_CHANNEL};
 
+pub const MAX_NICKNAME_LENGTH: usize = 9;
+
 pub const INVALID_NICKNAME_CHARACTERS: [char; 7] = [' ', ',', '*', '?', '!', '@', '.'];
 
 pub const INVALID_NICKNAME_PREFIXES: [char; 4] = ['$', ':', DISTRIBUTED_CHANNEL, LOCAL_CHANNEL];
+
+/// Nickname of the service that handles channel registration, which no client may take.
+pub const CHANSERV_NICKNAME: &str = "ChanServ";
+
+/// Nickname of the service that handles nickname registration, which no client may take.
+pub const NICKSERV_NICKNAME: &str = "NickServ";
+
+/// Nicknames of every service, reserved so that no client can impersonate them.
+pub const SERVICE_NICKNAMES: [&str; 2] = [CHANSERV_NICKNAME, NICKSERV_NICKNAME];
+
+/// Prefix of the nickname given to clients that keep a registered nickname without identifying.
+pub const GUEST_NICKNAME_PREFIX: &str = "Guest";
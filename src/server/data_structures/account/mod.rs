@@ -0,0 +1,28 @@
+use super::PasswordHash;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A nickname registered with NickServ. Clients log into it with its password,
+/// and only those logged into it may keep using the nickname.
+pub struct Account {
+    /// the registered nickname, which is also the account's name
+    pub name: String,
+    pub password: PasswordHash,
+    /// unix timestamp of the account's registration
+    pub registered: u64,
+}
+
+impl Account {
+    /// Creates an account for name, hashing password with a newly generated salt.
+    pub fn new(name: &str, password: &str, registered: u64) -> Self {
+        Self {
+            name: name.to_string(),
+            password: PasswordHash::generate(password),
+            registered,
+        }
+    }
+
+    /// Returns true if password is the one the account was registered with.
+    pub fn verify(&self, password: &str) -> bool {
+        self.password.matches(password)
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+#[cfg(test)]
+mod tests;
+
+/// Casemapping advertised to clients in RPL_ISUPPORT.
+pub const CASEMAPPING: &str = "rfc1459";
+
+/// Returns the RFC 1459 lowercase form of a character, where `[]\~` are the uppercase of `{}|^`.
+pub fn irc_lowercase_char(character: char) -> char {
+    match character {
+        '[' => '{',
+        ']' => '}',
+        '\\' => '|',
+        '~' => '^',
+        _ => character.to_ascii_lowercase(),
+    }
+}
+
+/// Returns the RFC 1459 lowercase form of a nickname or channel name.
+pub fn irc_lowercase(name: &str) -> String {
+    name.chars().map(irc_lowercase_char).collect()
+}
+
+/// Compares two nicknames or channel names under RFC 1459 casemapping.
+pub fn irc_eq(first: &str, second: &str) -> bool {
+    first.len() == second.len()
+        && first
+            .chars()
+            .zip(second.chars())
+            .all(|(first, second)| irc_lowercase_char(first) == irc_lowercase_char(second))
+}
+
+/// Nickname or channel name used as key, equal to every name with the same RFC 1459 lowercase form.
+/// Displays as originally written.
+#[derive(Debug, Clone)]
+pub struct NameKey {
+    name: String,
+    folded: String,
+}
+
+impl From<String> for NameKey {
+    fn from(name: String) -> Self {
+        let folded = irc_lowercase(&name);
+        Self { name, folded }
+    }
+}
+
+impl<T: AsRef<str> + ?Sized> From<&T> for NameKey {
+    fn from(name: &T) -> Self {
+        Self::from(name.as_ref().to_string())
+    }
+}
+
+impl PartialEq for NameKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.folded == other.folded
+    }
+}
+
+impl Eq for NameKey {}
+
+impl Hash for NameKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.folded.hash(state)
+    }
+}
+
+impl Display for NameKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
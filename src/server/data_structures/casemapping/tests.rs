@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::server::testing::dummy_client;
+
+#[test]
+fn lowercase_maps_special_characters() {
+    assert_eq!("nick{}|^", irc_lowercase("NICK[]\\~"));
+}
+
+#[test]
+fn names_differing_in_case_are_equal() {
+    assert!(irc_eq("Alice[away]", "alice{AWAY}"));
+    assert!(!irc_eq("alice", "alicee"));
+    assert!(!irc_eq("alice", "bob"));
+}
+
+#[test]
+fn keys_differing_in_case_find_same_entry() {
+    let mut map = HashMap::new();
+    map.insert(NameKey::from("#Rust"), 1);
+
+    assert_eq!(Some(&1), map.get(&NameKey::from("#rust")));
+    assert_eq!(Some(&1), map.get(&NameKey::from("#RUST")));
+}
+
+#[test]
+fn key_displays_original_name() {
+    assert_eq!("Alice[m]", NameKey::from("Alice[m]").to_string());
+}
+
+#[test]
+fn masks_match_regardless_of_case() {
+    let info = dummy_client("Nick[away]").get_info();
+
+    assert!(info.matches_nickmask("nick{*}"));
+    assert!(info.matches_usermask("USERNAME@*"));
+    assert!(!info.matches_nickmask("other*"));
+}
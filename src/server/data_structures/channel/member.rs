@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Status a client can hold on a channel, shown as a prefix to its nickname.
+pub enum MemberStatus {
+    Operator,
+    Speaker,
+}
+
+#[derive(Clone, Debug)]
+/// Record a channel keeps of a client: its statuses, and when it joined if it is a member.
+/// Statuses outlive the membership, so clients get them back when they rejoin.
+pub struct ChannelMember {
+    nickname: String,
+    statuses: HashSet<MemberStatus>,
+    /// unix timestamp of when the client joined, none while it is not on the channel
+    joined: Option<u64>,
+    /// position among the channel's records, orders them by arrival
+    sequence: u64,
+}
+
+impl ChannelMember {
+    /// Creates a [`ChannelMember`] with no statuses that is not on the channel.
+    pub fn new(nickname: String, sequence: u64) -> Self {
+        Self {
+            nickname,
+            statuses: Default::default(),
+            joined: None,
+            sequence,
+        }
+    }
+
+    pub fn nickname(&self) -> &str {
+        &self.nickname
+    }
+
+    pub fn set_nickname(&mut self, nickname: String) {
+        self.nickname = nickname
+    }
+
+    pub fn join(&mut self, time: u64) {
+        self.joined.get_or_insert(time);
+    }
+
+    pub fn leave(&mut self) {
+        self.joined = None
+    }
+
+    pub fn is_joined(&self) -> bool {
+        self.joined.is_some()
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn has_status(&self, status: MemberStatus) -> bool {
+        self.statuses.contains(&status)
+    }
+
+    pub fn add_status(&mut self, status: MemberStatus) {
+        self.statuses.insert(status);
+    }
+
+    pub fn remove_status(&mut self, status: MemberStatus) {
+        self.statuses.remove(&status);
+    }
+
+    /// Returns true when the record holds nothing worth keeping.
+    pub fn is_empty(&self) -> bool {
+        !self.is_joined() && self.statuses.is_empty()
+    }
+}
@@ -1,30 +1,73 @@
 /// Contains a channel's configuration.
 mod channel_configuration;
+/// Contains the record a channel keeps of each client.
+mod member;
+/// Contains the connections a channel message is delivered through.
+mod recipients;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, HashSet};
+
 pub use channel_configuration::ChannelConfiguration;
+pub use member::{ChannelMember, MemberStatus};
+pub use recipients::ChannelRecipients;
 
+use crate::macros::some_or_return;
 use crate::server::consts::channel_flag::ChannelFlag;
 
+use super::{irc_lowercase, unix_time, NameKey};
+
 /// Represents a Channel that has a name, clients and specific configurations.
+///
+/// Clients are indexed by nickname, each with a record of its statuses and join time.
 pub struct Channel {
     name: String,
-    clients: Vec<String>,
+    members: HashMap<NameKey, ChannelMember>,
+    /// number of records created, used to order members by arrival
+    arrivals: u64,
+    /// number of records of clients on the channel
+    population: usize,
+    /// configuration without operators and speakers, which are kept in the member records
     config: ChannelConfiguration,
-    invites: Vec<String>,
+    invites: HashSet<NameKey>,
+    /// registered channels belong to their founder's account, and are kept when empty
+    founder: Option<String>,
 }
 
 impl Channel {
     /// Creates a new [`Channel`]
     pub fn new(name: String, creator: String) -> Self {
-        let clients = vec![creator];
+        let mut channel = Self::restore(name, None, ChannelConfiguration::new());
+        channel.add_member(creator);
+
+        channel
+    }
 
-        let config = ChannelConfiguration::new();
+    /// Restores a [`Channel`] with its configuration, with no members until someone joins.
+    pub fn restore(name: String, founder: Option<String>, config: ChannelConfiguration) -> Self {
+        let mut config = config;
+        let operators = std::mem::take(&mut config.operators);
+        let speakers = std::mem::take(&mut config.speakers);
 
-        Self {
+        let mut channel = Self {
             name,
-            clients,
+            members: Default::default(),
+            arrivals: 0,
+            population: 0,
             config,
             invites: Default::default(),
+            founder,
+        };
+        for operator in operators {
+            channel.add_operator(operator);
         }
+        for speaker in speakers {
+            channel.add_speaker(speaker);
+        }
+
+        channel
     }
 
     pub fn add_banmask(&mut self, banmask: String) {
@@ -32,27 +75,44 @@ impl Channel {
     }
 
     pub fn add_member(&mut self, nickname: String) {
-        self.clients.push(nickname)
+        let member = self.record(nickname);
+        if !member.is_joined() {
+            member.join(unix_time());
+            self.population += 1;
+        }
     }
 
     pub fn add_operator(&mut self, nickname: String) {
-        self.config.operators.push(nickname)
+        self.record(nickname).add_status(MemberStatus::Operator)
     }
 
     pub fn add_speaker(&mut self, nickname: String) {
-        self.config.speakers.push(nickname)
+        self.record(nickname).add_status(MemberStatus::Speaker)
     }
 
     pub fn get_banmasks(&self) -> Vec<String> {
         self.config.banmasks.clone()
     }
 
+    /// Returns the channel's members, in the order they arrived.
     pub fn get_clients(&self) -> Vec<String> {
-        self.clients.clone()
+        self.records()
+            .filter(|member| member.is_joined())
+            .map(|member| member.nickname().to_string())
+            .collect()
+    }
+
+    /// Returns the records of clients on the channel, in no particular order.
+    pub fn members(&self) -> impl Iterator<Item = &ChannelMember> {
+        self.members.values().filter(|member| member.is_joined())
     }
 
     pub fn get_config(&self) -> ChannelConfiguration {
-        self.config.clone()
+        let mut config = self.config.clone();
+        config.operators = self.with_status(MemberStatus::Operator);
+        config.speakers = self.with_status(MemberStatus::Speaker);
+
+        config
     }
 
     pub fn get_key(&self) -> Option<String> {
@@ -72,31 +132,40 @@ impl Channel {
     }
 
     pub fn is_member(&self, nickname: &str) -> bool {
-        self.clients.iter().any(|n| n == nickname)
+        self.member(nickname).is_some_and(ChannelMember::is_joined)
     }
 
     pub fn is_operator(&self, nickname: &str) -> bool {
-        self.config.operators.iter().any(|n| n == nickname)
+        self.member(nickname)
+            .is_some_and(|member| member.has_status(MemberStatus::Operator))
     }
 
     pub fn is_speaker(&self, nickname: &str) -> bool {
-        self.config.speakers.iter().any(|n| n == nickname)
+        self.member(nickname)
+            .is_some_and(|member| member.has_status(MemberStatus::Speaker))
     }
 
     pub fn remove_banmask(&mut self, mask: &str) {
         remove(&mut self.config.banmasks, &mask.to_string())
     }
 
+    /// Removes client from the channel. It keeps its statuses for when it rejoins.
     pub fn remove_client(&mut self, nickname: &str) {
-        remove_string(&mut self.clients, nickname);
+        let key = NameKey::from(nickname);
+        let member = some_or_return!(self.members.get_mut(&key));
+        if member.is_joined() {
+            member.leave();
+            self.population -= 1;
+        }
+        self.forget_if_empty(&key);
     }
 
     pub fn remove_operator(&mut self, nickname: &str) {
-        remove_string(&mut self.config.operators, nickname);
+        self.remove_status(nickname, MemberStatus::Operator)
     }
 
     pub fn remove_speaker(&mut self, nickname: &str) {
-        remove_string(&mut self.config.speakers, nickname)
+        self.remove_status(nickname, MemberStatus::Speaker)
     }
 
     pub fn set_key(&mut self, key: Option<String>) {
@@ -119,35 +188,98 @@ impl Channel {
         remove(&mut self.config.flags, &flag);
     }
 
+    /// Moves the client's record to its new nickname, along with its statuses.
     pub fn update_nickname(&mut self, old_nickname: &str, new_nickname: &str) {
-        for client in &mut self.clients {
-            if client == old_nickname {
-                *client = new_nickname.to_string()
-            }
-        }
+        let mut member = some_or_return!(self.members.remove(&NameKey::from(old_nickname)));
+        member.set_nickname(new_nickname.to_string());
+
+        self.members.insert(NameKey::from(new_nickname), member);
     }
 
     pub fn add_client_invite(&mut self, client: String) {
-        self.invites.push(client);
+        self.invites.insert(NameKey::from(client));
+    }
+
+    /// Returns the invited clients, sorted by nickname.
+    pub fn get_invites(&self) -> Vec<String> {
+        let mut invites: Vec<String> = self.invites.iter().map(NameKey::to_string).collect();
+        invites.sort_by_key(|invite| irc_lowercase(invite));
+
+        invites
     }
 
     pub fn has_invite(&self, client: &str) -> bool {
-        self.invites.iter().any(|c| c == client)
+        self.invites.contains(&NameKey::from(client))
     }
 
     pub fn name(&self) -> String {
         self.name.clone()
     }
+
+    pub fn founder(&self) -> Option<String> {
+        self.founder.clone()
+    }
+
+    pub fn set_founder(&mut self, founder: Option<String>) {
+        self.founder = founder
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.founder.is_some()
+    }
+
+    /// Returns true when the channel may be deleted, having no members and no founder.
+    pub fn is_abandoned(&self) -> bool {
+        self.population == 0 && !self.is_registered()
+    }
 }
 
-fn remove<T: Eq>(elements: &mut Vec<T>, element: &T) {
-    elements
-        .iter()
-        .position(|e| e == element)
-        .map(|index| elements.remove(index));
+impl Channel {
+    fn member(&self, nickname: &str) -> Option<&ChannelMember> {
+        self.members.get(&NameKey::from(nickname))
+    }
+
+    /// Returns the client's record, creating an empty one if the channel has none.
+    fn record(&mut self, nickname: String) -> &mut ChannelMember {
+        let sequence = self.arrivals;
+        let key = NameKey::from(&nickname);
+
+        self.members.entry(key).or_insert_with(|| {
+            self.arrivals += 1;
+            ChannelMember::new(nickname, sequence)
+        })
+    }
+
+    /// Returns every record, in the order they were created.
+    fn records(&self) -> impl Iterator<Item = &ChannelMember> {
+        let mut records: Vec<&ChannelMember> = self.members.values().collect();
+        records.sort_by_key(|member| member.sequence());
+
+        records.into_iter()
+    }
+
+    fn with_status(&self, status: MemberStatus) -> Vec<String> {
+        self.records()
+            .filter(|member| member.has_status(status))
+            .map(|member| member.nickname().to_string())
+            .collect()
+    }
+
+    fn remove_status(&mut self, nickname: &str, status: MemberStatus) {
+        let key = NameKey::from(nickname);
+        let member = some_or_return!(self.members.get_mut(&key));
+        member.remove_status(status);
+        self.forget_if_empty(&key);
+    }
+
+    fn forget_if_empty(&mut self, key: &NameKey) {
+        if self.members.get(key).is_some_and(ChannelMember::is_empty) {
+            self.members.remove(key);
+        }
+    }
 }
 
-fn remove_string(elements: &mut Vec<String>, element: &str) {
+fn remove<T: Eq>(elements: &mut Vec<T>, element: &T) {
     elements
         .iter()
         .position(|e| e == element)
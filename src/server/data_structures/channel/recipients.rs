@@ -0,0 +1,22 @@
+use crate::server::connection::Connection;
+use crate::server::data_structures::OutputQueue;
+
+/// Connections a message sent to a channel goes through, gathered in a single request.
+pub struct ChannelRecipients<C: Connection> {
+    /// queues of local members that enabled server-time
+    pub tagged: Vec<OutputQueue<C>>,
+    /// queues of every other local member
+    pub untagged: Vec<OutputQueue<C>>,
+    /// immediate servers leading to the channel's remote members, each one listed once
+    pub servers: Vec<String>,
+}
+
+impl<C: Connection> Default for ChannelRecipients<C> {
+    fn default() -> Self {
+        Self {
+            tagged: vec![],
+            untagged: vec![],
+            servers: vec![],
+        }
+    }
+}
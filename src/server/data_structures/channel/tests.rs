@@ -0,0 +1,95 @@
+use super::*;
+
+fn channel() -> Channel {
+    let mut channel = Channel::new("#channel".to_string(), "nick1".to_string());
+    channel.add_member("nick2".to_string());
+    channel.add_member("nick3".to_string());
+    channel
+}
+
+#[test]
+fn members_are_listed_in_the_order_they_joined() {
+    let channel = channel();
+
+    assert_eq!(vec!["nick1", "nick2", "nick3"], channel.get_clients());
+}
+
+#[test]
+fn members_are_found_regardless_of_case() {
+    let channel = channel();
+
+    assert!(channel.is_member("NICK2"));
+    assert!(!channel.is_member("nick4"));
+}
+
+#[test]
+fn joining_twice_keeps_a_single_member() {
+    let mut channel = channel();
+
+    channel.add_member("Nick2".to_string());
+
+    assert_eq!(vec!["nick1", "nick2", "nick3"], channel.get_clients());
+}
+
+#[test]
+fn statuses_are_kept_until_the_client_rejoins() {
+    let mut channel = channel();
+    channel.add_operator("nick2".to_string());
+
+    channel.remove_client("nick2");
+    assert!(!channel.is_member("nick2"));
+    assert!(channel.is_operator("nick2"));
+
+    channel.add_member("nick2".to_string());
+    assert!(channel.is_operator("nick2"));
+}
+
+#[test]
+fn nickname_change_keeps_statuses() {
+    let mut channel = channel();
+    channel.add_speaker("nick2".to_string());
+
+    channel.update_nickname("nick2", "new_nick");
+
+    assert!(channel.is_member("new_nick"));
+    assert!(channel.is_speaker("new_nick"));
+    assert!(!channel.is_member("nick2"));
+    assert_eq!(vec!["nick1", "new_nick", "nick3"], channel.get_clients());
+}
+
+#[test]
+fn channel_is_abandoned_once_every_member_leaves() {
+    let mut channel = channel();
+
+    channel.remove_client("nick1");
+    channel.remove_client("nick2");
+    assert!(!channel.is_abandoned());
+
+    channel.remove_client("nick3");
+    assert!(channel.is_abandoned());
+}
+
+#[test]
+fn configuration_lists_operators_and_speakers() {
+    let mut channel = channel();
+    channel.add_operator("nick1".to_string());
+    channel.add_speaker("nick3".to_string());
+    channel.add_operator("nick3".to_string());
+
+    let config = channel.get_config();
+
+    assert_eq!(vec!["nick1", "nick3"], config.operators);
+    assert_eq!(vec!["nick3"], config.speakers);
+}
+
+#[test]
+fn restored_channel_keeps_statuses_without_members() {
+    let mut config = ChannelConfiguration::new();
+    config.operators = vec!["founder".to_string()];
+
+    let channel = Channel::restore("#channel".to_string(), Some("founder".to_string()), config);
+
+    assert!(channel.is_operator("founder"));
+    assert!(channel.get_clients().is_empty());
+    assert_eq!(vec!["founder"], channel.get_config().operators);
+}
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::server::connection::Connection;
+use crate::server::data_structures::OutputQueue;
 
 use super::{ClientInfo, ExternalClient, LocalClient};
 /// Stores Client's information until it's complete and a client can be built.
 pub struct ClientBuilder<C: Connection> {
     immediate: Option<String>,
-    stream: Option<C>,
+    queue: Option<OutputQueue<C>>,
     nickname: Option<String>,
     password: Option<String>,
     username: Option<String>,
@@ -14,13 +16,15 @@ pub struct ClientBuilder<C: Connection> {
     servername: Option<String>,
     realname: Option<String>,
     hopcount: usize,
+    signon: Option<u64>,
+    account: Option<String>,
 }
 
 impl<C: Connection> ClientBuilder<C> {
     pub fn new() -> Self {
         Self {
             immediate: None,
-            stream: None,
+            queue: None,
             nickname: None,
             password: None,
             username: None,
@@ -28,6 +32,8 @@ impl<C: Connection> ClientBuilder<C> {
             servername: None,
             realname: None,
             hopcount: 0,
+            signon: None,
+            account: None,
         }
     }
 
@@ -36,8 +42,9 @@ impl<C: Connection> ClientBuilder<C> {
         self
     }
 
-    pub fn stream(mut self, stream: C) -> Self {
-        self.stream = Some(stream);
+    /// Sets the queue messages to the client are written through.
+    pub fn queue(mut self, queue: OutputQueue<C>) -> Self {
+        self.queue = Some(queue);
         self
     }
 
@@ -71,6 +78,18 @@ impl<C: Connection> ClientBuilder<C> {
         self
     }
 
+    /// Sets the registration timestamp, defaults to the moment the client is built.
+    pub fn signon(mut self, signon: u64) -> Self {
+        self.signon = Some(signon);
+        self
+    }
+
+    /// Sets the account the client logged into before registering, if any.
+    pub fn account(mut self, account: Option<&String>) -> Self {
+        self.account = account.cloned();
+        self
+    }
+
     pub fn immediate(mut self, immediate: &str) -> Self {
         self.immediate = Some(immediate.to_string());
         self
@@ -84,8 +103,10 @@ impl<C: Connection> ClientBuilder<C> {
             servername: self.servername.take()?,
             realname: self.realname.take()?,
             hopcount: self.hopcount,
+            signon: self.signon.unwrap_or_else(unix_time),
             away: None,
             flags: HashMap::new(),
+            account: self.account.take(),
         };
         Some(info)
     }
@@ -93,7 +114,7 @@ impl<C: Connection> ClientBuilder<C> {
     pub fn build_local_client(mut self) -> Option<LocalClient<C>> {
         let info = self.build_info()?;
 
-        let client = LocalClient::new(self.stream?, self.password, info);
+        let client = LocalClient::new(self.queue?, self.password, info);
 
         Some(client)
     }
@@ -106,3 +127,11 @@ impl<C: Connection> ClientBuilder<C> {
         Some(client)
     }
 }
+
+/// Seconds since the unix epoch, used as signon timestamp.
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
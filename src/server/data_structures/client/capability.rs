@@ -0,0 +1,77 @@
+use crate::macros::some_or_return;
+
+/// Prefix that marks a capability as being disabled in a CAP REQ.
+pub const DISABLE_PREFIX: char = '-';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// IRCv3 capabilities a local client may enable with CAP.
+pub enum Capability {
+    ServerTime,
+    MultiPrefix,
+    AwayNotify,
+    Sasl,
+}
+
+impl Capability {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ServerTime => "server-time",
+            Self::MultiPrefix => "multi-prefix",
+            Self::AwayNotify => "away-notify",
+            Self::Sasl => "sasl",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "server-time" => Some(Self::ServerTime),
+            "multi-prefix" => Some(Self::MultiPrefix),
+            "away-notify" => Some(Self::AwayNotify),
+            "sasl" => Some(Self::Sasl),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::ServerTime,
+            Self::MultiPrefix,
+            Self::AwayNotify,
+            Self::Sasl,
+        ]
+    }
+
+    /// Space separated names of capabilities, as sent in CAP replies.
+    pub fn names(capabilities: &[Self]) -> String {
+        let names: Vec<&str> = capabilities.iter().map(Self::name).collect();
+        names.join(" ")
+    }
+
+    /// Applies a CAP REQ to the enabled capabilities.
+    /// Requests are all or nothing: if any capability is unknown, nothing changes and false is returned.
+    pub fn apply_request(enabled: &mut Vec<Self>, request: &str) -> bool {
+        let mut changes = Vec::new();
+
+        for name in request.split_whitespace() {
+            let (name, enable) = match name.strip_prefix(DISABLE_PREFIX) {
+                Some(name) => (name, false),
+                None => (name, true),
+            };
+            let capability = some_or_return!(Self::from_name(name), false);
+            changes.push((capability, enable));
+        }
+
+        if changes.is_empty() {
+            return false;
+        }
+
+        for (capability, enable) in changes {
+            enabled.retain(|enabled| *enabled != capability);
+            if enable {
+                enabled.push(capability);
+            }
+        }
+
+        true
+    }
+}
@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use crate::server::consts::user_flag::UserFlag;
 
+use crate::server::data_structures::irc_lowercase;
+
 #[derive(PartialEq, Eq, Debug, Clone)]
 /// ClientInfo contains public client information.
 pub struct ClientInfo {
@@ -12,10 +14,14 @@ pub struct ClientInfo {
     pub realname: String,
     /// how many servers away is the client from the local server
     pub hopcount: usize,
+    /// unix timestamp of the client's registration, the older client wins nick collisions
+    pub signon: u64,
     /// a client may have an away message
     pub away: Option<String>,
     /// stores the user's flags
     pub flags: HashMap<UserFlag, ()>,
+    /// name of the account the client is logged into, if any
+    pub account: Option<String>,
 }
 
 impl ClientInfo {
@@ -58,6 +64,10 @@ impl ClientInfo {
         false
     }
 
+    pub fn matches_usermask(&self, query: &str) -> bool {
+        matches(&format!("{}@{}", self.username, self.hostname), query)
+    }
+
     pub fn matches_nickmask(&self, query: &str) -> bool {
         matches(&self.nickname, query)
     }
@@ -74,13 +84,15 @@ impl ClientInfo {
         self.flags.remove(&flag);
     }
 }
-/// Iterates through base checking if pattern matches it in some way.
+/// Iterates through base checking if pattern matches it in some way, ignoring case as RFC 1459 does.
 /// '?' matches any character
 /// '*' matches any sequence of characters
 pub fn matches(base: &str, pattern: &str) -> bool {
     if pattern.is_empty() {
         return base.is_empty();
     }
+    let base = irc_lowercase(base);
+    let pattern = irc_lowercase(pattern);
     let base = base.as_bytes();
     let pattern = pattern.as_bytes();
 
@@ -1,20 +1,28 @@
 use crate::server::connection::Connection;
+use crate::server::data_structures::{Capability, OperatorPrivilege, OutputQueue};
 
 use super::ClientInfo;
 
 /// Represents a local client that is directly connected to the Server.
 pub struct LocalClient<C: Connection> {
-    stream: Option<C>,
+    /// queue messages to the client wait in, none once disconnected
+    queue: Option<OutputQueue<C>>,
     _password: Option<String>,
     info: ClientInfo,
+    /// privileges granted by the operator account the client used, if any
+    privileges: Vec<OperatorPrivilege>,
+    /// capabilities enabled with CAP, they change how messages are delivered to the client
+    capabilities: Vec<Capability>,
 }
 
 impl<C: Connection> LocalClient<C> {
-    pub fn new(stream: C, _password: Option<String>, info: ClientInfo) -> Self {
+    pub fn new(queue: OutputQueue<C>, _password: Option<String>, info: ClientInfo) -> Self {
         Self {
-            stream: Some(stream),
+            queue: Some(queue),
             info,
             _password,
+            privileges: Vec::new(),
+            capabilities: Vec::new(),
         }
     }
 
@@ -22,18 +30,37 @@ impl<C: Connection> LocalClient<C> {
         self.info.clone()
     }
 
+    /// Hangs up the client's connection, its handler closes it once what was pending is written.
     pub fn disconnect(&mut self) {
-        self.stream = None;
+        if let Some(queue) = self.queue.take() {
+            queue.hang_up();
+        }
     }
 
-    pub fn stream(&self) -> Option<&C> {
-        self.stream.as_ref()
+    pub fn queue(&self) -> Option<&OutputQueue<C>> {
+        self.queue.as_ref()
     }
 
     pub fn nickname(&self) -> String {
         self.info.nickname.clone()
     }
 
+    pub fn privileges(&self) -> &[OperatorPrivilege] {
+        &self.privileges
+    }
+
+    pub fn set_privileges(&mut self, privileges: Vec<OperatorPrivilege>) {
+        self.privileges = privileges;
+    }
+
+    pub fn capabilities(&self) -> &[Capability] {
+        &self.capabilities
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: Vec<Capability>) {
+        self.capabilities = capabilities;
+    }
+
     pub fn info_mut(&mut self) -> &mut ClientInfo {
         &mut self.info
     }
@@ -1,18 +1,43 @@
+/// This module contains a nickname account's structure and logic.
+/// Accounts are registered with NickServ and logged into with IDENTIFY or SASL.
+mod account;
+/// This module contains the RFC 1459 casemapping used to compare nicknames and channel names.
+/// Names that only differ in case refer to the same client or channel.
+mod casemapping;
 /// This module contains a channel's structure and logic.
 /// A channel contains different clients and configurations that can only be modified by a channel operator.
 mod channel;
 /// This module contains a client's structure and logic.
 /// A server stores different client connections and their information.
 mod client;
+/// This module contains an operator account's structure and logic.
+/// Operator accounts are configured in the server and used by the OPER command.
+mod operator;
+/// This module contains the queue a connection's outgoing messages wait in.
+/// Senders never wait on a slow connection, its own handler drains the queue.
+mod output_queue;
+/// This module contains the reasons connections are refused with before registering.
+/// Refusals are counted for operators to review.
+mod refusals;
 /// This module contains a server's structure and logic.
 /// It is used by a local server to store incoming server connections and their information.
 mod server;
 
+pub use account::Account;
+pub use casemapping::{irc_eq, irc_lowercase, NameKey, CASEMAPPING};
 pub use channel::Channel;
 pub use channel::ChannelConfiguration;
+pub use channel::ChannelRecipients;
+pub use client::unix_time;
+pub use client::Capability;
 pub use client::ClientBuilder;
 pub use client::ClientInfo;
 pub use client::ExternalClient;
 pub use client::LocalClient;
+pub use operator::OperatorBlock;
+pub use operator::OperatorPrivilege;
+pub use operator::PasswordHash;
+pub use output_queue::{OutputQueue, SENDQ_EXCEEDED_MESSAGE};
+pub use refusals::{Refusal, Refusals};
 pub use server::ImmediateServer;
 pub use server::ServerInfo;
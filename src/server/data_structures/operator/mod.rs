@@ -0,0 +1,11 @@
+/// This module contains an operator account's structure and logic.
+mod operator_block;
+/// This module contains the salted hash used to store operator passwords.
+mod password_hash;
+
+#[cfg(test)]
+mod tests;
+
+pub use operator_block::OperatorBlock;
+pub use operator_block::OperatorPrivilege;
+pub use password_hash::PasswordHash;
@@ -0,0 +1,71 @@
+use crate::server::data_structures::ClientInfo;
+
+use super::PasswordHash;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+/// Actions an operator may be allowed to take.
+pub enum OperatorPrivilege {
+    Kill,
+    Rehash,
+    Squit,
+}
+
+impl OperatorPrivilege {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Kill => "kill",
+            Self::Rehash => "rehash",
+            Self::Squit => "squit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "kill" => Some(Self::Kill),
+            "rehash" => Some(Self::Rehash),
+            "squit" => Some(Self::Squit),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> Vec<Self> {
+        vec![Self::Kill, Self::Rehash, Self::Squit]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An operator account, as configured in the server.
+pub struct OperatorBlock {
+    pub name: String,
+    pub password: PasswordHash,
+    /// user@host mask the client must match, any client may use the account if absent
+    pub host_mask: Option<String>,
+    pub privileges: Vec<OperatorPrivilege>,
+}
+
+impl OperatorBlock {
+    pub fn new(
+        name: &str,
+        password: PasswordHash,
+        host_mask: Option<String>,
+        privileges: Vec<OperatorPrivilege>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            password,
+            host_mask,
+            privileges,
+        }
+    }
+
+    /// Returns true if client may use this account with the given password.
+    pub fn accepts(&self, password: &str, client: &ClientInfo) -> bool {
+        if let Some(host_mask) = &self.host_mask {
+            if !client.matches_usermask(host_mask) {
+                return false;
+            }
+        }
+
+        self.password.matches(password)
+    }
+}
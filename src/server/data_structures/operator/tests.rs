@@ -0,0 +1,97 @@
+use super::password_hash::sha256;
+use super::{OperatorBlock, OperatorPrivilege, PasswordHash};
+use crate::server::testing::{dummy_client, dummy_operator};
+
+fn hex(digest: [u8; 32]) -> String {
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[test]
+fn sha256_of_empty_message() {
+    assert_eq!(
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        hex(sha256(b""))
+    );
+}
+
+#[test]
+fn sha256_of_multiple_blocks() {
+    let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    assert_eq!(
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        hex(sha256(message))
+    );
+}
+
+#[test]
+fn hash_matches_original_password_only() {
+    let hash = PasswordHash::generate("password");
+
+    assert!(hash.matches("password"));
+    assert!(!hash.matches("passwore"));
+}
+
+#[test]
+fn hash_can_be_written_and_parsed() {
+    let hash = PasswordHash::with_salt("password", "salt");
+
+    let parsed: PasswordHash = hash.to_string().parse().unwrap();
+
+    assert_eq!(hash, parsed);
+    assert!(parsed.matches("password"));
+}
+
+#[test]
+fn parsing_fails_without_salt() {
+    assert!("0123".parse::<PasswordHash>().is_err());
+    assert!(format!("${}", "0".repeat(64))
+        .parse::<PasswordHash>()
+        .is_err());
+}
+
+#[test]
+fn parsing_fails_for_non_ascii_digest() {
+    let hash = format!("salt$a€{}", "0".repeat(60));
+
+    assert_eq!(64, hash.len() - "salt$".len());
+    assert!(hash.parse::<PasswordHash>().is_err());
+}
+
+#[test]
+fn privileges_are_parsed_by_name() {
+    for privilege in OperatorPrivilege::all() {
+        assert_eq!(
+            Some(privilege),
+            OperatorPrivilege::from_name(privilege.name())
+        );
+    }
+    assert_eq!(None, OperatorPrivilege::from_name("invalid"));
+}
+
+#[test]
+fn block_accepts_matching_password() {
+    let block = dummy_operator("admin", "secret");
+    let client = dummy_client("nick").get_info();
+
+    assert!(block.accepts("secret", &client));
+    assert!(!block.accepts("admin", &client));
+}
+
+#[test]
+fn block_rejects_clients_outside_host_mask() {
+    let password = PasswordHash::generate("secret");
+    let block = OperatorBlock::new("admin", password, Some("*@10.*".to_string()), vec![]);
+    let client = dummy_client("nick").get_info();
+
+    assert!(!block.accepts("secret", &client));
+}
+
+#[test]
+fn block_accepts_clients_inside_host_mask() {
+    let password = PasswordHash::generate("secret");
+    let mask = Some("username@127.0.0.*".to_string());
+    let block = OperatorBlock::new("admin", password, mask, vec![]);
+    let client = dummy_client("nick").get_info();
+
+    assert!(block.accepts("secret", &client));
+}
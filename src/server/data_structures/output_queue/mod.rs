@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::macros::{debug_print, some_or_return};
+use crate::message::{ParsingError, ToMessage};
+use crate::server::connection::Connection;
+use crate::server::reactor::Waker;
+
+#[cfg(test)]
+mod tests;
+
+/// Reason given to connections dropped for going over their queue limit.
+pub const SENDQ_EXCEEDED_MESSAGE: &str = "SendQ exceeded";
+
+/// Bounded queue of bytes waiting to be written to a connection.
+///
+/// Senders never write, they only queue and wake the connection's own handler,
+/// which drains the queue with [`OutputQueue::flush`] whenever it is polled.
+/// Going over the limit marks the queue as exceeded, so the handler can drop the connection.
+/// Once closed with a last message, everything sent afterwards is discarded.
+pub struct OutputQueue<C: Connection> {
+    state: Arc<Mutex<QueueState<C>>>,
+}
+
+struct QueueState<C: Connection> {
+    stream: C,
+    pending: VecDeque<u8>,
+    limit: usize,
+    exceeded: bool,
+    closing: bool,
+    hung_up: bool,
+    /// has the handler polled when output is queued, set once a worker owns it
+    waker: Option<Waker>,
+}
+
+impl<C: Connection> OutputQueue<C> {
+    /// Creates empty [`OutputQueue`] writing to stream, holding up to limit bytes.
+    pub fn new(stream: C, limit: usize) -> Self {
+        let state = QueueState {
+            stream,
+            pending: VecDeque::new(),
+            limit,
+            exceeded: false,
+            closing: false,
+            hung_up: false,
+            waker: None,
+        };
+
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Queues message trailed with CRLF, for the connection's handler to write.
+    /// Fails if the queue goes over its limit.
+    /// Messages that could not be built are dropped, without affecting the connection.
+    pub fn send(&self, message: &dyn ToMessage) -> io::Result<()> {
+        let bytes = some_or_return!(encode(message), Ok(()));
+
+        let mut state = self.lock();
+
+        if state.closing {
+            return Ok(());
+        }
+
+        if state.exceeded || state.pending.len() + bytes.len() > state.limit {
+            if !state.exceeded {
+                state.exceeded = true;
+                state.wake();
+            }
+            return Err(io::Error::other(SENDQ_EXCEEDED_MESSAGE));
+        }
+
+        state.enqueue(bytes);
+
+        Ok(())
+    }
+
+    /// Queues message as the last one, whatever the limit, discarding every message sent afterwards.
+    /// The connection is left open, for its handler to flush what is pending before closing it.
+    pub fn close(&self, message: &dyn ToMessage) -> io::Result<()> {
+        let bytes = encode(message).unwrap_or_default();
+
+        let mut state = self.lock();
+
+        if state.closing {
+            return Ok(());
+        }
+        state.closing = true;
+        state.enqueue(bytes);
+
+        Ok(())
+    }
+
+    /// Returns true once the queue was closed, even if its last message is still pending.
+    pub fn closing(&self) -> bool {
+        self.lock().closing
+    }
+
+    /// Writes as many pending bytes as the connection accepts.
+    /// Only the connection's own handler flushes, so writes never block other threads.
+    pub fn flush(&self) -> io::Result<()> {
+        self.lock().write_pending()
+    }
+
+    /// Returns how many bytes are waiting to be written.
+    pub fn queued(&self) -> usize {
+        self.lock().pending.len()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.lock().limit
+    }
+
+    pub fn set_limit(&self, limit: usize) {
+        self.lock().limit = limit;
+    }
+
+    /// Returns true once a message did not fit in the queue.
+    pub fn exceeded(&self) -> bool {
+        self.lock().exceeded
+    }
+
+    pub fn try_clone_stream(&self) -> io::Result<C> {
+        self.lock().stream.try_clone()
+    }
+
+    /// Has the connection's handler polled whenever output is queued.
+    pub fn set_waker(&self, waker: Waker) {
+        self.lock().waker = Some(waker);
+    }
+
+    /// Discards every message sent afterwards and wakes the connection's handler,
+    /// which writes what is still pending and then closes the connection.
+    pub fn hang_up(&self) {
+        let mut state = self.lock();
+        state.closing = true;
+        state.hung_up = true;
+        state.wake();
+    }
+
+    /// Returns true once the connection should be closed after its pending output.
+    pub fn hung_up(&self) -> bool {
+        self.lock().hung_up
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState<C>> {
+        self.state
+            .lock()
+            .expect("Output queue lock should not be poisoned")
+    }
+}
+
+impl<C: Connection> Clone for OutputQueue<C> {
+    fn clone(&self) -> Self {
+        Self {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+/// Returns message trailed with CRLF, or None if there is nothing to send or it could not be built.
+fn encode(message: &dyn ToMessage) -> Option<Vec<u8>> {
+    let message = match message.to_message() {
+        Ok(message) => message,
+        Err(ParsingError::EmptyMessage) => return None,
+        Err(error) => {
+            debug_print!("Dropping message that could not be built: {error}");
+            return None;
+        }
+    };
+
+    let mut bytes = Vec::new();
+    message.send_to(&mut bytes).ok()?;
+
+    Some(bytes)
+}
+
+impl<C: Connection> QueueState<C> {
+    /// Appends bytes, waking the handler if it had nothing left to write.
+    fn enqueue(&mut self, bytes: Vec<u8>) {
+        let idle = self.pending.is_empty();
+        self.pending.extend(bytes);
+
+        if idle {
+            self.wake();
+        }
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = &self.waker {
+            waker.wake();
+        }
+    }
+
+    /// Stops at the first write the peer does not accept, keeping the rest queued.
+    fn write_pending(&mut self) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            let (front, _) = self.pending.as_slices();
+
+            match self.stream.write(front) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+}
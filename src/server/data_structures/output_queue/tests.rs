@@ -0,0 +1,190 @@
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use crate::message::Message;
+use crate::server::testing::MockTcpStream;
+
+use super::*;
+
+/// Stream whose peer only accepts as many bytes as it is allowed to.
+struct StalledStream {
+    stream: MockTcpStream,
+    accepted: Arc<Mutex<usize>>,
+}
+
+impl StalledStream {
+    fn new() -> Self {
+        Self {
+            stream: MockTcpStream::new(),
+            accepted: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    fn accept(&self, bytes: usize) {
+        *self.accepted.lock().unwrap() += bytes;
+    }
+
+    fn written(&mut self) -> String {
+        self.stream.read_wbuf_to_string()
+    }
+}
+
+impl Read for StalledStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for StalledStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut accepted = self.accepted.lock().unwrap();
+        if *accepted == 0 {
+            return Err(ErrorKind::WouldBlock.into());
+        }
+
+        let written = self.stream.write(&buf[..buf.len().min(*accepted)])?;
+        *accepted -= written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl Connection for StalledStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            stream: self.stream.try_clone()?,
+            accepted: Arc::clone(&self.accepted),
+        })
+    }
+
+    fn peer_address(&self) -> io::Result<SocketAddr> {
+        self.stream.peer_address()
+    }
+
+    fn shutdown(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn ping() -> Message {
+    Message::new("PING :token").unwrap()
+}
+
+#[test]
+fn sent_messages_are_only_written_on_flush() {
+    let mut stream = MockTcpStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 100);
+
+    queue.send(&ping()).unwrap();
+    queue.send(&ping()).unwrap();
+
+    assert_eq!("", stream.read_wbuf_to_string());
+    assert_eq!(26, queue.queued());
+
+    queue.flush().unwrap();
+
+    assert_eq!(
+        "PING :token\r\nPING :token\r\n",
+        stream.read_wbuf_to_string()
+    );
+    assert_eq!(0, queue.queued());
+}
+
+#[test]
+fn bytes_not_accepted_stay_queued() {
+    let mut stream = StalledStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 100);
+
+    stream.accept(4);
+    queue.send(&ping()).unwrap();
+    queue.flush().unwrap();
+
+    assert_eq!("PING", stream.written());
+    assert_eq!(9, queue.queued());
+}
+
+#[test]
+fn going_over_limit_fails_and_marks_queue_exceeded() {
+    let stream = StalledStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 20);
+
+    queue.send(&ping()).unwrap();
+    assert!(!queue.exceeded());
+
+    let error = queue.send(&ping()).unwrap_err();
+
+    assert_eq!(SENDQ_EXCEEDED_MESSAGE, error.to_string());
+    assert!(queue.exceeded());
+    assert_eq!(13, queue.queued());
+}
+
+#[test]
+fn exceeded_queue_refuses_further_messages() {
+    let stream = StalledStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 20);
+
+    queue.send(&ping()).unwrap();
+    queue.send(&ping()).unwrap_err();
+    stream.accept(100);
+    queue.flush().unwrap();
+
+    assert!(queue.send(&ping()).is_err());
+}
+
+#[test]
+fn clones_share_the_same_queue() {
+    let stream = StalledStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 100);
+    let clone = queue.clone();
+
+    clone.send(&ping()).unwrap();
+
+    assert_eq!(13, queue.queued());
+}
+
+#[test]
+fn closed_queue_discards_later_messages() {
+    let mut stream = MockTcpStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 100);
+
+    queue.close(&ping()).unwrap();
+    queue.send(&ping()).unwrap();
+    queue.flush().unwrap();
+
+    assert!(queue.closing());
+    assert_eq!("PING :token\r\n", stream.read_wbuf_to_string());
+}
+
+#[test]
+fn closing_message_is_queued_over_limit() {
+    let mut stream = StalledStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 20);
+
+    queue.send(&ping()).unwrap();
+    queue.close(&ping()).unwrap();
+
+    assert_eq!(26, queue.queued());
+
+    stream.accept(100);
+    queue.flush().unwrap();
+
+    assert_eq!("PING :token\r\nPING :token\r\n", stream.written());
+}
+
+#[test]
+fn hung_up_queue_keeps_what_was_pending() {
+    let mut stream = MockTcpStream::new();
+    let queue = OutputQueue::new(stream.try_clone().unwrap(), 100);
+
+    queue.send(&ping()).unwrap();
+    queue.hang_up();
+    queue.send(&ping()).unwrap();
+    queue.flush().unwrap();
+
+    assert!(queue.hung_up());
+    assert_eq!("PING :token\r\n", stream.read_wbuf_to_string());
+}
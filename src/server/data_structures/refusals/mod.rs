@@ -0,0 +1,45 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Reasons a connection is refused before registering.
+pub enum Refusal {
+    /// the server handles as many connections as it may
+    ServerFull,
+    /// the address block of the connection has as many connections as it may
+    TooManyFromHost,
+    /// the address connected too many times in a short period
+    Throttled,
+}
+
+impl Refusal {
+    /// Returns the reason sent to refused connections.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Refusal::ServerFull => "Server is full",
+            Refusal::TooManyFromHost => "Too many connections from your host",
+            Refusal::Throttled => "Connecting too fast, try again later",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How many connections were refused for each reason since the server started.
+///
+/// Only the console `STATS` command reads these; clients, operators included, cannot query them.
+pub struct Refusals {
+    pub server_full: usize,
+    pub too_many_from_host: usize,
+    pub throttled: usize,
+}
+
+impl Refusals {
+    pub fn record(&mut self, refusal: Refusal) {
+        match refusal {
+            Refusal::ServerFull => self.server_full += 1,
+            Refusal::TooManyFromHost => self.too_many_from_host += 1,
+            Refusal::Throttled => self.throttled += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.server_full + self.too_many_from_host + self.throttled
+    }
+}
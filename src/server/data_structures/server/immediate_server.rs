@@ -1,22 +1,32 @@
 use crate::server::connection::Connection;
+use crate::server::data_structures::OutputQueue;
 
 use super::ServerInfo;
 /// Represents a server connected to local server.
 pub struct ImmediateServer<C: Connection> {
-    stream: C,
+    queue: OutputQueue<C>,
     info: ServerInfo,
 }
 
 impl<C: Connection> ImmediateServer<C> {
-    pub fn new(stream: C, servername: String, serverinfo: String, hopcount: usize) -> Self {
+    pub fn new(
+        queue: OutputQueue<C>,
+        servername: String,
+        serverinfo: String,
+        hopcount: usize,
+    ) -> Self {
         Self {
-            stream,
+            queue,
             info: ServerInfo::new(servername, serverinfo, hopcount),
         }
     }
 
     pub fn get_stream(&self) -> Result<C, std::io::Error> {
-        self.stream.try_clone()
+        self.queue.try_clone_stream()
+    }
+
+    pub fn queue(&self) -> &OutputQueue<C> {
+        &self.queue
     }
 
     pub fn info(&self) -> ServerInfo {
@@ -1,4 +1,4 @@
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// ServerInfo contains public server information.
 pub struct ServerInfo {
     pub servername: String,
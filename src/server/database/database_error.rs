@@ -1,3 +1,5 @@
+use std::io;
+
 #[derive(Debug)]
 /// Errors that may happen when handling requests.
 pub enum DatabaseError {
@@ -7,3 +9,9 @@ pub enum DatabaseError {
     ClientIsOffline,
     CannotCloneStream,
 }
+
+impl From<DatabaseError> for io::Error {
+    fn from(error: DatabaseError) -> Self {
+        io::Error::other(format!("{error:?}"))
+    }
+}
@@ -1,10 +1,16 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, SystemTime};
 
 use crate::macros::own;
+use crate::server::config::{BanBlock, Limits, ServerConfig};
 use crate::server::consts::user_flag::UserFlag;
 use crate::server::data_structures::*;
+use crate::server::snapshot::Snapshot;
 use crate::server::{connection::Connection, consts::channel_flag::ChannelFlag};
 
+use super::JoinVerdict;
 use super::{database_error::DatabaseError, database_message::DatabaseMessage};
 
 /// A DatabaseHandle handles and makes request to the main Database.
@@ -17,6 +23,19 @@ impl<C: Connection> DatabaseHandle<C> {
     pub fn new(sender: Sender<DatabaseMessage<C>>) -> Self {
         Self { sender }
     }
+    pub fn add_account(&self, account: Account) -> bool {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::AddAccount {
+            account,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn add_channel_banmask(&self, channel: &str, mask: &str) {
         own!(channel, mask);
         let request = DatabaseMessage::AddChannelBanmask { channel, mask };
@@ -70,18 +89,24 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
-    pub fn add_local_client(&self, client: LocalClient<C>) {
-        let request = DatabaseMessage::AddLocalClient { client };
+    /// Adds client unless its nickname is already taken, returning whether it was added.
+    pub fn add_local_client(&self, client: LocalClient<C>) -> bool {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::AddLocalClient { client, respond_to };
         self.sender
             .send(request)
             .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
     }
-    pub fn are_credentials_valid(&self, username: &str, password: &str) -> bool {
-        own!(username, password);
+    pub fn are_credentials_valid(&self, username: &str, password: &str, nickname: &str) -> bool {
+        own!(username, password, nickname);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
         let request = DatabaseMessage::AreCredentialsValid {
             username,
             password,
+            nickname,
             respond_to,
         };
         self.sender
@@ -121,6 +146,12 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn configure(&self, config: ServerConfig) {
+        let request = DatabaseMessage::Configure { config };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
     pub fn contains_channel(&self, channel: &str) -> bool {
         own!(channel);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -170,6 +201,17 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
+    pub fn get_account(&self, name: &str) -> Option<Account> {
+        own!(name);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetAccount { name, respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_all_channels(&self) -> Vec<String> {
         let (respond_to, receive_from) = std::sync::mpsc::channel();
         let request = DatabaseMessage::GetAllChannels { respond_to };
@@ -242,6 +284,24 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    /// Returns every connection a message to the channel goes through, in a single request.
+    pub fn get_channel_recipients(
+        &self,
+        channel: &str,
+    ) -> Result<ChannelRecipients<C>, DatabaseError> {
+        own!(channel);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetChannelRecipients {
+            channel,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_channel_config(&self, channel: &str) -> Result<ChannelConfiguration, DatabaseError> {
         own!(channel);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -256,6 +316,20 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_channel_founder(&self, channel: &str) -> Option<String> {
+        own!(channel);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetChannelFounder {
+            channel,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_channel_key(&self, channel: &str) -> Result<Option<String>, DatabaseError> {
         own!(channel);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -312,6 +386,21 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    /// Returns the capabilities enabled by a local client, external clients have none.
+    pub fn get_client_capabilities(&self, nickname: &str) -> Vec<Capability> {
+        own!(nickname);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetClientCapabilities {
+            nickname,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_client_info(&self, client: &str) -> Result<ClientInfo, DatabaseError> {
         own!(client);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -323,6 +412,26 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_config(&self) -> Option<ServerConfig> {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetConfig { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_creation_time(&self) -> SystemTime {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetCreationTime { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_immediate_server(&self, client: &str) -> Result<String, DatabaseError> {
         own!(client);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -334,6 +443,40 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_keepalive_settings(&self) -> (Duration, Duration) {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetKeepaliveSettings { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_limits(&self) -> Limits {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetLimits { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_local_queue(&self, nickname: &str) -> Result<OutputQueue<C>, DatabaseError> {
+        own!(nickname);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetLocalQueue {
+            nickname,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_local_stream(&self, nickname: &str) -> Result<C, DatabaseError> {
         own!(nickname);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -348,6 +491,30 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_link_password(&self, servername: &str) -> Option<String> {
+        own!(servername);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetLinkPassword {
+            servername,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_motd_file(&self) -> Option<PathBuf> {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetMotdFile { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_own_server_info(&self) -> String {
         let (respond_to, receive_from) = std::sync::mpsc::channel();
         let request = DatabaseMessage::GetOwnServerInfo { respond_to };
@@ -358,6 +525,26 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_refusals(&self) -> Refusals {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetRefusals { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_server_bans(&self) -> Vec<BanBlock> {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetServerBans { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_server_info(&self, server: &str) -> Result<ServerInfo, DatabaseError> {
         own!(server);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -379,6 +566,27 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_server_password(&self) -> Option<String> {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetServerPassword { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn get_server_queue(&self, server: &str) -> Result<OutputQueue<C>, DatabaseError> {
+        own!(server);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetServerQueue { server, respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn get_server_stream(&self, server: &str) -> Result<C, DatabaseError> {
         own!(server);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -390,6 +598,51 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    pub fn get_snapshot(&self) -> Snapshot {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::GetSnapshot { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn has_account_store(&self) -> bool {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::HasAccountStore { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn has_channel_store(&self) -> bool {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::HasChannelStore { respond_to };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn has_operator_privilege(&self, nickname: &str, privilege: OperatorPrivilege) -> bool {
+        own!(nickname);
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::HasOperatorPrivilege {
+            nickname,
+            privilege,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn is_channel_operator(&self, channel: &str, nickname: &str) -> bool {
         own!(channel, nickname);
         let (respond_to, receive_from) = std::sync::mpsc::channel();
@@ -474,6 +727,30 @@ impl<C: Connection> DatabaseHandle<C> {
             .recv()
             .expect("Handle sender should not be dropped")
     }
+    /// Checks whether client may join channel and, if it may, adds it in the same request.
+    /// The client is made operator when it creates the channel or founded it.
+    pub fn join_channel(
+        &self,
+        nickname: &str,
+        channel: &str,
+        key: &Option<String>,
+    ) -> JoinVerdict<C> {
+        own!(nickname, channel);
+        let key = key.clone();
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::JoinChannel {
+            nickname,
+            channel,
+            key,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
     pub fn remove_channel_banmask(&self, channel: &str, mask: &str) {
         own!(channel, mask);
         let request = DatabaseMessage::RemoveChannelBanmask { channel, mask };
@@ -495,6 +772,32 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
+    /// Returns true if too many OPER attempts from address failed recently.
+    pub fn oper_attempts_exceeded(&self, address: IpAddr) -> bool {
+        let (respond_to, receive_from) = std::sync::mpsc::channel();
+        let request = DatabaseMessage::OperAttemptsExceeded {
+            address,
+            respond_to,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+        receive_from
+            .recv()
+            .expect("Handle sender should not be dropped")
+    }
+    pub fn record_failed_oper(&self, address: IpAddr) {
+        let request = DatabaseMessage::RecordFailedOper { address };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn record_refusal(&self, refusal: Refusal) {
+        let request = DatabaseMessage::RecordRefusal { refusal };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
     pub fn remove_client_from_channel(&self, channel: &str, nickname: &str) {
         own!(channel, nickname);
         let request = DatabaseMessage::RemoveClientFromChannel { channel, nickname };
@@ -509,6 +812,12 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
+    pub fn restore_snapshot(&self, snapshot: Snapshot) {
+        let request = DatabaseMessage::RestoreSnapshot { snapshot };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
     pub fn set_away_message(&self, nickname: &str, message: Option<String>) {
         own!(nickname);
         let request = DatabaseMessage::SetAwayMessage { nickname, message };
@@ -523,6 +832,19 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
+    pub fn set_account_store(&self, path: PathBuf) {
+        let request = DatabaseMessage::SetAccountStore { path };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_channel_founder(&self, channel: &str, founder: Option<String>) {
+        own!(channel);
+        let request = DatabaseMessage::SetChannelFounder { channel, founder };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
     pub fn set_channel_key(&self, channel: &str, key: Option<String>) {
         own!(channel);
         let request = DatabaseMessage::SetChannelKey { channel, key };
@@ -544,9 +866,72 @@ impl<C: Connection> DatabaseHandle<C> {
             .send(request)
             .expect("Database receiver should not be dropped");
     }
-    pub fn set_server_operator(&self, nickname: &str) {
+    pub fn set_channel_store(&self, path: PathBuf) {
+        let request = DatabaseMessage::SetChannelStore { path };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_client_account(&self, nickname: &str, account: Option<String>) {
+        own!(nickname);
+        let request = DatabaseMessage::SetClientAccount { nickname, account };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_client_capabilities(&self, nickname: &str, capabilities: Vec<Capability>) {
         own!(nickname);
-        let request = DatabaseMessage::SetServerOperator { nickname };
+        let request = DatabaseMessage::SetClientCapabilities {
+            nickname,
+            capabilities,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_keepalive_settings(&self, interval: Duration, timeout: Duration) {
+        let request = DatabaseMessage::SetKeepaliveSettings { interval, timeout };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_limits(&self, limits: Limits) {
+        let request = DatabaseMessage::SetLimits { limits };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_link_password(&self, servername: &str, password: &str) {
+        own!(servername, password);
+        let request = DatabaseMessage::SetLinkPassword {
+            servername,
+            password,
+        };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_motd_file(&self, path: PathBuf) {
+        let request = DatabaseMessage::SetMotdFile { path };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_server_password(&self, password: Option<String>) {
+        let request = DatabaseMessage::SetServerPassword { password };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_operators(&self, operators: Vec<OperatorBlock>) {
+        let request = DatabaseMessage::SetOperators { operators };
+        self.sender
+            .send(request)
+            .expect("Database receiver should not be dropped");
+    }
+    pub fn set_server_operator(&self, nickname: &str, operator: &str) {
+        own!(nickname, operator);
+        let request = DatabaseMessage::SetServerOperator { nickname, operator };
         self.sender
             .send(request)
             .expect("Database receiver should not be dropped");
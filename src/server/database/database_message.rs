@@ -1,15 +1,25 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, SystemTime};
 
+use crate::server::config::{BanBlock, Limits, ServerConfig};
 use crate::server::consts::channel_flag::ChannelFlag;
 use crate::server::consts::user_flag::UserFlag;
 use crate::server::data_structures::*;
 
 use crate::server::connection::Connection;
+use crate::server::snapshot::Snapshot;
 
 use super::database_error::DatabaseError;
+use super::JoinVerdict;
 
 /// Requests a Database can receive and must be able to answer.
 pub enum DatabaseMessage<C: Connection> {
+    AddAccount {
+        account: Account,
+        respond_to: Sender<bool>,
+    },
     AddChannelBanmask {
         channel: String,
         mask: String,
@@ -41,10 +51,12 @@ pub enum DatabaseMessage<C: Connection> {
     },
     AddLocalClient {
         client: LocalClient<C>,
+        respond_to: Sender<bool>,
     },
     AreCredentialsValid {
         username: String,
         password: String,
+        nickname: String,
         respond_to: Sender<bool>,
     },
     ChannelHasClientInvite {
@@ -57,6 +69,9 @@ pub enum DatabaseMessage<C: Connection> {
         flag: ChannelFlag,
         respond_to: Sender<bool>,
     },
+    Configure {
+        config: ServerConfig,
+    },
     ContainsChannel {
         channel: String,
         respond_to: Sender<bool>,
@@ -72,6 +87,10 @@ pub enum DatabaseMessage<C: Connection> {
     DisconnectClient {
         nickname: String,
     },
+    GetAccount {
+        name: String,
+        respond_to: Sender<Option<Account>>,
+    },
     GetAllChannels {
         respond_to: Sender<Vec<String>>,
     },
@@ -93,10 +112,18 @@ pub enum DatabaseMessage<C: Connection> {
         channel: String,
         respond_to: Sender<Result<Vec<String>, DatabaseError>>,
     },
+    GetChannelRecipients {
+        channel: String,
+        respond_to: Sender<Result<ChannelRecipients<C>, DatabaseError>>,
+    },
     GetChannelConfig {
         channel: String,
         respond_to: Sender<Result<ChannelConfiguration, DatabaseError>>,
     },
+    GetChannelFounder {
+        channel: String,
+        respond_to: Sender<Option<String>>,
+    },
     GetChannelKey {
         channel: String,
         respond_to: Sender<Result<Option<String>, DatabaseError>>,
@@ -113,21 +140,54 @@ pub enum DatabaseMessage<C: Connection> {
         nickname: String,
         respond_to: Sender<Result<Vec<String>, DatabaseError>>,
     },
+    GetClientCapabilities {
+        nickname: String,
+        respond_to: Sender<Vec<Capability>>,
+    },
     GetClientInfo {
         client: String,
         respond_to: Sender<Result<ClientInfo, DatabaseError>>,
     },
+    GetConfig {
+        respond_to: Sender<Option<ServerConfig>>,
+    },
+    GetCreationTime {
+        respond_to: Sender<SystemTime>,
+    },
     GetImmediateServer {
         client: String,
         respond_to: Sender<Result<String, DatabaseError>>,
     },
+    GetKeepaliveSettings {
+        respond_to: Sender<(Duration, Duration)>,
+    },
+    GetLimits {
+        respond_to: Sender<Limits>,
+    },
+    GetLocalQueue {
+        nickname: String,
+        respond_to: Sender<Result<OutputQueue<C>, DatabaseError>>,
+    },
     GetLocalStream {
         nickname: String,
         respond_to: Sender<Result<C, DatabaseError>>,
     },
+    GetLinkPassword {
+        servername: String,
+        respond_to: Sender<Option<String>>,
+    },
+    GetMotdFile {
+        respond_to: Sender<Option<PathBuf>>,
+    },
     GetOwnServerInfo {
         respond_to: Sender<String>,
     },
+    GetRefusals {
+        respond_to: Sender<Refusals>,
+    },
+    GetServerBans {
+        respond_to: Sender<Vec<BanBlock>>,
+    },
     GetServerInfo {
         server: String,
         respond_to: Sender<Result<ServerInfo, DatabaseError>>,
@@ -135,10 +195,31 @@ pub enum DatabaseMessage<C: Connection> {
     GetServerName {
         respond_to: Sender<String>,
     },
+    GetServerPassword {
+        respond_to: Sender<Option<String>>,
+    },
+    GetServerQueue {
+        server: String,
+        respond_to: Sender<Result<OutputQueue<C>, DatabaseError>>,
+    },
     GetServerStream {
         server: String,
         respond_to: Sender<Result<C, DatabaseError>>,
     },
+    GetSnapshot {
+        respond_to: Sender<Snapshot>,
+    },
+    HasAccountStore {
+        respond_to: Sender<bool>,
+    },
+    HasChannelStore {
+        respond_to: Sender<bool>,
+    },
+    HasOperatorPrivilege {
+        nickname: String,
+        privilege: OperatorPrivilege,
+        respond_to: Sender<bool>,
+    },
     IsChannelOperator {
         channel: String,
         nickname: String,
@@ -166,6 +247,22 @@ pub enum DatabaseMessage<C: Connection> {
         nickname: String,
         respond_to: Sender<bool>,
     },
+    JoinChannel {
+        nickname: String,
+        channel: String,
+        key: Option<String>,
+        respond_to: Sender<JoinVerdict<C>>,
+    },
+    OperAttemptsExceeded {
+        address: IpAddr,
+        respond_to: Sender<bool>,
+    },
+    RecordFailedOper {
+        address: IpAddr,
+    },
+    RecordRefusal {
+        refusal: Refusal,
+    },
     RemoveChannelBanmask {
         channel: String,
         mask: String,
@@ -185,6 +282,9 @@ pub enum DatabaseMessage<C: Connection> {
     RemoveServer {
         servername: String,
     },
+    RestoreSnapshot {
+        snapshot: Snapshot,
+    },
     SetAwayMessage {
         message: Option<String>,
         nickname: String,
@@ -193,6 +293,13 @@ pub enum DatabaseMessage<C: Connection> {
         channel: String,
         flag: ChannelFlag,
     },
+    SetAccountStore {
+        path: PathBuf,
+    },
+    SetChannelFounder {
+        channel: String,
+        founder: Option<String>,
+    },
     SetChannelKey {
         channel: String,
         key: Option<String>,
@@ -205,8 +312,40 @@ pub enum DatabaseMessage<C: Connection> {
         channel: String,
         topic: String,
     },
+    SetChannelStore {
+        path: PathBuf,
+    },
+    SetClientAccount {
+        nickname: String,
+        account: Option<String>,
+    },
+    SetClientCapabilities {
+        nickname: String,
+        capabilities: Vec<Capability>,
+    },
+    SetKeepaliveSettings {
+        interval: Duration,
+        timeout: Duration,
+    },
+    SetLimits {
+        limits: Limits,
+    },
+    SetLinkPassword {
+        servername: String,
+        password: String,
+    },
+    SetMotdFile {
+        path: PathBuf,
+    },
+    SetServerPassword {
+        password: Option<String>,
+    },
+    SetOperators {
+        operators: Vec<OperatorBlock>,
+    },
     SetServerOperator {
         nickname: String,
+        operator: String,
     },
     SetUserFlag {
         user: String,
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use crate::macros::{debug_print, ok_or_return, some_or_return};
+use crate::server::{
+    account_store::AccountStore,
+    connection::Connection,
+    data_structures::{irc_lowercase, Account, NameKey},
+    database::Database,
+};
+
+impl<C: Connection> Database<C> {
+    pub fn handle_set_account_store(&mut self, path: PathBuf) {
+        self.set_account_store(path);
+    }
+
+    pub fn handle_has_account_store(&self, respond_to: Sender<bool>) {
+        respond_to
+            .send(self.account_store.is_some())
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_add_account(&mut self, account: Account, respond_to: Sender<bool>) {
+        let added = self.add_account(account);
+        respond_to
+            .send(added)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_get_account(&self, name: String, respond_to: Sender<Option<Account>>) {
+        let account = self.accounts.get(&NameKey::from(&name)).cloned();
+        respond_to
+            .send(account)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_client_account(&mut self, nickname: String, account: Option<String>) {
+        let info = ok_or_return!(self.get_client_info(&nickname));
+        debug_print!("Setting {nickname}'s account to {account:?}");
+
+        info.account = account;
+    }
+}
+
+impl<C: Connection> Database<C> {
+    /// Starts saving accounts to path, restoring the ones it already holds.
+    /// Nothing changes if accounts were already saved to path.
+    pub(in crate::server::database) fn set_account_store(&mut self, path: PathBuf) {
+        if let Some(store) = &self.account_store {
+            if store.path() == path {
+                return;
+            }
+        }
+
+        let store = AccountStore::new(path);
+        let accounts = match store.load() {
+            Ok(accounts) => accounts,
+            Err(error) => {
+                return eprintln!(
+                    "Could not restore accounts from {:?}: {error}",
+                    store.path()
+                )
+            }
+        };
+        for account in accounts {
+            debug_print!("Restoring account {}", account.name);
+            self.accounts.insert(NameKey::from(&account.name), account);
+        }
+
+        self.account_store = Some(store);
+        self.save_accounts();
+    }
+
+    /// Returns false if the account's name was already registered.
+    fn add_account(&mut self, account: Account) -> bool {
+        let key = NameKey::from(&account.name);
+        if self.accounts.contains_key(&key) {
+            return false;
+        }
+
+        debug_print!("Registering account {}", account.name);
+        self.accounts.insert(key, account);
+        self.save_accounts();
+
+        true
+    }
+
+    fn save_accounts(&self) {
+        let store = some_or_return!(&self.account_store);
+
+        let mut accounts: Vec<Account> = self.accounts.values().cloned().collect();
+        accounts.sort_by_key(|account| irc_lowercase(&account.name));
+
+        if let Err(error) = store.save(&accounts) {
+            eprintln!("Could not save accounts to {:?}: {error}", store.path());
+        }
+    }
+}
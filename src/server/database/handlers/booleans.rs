@@ -1,6 +1,7 @@
 use std::sync::mpsc::Sender;
 
 use crate::macros::{ok_or_return, some_or_return};
+use crate::server::data_structures::{NameKey, OperatorPrivilege};
 use crate::server::{connection::Connection, database::Database};
 
 impl<C: Connection> Database<C> {
@@ -11,6 +12,18 @@ impl<C: Connection> Database<C> {
             .expect("Handler receiver should not be dropped");
     }
 
+    pub fn handle_has_operator_privilege(
+        &self,
+        nickname: String,
+        privilege: OperatorPrivilege,
+        respond_to: Sender<bool>,
+    ) {
+        let has_privilege = self.has_operator_privilege(nickname, privilege);
+        respond_to
+            .send(has_privilege)
+            .expect("Handler receiver should not be dropped");
+    }
+
     pub fn handle_contains_client(&self, nickname: String, respond_to: Sender<bool>) {
         let contains_client = self.contains_client(nickname);
         respond_to
@@ -67,12 +80,13 @@ impl<C: Connection> Database<C> {
     }
 
     pub fn handle_are_credentials_valid(
-        &self,
+        &mut self,
         username: String,
         password: String,
+        nickname: String,
         respond_to: Sender<bool>,
     ) {
-        let are_credentials_valid = self.are_credentials_valid(username, password);
+        let are_credentials_valid = self.are_credentials_valid(username, password, nickname);
         respond_to
             .send(are_credentials_valid)
             .expect("Handler receiver should not be dropped");
@@ -109,9 +123,22 @@ impl<C: Connection> Database<C> {
     fn is_immediate_server(&self, server: &str) -> bool {
         self.immediate_servers.contains_key(server)
     }
-    fn are_credentials_valid(&self, username: String, password: String) -> bool {
-        let real_password = some_or_return!(self.credentials.get(&username), false);
-        &password == real_password
+    fn are_credentials_valid(
+        &mut self,
+        username: String,
+        password: String,
+        nickname: String,
+    ) -> bool {
+        let client = ok_or_return!(self.get_client_info(&nickname), false).clone();
+        let operator = some_or_return!(self.operators.get(&username), false);
+
+        operator.accepts(&password, &client)
+    }
+
+    fn has_operator_privilege(&self, nickname: String, privilege: OperatorPrivilege) -> bool {
+        let client = some_or_return!(self.local_clients.get(&NameKey::from(&nickname)), false);
+
+        client.get_info().is_operator() && client.privileges().contains(&privilege)
     }
 
     fn is_server_operator(&mut self, nickname: String) -> bool {
@@ -121,29 +148,32 @@ impl<C: Connection> Database<C> {
     }
 
     pub fn contains_client(&self, nickname: String) -> bool {
-        self.local_clients.contains_key(&nickname) || self.external_clients.contains_key(&nickname)
+        self.local_clients.contains_key(&NameKey::from(&nickname))
+            || self
+                .external_clients
+                .contains_key(&NameKey::from(&nickname))
     }
 
     fn is_local_client(&self, nickname: String) -> bool {
-        self.local_clients.contains_key(&nickname)
+        self.local_clients.contains_key(&NameKey::from(&nickname))
     }
 
     fn contains_channel(&self, channel: String) -> bool {
-        self.channels.contains_key(&channel)
+        self.channels.contains_key(&NameKey::from(&channel))
     }
 
     fn is_client_in_channel(&self, nickname: String, channel: String) -> bool {
-        let channel = some_or_return!(self.channels.get(&channel), false);
+        let channel = some_or_return!(self.channels.get(&NameKey::from(&channel)), false);
         channel.is_member(&nickname)
     }
 
     fn is_channel_operator(&self, channel: String, nickname: String) -> bool {
-        let channel = some_or_return!(self.channels.get(&channel), false);
+        let channel = some_or_return!(self.channels.get(&NameKey::from(&channel)), false);
         channel.is_operator(&nickname)
     }
 
     fn is_channel_speaker(&self, channel: String, nickname: String) -> bool {
-        let channel = some_or_return!(self.channels.get(&channel), false);
+        let channel = some_or_return!(self.channels.get(&NameKey::from(&channel)), false);
         channel.is_speaker(&nickname)
     }
 
@@ -153,7 +183,7 @@ impl<C: Connection> Database<C> {
     }
 
     fn channel_has_client_invite(&self, channel: String, client: String) -> bool {
-        let channel = some_or_return!(self.channels.get(&channel), false);
+        let channel = some_or_return!(self.channels.get(&NameKey::from(&channel)), false);
         channel.has_invite(&client)
     }
 }
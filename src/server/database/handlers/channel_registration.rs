@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+
+use crate::macros::{debug_print, some_or_return};
+use crate::server::{
+    channel_store::{ChannelStore, RegisteredChannel},
+    connection::Connection,
+    data_structures::{irc_lowercase, Channel, NameKey},
+    database::Database,
+};
+
+impl<C: Connection> Database<C> {
+    pub fn handle_set_channel_store(&mut self, path: PathBuf) {
+        self.set_channel_store(path);
+    }
+
+    pub fn handle_has_channel_store(&self, respond_to: Sender<bool>) {
+        respond_to
+            .send(self.channel_store.is_some())
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_get_channel_founder(&self, channel: String, respond_to: Sender<Option<String>>) {
+        let founder = self
+            .channels
+            .get(&NameKey::from(&channel))
+            .and_then(Channel::founder);
+        respond_to
+            .send(founder)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_channel_founder(&mut self, channel: String, founder: Option<String>) {
+        self.set_channel_founder(channel, founder);
+    }
+}
+
+impl<C: Connection> Database<C> {
+    /// Starts saving registered channels to path, restoring the ones it already holds.
+    /// Nothing changes if channels were already saved to path.
+    pub(in crate::server::database) fn set_channel_store(&mut self, path: PathBuf) {
+        if let Some(store) = &self.channel_store {
+            if store.path() == path {
+                return;
+            }
+        }
+
+        let store = ChannelStore::new(path);
+        let registered = match store.load() {
+            Ok(registered) => registered,
+            Err(error) => {
+                return eprintln!(
+                    "Could not restore channels from {:?}: {error}",
+                    store.path()
+                )
+            }
+        };
+        for channel in registered {
+            self.restore_channel(channel);
+        }
+
+        self.channel_store = Some(store);
+        self.save_channels();
+    }
+
+    fn restore_channel(&mut self, registered: RegisteredChannel) {
+        let RegisteredChannel {
+            name,
+            founder,
+            config,
+        } = registered;
+        debug_print!("Restoring channel {name} registered to {founder}");
+
+        match self.channels.get_mut(&NameKey::from(&name)) {
+            Some(channel) => channel.set_founder(Some(founder)),
+            None => {
+                let channel = Channel::restore(name.clone(), Some(founder), config);
+                self.channels.insert(NameKey::from(name), channel);
+            }
+        }
+    }
+
+    fn set_channel_founder(&mut self, channel_name: String, founder: Option<String>) {
+        let key = NameKey::from(&channel_name);
+        let channel = some_or_return!(self.channels.get_mut(&key));
+
+        debug_print!("Setting founder of {channel_name} to {founder:?}");
+        channel.set_founder(founder);
+        if channel.is_abandoned() {
+            self.channels.remove(&key);
+        }
+
+        self.save_channels();
+    }
+
+    /// Saves registered channels again if channel is one of them.
+    pub(in crate::server::database) fn channel_changed(&self, channel: &str) {
+        let channel = some_or_return!(self.channels.get(&NameKey::from(&channel)));
+        if channel.is_registered() {
+            self.save_channels();
+        }
+    }
+
+    fn save_channels(&self) {
+        let store = some_or_return!(&self.channel_store);
+
+        let mut registered: Vec<RegisteredChannel> = self
+            .channels
+            .values()
+            .filter_map(|channel| {
+                Some(RegisteredChannel {
+                    name: channel.name(),
+                    founder: channel.founder()?,
+                    config: channel.get_config(),
+                })
+            })
+            .collect();
+        registered.sort_by_key(|channel| irc_lowercase(&channel.name));
+
+        if let Err(error) = store.save(&registered) {
+            eprintln!("Could not save channels to {:?}: {error}", store.path());
+        }
+    }
+}
@@ -1,7 +1,11 @@
 use std::sync::mpsc::Sender;
 
 use crate::server::database::database_error::DatabaseError;
-use crate::server::{connection::Connection, data_structures::Channel, database::Database};
+use crate::server::{
+    connection::Connection,
+    data_structures::{Capability, Channel, ChannelRecipients, NameKey},
+    database::Database,
+};
 
 use crate::macros::{debug_print, some_or_return};
 
@@ -17,6 +21,17 @@ impl<C: Connection> Database<C> {
             .expect("Handler receiver should not be dropped");
     }
 
+    pub fn handle_get_channel_recipients(
+        &self,
+        channel: String,
+        respond_to: Sender<Result<ChannelRecipients<C>, DatabaseError>>,
+    ) {
+        let recipients = self.get_channel_recipients(channel);
+        respond_to
+            .send(recipients)
+            .expect("Handler receiver should not be dropped");
+    }
+
     pub fn handle_get_all_channels(&self, respond_to: Sender<Vec<String>>) {
         let channels = self.get_channels();
         respond_to
@@ -38,8 +53,8 @@ impl<C: Connection> Database<C> {
 }
 
 impl<C: Connection> Database<C> {
-    fn add_client_to_channel(&mut self, channel: String, nickname: String) {
-        match self.channels.get_mut(&channel) {
+    pub(super) fn add_client_to_channel(&mut self, channel: String, nickname: String) {
+        match self.channels.get_mut(&NameKey::from(&channel)) {
             Some(channel) => {
                 debug_print!("Adding {} to channel {}", nickname, channel.name());
                 channel.add_member(nickname)
@@ -52,28 +67,64 @@ impl<C: Connection> Database<C> {
     }
 
     fn remove_client_from_channel(&mut self, channel_name: String, nickname: String) {
-        let channel = some_or_return!(self.channels.get_mut(&channel_name.to_string()));
+        let channel = some_or_return!(self.channels.get_mut(&NameKey::from(&channel_name)));
 
         debug_print!("Removing {} from channel {}", nickname, channel_name);
         channel.remove_client(&nickname);
-        if channel.get_clients().is_empty() {
-            self.channels.remove(&channel_name);
+        if channel.is_abandoned() {
+            self.channels.remove(&NameKey::from(&channel_name));
         }
     }
     fn get_channel_clients(&self, channel: String) -> Result<Vec<String>, DatabaseError> {
         let channel = some_or_return!(
-            self.channels.get(&channel),
+            self.channels.get(&NameKey::from(&channel)),
             Err(DatabaseError::NoSuchChannel)
         );
         Ok(channel.get_clients())
     }
 
+    /// Gathers the queues of local members and the servers leading to remote ones,
+    /// so a message reaches a channel without a request per member.
+    pub(super) fn get_channel_recipients(
+        &self,
+        channel: String,
+    ) -> Result<ChannelRecipients<C>, DatabaseError> {
+        let channel = some_or_return!(
+            self.channels.get(&NameKey::from(&channel)),
+            Err(DatabaseError::NoSuchChannel)
+        );
+
+        let mut recipients = ChannelRecipients::default();
+        for member in channel.members() {
+            let key = NameKey::from(member.nickname());
+
+            if let Some(client) = self.local_clients.get(&key) {
+                let queue = match client.queue() {
+                    Some(queue) => queue.clone(),
+                    None => continue,
+                };
+                if client.capabilities().contains(&Capability::ServerTime) {
+                    recipients.tagged.push(queue);
+                } else {
+                    recipients.untagged.push(queue);
+                }
+            } else if let Some(client) = self.external_clients.get(&key) {
+                let server = client.immediate();
+                if !recipients.servers.contains(&server) {
+                    recipients.servers.push(server);
+                }
+            }
+        }
+
+        Ok(recipients)
+    }
+
     fn get_channels(&self) -> Vec<String> {
-        self.channels.keys().cloned().collect()
+        self.channels.values().map(Channel::name).collect()
     }
 
     fn add_channel_invitation(&mut self, channel: String, client: String) {
-        let channel = some_or_return!(self.channels.get_mut(&channel));
+        let channel = some_or_return!(self.channels.get_mut(&NameKey::from(&channel)));
         channel.add_client_invite(client);
     }
 }
@@ -82,6 +133,6 @@ impl<C: Connection> Database<C> {
     fn create_channel(&mut self, channel: String, nickname: String) {
         let channel = Channel::new(channel, nickname);
         let name = channel.name();
-        self.channels.insert(name, channel);
+        self.channels.insert(NameKey::from(name), channel);
     }
 }
@@ -1,11 +1,16 @@
+use std::net::IpAddr;
 use std::sync::mpsc::Sender;
 
 use crate::{
     macros::ok_or_return,
     server::{
         connection::Connection,
+        connection_handler::FailedAttempts,
         consts::user_flag::UserFlag,
-        data_structures::{ClientInfo, ExternalClient, LocalClient},
+        data_structures::{
+            Capability, ClientInfo, ExternalClient, LocalClient, NameKey, OperatorBlock,
+            OutputQueue,
+        },
         database::{database_error::DatabaseError, Database},
     },
 };
@@ -13,17 +18,51 @@ use crate::{
 use crate::macros::{debug_print, some_or_return};
 
 impl<C: Connection> Database<C> {
-    pub fn handle_add_local_client(&mut self, client: LocalClient<C>) {
-        self.add_local_client(client);
+    /// Returns response to AddLocalClient request.
+    pub fn handle_add_local_client(&mut self, client: LocalClient<C>, respond_to: Sender<bool>) {
+        let added = self.add_local_client(client);
+        respond_to
+            .send(added)
+            .expect("Handler receiver should not be dropped");
     }
 
     pub fn handle_add_external_client(&mut self, client: ExternalClient) {
         self.add_external_client(client);
     }
 
-    /// Sets client as server operator.
-    pub fn handle_set_server_operator(&mut self, nickname: String) {
-        self.set_server_operator(nickname);
+    /// Sets client as server operator, granting it the privileges of the operator account.
+    pub fn handle_set_server_operator(&mut self, nickname: String, operator: String) {
+        self.set_server_operator(nickname, operator);
+    }
+
+    /// Replaces every configured operator account.
+    pub fn handle_set_operators(&mut self, operators: Vec<OperatorBlock>) {
+        debug_print!("Setting {} operator accounts", operators.len());
+
+        self.operators = operators
+            .into_iter()
+            .map(|operator| (operator.name.clone(), operator))
+            .collect();
+    }
+
+    /// Returns response to OperAttemptsExceeded request.
+    pub fn handle_oper_attempts_exceeded(&self, address: IpAddr, respond_to: Sender<bool>) {
+        let exceeded = self
+            .oper_attempts
+            .get(&address)
+            .is_some_and(FailedAttempts::exceeded);
+        respond_to
+            .send(exceeded)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    /// Counts a failed OPER attempt against address, forgetting addresses whose failures expired.
+    pub fn handle_record_failed_oper(&mut self, address: IpAddr) {
+        debug_print!("Failed OPER attempt from {address}");
+
+        self.oper_attempts
+            .retain(|_, attempts| !attempts.is_expired());
+        self.oper_attempts.entry(address).or_default().failed();
     }
 
     pub fn handle_get_immediate_server(
@@ -87,6 +126,18 @@ impl<C: Connection> Database<C> {
             .send(stream)
             .expect("Handler receiver should not be dropped");
     }
+
+    pub fn handle_get_local_queue(
+        &self,
+        nickname: String,
+        respond_to: Sender<Result<OutputQueue<C>, DatabaseError>>,
+    ) {
+        let queue = self.get_local_queue(nickname);
+        respond_to
+            .send(queue)
+            .expect("Handler receiver should not be dropped");
+    }
+
     pub fn handle_disconnect_client(&mut self, nickname: String) {
         self.disconnect_client(nickname);
     }
@@ -102,6 +153,25 @@ impl<C: Connection> Database<C> {
             .expect("Handler receiver should not be dropped");
     }
 
+    pub fn handle_get_client_capabilities(
+        &self,
+        nickname: String,
+        respond_to: Sender<Vec<Capability>>,
+    ) {
+        let capabilities = self.get_client_capabilities(nickname);
+        respond_to
+            .send(capabilities)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_client_capabilities(
+        &mut self,
+        nickname: String,
+        capabilities: Vec<Capability>,
+    ) {
+        self.set_client_capabilities(nickname, capabilities);
+    }
+
     pub fn handle_set_user_flag(&mut self, user: String, flag: UserFlag) {
         self.set_user_flag(user, flag);
     }
@@ -112,14 +182,30 @@ impl<C: Connection> Database<C> {
 }
 
 impl<C: Connection> Database<C> {
+    /// Removes client from the database, along with all its channel memberships.
     fn disconnect_client(&mut self, nickname: String) {
-        if let Some(client) = self.local_clients.get_mut(&nickname) {
-            client.disconnect();
-        }
-        if let Some(client) = self.external_clients.get_mut(&nickname) {
+        debug_print!("Disconnecting client {nickname}");
+
+        if let Some(mut client) = self.local_clients.remove(&NameKey::from(&nickname)) {
             client.disconnect();
         }
+        self.external_clients.remove(&NameKey::from(&nickname));
+
+        self.remove_client_from_all_channels(&nickname);
     }
+    fn get_client_capabilities(&self, nickname: String) -> Vec<Capability> {
+        let client = some_or_return!(self.local_clients.get(&NameKey::from(&nickname)), vec![]);
+
+        client.capabilities().to_vec()
+    }
+
+    fn set_client_capabilities(&mut self, nickname: String, capabilities: Vec<Capability>) {
+        let client = some_or_return!(self.local_clients.get_mut(&NameKey::from(&nickname)));
+        debug_print!("Setting {nickname}'s capabilities to {capabilities:?}");
+
+        client.set_capabilities(capabilities);
+    }
+
     fn set_away_message(&mut self, nickname: String, message: Option<String>) {
         let client = ok_or_return!(self.get_client_info(&nickname));
         debug_print!("Setting {nickname}'s away message to {message:?}");
@@ -131,20 +217,39 @@ impl<C: Connection> Database<C> {
         debug_print!("Adding external client {:?}", client.get_info());
 
         let nickname = client.nickname();
-        self.external_clients.insert(nickname, client);
+        self.external_clients
+            .insert(NameKey::from(nickname), client);
     }
 
-    fn set_server_operator(&mut self, nickname: String) {
+    fn set_server_operator(&mut self, nickname: String, operator: String) {
         let info = ok_or_return!(self.get_client_info(&nickname));
-        debug_print!("Setting {} as server operator", nickname);
+        debug_print!("Setting {} as server operator {}", nickname, operator);
+
+        info.add_flag(UserFlag::Operator);
 
-        info.add_flag(UserFlag::Operator)
+        let privileges = some_or_return!(self.operators.get(&operator))
+            .privileges
+            .clone();
+        if let Some(client) = self.local_clients.get_mut(&NameKey::from(&nickname)) {
+            client.set_privileges(privileges);
+        }
     }
-    fn add_local_client(&mut self, client: LocalClient<C>) {
+    /// Checks and reserves the nickname in the same request, so two clients never register the same one.
+    fn add_local_client(&mut self, client: LocalClient<C>) -> bool {
+        let nickname = client.nickname();
+        if self.contains_client(nickname.clone()) {
+            debug_print!("Refusing local client, nickname {nickname} is taken");
+            return false;
+        }
+
         debug_print!("Adding local client {:?}", client.get_info());
 
-        let nickname = client.nickname();
-        self.local_clients.insert(nickname, client);
+        if let Some(queue) = client.queue() {
+            queue.set_limit(self.limits.sendq);
+        }
+
+        self.local_clients.insert(NameKey::from(nickname), client);
+        true
     }
 
     fn update_nickname(&mut self, old_nickname: String, new_nickname: String) {
@@ -164,10 +269,9 @@ impl<C: Connection> Database<C> {
 
         let mut channels = vec![];
 
-        for (channel_name, channel) in self.channels.iter() {
-            let clients = channel.get_clients();
-            if clients.contains(&nickname.to_string()) {
-                channels.push(channel_name.clone());
+        for channel in self.channels.values() {
+            if channel.is_member(&nickname) {
+                channels.push(channel.name());
             }
         }
 
@@ -184,22 +288,26 @@ impl<C: Connection> Database<C> {
     }
 
     fn get_local_stream(&self, nickname: String) -> Result<C, DatabaseError> {
+        self.get_local_queue(nickname)?
+            .try_clone_stream()
+            .map_err(|_| DatabaseError::CannotCloneStream)
+    }
+
+    fn get_local_queue(&self, nickname: String) -> Result<OutputQueue<C>, DatabaseError> {
         let client = some_or_return!(
-            self.local_clients.get(&nickname),
+            self.local_clients.get(&NameKey::from(&nickname)),
             Err(DatabaseError::NoSuchClient)
         );
 
-        match &client.stream() {
-            Some(stream) => stream
-                .try_clone()
-                .map_err(|_| DatabaseError::CannotCloneStream),
+        match client.queue() {
+            Some(queue) => Ok(queue.clone()),
             None => Err(DatabaseError::ClientIsOffline),
         }
     }
 
     fn get_immediate_server(&self, nickname: String) -> Result<String, DatabaseError> {
         let client = some_or_return!(
-            self.external_clients.get(&nickname),
+            self.external_clients.get(&NameKey::from(&nickname)),
             Err(DatabaseError::NoSuchClient)
         );
 
@@ -207,7 +315,7 @@ impl<C: Connection> Database<C> {
     }
 
     /// Returns array with ClientInfo for connected clients.
-    fn get_all_clients(&self) -> Vec<ClientInfo> {
+    pub(in crate::server::database) fn get_all_clients(&self) -> Vec<ClientInfo> {
         let mut clients: Vec<ClientInfo> = self
             .local_clients
             .values()
@@ -232,19 +340,33 @@ impl<C: Connection> Database<C> {
 
     fn unset_user_flag(&mut self, user: String, flag: UserFlag) {
         let info = ok_or_return!(self.get_client_info(&user));
-        info.remove_flag(flag);
+        info.remove_flag(flag.clone());
+
+        if flag == UserFlag::Operator {
+            if let Some(client) = self.local_clients.get_mut(&NameKey::from(&user)) {
+                client.set_privileges(Vec::new());
+            }
+        }
     }
 }
 
 impl<C: Connection> Database<C> {
     fn update_nickname_keys(&mut self, old_nickname: &String, new_nickname: &String) {
-        if let Some(client) = self.local_clients.remove(old_nickname) {
-            self.local_clients.insert(new_nickname.to_string(), client);
+        if let Some(client) = self.local_clients.remove(&NameKey::from(&old_nickname)) {
+            self.local_clients
+                .insert(NameKey::from(new_nickname), client);
         }
-        if let Some(client) = self.external_clients.remove(old_nickname) {
+        if let Some(client) = self.external_clients.remove(&NameKey::from(&old_nickname)) {
             self.external_clients
-                .insert(new_nickname.to_string(), client);
+                .insert(NameKey::from(new_nickname), client);
+        }
+    }
+
+    fn remove_client_from_all_channels(&mut self, nickname: &str) {
+        for channel in self.channels.values_mut() {
+            channel.remove_client(nickname);
         }
+        self.channels.retain(|_, channel| !channel.is_abandoned());
     }
 
     fn update_nickname_in_channels(&mut self, old_nickname: String, new_nickname: String) {
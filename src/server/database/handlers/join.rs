@@ -0,0 +1,108 @@
+use std::sync::mpsc::Sender;
+
+use crate::server::{
+    connection::Connection,
+    consts::channel_flag::ChannelFlag,
+    data_structures::{irc_eq, NameKey},
+    database::{Database, JoinPermit, JoinRefusal, JoinVerdict},
+};
+
+use crate::macros::debug_print;
+
+impl<C: Connection> Database<C> {
+    pub fn handle_join_channel(
+        &mut self,
+        nickname: String,
+        channel: String,
+        key: Option<String>,
+        respond_to: Sender<JoinVerdict<C>>,
+    ) {
+        let verdict = self.join_channel(nickname, channel, key);
+        respond_to
+            .send(verdict)
+            .expect("Handler receiver should not be dropped");
+    }
+}
+
+impl<C: Connection> Database<C> {
+    /// Adds client to channel if it passes every check, so no other request sees it half joined.
+    fn join_channel(
+        &mut self,
+        nickname: String,
+        channel: String,
+        key: Option<String>,
+    ) -> JoinVerdict<C> {
+        let operator = self.check_join(&nickname, &channel, key)?;
+        let recipients = self
+            .get_channel_recipients(channel.clone())
+            .unwrap_or_default();
+
+        debug_print!("Joining {nickname} to {channel}");
+        self.add_client_to_channel(channel.clone(), nickname.clone());
+        if operator {
+            self.handle_add_channop(channel, nickname);
+        }
+
+        Ok(JoinPermit {
+            recipients,
+            operator,
+        })
+    }
+
+    /// Runs every check a client goes through before joining a channel, in the order they are reported.
+    /// Returns whether the client should be made operator on joining.
+    fn check_join(
+        &mut self,
+        nickname: &str,
+        channel: &str,
+        key: Option<String>,
+    ) -> Result<bool, JoinRefusal> {
+        let joined = self
+            .channels
+            .values()
+            .filter(|channel| channel.is_member(nickname))
+            .count();
+        if joined >= self.limits.max_channels {
+            return Err(JoinRefusal::TooManyChannels);
+        }
+
+        let client = self.get_client_info(nickname).ok().cloned();
+        let account = client.as_ref().and_then(|client| client.account.clone());
+
+        let channel = match self.channels.get(&NameKey::from(channel)) {
+            Some(channel) => channel,
+            None => return Ok(true),
+        };
+
+        if channel.is_member(nickname) {
+            return Err(JoinRefusal::AlreadyOnChannel);
+        }
+        if channel.get_key() != key {
+            return Err(JoinRefusal::BadKey);
+        }
+        if channel
+            .get_limit()
+            .is_some_and(|limit| channel.get_clients().len() >= limit)
+        {
+            return Err(JoinRefusal::ChannelIsFull);
+        }
+        if let Some(client) = client {
+            if channel
+                .get_banmasks()
+                .iter()
+                .any(|mask| client.matches_banmask(mask))
+            {
+                return Err(JoinRefusal::Banned);
+            }
+        }
+        if channel.has_mode(ChannelFlag::InviteOnly) && !channel.has_invite(nickname) {
+            return Err(JoinRefusal::InviteOnly);
+        }
+
+        let founder = match (channel.founder(), account) {
+            (Some(founder), Some(account)) => irc_eq(&founder, &account),
+            _ => false,
+        };
+        Ok(founder && !channel.is_operator(nickname))
+    }
+}
@@ -1,24 +1,35 @@
-use crate::server::{connection::Connection, data_structures::ClientInfo};
+use crate::server::{
+    connection::Connection,
+    data_structures::{ClientInfo, NameKey},
+};
 
 use super::{database_error::DatabaseError, Database};
 
+/// Contains requests related to nickname accounts and logging into them.
+mod accounts;
 /// Contains requests that have a boolean response.
 mod booleans;
 /// Contains requests related to a channel's configuration.
 mod channel_configuration;
+/// Contains requests related to registering channels and saving them.
+mod channel_registration;
 /// Contains requests related to channels.
 mod channels;
 /// Contains requests related to clients.
 mod clients;
+/// Contains the request that checks and applies a client joining a channel.
+mod join;
 /// Contains requests related to servers.
 mod servers;
+/// Contains requests that save the whole state and restore it.
+mod snapshot;
 
 impl<C: Connection> Database<C> {
     pub fn get_client_info(&mut self, nickname: &str) -> Result<&mut ClientInfo, DatabaseError> {
-        if let Some(client) = self.local_clients.get_mut(nickname) {
+        if let Some(client) = self.local_clients.get_mut(&NameKey::from(&nickname)) {
             return Ok(client.info_mut());
         }
-        if let Some(client) = self.external_clients.get_mut(nickname) {
+        if let Some(client) = self.external_clients.get_mut(&NameKey::from(&nickname)) {
             return Ok(client.info_mut());
         }
         Err(DatabaseError::NoSuchClient)
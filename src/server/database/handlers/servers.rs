@@ -1,8 +1,11 @@
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, SystemTime};
 
 use crate::server::{
+    config::{BanBlock, Limits, ServerConfig},
     connection::Connection,
-    data_structures::{ImmediateServer, ServerInfo},
+    data_structures::{ImmediateServer, OutputQueue, Refusal, Refusals, ServerInfo},
     database::{database_error::DatabaseError, Database},
 };
 
@@ -42,6 +45,17 @@ impl<C: Connection> Database<C> {
             .expect("Handler receiver should not be dropped");
     }
 
+    pub fn handle_get_server_queue(
+        &self,
+        server: String,
+        respond_to: Sender<Result<OutputQueue<C>, DatabaseError>>,
+    ) {
+        let queue = self.get_server_queue(&server);
+        respond_to
+            .send(queue)
+            .expect("Handler receiver should not be dropped");
+    }
+
     pub fn handle_get_all_servers(&self, respond_to: Sender<Vec<String>>) {
         let servers = self.get_all_servers();
         respond_to
@@ -63,6 +77,120 @@ impl<C: Connection> Database<C> {
             .send(server_info)
             .expect("Handler receiver should not be dropped");
     }
+
+    pub fn handle_get_keepalive_settings(&self, respond_to: Sender<(Duration, Duration)>) {
+        let settings = (self.limits.ping_interval, self.limits.ping_timeout);
+        respond_to
+            .send(settings)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_keepalive_settings(&mut self, interval: Duration, timeout: Duration) {
+        debug_print!("Setting ping interval to {interval:?} and ping timeout to {timeout:?}");
+        self.limits.ping_interval = interval;
+        self.limits.ping_timeout = timeout;
+    }
+
+    pub fn handle_get_limits(&self, respond_to: Sender<Limits>) {
+        respond_to
+            .send(self.limits.clone())
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_limits(&mut self, limits: Limits) {
+        debug_print!("Setting limits to {limits:?}");
+        self.limits = limits;
+        self.update_sendq_limits();
+    }
+
+    pub fn handle_get_creation_time(&self, respond_to: Sender<SystemTime>) {
+        respond_to
+            .send(self.created)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_get_motd_file(&self, respond_to: Sender<Option<PathBuf>>) {
+        let motd_file = self.motd_file.clone();
+        respond_to
+            .send(motd_file)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_motd_file(&mut self, path: PathBuf) {
+        debug_print!("Setting MOTD file to {path:?}");
+        self.motd_file = Some(path);
+    }
+
+    pub fn handle_get_server_password(&self, respond_to: Sender<Option<String>>) {
+        let password = self.password.clone();
+        respond_to
+            .send(password)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_server_password(&mut self, password: Option<String>) {
+        self.password = password;
+    }
+
+    pub fn handle_get_link_password(&self, servername: String, respond_to: Sender<Option<String>>) {
+        let password = self.link_passwords.get(&servername).cloned();
+        respond_to
+            .send(password)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_set_link_password(&mut self, servername: String, password: String) {
+        debug_print!("Setting link password for {servername}");
+        self.link_passwords.insert(servername, password);
+    }
+
+    /// Applies every setting in config that may change while running, replacing previous ones.
+    pub fn handle_configure(&mut self, config: ServerConfig) {
+        debug_print!("Applying configuration for {}", config.servername);
+
+        self.motd_file = config.motd_file.clone();
+        self.password = config.password.clone();
+        self.handle_set_operators(config.operators.clone());
+        self.link_passwords = config
+            .links
+            .iter()
+            .map(|link| (link.servername.clone(), link.password.clone()))
+            .collect();
+        self.bans = config.bans.clone();
+        self.limits = config.limits.clone();
+        self.update_sendq_limits();
+        if let Some(path) = config.channels_file.clone() {
+            self.set_channel_store(path);
+        }
+        if let Some(path) = config.accounts_file.clone() {
+            self.set_account_store(path);
+        }
+
+        self.config = Some(config);
+    }
+
+    pub fn handle_get_config(&self, respond_to: Sender<Option<ServerConfig>>) {
+        respond_to
+            .send(self.config.clone())
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_get_server_bans(&self, respond_to: Sender<Vec<BanBlock>>) {
+        respond_to
+            .send(self.bans.clone())
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_record_refusal(&mut self, refusal: Refusal) {
+        debug_print!("Refused connection: {}", refusal.reason());
+        self.refusals.record(refusal);
+    }
+
+    pub fn handle_get_refusals(&self, respond_to: Sender<Refusals>) {
+        respond_to
+            .send(self.refusals)
+            .expect("Handler receiver should not be dropped");
+    }
 }
 
 impl<C: Connection> Database<C> {
@@ -84,6 +212,30 @@ impl<C: Connection> Database<C> {
             .map_err(|_| DatabaseError::CannotCloneStream)
     }
 
+    fn get_server_queue(&self, server: &str) -> Result<OutputQueue<C>, DatabaseError> {
+        let server = some_or_return!(
+            self.immediate_servers.get(server),
+            Err(DatabaseError::NoSuchServer)
+        );
+
+        Ok(server.queue().clone())
+    }
+
+    /// Applies the configured sendq limit to every connection's output queue.
+    fn update_sendq_limits(&self) {
+        let sendq = self.limits.sendq;
+
+        let local_queues = self
+            .local_clients
+            .values()
+            .filter_map(|client| client.queue());
+        let server_queues = self.immediate_servers.values().map(|server| server.queue());
+
+        for queue in local_queues.chain(server_queues) {
+            queue.set_limit(sendq);
+        }
+    }
+
     fn get_all_servers(&self) -> Vec<String> {
         self.immediate_servers
             .keys()
@@ -94,6 +246,7 @@ impl<C: Connection> Database<C> {
     fn add_immediate_server(&mut self, server: ImmediateServer<C>) {
         let servername = server.info().servername;
         debug_print!("Adding immediate server {servername}");
+        server.queue().set_limit(self.limits.sendq);
         self.immediate_servers.insert(servername, server);
     }
     fn add_distant_server(&mut self, server: ServerInfo) {
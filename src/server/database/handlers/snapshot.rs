@@ -0,0 +1,95 @@
+use std::sync::mpsc::Sender;
+
+use crate::macros::debug_print;
+use crate::server::{
+    connection::Connection,
+    data_structures::{irc_lowercase, unix_time, Channel, ImmediateServer, NameKey},
+    database::Database,
+    snapshot::{ChannelSnapshot, Snapshot},
+};
+
+impl<C: Connection> Database<C> {
+    pub fn handle_get_snapshot(&self, respond_to: Sender<Snapshot>) {
+        let snapshot = self.snapshot();
+        respond_to
+            .send(snapshot)
+            .expect("Handler receiver should not be dropped");
+    }
+
+    pub fn handle_restore_snapshot(&mut self, snapshot: Snapshot) {
+        debug_print!(
+            "Restoring snapshot taken by {} at {}",
+            snapshot.servername,
+            snapshot.created
+        );
+
+        for channel in snapshot.channels {
+            self.restore_channel_snapshot(channel);
+        }
+    }
+}
+
+impl<C: Connection> Database<C> {
+    fn snapshot(&self) -> Snapshot {
+        let mut servers: Vec<_> = self
+            .immediate_servers
+            .values()
+            .map(ImmediateServer::info)
+            .chain(self.distant_servers.values().cloned())
+            .collect();
+        servers.sort_by(|first, second| first.servername.cmp(&second.servername));
+
+        let mut clients = self.get_all_clients();
+        clients.sort_by_key(|client| irc_lowercase(&client.nickname));
+
+        let mut channels: Vec<ChannelSnapshot> = self
+            .channels
+            .values()
+            .map(|channel| ChannelSnapshot {
+                name: channel.name(),
+                founder: channel.founder(),
+                members: channel.get_clients(),
+                invites: channel.get_invites(),
+                config: channel.get_config(),
+            })
+            .collect();
+        channels.sort_by_key(|channel| irc_lowercase(&channel.name));
+
+        Snapshot {
+            servername: self.info.servername.clone(),
+            created: unix_time(),
+            servers,
+            clients,
+            channels,
+        }
+    }
+
+    /// Rebuilds a channel with its configuration and invites. Members rejoin on their own,
+    /// since their connections do not outlive the server. Channels that already have members
+    /// are left as they are, their state being more recent.
+    fn restore_channel_snapshot(&mut self, snapshot: ChannelSnapshot) {
+        let ChannelSnapshot {
+            name,
+            founder,
+            invites,
+            config,
+            ..
+        } = snapshot;
+
+        let key = NameKey::from(&name);
+        if let Some(channel) = self.channels.get(&key) {
+            if !channel.get_clients().is_empty() {
+                return;
+            }
+        }
+        debug_print!("Restoring channel {name}");
+
+        let mut channel = Channel::restore(name.clone(), founder, config);
+        for invite in invites {
+            channel.add_client_invite(invite);
+        }
+        self.channels.insert(key, channel);
+
+        self.channel_changed(&name);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::server::{connection::Connection, data_structures::ChannelRecipients};
+
+/// Outcome of a client joining a channel, checked and applied in a single request.
+pub type JoinVerdict<C> = Result<JoinPermit<C>, JoinRefusal>;
+
+/// What a client that joined should send to the channel.
+pub struct JoinPermit<C: Connection> {
+    /// local members and servers that were on the channel before the client joined
+    pub recipients: ChannelRecipients<C>,
+    /// the client was made channel operator on joining
+    pub operator: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Reasons a client may not join a channel.
+pub enum JoinRefusal {
+    TooManyChannels,
+    AlreadyOnChannel,
+    BadKey,
+    ChannelIsFull,
+    Banned,
+    InviteOnly,
+}
@@ -7,14 +7,19 @@ mod database_handle;
 mod database_message;
 /// This module contains specific functions to handle each request.
 mod handlers;
+/// This module contains the outcome of a client joining a channel.
+mod join_verdict;
 
 /// Unit tests for the Database's basic functionalities.
 #[cfg(test)]
 mod tests;
 
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::mpsc::{self, Receiver};
 use std::thread::{self, JoinHandle};
+use std::time::SystemTime;
 
 use crate::server::data_structures::*;
 
@@ -22,17 +27,35 @@ use database_message::DatabaseMessage::*;
 
 pub use database_handle::DatabaseHandle;
 use database_message::DatabaseMessage;
+pub use join_verdict::{JoinPermit, JoinRefusal, JoinVerdict};
 
+use super::account_store::AccountStore;
+use super::channel_store::ChannelStore;
+use super::config::{BanBlock, Limits, ServerConfig};
 use super::connection::Connection;
+use super::connection_handler::FailedAttempts;
 /// Represents a Database that stores all information a server should have.
 pub struct Database<C: Connection> {
     receiver: Receiver<DatabaseMessage<C>>,
     info: ServerInfo,
-    credentials: HashMap<String, String>,
+    operators: HashMap<String, OperatorBlock>,
+    limits: Limits,
+    created: SystemTime,
+    motd_file: Option<PathBuf>,
+    password: Option<String>,
+    link_passwords: HashMap<String, String>,
+    bans: Vec<BanBlock>,
+    config: Option<ServerConfig>,
+    refusals: Refusals,
+    /// failed OPER attempts by source address, shared by all its connections
+    oper_attempts: HashMap<IpAddr, FailedAttempts>,
+    channel_store: Option<ChannelStore>,
+    accounts: HashMap<NameKey, Account>,
+    account_store: Option<AccountStore>,
 
-    local_clients: HashMap<String, LocalClient<C>>,
-    external_clients: HashMap<String, ExternalClient>,
-    channels: HashMap<String, Channel>,
+    local_clients: HashMap<NameKey, LocalClient<C>>,
+    external_clients: HashMap<NameKey, ExternalClient>,
+    channels: HashMap<NameKey, Channel>,
 
     immediate_servers: HashMap<String, ImmediateServer<C>>,
     distant_servers: HashMap<String, ServerInfo>,
@@ -41,22 +64,28 @@ pub struct Database<C: Connection> {
 impl<C: Connection> Database<C> {
     /// Creates [`Database`] for a specific server and stores receiver end from which to listen for requests.
     fn new(receiver: Receiver<DatabaseMessage<C>>, servername: String, serverinfo: String) -> Self {
-        let mut database = Self {
+        Self {
             receiver,
             info: ServerInfo::new(servername, serverinfo, 0),
-            credentials: Default::default(),
+            operators: Default::default(),
+            limits: Default::default(),
+            created: SystemTime::now(),
+            motd_file: None,
+            password: None,
+            link_passwords: Default::default(),
+            bans: Default::default(),
+            config: None,
+            refusals: Default::default(),
+            oper_attempts: Default::default(),
+            channel_store: None,
+            accounts: Default::default(),
+            account_store: None,
             local_clients: Default::default(),
             external_clients: Default::default(),
             channels: Default::default(),
             immediate_servers: Default::default(),
             distant_servers: Default::default(),
-        };
-
-        database
-            .credentials
-            .insert("admin".to_string(), "admin".to_string());
-
-        database
+        }
     }
 
     /// Returns new [`DatabaseHandle`] and spawns thread that is listening for requests.
@@ -80,7 +109,9 @@ impl<C: Connection> Database<C> {
     fn handle_message(&mut self, request: DatabaseMessage<C>) {
         match request {
             DisconnectClient { nickname } => self.handle_disconnect_client(nickname),
-            SetServerOperator { nickname } => self.handle_set_server_operator(nickname),
+            SetServerOperator { nickname, operator } => {
+                self.handle_set_server_operator(nickname, operator)
+            }
             IsServerOperator {
                 nickname,
                 respond_to: response,
@@ -96,6 +127,12 @@ impl<C: Connection> Database<C> {
             AddClientToChannel { nickname, channel } => {
                 self.handle_add_client_to_channel(nickname, channel)
             }
+            JoinChannel {
+                nickname,
+                channel,
+                key,
+                respond_to,
+            } => self.handle_join_channel(nickname, channel, key, respond_to),
             RemoveClientFromChannel { nickname, channel } => {
                 self.handle_remove_client_from_channel(nickname, channel)
             }
@@ -112,6 +149,10 @@ impl<C: Connection> Database<C> {
                 channel,
                 respond_to,
             } => self.handle_get_channel_clients(channel, respond_to),
+            GetChannelRecipients {
+                channel,
+                respond_to,
+            } => self.handle_get_channel_recipients(channel, respond_to),
             GetAllClients { respond_to } => self.handle_get_all_clients(respond_to),
             GetAllChannels { respond_to } => self.handle_get_all_channels(respond_to),
             UpdateNickname {
@@ -121,8 +162,20 @@ impl<C: Connection> Database<C> {
             AreCredentialsValid {
                 username,
                 password,
+                nickname,
                 respond_to,
-            } => self.handle_are_credentials_valid(username, password, respond_to),
+            } => self.handle_are_credentials_valid(username, password, nickname, respond_to),
+            SetOperators { operators } => self.handle_set_operators(operators),
+            OperAttemptsExceeded {
+                address,
+                respond_to,
+            } => self.handle_oper_attempts_exceeded(address, respond_to),
+            RecordFailedOper { address } => self.handle_record_failed_oper(address),
+            HasOperatorPrivilege {
+                nickname,
+                privilege,
+                respond_to,
+            } => self.handle_has_operator_privilege(nickname, privilege, respond_to),
             SetAwayMessage { message, nickname } => self.handle_set_away_message(message, nickname),
             GetAwayMessage {
                 nickname,
@@ -189,13 +242,22 @@ impl<C: Connection> Database<C> {
                 channel,
                 respond_to,
             } => self.handle_get_channel_config(channel, respond_to),
+            GetServerQueue { server, respond_to } => {
+                self.handle_get_server_queue(server, respond_to)
+            }
             GetServerStream { server, respond_to } => {
                 self.handle_get_server_stream(server, respond_to)
             }
             GetAllServers { respond_to } => self.handle_get_all_servers(respond_to),
             AddDistantServer { server } => self.handle_add_distant_server(server),
             AddImmediateServer { server } => self.handle_add_immediate_server(server),
-            AddLocalClient { client } => self.handle_add_local_client(client),
+            AddLocalClient { client, respond_to } => {
+                self.handle_add_local_client(client, respond_to)
+            }
+            GetLocalQueue {
+                nickname,
+                respond_to,
+            } => self.handle_get_local_queue(nickname, respond_to),
             GetLocalStream {
                 nickname,
                 respond_to,
@@ -208,6 +270,14 @@ impl<C: Connection> Database<C> {
                 self.handle_get_immediate_server(client, respond_to)
             }
             GetClientInfo { client, respond_to } => self.handle_get_client_info(client, respond_to),
+            GetClientCapabilities {
+                nickname,
+                respond_to,
+            } => self.handle_get_client_capabilities(nickname, respond_to),
+            SetClientCapabilities {
+                nickname,
+                capabilities,
+            } => self.handle_set_client_capabilities(nickname, capabilities),
             IsImmediateServer { server, respond_to } => {
                 self.handle_is_immediate_server(server, respond_to)
             }
@@ -223,6 +293,51 @@ impl<C: Connection> Database<C> {
                 respond_to,
             } => self.handle_channel_has_client_invite(channel, client, respond_to),
             GetServerInfo { server, respond_to } => self.handle_get_server_info(server, respond_to),
+            GetKeepaliveSettings { respond_to } => self.handle_get_keepalive_settings(respond_to),
+            GetLimits { respond_to } => self.handle_get_limits(respond_to),
+            SetLimits { limits } => self.handle_set_limits(limits),
+            SetKeepaliveSettings { interval, timeout } => {
+                self.handle_set_keepalive_settings(interval, timeout)
+            }
+            GetCreationTime { respond_to } => self.handle_get_creation_time(respond_to),
+            GetMotdFile { respond_to } => self.handle_get_motd_file(respond_to),
+            Configure { config } => self.handle_configure(config),
+            GetConfig { respond_to } => self.handle_get_config(respond_to),
+            GetServerBans { respond_to } => self.handle_get_server_bans(respond_to),
+            RecordRefusal { refusal } => self.handle_record_refusal(refusal),
+            GetRefusals { respond_to } => self.handle_get_refusals(respond_to),
+            SetChannelStore { path } => self.handle_set_channel_store(path),
+            SetAccountStore { path } => self.handle_set_account_store(path),
+            HasAccountStore { respond_to } => self.handle_has_account_store(respond_to),
+            AddAccount {
+                account,
+                respond_to,
+            } => self.handle_add_account(account, respond_to),
+            GetAccount { name, respond_to } => self.handle_get_account(name, respond_to),
+            SetClientAccount { nickname, account } => {
+                self.handle_set_client_account(nickname, account)
+            }
+            HasChannelStore { respond_to } => self.handle_has_channel_store(respond_to),
+            GetSnapshot { respond_to } => self.handle_get_snapshot(respond_to),
+            RestoreSnapshot { snapshot } => self.handle_restore_snapshot(snapshot),
+            GetChannelFounder {
+                channel,
+                respond_to,
+            } => self.handle_get_channel_founder(channel, respond_to),
+            SetChannelFounder { channel, founder } => {
+                self.handle_set_channel_founder(channel, founder)
+            }
+            SetMotdFile { path } => self.handle_set_motd_file(path),
+            GetServerPassword { respond_to } => self.handle_get_server_password(respond_to),
+            SetServerPassword { password } => self.handle_set_server_password(password),
+            GetLinkPassword {
+                servername,
+                respond_to,
+            } => self.handle_get_link_password(servername, respond_to),
+            SetLinkPassword {
+                servername,
+                password,
+            } => self.handle_set_link_password(servername, password),
         }
     }
 }
@@ -1,11 +1,13 @@
 use std::collections::HashMap;
+use std::{env, fs, process};
 
 use crate::server::{
-    connection::Connection,
     consts::channel_flag::ChannelFlag,
-    data_structures::{ChannelConfiguration, ClientInfo},
+    data_structures::{Account, Capability, ChannelConfiguration, ClientInfo, Refusal},
+    database::JoinRefusal,
     testing::{
-        dummy_client, dummy_database, dummy_distant_server, dummy_external_client, dummy_server,
+        dummy_client, dummy_database, dummy_distant_server, dummy_external_client, dummy_operator,
+        dummy_server, DUMMY_SIGNON,
     },
 };
 //holaaaa
@@ -22,6 +24,18 @@ fn can_add_client() {
     assert!(database.contains_client("nickname2"));
 }
 
+#[test]
+fn cannot_add_client_with_taken_nickname() {
+    let database = dummy_database();
+
+    assert!(database.add_local_client(dummy_client("nickname")));
+    assert!(!database.add_local_client(dummy_client("NICKNAME")));
+
+    database.add_external_client(dummy_external_client("external", "servername2"));
+    assert!(!database.add_local_client(dummy_client("external")));
+    assert!(!database.is_local_client("external"));
+}
+
 #[test]
 fn can_set_server_operator() {
     let database = dummy_database();
@@ -29,7 +43,7 @@ fn can_set_server_operator() {
     database.add_local_client(dummy_client("nickname"));
 
     assert!(!database.is_server_operator("nickname"));
-    database.set_server_operator("nickname");
+    database.set_server_operator("nickname", "admin");
     assert!(database.is_server_operator("nickname"));
 }
 
@@ -38,7 +52,7 @@ fn can_get_client_stream() {
     let database = dummy_database();
 
     let client = dummy_client("nickname");
-    let stream_ref_expected = client.stream().as_ref().unwrap().try_clone().unwrap();
+    let stream_ref_expected = client.queue().unwrap().try_clone_stream().unwrap();
     database.add_local_client(client);
 
     let stream_ref_actual = database.get_local_stream("nickname").unwrap();
@@ -109,6 +123,80 @@ fn can_get_all_clients_from_channel() {
     assert_eq!(value, expected)
 }
 
+#[test]
+fn clients_are_found_regardless_of_case() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("Nick[name]"));
+    database.add_external_client(dummy_external_client("Other", "servername1"));
+
+    assert!(database.contains_client("nick{name}"));
+    assert!(database.is_local_client("NICK[NAME]"));
+    assert!(database.contains_client("oTHER"));
+    assert_eq!(
+        "Nick[name]",
+        database.get_client_info("nick{NAME}").unwrap().nickname
+    );
+}
+
+#[test]
+fn channels_are_found_regardless_of_case() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname1"));
+    database.add_local_client(dummy_client("nickname2"));
+
+    database.add_client_to_channel("#Channel", "nickname1");
+    database.add_client_to_channel("#chANNEL", "Nickname2");
+
+    assert_eq!(vec!["#Channel".to_string()], database.get_all_channels());
+    assert!(database.is_client_in_channel("#CHANNEL", "NICKNAME1"));
+    assert!(database.is_client_in_channel("#channel", "nickname2"));
+    assert_eq!(
+        vec!["#Channel".to_string()],
+        database.get_channels_for_client("NickName1").unwrap()
+    );
+}
+
+#[test]
+fn channel_recipients_split_local_queues_by_server_time() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname1"));
+    database.add_local_client(dummy_client("nickname2"));
+    database.set_client_capabilities("nickname2", vec![Capability::ServerTime]);
+
+    database.add_client_to_channel("#channel", "nickname1");
+    database.add_client_to_channel("#channel", "nickname2");
+
+    let recipients = database.get_channel_recipients("#channel").unwrap();
+
+    assert_eq!(1, recipients.untagged.len());
+    assert_eq!(1, recipients.tagged.len());
+    assert!(recipients.servers.is_empty());
+}
+
+#[test]
+fn channel_recipients_list_each_immediate_server_once() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_external_client(dummy_external_client("external1", "servername1"));
+    database.add_external_client(dummy_external_client("external2", "servername1"));
+    database.add_external_client(dummy_external_client("external3", "servername2"));
+
+    for client in ["nickname", "external1", "external2", "external3"] {
+        database.add_client_to_channel("#channel", client);
+    }
+
+    let mut servers = database.get_channel_recipients("#channel").unwrap().servers;
+    servers.sort();
+
+    assert_eq!(vec!["servername1", "servername2"], servers);
+}
+
+#[test]
+fn cannot_get_recipients_for_nonexistent_channel() {
+    let database = dummy_database();
+    assert!(database.get_channel_recipients("#channel").is_err())
+}
+
 #[test]
 fn cannot_get_clients_from_nonexistent_channel() {
     let database = dummy_database();
@@ -428,9 +516,32 @@ fn can_set_and_unset_channel_banmask() {
 #[test]
 fn can_ask_if_operator_credentials_are_valid() {
     let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.set_operators(vec![dummy_operator("admin", "secret")]);
 
-    assert!(!database.are_credentials_valid("username", "password"));
-    assert!(database.are_credentials_valid("admin", "admin"));
+    assert!(!database.are_credentials_valid("username", "password", "nickname"));
+    assert!(!database.are_credentials_valid("admin", "admin", "nickname"));
+    assert!(database.are_credentials_valid("admin", "secret", "nickname"));
+}
+
+#[test]
+fn there_are_no_default_operator_credentials() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    assert!(!database.are_credentials_valid("admin", "admin", "nickname"));
+}
+
+#[test]
+fn operator_credentials_are_invalid_outside_host_mask() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    let mut operator = dummy_operator("admin", "secret");
+    operator.host_mask = Some("*@192.168.*".to_string());
+    database.set_operators(vec![operator]);
+
+    assert!(!database.are_credentials_valid("admin", "secret", "nickname"));
 }
 
 #[test]
@@ -472,8 +583,10 @@ fn can_get_client_info() {
         servername: "servername".to_string(),
         realname: "realname".to_string(),
         hopcount: 0,
+        signon: DUMMY_SIGNON,
         away: None,
         flags: HashMap::new(),
+        account: None,
     };
 
     assert_eq!(expected_info, database.get_client_info("nickname").unwrap());
@@ -617,3 +730,275 @@ fn can_get_local_and_external_clients() {
 
     assert_eq!(expected, database.get_all_clients())
 }
+
+#[test]
+fn refusals_are_counted_by_reason() {
+    let database = dummy_database();
+
+    database.record_refusal(Refusal::Throttled);
+    database.record_refusal(Refusal::Throttled);
+    database.record_refusal(Refusal::ServerFull);
+
+    let refusals = database.get_refusals();
+
+    assert_eq!(2, refusals.throttled);
+    assert_eq!(1, refusals.server_full);
+    assert_eq!(0, refusals.too_many_from_host);
+    assert_eq!(3, refusals.total());
+}
+
+#[test]
+fn registered_channel_is_kept_when_empty() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_founder("#channel", Some("nickname".to_string()));
+    database.remove_client_from_channel("#channel", "nickname");
+
+    assert!(database.contains_channel("#channel"));
+    assert_eq!(
+        Some("nickname".to_string()),
+        database.get_channel_founder("#channel")
+    );
+}
+
+#[test]
+fn dropping_empty_channel_deletes_it() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_founder("#channel", Some("nickname".to_string()));
+    database.remove_client_from_channel("#channel", "nickname");
+    database.set_channel_founder("#channel", None);
+
+    assert!(!database.contains_channel("#channel"));
+}
+
+#[test]
+fn registered_channels_are_restored_from_channel_store() {
+    let path = env::temp_dir().join(format!("restored-channels-{}.db", process::id()));
+    let _ = fs::remove_file(&path);
+
+    let database = dummy_database();
+    database.set_channel_store(path.clone());
+    database.add_local_client(dummy_client("nickname"));
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_founder("#channel", Some("nickname".to_string()));
+    database.set_channel_topic("#channel", "topic");
+    assert!(database.has_channel_store());
+
+    let restored = dummy_database();
+    restored.set_channel_store(path.clone());
+
+    assert!(restored.has_channel_store());
+    assert_eq!(
+        Some("nickname".to_string()),
+        restored.get_channel_founder("#channel")
+    );
+    assert_eq!(
+        Some("topic".to_string()),
+        restored.get_channel_topic("#channel").unwrap()
+    );
+    assert!(restored.get_channel_clients("#channel").unwrap().is_empty());
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn account_name_can_only_be_registered_once() {
+    let database = dummy_database();
+
+    assert!(database.add_account(Account::new("nickname", "password", DUMMY_SIGNON)));
+    assert!(!database.add_account(Account::new("NickName", "other", DUMMY_SIGNON)));
+
+    let account = database.get_account("NICKNAME").unwrap();
+    assert_eq!("nickname", account.name);
+    assert!(account.verify("password"));
+}
+
+#[test]
+fn can_set_client_account() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    database.set_client_account("nickname", Some("account".to_string()));
+
+    let info = database.get_client_info("nickname").unwrap();
+    assert_eq!(Some("account".to_string()), info.account);
+}
+
+#[test]
+fn snapshot_holds_clients_channels_and_servers() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_immediate_server(dummy_server("servername2"));
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_topic("#channel", "topic");
+    database.add_channel_invite("#channel", "other");
+    database.set_away_message("nickname", Some("away".to_string()));
+
+    let snapshot = database.get_snapshot();
+
+    assert_eq!("servername", snapshot.servername);
+    assert_eq!(1, snapshot.servers.len());
+    assert_eq!("servername2", snapshot.servers[0].servername);
+    assert_eq!(Some("away".to_string()), snapshot.clients[0].away);
+
+    let channel = &snapshot.channels[0];
+    assert_eq!(vec!["nickname".to_string()], channel.members);
+    assert_eq!(vec!["other".to_string()], channel.invites);
+    assert_eq!(Some("topic".to_string()), channel.config.topic);
+}
+
+#[test]
+fn restoring_snapshot_rebuilds_channels_without_members() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_topic("#channel", "topic");
+    database.add_channel_banmask("#channel", "*!*@host");
+    database.add_channel_invite("#channel", "other");
+    let snapshot = database.get_snapshot();
+
+    let restored = dummy_database();
+    restored.restore_snapshot(snapshot);
+
+    assert!(restored.contains_channel("#channel"));
+    assert_eq!(
+        Some("topic".to_string()),
+        restored.get_channel_topic("#channel").unwrap()
+    );
+    assert_eq!(
+        vec!["*!*@host".to_string()],
+        restored.get_channel_banmask("#channel").unwrap()
+    );
+    assert!(restored.channel_has_invite("#channel", "other"));
+    assert!(restored.get_channel_clients("#channel").unwrap().is_empty());
+    assert!(!restored.contains_client("nickname"));
+}
+
+#[test]
+fn restoring_snapshot_keeps_channels_that_have_members() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_client_to_channel("#channel", "nickname");
+    database.set_channel_topic("#channel", "old topic");
+    let snapshot = database.get_snapshot();
+
+    database.set_channel_topic("#channel", "new topic");
+    database.restore_snapshot(snapshot);
+
+    assert_eq!(
+        Some("new topic".to_string()),
+        database.get_channel_topic("#channel").unwrap()
+    );
+    assert!(database.is_client_in_channel("#channel", "nickname"));
+}
+
+#[test]
+fn joining_new_channel_creates_it_with_client_as_operator() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+
+    let permit = database
+        .join_channel("nickname", "#channel", &None)
+        .unwrap();
+
+    assert!(permit.operator);
+    assert!(permit.recipients.untagged.is_empty());
+    assert!(database.is_client_in_channel("#channel", "nickname"));
+    assert!(database.is_channel_operator("#channel", "nickname"));
+}
+
+#[test]
+fn joining_existing_channel_lists_members_before_the_client() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_local_client(dummy_client("nickname2"));
+    database.add_client_to_channel("#channel", "nickname2");
+
+    let permit = database
+        .join_channel("nickname", "#channel", &None)
+        .unwrap();
+
+    assert!(!permit.operator);
+    assert_eq!(1, permit.recipients.untagged.len());
+    assert!(database.is_client_in_channel("#channel", "nickname"));
+    assert!(!database.is_channel_operator("#channel", "nickname"));
+}
+
+#[test]
+fn join_reports_first_refusal() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_local_client(dummy_client("nickname2"));
+    database.add_client_to_channel("#channel", "nickname2");
+    database.set_channel_key("#channel", Some("key".to_string()));
+    database.set_channel_flag("#channel", ChannelFlag::InviteOnly);
+
+    assert_eq!(
+        Some(JoinRefusal::BadKey),
+        database.join_channel("nickname", "#channel", &None).err()
+    );
+    assert_eq!(
+        Some(JoinRefusal::InviteOnly),
+        database
+            .join_channel("nickname", "#channel", &Some("key".to_string()))
+            .err()
+    );
+    assert_eq!(
+        Some(JoinRefusal::AlreadyOnChannel),
+        database.join_channel("nickname2", "#channel", &None).err()
+    );
+    assert!(!database.is_client_in_channel("#channel", "nickname"));
+}
+
+#[test]
+fn join_refuses_clients_on_too_many_channels() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    let mut limits = database.get_limits();
+    limits.max_channels = 1;
+    database.set_limits(limits);
+    database.add_client_to_channel("#channel", "nickname");
+
+    assert_eq!(
+        Some(JoinRefusal::TooManyChannels),
+        database.join_channel("nickname", "#other", &None).err()
+    );
+    assert!(!database.contains_channel("#other"));
+}
+
+#[test]
+fn founder_is_made_operator_of_registered_channel() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_local_client(dummy_client("nickname2"));
+    database.add_client_to_channel("#channel", "nickname2");
+    database.set_channel_founder("#channel", Some("Account".to_string()));
+    database.set_client_account("nickname", Some("account".to_string()));
+
+    let permit = database
+        .join_channel("nickname", "#channel", &None)
+        .unwrap();
+
+    assert!(permit.operator);
+    assert!(database.is_channel_operator("#channel", "nickname"));
+}
+
+#[test]
+fn client_with_founder_nickname_but_no_account_is_not_made_operator() {
+    let database = dummy_database();
+    database.add_local_client(dummy_client("nickname"));
+    database.add_local_client(dummy_client("nickname2"));
+    database.add_client_to_channel("#channel", "nickname2");
+    database.set_channel_founder("#channel", Some("nickname".to_string()));
+
+    let permit = database
+        .join_channel("nickname", "#channel", &None)
+        .unwrap();
+
+    assert!(!permit.operator);
+}
@@ -0,0 +1,80 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Instant;
+
+use crate::server::config::Limits;
+use crate::server::data_structures::Refusal;
+
+/// Decides which connections the server accepts, remembering when each address connected
+/// to throttle the ones connecting too fast.
+pub struct Admission {
+    attempts: HashMap<IpAddr, VecDeque<Instant>>,
+    last_cleanup: Instant,
+}
+
+impl Admission {
+    pub fn new() -> Self {
+        Self {
+            attempts: HashMap::new(),
+            last_cleanup: Instant::now(),
+        }
+    }
+
+    /// Records a connection attempt from address, returning why it is refused, if it is.
+    /// Expects how many connections are served in total, and from the address' network.
+    pub fn admit(
+        &mut self,
+        address: IpAddr,
+        limits: &Limits,
+        connections: usize,
+        from_network: usize,
+    ) -> Result<(), Refusal> {
+        if self.throttled(address.to_canonical(), limits) {
+            return Err(Refusal::Throttled);
+        }
+        if from_network >= limits.max_per_ip {
+            return Err(Refusal::TooManyFromHost);
+        }
+        if connections >= limits.max_clients {
+            return Err(Refusal::ServerFull);
+        }
+
+        Ok(())
+    }
+
+    /// Records attempt, returning true if address connected too many times within the throttle period.
+    fn throttled(&mut self, address: IpAddr, limits: &Limits) -> bool {
+        let now = Instant::now();
+        let period = limits.throttle_period;
+
+        if now.duration_since(self.last_cleanup) >= period {
+            self.attempts.retain(|_, attempts| {
+                attempts.retain(|attempt| now.duration_since(*attempt) < period);
+                !attempts.is_empty()
+            });
+            self.last_cleanup = now;
+        }
+
+        let attempts = self.attempts.entry(address).or_default();
+        attempts.retain(|attempt| now.duration_since(*attempt) < period);
+        attempts.push_back(now);
+
+        attempts.len() > limits.throttle_connections
+    }
+}
+
+/// Returns the network address belongs to, keeping only the prefix configured for its family.
+pub fn network(address: IpAddr, limits: &Limits) -> IpAddr {
+    match address.to_canonical() {
+        IpAddr::V4(address) => {
+            let mask = u32::MAX.checked_shl(32 - limits.ipv4_cidr as u32);
+            let bits = u32::from(address) & mask.unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(bits))
+        }
+        IpAddr::V6(address) => {
+            let mask = u128::MAX.checked_shl(128 - limits.ipv6_cidr as u32);
+            let bits = u128::from(address) & mask.unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(bits))
+        }
+    }
+}
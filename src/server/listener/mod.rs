@@ -0,0 +1,135 @@
+/// Admission policy for new connections: total, per network and per rate limits.
+mod admission;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+    io,
+    net::{IpAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use super::{
+    connection::Connection,
+    connection_handler::RegistrationHandler,
+    data_structures::Refusal,
+    database::DatabaseHandle,
+    reactor::{Poller, Reactor, TICK},
+    responses::CommandResponse,
+};
+
+use admission::{network, Admission};
+
+const LISTENER_TOKEN: u64 = 0;
+
+/// In charge of creating handlers for each new client or server
+///  connecting to the specified address.
+pub struct ConnectionListener {
+    database: DatabaseHandle<TcpStream>,
+    listener: TcpListener,
+    reactor: Reactor,
+    admission: Admission,
+    online: Arc<AtomicBool>,
+}
+
+impl ConnectionListener {
+    /// Creates new [`ConnectionListener`] from an address to listen from,
+    ///  handing every connection over to reactor.
+    pub fn new(
+        address: String,
+        database: DatabaseHandle<TcpStream>,
+        reactor: Reactor,
+        online: Arc<AtomicBool>,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+
+        let connection_listener = Self {
+            database,
+            listener,
+            reactor,
+            admission: Admission::new(),
+            online,
+        };
+
+        Ok(connection_listener)
+    }
+
+    /// Starts listening from configured address, waking up only when there are connections to accept.
+    pub fn listen(mut self) {
+        let poller = match Poller::new() {
+            Ok(poller) => poller,
+            Err(error) => return eprintln!("Could not listen from address {error:?}"),
+        };
+        if let Err(error) = poller.add(&self.listener, LISTENER_TOKEN) {
+            return eprintln!("Could not listen from address {error:?}");
+        }
+
+        let mut ready = Vec::new();
+        while self.online.load(Ordering::Relaxed) {
+            if let Err(error) = poller.wait(&mut ready, TICK) {
+                return eprintln!("Could not listen from address {error:?}");
+            }
+            ready.clear();
+
+            self.accept_connections();
+        }
+    }
+
+    /// Accepts every pending connection.
+    fn accept_connections(&mut self) {
+        loop {
+            let client = match self.listener.accept() {
+                Ok((client, _)) => client,
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => return,
+                Err(error) => return eprintln!("Could not accept connection {error:?}"),
+            };
+
+            if let Err(error) = self.serve(client) {
+                eprintln!("Could not create handler {error:?}");
+            }
+        }
+    }
+
+    /// Hands client over to the reactor, unless it is refused admission.
+    fn serve(&mut self, client: TcpStream) -> io::Result<()> {
+        let address = client.peer_address()?.ip();
+        if let Err(refusal) = self.admit(address) {
+            return self.refuse(client, address, refusal);
+        }
+
+        let handler = self.handler(client.try_clone()?)?;
+        self.reactor.register(client, Box::new(handler))
+    }
+
+    fn admit(&mut self, address: IpAddr) -> Result<(), Refusal> {
+        let limits = self.database.get_limits();
+
+        let own_network = network(address, &limits);
+        let from_network = self
+            .reactor
+            .connections_from(|other| network(*other, &limits) == own_network);
+
+        self.admission
+            .admit(address, &limits, self.reactor.connections(), from_network)
+    }
+
+    /// Tells client why it is refused, counting the refusal for operators.
+    fn refuse(&self, mut client: TcpStream, address: IpAddr, refusal: Refusal) -> io::Result<()> {
+        self.database.record_refusal(refusal);
+
+        let message = format!("Closing Link: {address} ({})", refusal.reason());
+        client.send(&CommandResponse::error(&message))
+    }
+
+    /// Creates RegistrationHandler for given stream.
+    fn handler(&self, client: TcpStream) -> io::Result<RegistrationHandler<TcpStream>> {
+        let database = self.database.clone();
+        let online = Arc::clone(&self.online);
+        RegistrationHandler::<TcpStream>::from_connection(client, database, online)
+    }
+}
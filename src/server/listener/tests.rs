@@ -0,0 +1,101 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::server::config::Limits;
+use crate::server::data_structures::Refusal;
+
+use super::admission::{network, Admission};
+
+fn address(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+#[test]
+fn connection_within_limits_is_admitted() {
+    let mut admission = Admission::new();
+
+    let result = admission.admit(address("10.0.0.1"), &Limits::default(), 0, 0);
+
+    assert_eq!(Ok(()), result);
+}
+
+#[test]
+fn connection_is_refused_when_server_is_full() {
+    let mut admission = Admission::new();
+    let limits = Limits {
+        max_clients: 2,
+        ..Default::default()
+    };
+
+    let result = admission.admit(address("10.0.0.1"), &limits, 2, 0);
+
+    assert_eq!(Err(Refusal::ServerFull), result);
+}
+
+#[test]
+fn connection_is_refused_when_its_network_is_full() {
+    let mut admission = Admission::new();
+    let limits = Limits {
+        max_per_ip: 2,
+        ..Default::default()
+    };
+
+    let result = admission.admit(address("10.0.0.1"), &limits, 2, 2);
+
+    assert_eq!(Err(Refusal::TooManyFromHost), result);
+}
+
+#[test]
+fn address_connecting_too_fast_is_throttled() {
+    let mut admission = Admission::new();
+    let limits = Limits {
+        throttle_connections: 2,
+        ..Default::default()
+    };
+
+    assert!(admission.admit(address("10.0.0.1"), &limits, 0, 0).is_ok());
+    assert!(admission.admit(address("10.0.0.1"), &limits, 0, 0).is_ok());
+
+    let result = admission.admit(address("10.0.0.1"), &limits, 0, 0);
+    assert_eq!(Err(Refusal::Throttled), result);
+
+    assert!(admission.admit(address("10.0.0.2"), &limits, 0, 0).is_ok());
+}
+
+#[test]
+fn attempts_outside_throttle_period_are_forgotten() {
+    let mut admission = Admission::new();
+    let limits = Limits {
+        throttle_connections: 1,
+        throttle_period: Duration::ZERO,
+        ..Default::default()
+    };
+
+    assert!(admission.admit(address("10.0.0.1"), &limits, 0, 0).is_ok());
+    assert!(admission.admit(address("10.0.0.1"), &limits, 0, 0).is_ok());
+}
+
+#[test]
+fn addresses_are_grouped_by_configured_prefix() {
+    let limits = Limits {
+        ipv4_cidr: 24,
+        ipv6_cidr: 64,
+        ..Default::default()
+    };
+
+    assert_eq!(address("10.0.0.0"), network(address("10.0.0.17"), &limits));
+    assert_eq!(
+        address("2001:db8::"),
+        network(address("2001:db8::1:2:3:4"), &limits)
+    );
+}
+
+#[test]
+fn ipv4_mapped_addresses_are_grouped_as_ipv4() {
+    let limits = Limits::default();
+
+    assert_eq!(
+        address("10.0.0.1"),
+        network(address("::ffff:10.0.0.1"), &limits)
+    );
+}
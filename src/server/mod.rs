@@ -2,9 +2,18 @@
 /// Utils for testing different functionalities.
 mod testing;
 
+/// Contains the file nickname accounts are saved to, and restored from at startup.
+mod account_store;
+
+/// Contains the file registered channels are saved to, and restored from at startup.
+mod channel_store;
+
 /// Definition of the trait used in the project's structures.
 mod connection;
 
+/// Contains the file the state of the network is saved to, and restored from at startup.
+mod snapshot;
+
 /// Contains structure for database. A Database stores and updates information regarding clients, channels and other servers.
 mod database;
 
@@ -18,16 +27,23 @@ pub mod connection_handler;
 /// Contains structure for connection listener, this structure listens to an address and handles all clients connecting to that address.
 mod listener;
 
+/// Contains the event loop every connection is served from, on a few threads.
+mod reactor;
+
 /// Contains structure that handles the setup when two servers are connecting with each other.
 mod server_connection_setup;
 
 /// Contains constant values used throughout the project.
 pub(crate) mod consts;
 
+/// Contains the server's configuration, read from a file at startup.
+mod config;
+
 /// Contains structures used to store information:
 ///     - Client
 ///     - Channel
 ///     - Server
+///     - Operator
 mod data_structures;
 
 /// Contains different responses to commands that may be received:
@@ -36,27 +52,45 @@ mod data_structures;
 ///     - Responses
 mod responses;
 
+pub use config::{BanBlock, ConfigError, Limits, LinkBlock, ServerConfig};
+pub use data_structures::{OperatorBlock, OperatorPrivilege, PasswordHash, Refusals};
 use database::Database;
 use std::io;
 use std::net::TcpStream;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
-use self::connection_handler::{ConnectionHandler, ServerHandler};
+use self::connection_handler::ServerHandler;
+use self::data_structures::OutputQueue;
 use self::database::DatabaseHandle;
 use self::listener::ConnectionListener;
+use self::reactor::Reactor;
 use self::server_connection_setup::ServerConnectionSetup;
-
-const MAX_CLIENTS: usize = 26;
-
-pub const OPER_USERNAME: &str = "admin";
-pub const OPER_PASSWORD: &str = "admin";
+use self::snapshot::SnapshotFile;
+
+const MAX_CLIENTS: usize = 1024;
+const MAX_CONNECTIONS_PER_IP: usize = 10;
+const IPV4_CIDR: u8 = 32;
+const IPV6_CIDR: u8 = 64;
+const THROTTLE_CONNECTIONS: usize = 10;
+const THROTTLE_PERIOD_SECS: u64 = 60;
+const REACTOR_THREADS: usize = 4;
+const PING_INTERVAL_SECS: u64 = 120;
+const PING_TIMEOUT_SECS: u64 = 60;
+const REGISTRATION_TIMELIMIT_SECS: u64 = 60;
+const SENDQ_BYTES: usize = 262_144;
+const SERVER_VERSION: &str = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION"));
+const MAX_FAILED_ATTEMPTS: usize = 3;
+const FAILED_ATTEMPTS_WINDOW_SECS: u64 = 60;
+const NICK_PROTECTION_SECS: u64 = 60;
 
 /// Represents a Server clients and other servers can connect to.
 /// Contains a Database that stores relevant information.
 pub struct Server {
     database: Option<DatabaseHandle<TcpStream>>,
+    reactor: Reactor,
     online: Arc<AtomicBool>,
     threads: Vec<JoinHandle<()>>,
 }
@@ -67,31 +101,92 @@ impl Server {
         let online = Arc::new(AtomicBool::new(true));
 
         let (database, database_thread) = Database::start(servername, serverinfo);
+        let (reactor, reactor_threads) = Reactor::start(REACTOR_THREADS, Arc::clone(&online))
+            .expect("Event loop should be able to start");
 
-        let threads = vec![database_thread];
+        let mut threads = vec![database_thread];
+        threads.extend(reactor_threads);
         let database = Some(database);
 
         Self {
             online,
             database,
+            reactor,
             threads,
         }
     }
 
     /// Marks server as offline, closing all its threads.
+    /// Clients are told the link is closing, and linked servers that this server quit.
     pub fn quit(&self) {
         self.online.store(false, Ordering::Relaxed);
+        self.reactor.wake();
     }
 
-    /// Listens for incoming clients from an address and handles each request in a new thread.
+    /// Applies every setting in config, except the addresses to listen from.
+    pub fn configure(&self, config: &ServerConfig) {
+        if let Some(database) = &self.database {
+            database.configure(config.clone());
+        }
+    }
+
+    /// Rereads the configuration file, applying every setting that may change while running.
+    /// Returns the settings that changed but only take effect on restart.
+    pub fn rehash(&self) -> Result<Vec<&'static str>, ConfigError> {
+        let database = self
+            .database
+            .as_ref()
+            .expect("DatabaseHandle should only be None when dropped");
+
+        config::rehash(database)
+    }
+
+    /// Returns how many connections were refused since the server started, by reason.
+    /// Shown by the console `STATS` command, it is not reachable from any client.
+    pub fn refused_connections(&self) -> Refusals {
+        self.database
+            .as_ref()
+            .expect("DatabaseHandle should only be None when dropped")
+            .get_refusals()
+    }
+
+    /// Saves the state of the network to path, so that it can be restored on restart.
+    pub fn save_snapshot(&self, path: &Path) -> io::Result<()> {
+        let database = self
+            .database
+            .as_ref()
+            .expect("DatabaseHandle should only be None when dropped");
+
+        SnapshotFile::new(path.to_path_buf()).save(&database.get_snapshot())
+    }
+
+    /// Restores the state saved to path by a previous run.
+    /// Returns false if nothing was saved to path.
+    pub fn restore_snapshot(&self, path: &Path) -> Result<bool, ConfigError> {
+        let database = self
+            .database
+            .as_ref()
+            .expect("DatabaseHandle should only be None when dropped");
+
+        let snapshot = match SnapshotFile::new(path.to_path_buf()).load()? {
+            Some(snapshot) => snapshot,
+            None => return Ok(false),
+        };
+        database.restore_snapshot(snapshot);
+
+        Ok(true)
+    }
+
+    /// Listens for incoming clients from an address, serving each one from the event loop.
     pub fn listen_to(&mut self, address: String) -> io::Result<()> {
         let online = Arc::clone(&self.online);
         let database = self
             .database
             .clone()
             .expect("DatabaseHandle should only be None when dropped");
+        let reactor = self.reactor.clone();
 
-        let connection_listener = ConnectionListener::new(address, database, online)?;
+        let connection_listener = ConnectionListener::new(address, database, reactor, online)?;
 
         let thread = thread::spawn(|| connection_listener.listen());
 
@@ -100,33 +195,33 @@ impl Server {
         Ok(())
     }
 
-    /// Establishes a connection with another server, listening from address.
-    pub fn connect_to(&mut self, address: &str) {
-        if let Err(error) = self.try_connect_to(address) {
+    /// Establishes a connection with server `servername`, listening from address.
+    pub fn connect_to(&mut self, servername: &str, address: &str) {
+        if let Err(error) = self.try_connect_to(servername, address) {
             eprintln!("Could not connect to {address}, with error {error:?}");
         }
     }
 
-    fn try_connect_to(&mut self, address: &str) -> io::Result<()> {
+    fn try_connect_to(&mut self, servername: &str, address: &str) -> io::Result<()> {
         let stream = TcpStream::connect(address)?;
         let database = self
             .database
             .clone()
             .expect("DatabaseHandle should only be None when dropped");
 
-        let mut registerer = ServerConnectionSetup::new(stream.try_clone()?, database.clone());
+        let output = OutputQueue::new(stream.try_clone()?, database.get_limits().sendq);
+        let mut registerer =
+            ServerConnectionSetup::new(stream.try_clone()?, output, database.clone());
 
-        registerer.register_outcoming()?;
+        registerer.register_outcoming(servername)?;
 
         let online = Arc::clone(&self.online);
         let servername = registerer.servername();
-        let server_handle =
-            ServerHandler::from_connection(stream.try_clone()?, servername, database, online)?;
+        let server_handler =
+            ServerHandler::from_connection(stream.try_clone()?, servername, database, online)?
+                .with_reader(registerer.take_reader());
 
-        let handle = thread::spawn(|| server_handle.handle());
-        self.threads.push(handle);
-
-        Ok(())
+        self.reactor.register(stream, Box::new(server_handler))
     }
 }
 
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard};
+
+/// Connections being served, in total and from each address.
+#[derive(Default)]
+pub struct Connections {
+    addresses: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl Connections {
+    pub fn add(&self, address: IpAddr) {
+        *self.lock().entry(address).or_default() += 1;
+    }
+
+    pub fn remove(&self, address: IpAddr) {
+        let mut addresses = self.lock();
+        if let Some(count) = addresses.get_mut(&address) {
+            *count -= 1;
+            if *count == 0 {
+                addresses.remove(&address);
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.lock().values().sum()
+    }
+
+    /// Returns how many connections come from addresses matching filter.
+    pub fn from(&self, filter: impl Fn(&IpAddr) -> bool) -> usize {
+        self.lock()
+            .iter()
+            .filter(|(address, _)| filter(address))
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<IpAddr, usize>> {
+        self.addresses
+            .lock()
+            .expect("Connections lock should not be poisoned")
+    }
+}
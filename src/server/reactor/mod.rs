@@ -0,0 +1,164 @@
+use std::io;
+use std::net::{IpAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Count of the connections being served, by address.
+mod connections;
+/// Readiness notification for sockets.
+mod poller;
+/// Threads that own connections and poll the ones that are ready.
+mod worker;
+
+#[cfg(test)]
+mod tests;
+
+pub use poller::Poller;
+
+use connections::Connections;
+
+use worker::{Registration, Worker};
+
+/// Longest a worker waits before polling every connection,
+/// so timeouts and shutdown are noticed on quiet connections too.
+pub const TICK: Duration = Duration::from_millis(500);
+
+/// Longest workers keep serving connections once offline, so their goodbyes get written.
+/// Connections still open by then are closed without waiting for them.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A connection driven by the [`Reactor`].
+pub trait Task: Send {
+    /// Handles whatever the connection has ready, without blocking.
+    fn poll(&mut self) -> Progress;
+
+    /// Called once a worker owns the task, with what lets any thread have it polled.
+    fn set_waker(&mut self, _waker: Waker) {}
+
+    /// Called after polling the task panicked, before its connection is closed,
+    /// so it can release whatever it held for the connection.
+    fn abort(&mut self) {}
+}
+
+/// Has a worker poll one of its connections soon, from any thread.
+#[derive(Clone)]
+pub struct Waker {
+    token: u64,
+    woken: Arc<Mutex<Vec<u64>>>,
+    poller: Arc<Poller>,
+}
+
+impl Waker {
+    /// Marks the connection as ready, interrupting its worker's wait.
+    pub fn wake(&self) {
+        self.woken
+            .lock()
+            .expect("Woken tokens lock should not be poisoned")
+            .push(self.token);
+        self.poller.wake().ok();
+    }
+}
+
+/// What becomes of a connection after it was polled.
+pub enum Progress {
+    /// the connection is open, waiting to be ready again
+    Pending,
+    /// the connection is taken over by another task, which is polled right away
+    Handover(Box<dyn Task>),
+    /// the connection ended
+    Closed,
+}
+
+/// Event loop serving every connection from a few threads.
+///
+/// Each connection is owned by a single worker, which only polls it when its
+/// socket is ready, or every [`TICK`] otherwise.
+#[derive(Clone)]
+pub struct Reactor {
+    workers: Arc<[WorkerHandle]>,
+    next_token: Arc<AtomicU64>,
+    connections: Arc<Connections>,
+}
+
+struct WorkerHandle {
+    sender: Sender<Registration>,
+    poller: Arc<Poller>,
+}
+
+impl Reactor {
+    /// Starts a [`Reactor`] with the given amount of worker threads, which run while online.
+    pub fn start(
+        threads: usize,
+        online: Arc<AtomicBool>,
+    ) -> io::Result<(Self, Vec<JoinHandle<()>>)> {
+        let connections = Arc::new(Connections::default());
+
+        let mut workers = Vec::with_capacity(threads);
+        let mut handles = Vec::with_capacity(threads);
+        for _ in 0..threads.max(1) {
+            let (sender, receiver) = mpsc::channel();
+            let poller = Arc::new(Poller::new()?);
+
+            let worker = Worker::new(
+                Arc::clone(&poller),
+                receiver,
+                Arc::clone(&connections),
+                Arc::clone(&online),
+            );
+            handles.push(thread::spawn(|| worker.run()));
+            workers.push(WorkerHandle { sender, poller });
+        }
+
+        let reactor = Self {
+            workers: workers.into(),
+            next_token: Arc::new(AtomicU64::new(0)),
+            connections,
+        };
+
+        Ok((reactor, handles))
+    }
+
+    /// Hands the connection over to a worker, which polls task whenever stream is ready.
+    /// The stream is made non blocking, so the task never waits on it.
+    pub fn register(&self, stream: TcpStream, task: Box<dyn Task>) -> io::Result<()> {
+        stream.set_nonblocking(true)?;
+        let address = stream.peer_addr()?.ip();
+
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let worker = &self.workers[token as usize % self.workers.len()];
+
+        let registration = Registration {
+            token,
+            address,
+            stream,
+            task,
+        };
+        self.connections.add(address);
+        if worker.sender.send(registration).is_err() {
+            self.connections.remove(address);
+            return Err(io::Error::other("Reactor is not running"));
+        }
+
+        worker.poller.wake()
+    }
+
+    /// Interrupts every worker's wait, so they notice the server went offline right away.
+    pub fn wake(&self) {
+        for worker in self.workers.iter() {
+            worker.poller.wake().ok();
+        }
+    }
+
+    /// Returns how many connections are being served.
+    pub fn connections(&self) -> usize {
+        self.connections.total()
+    }
+
+    /// Returns how many connections are being served from addresses matching filter.
+    pub fn connections_from(&self, filter: impl Fn(&IpAddr) -> bool) -> usize {
+        self.connections.from(filter)
+    }
+}
@@ -0,0 +1,180 @@
+pub use platform::Poller;
+
+/// Readiness through epoll, reporting each socket once every time it becomes readable or writable.
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs::File;
+    use std::io::{self, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::time::Duration;
+
+    const EPOLL_CTL_ADD: i32 = 1;
+    const EPOLL_CTL_DEL: i32 = 2;
+    const EPOLLIN: u32 = 0x001;
+    const EPOLLOUT: u32 = 0x004;
+    const EPOLLRDHUP: u32 = 0x2000;
+    const EPOLLET: u32 = 1 << 31;
+    const EFD_NONBLOCK: i32 = 0o4000;
+
+    /// Most events collected by a single wait, the rest are reported by the next one.
+    const MAX_EVENTS: usize = 256;
+    /// Token of the eventfd used to interrupt a wait.
+    const WAKER_TOKEN: u64 = u64::MAX;
+
+    #[repr(C)]
+    #[cfg_attr(target_arch = "x86_64", repr(packed))]
+    #[derive(Clone, Copy)]
+    struct EpollEvent {
+        events: u32,
+        data: u64,
+    }
+
+    extern "C" {
+        fn epoll_create1(flags: i32) -> i32;
+        fn epoll_ctl(epfd: i32, op: i32, fd: i32, event: *mut EpollEvent) -> i32;
+        fn epoll_wait(epfd: i32, events: *mut EpollEvent, maxevents: i32, timeout: i32) -> i32;
+        fn eventfd(initval: u32, flags: i32) -> i32;
+    }
+
+    pub struct Poller {
+        epoll: OwnedFd,
+        waker: File,
+    }
+
+    impl Poller {
+        pub fn new() -> io::Result<Self> {
+            // SAFETY: both calls only create new descriptors, which are owned right away.
+            let epoll = unsafe { OwnedFd::from_raw_fd(check(epoll_create1(0))?) };
+            let waker = unsafe { File::from_raw_fd(check(eventfd(0, EFD_NONBLOCK))?) };
+
+            let poller = Self { epoll, waker };
+            poller.control(EPOLL_CTL_ADD, poller.waker.as_raw_fd(), WAKER_TOKEN)?;
+
+            Ok(poller)
+        }
+
+        /// Starts reporting source with token, edge triggered:
+        /// it is only reported again after everything it had ready was consumed.
+        pub fn add(&self, source: &impl AsRawFd, token: u64) -> io::Result<()> {
+            self.control(EPOLL_CTL_ADD, source.as_raw_fd(), token)
+        }
+
+        pub fn delete(&self, source: &impl AsRawFd, _token: u64) -> io::Result<()> {
+            self.control(EPOLL_CTL_DEL, source.as_raw_fd(), 0)
+        }
+
+        /// Waits up to timeout for sources to be ready, appending their tokens to ready.
+        pub fn wait(&self, ready: &mut Vec<u64>, timeout: Duration) -> io::Result<()> {
+            let mut events = [EpollEvent { events: 0, data: 0 }; MAX_EVENTS];
+            let timeout = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+            // SAFETY: events is valid for MAX_EVENTS entries during the whole call.
+            let count = unsafe {
+                epoll_wait(
+                    self.epoll.as_raw_fd(),
+                    events.as_mut_ptr(),
+                    MAX_EVENTS as i32,
+                    timeout,
+                )
+            };
+            let count = match check(count) {
+                Ok(count) => count as usize,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => return Ok(()),
+                Err(error) => return Err(error),
+            };
+
+            for event in &events[..count] {
+                let token = event.data;
+                match token {
+                    WAKER_TOKEN => self.clear_wake()?,
+                    token => ready.push(token),
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Interrupts the current or next wait.
+        pub fn wake(&self) -> io::Result<()> {
+            (&self.waker).write_all(&1u64.to_ne_bytes())
+        }
+
+        /// Resets the waker, which is also reported when it only became writable.
+        fn clear_wake(&self) -> io::Result<()> {
+            let mut counter = [0; 8];
+            match (&self.waker).read(&mut counter) {
+                Err(error) if error.kind() != io::ErrorKind::WouldBlock => Err(error),
+                _ => Ok(()),
+            }
+        }
+
+        fn control(&self, operation: i32, fd: i32, token: u64) -> io::Result<()> {
+            let mut event = EpollEvent {
+                events: EPOLLIN | EPOLLOUT | EPOLLRDHUP | EPOLLET,
+                data: token,
+            };
+
+            // SAFETY: event is valid during the whole call, and epoll does not keep the pointer.
+            check(unsafe { epoll_ctl(self.epoll.as_raw_fd(), operation, fd, &mut event) })?;
+
+            Ok(())
+        }
+    }
+
+    /// Turns the -1 returned by failed system calls into the error they set.
+    fn check(result: i32) -> io::Result<i32> {
+        match result {
+            -1 => Err(io::Error::last_os_error()),
+            result => Ok(result),
+        }
+    }
+}
+
+/// Without epoll every source is reported ready after a short sleep,
+/// so connections are polled regularly instead of on readiness.
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use std::io;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    pub struct Poller {
+        tokens: Mutex<Vec<u64>>,
+    }
+
+    impl Poller {
+        pub fn new() -> io::Result<Self> {
+            let tokens = Mutex::new(Vec::new());
+            Ok(Self { tokens })
+        }
+
+        pub fn add<S>(&self, _source: &S, token: u64) -> io::Result<()> {
+            self.lock().push(token);
+            Ok(())
+        }
+
+        pub fn delete<S>(&self, _source: &S, token: u64) -> io::Result<()> {
+            self.lock().retain(|registered| *registered != token);
+            Ok(())
+        }
+
+        pub fn wait(&self, ready: &mut Vec<u64>, timeout: Duration) -> io::Result<()> {
+            thread::sleep(timeout.min(POLL_INTERVAL));
+            ready.extend(self.lock().iter());
+            Ok(())
+        }
+
+        pub fn wake(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn lock(&self) -> std::sync::MutexGuard<'_, Vec<u64>> {
+            self.tokens
+                .lock()
+                .expect("Poller lock should not be poisoned")
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::*;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+/// Reports every read through a channel, closing once the peer does.
+struct ReportingTask {
+    stream: TcpStream,
+    reports: Sender<String>,
+}
+
+impl Task for ReportingTask {
+    fn poll(&mut self) -> Progress {
+        let mut buffer = [0; 64];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    self.reports.send("closed".to_string()).ok();
+                    return Progress::Closed;
+                }
+                Ok(read) => {
+                    let report = String::from_utf8_lossy(&buffer[..read]).to_string();
+                    self.reports.send(report).ok();
+                }
+                Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                    return Progress::Pending
+                }
+                Err(_) => return Progress::Closed,
+            }
+        }
+    }
+}
+
+/// Hands the connection over to a [`ReportingTask`] the first time it is polled.
+struct HandoverTask {
+    task: Option<ReportingTask>,
+}
+
+impl Task for HandoverTask {
+    fn poll(&mut self) -> Progress {
+        match self.task.take() {
+            Some(task) => Progress::Handover(Box::new(task)),
+            None => Progress::Closed,
+        }
+    }
+}
+
+/// Panics the first time it is polled, reporting once it is aborted.
+struct PanickingTask {
+    reports: Sender<String>,
+}
+
+impl Task for PanickingTask {
+    fn poll(&mut self) -> Progress {
+        panic!("Task panicked")
+    }
+
+    fn abort(&mut self) {
+        self.reports.send("aborted".to_string()).ok();
+    }
+}
+
+/// Reports every poll, handing the waker it is given to the test.
+struct WakeableTask {
+    wakers: Sender<Waker>,
+    reports: Sender<String>,
+}
+
+impl Task for WakeableTask {
+    fn poll(&mut self) -> Progress {
+        self.reports.send("polled".to_string()).ok();
+        Progress::Pending
+    }
+
+    fn set_waker(&mut self, waker: Waker) {
+        self.wakers.send(waker).ok();
+    }
+}
+
+fn connected_pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    (client, server)
+}
+
+fn start_reactor() -> (Reactor, Arc<AtomicBool>, Vec<JoinHandle<()>>) {
+    let online = Arc::new(AtomicBool::new(true));
+    let (reactor, threads) = Reactor::start(2, Arc::clone(&online)).unwrap();
+    (reactor, online, threads)
+}
+
+fn register_reporting_task(reactor: &Reactor, stream: TcpStream) -> Receiver<String> {
+    let (reports, receiver) = mpsc::channel();
+    let task = ReportingTask {
+        stream: stream.try_clone().unwrap(),
+        reports,
+    };
+    reactor.register(stream, Box::new(task)).unwrap();
+    receiver
+}
+
+fn wait_for_connections(reactor: &Reactor, expected: usize) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < WAIT {
+        if reactor.connections() == expected {
+            return true;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
+
+fn stop(online: Arc<AtomicBool>, threads: Vec<JoinHandle<()>>) {
+    online.store(false, Ordering::Relaxed);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn registered_task_is_polled_when_input_arrives() {
+    let (reactor, online, threads) = start_reactor();
+    let (mut client, server) = connected_pair();
+
+    let reports = register_reporting_task(&reactor, server);
+    client.write_all(b"PING").unwrap();
+
+    assert_eq!("PING", reports.recv_timeout(WAIT).unwrap());
+    assert_eq!(1, reactor.connections());
+
+    stop(online, threads);
+}
+
+#[test]
+fn closed_task_is_no_longer_served() {
+    let (reactor, online, threads) = start_reactor();
+    let (client, server) = connected_pair();
+
+    let reports = register_reporting_task(&reactor, server);
+    drop(client);
+
+    assert_eq!("closed", reports.recv_timeout(WAIT).unwrap());
+    assert!(wait_for_connections(&reactor, 0));
+
+    stop(online, threads);
+}
+
+#[test]
+fn connection_is_served_by_the_task_it_was_handed_over_to() {
+    let (reactor, online, threads) = start_reactor();
+    let (mut client, server) = connected_pair();
+
+    let (reports, receiver) = mpsc::channel();
+    let task = ReportingTask {
+        stream: server.try_clone().unwrap(),
+        reports,
+    };
+    let handover = HandoverTask { task: Some(task) };
+    reactor.register(server, Box::new(handover)).unwrap();
+
+    client.write_all(b"NICK nickname").unwrap();
+
+    assert_eq!("NICK nickname", receiver.recv_timeout(WAIT).unwrap());
+    assert_eq!(1, reactor.connections());
+
+    stop(online, threads);
+}
+
+#[test]
+fn panicking_task_is_aborted_without_affecting_others() {
+    let (reactor, online, threads) = start_reactor();
+    let (mut client, server) = connected_pair();
+    let reports = register_reporting_task(&reactor, server);
+
+    for _ in 0..2 {
+        let (mut panicking, server) = connected_pair();
+        let (aborts, aborted) = mpsc::channel();
+        let task = PanickingTask { reports: aborts };
+        reactor.register(server, Box::new(task)).unwrap();
+
+        let mut buffer = [0; 1];
+        panicking.set_read_timeout(Some(WAIT)).unwrap();
+        assert_eq!(0, panicking.read(&mut buffer).unwrap());
+        assert_eq!("aborted", aborted.recv_timeout(WAIT).unwrap());
+    }
+    assert!(wait_for_connections(&reactor, 1));
+
+    client.write_all(b"PING").unwrap();
+    assert_eq!("PING", reports.recv_timeout(WAIT).unwrap());
+
+    stop(online, threads);
+}
+
+#[test]
+fn many_connections_are_served_by_few_threads() {
+    let (reactor, online, threads) = start_reactor();
+
+    let mut connections = vec![];
+    for _ in 0..100 {
+        let (client, server) = connected_pair();
+        let reports = register_reporting_task(&reactor, server);
+        connections.push((client, reports));
+    }
+
+    for (index, (client, _)) in connections.iter_mut().enumerate() {
+        client.write_all(index.to_string().as_bytes()).unwrap();
+    }
+    for (index, (_, reports)) in connections.iter().enumerate() {
+        assert_eq!(index.to_string(), reports.recv_timeout(WAIT).unwrap());
+    }
+
+    assert_eq!(100, reactor.connections());
+
+    stop(online, threads);
+}
+
+#[test]
+fn workers_stop_once_offline() {
+    let (reactor, online, threads) = start_reactor();
+    let (_client, server) = connected_pair();
+    let _reports = register_reporting_task(&reactor, server);
+
+    let start = Instant::now();
+    stop(online, threads);
+
+    assert!(start.elapsed() < WAIT);
+    assert_eq!(0, reactor.connections());
+}
+
+#[test]
+fn woken_task_is_polled_without_waiting_for_tick() {
+    let (reactor, online, threads) = start_reactor();
+    let (_client, server) = connected_pair();
+
+    let (wakers, waker) = mpsc::channel();
+    let (reports, polls) = mpsc::channel();
+    let task = WakeableTask { wakers, reports };
+    reactor.register(server, Box::new(task)).unwrap();
+
+    let waker = waker.recv_timeout(WAIT).unwrap();
+    while polls.try_recv().is_ok() {}
+
+    waker.wake();
+
+    assert_eq!("polled", polls.recv_timeout(TICK / 2).unwrap());
+
+    stop(online, threads);
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Shutdown, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use crate::macros::some_or_return;
+
+use super::{Connections, Poller, Progress, Task, Waker, SHUTDOWN_TIMEOUT, TICK};
+
+/// A connection handed over to a worker.
+pub struct Registration {
+    pub token: u64,
+    pub address: IpAddr,
+    /// kept to watch the connection, closed once the task ends
+    pub stream: TcpStream,
+    pub task: Box<dyn Task>,
+}
+
+pub struct Worker {
+    poller: Arc<Poller>,
+    incoming: Receiver<Registration>,
+    registrations: HashMap<u64, Registration>,
+    /// connections woken from other threads since the last wait
+    woken: Arc<Mutex<Vec<u64>>>,
+    connections: Arc<Connections>,
+    online: Arc<AtomicBool>,
+}
+
+impl Worker {
+    pub fn new(
+        poller: Arc<Poller>,
+        incoming: Receiver<Registration>,
+        connections: Arc<Connections>,
+        online: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            poller,
+            incoming,
+            registrations: HashMap::new(),
+            woken: Arc::default(),
+            connections,
+            online,
+        }
+    }
+
+    /// Polls connections as they become ready until the server goes offline.
+    /// Connections are then given up to [`SHUTDOWN_TIMEOUT`] to say goodbye.
+    pub fn run(mut self) {
+        let mut ready = Vec::new();
+        let mut last_tick = Instant::now();
+
+        while self.online.load(Ordering::Relaxed) {
+            if let Err(error) = self.poller.wait(&mut ready, TICK) {
+                return eprintln!("Could not wait for connections {error:?}");
+            }
+
+            self.receive_registrations();
+            ready.append(&mut self.lock_woken());
+
+            for token in ready.drain(..) {
+                self.poll(token);
+            }
+
+            if last_tick.elapsed() >= TICK {
+                self.poll_all();
+                last_tick = Instant::now();
+            }
+        }
+
+        self.shut_down(ready);
+    }
+
+    /// Keeps polling every connection until they all closed or the deadline passed,
+    /// closing whichever are left.
+    fn shut_down(&mut self, mut ready: Vec<u64>) {
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+
+        loop {
+            self.receive_registrations();
+            self.poll_all();
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if self.registrations.is_empty() || remaining.is_zero() {
+                break;
+            }
+
+            if self.poller.wait(&mut ready, remaining.min(TICK)).is_err() {
+                break;
+            }
+            ready.clear();
+        }
+
+        let tokens: Vec<u64> = self.registrations.keys().copied().collect();
+        for token in tokens {
+            self.close(token);
+        }
+    }
+
+    /// Starts watching new connections, polling each one since it may already have input.
+    fn receive_registrations(&mut self) {
+        while let Ok(mut registration) = self.incoming.try_recv() {
+            let token = registration.token;
+            if let Err(error) = self.poller.add(&registration.stream, token) {
+                eprintln!("Could not watch connection {error:?}");
+                self.connections.remove(registration.address);
+                continue;
+            }
+
+            registration.task.set_waker(self.waker(token));
+            self.registrations.insert(token, registration);
+            self.poll(token);
+        }
+    }
+
+    fn poll_all(&mut self) {
+        let tokens: Vec<u64> = self.registrations.keys().copied().collect();
+        for token in tokens {
+            self.poll(token);
+        }
+    }
+
+    /// Polls connection, following every handover until it waits for input or ends.
+    /// A task that panics is aborted and closed, leaving every other connection of the worker running.
+    fn poll(&mut self, token: u64) {
+        let waker = self.waker(token);
+        let registration = some_or_return!(self.registrations.get_mut(&token));
+
+        loop {
+            let task = &mut registration.task;
+            match panic::catch_unwind(AssertUnwindSafe(|| task.poll())) {
+                Ok(Progress::Pending) => return,
+                Ok(Progress::Handover(task)) => {
+                    registration.task = task;
+                    registration.task.set_waker(waker.clone());
+                }
+                Ok(Progress::Closed) => break,
+                Err(_) => {
+                    eprintln!("Connection from {} panicked", registration.address);
+                    let task = &mut registration.task;
+                    panic::catch_unwind(AssertUnwindSafe(|| task.abort())).ok();
+                    break;
+                }
+            }
+        }
+
+        self.close(token);
+    }
+
+    /// Returns what lets other threads have the connection polled.
+    fn waker(&self, token: u64) -> Waker {
+        Waker {
+            token,
+            woken: Arc::clone(&self.woken),
+            poller: Arc::clone(&self.poller),
+        }
+    }
+
+    fn lock_woken(&self) -> MutexGuard<'_, Vec<u64>> {
+        self.woken
+            .lock()
+            .expect("Woken tokens lock should not be poisoned")
+    }
+
+    fn close(&mut self, token: u64) {
+        let registration = some_or_return!(self.registrations.remove(&token));
+        self.poller.delete(&registration.stream, token).ok();
+        registration.stream.shutdown(Shutdown::Both).ok();
+        self.connections.remove(registration.address);
+    }
+}
@@ -1,13 +1,17 @@
-use std::fmt::Display;
+use crate::message::{CommandError, Message, ParsingError, ToMessage};
 
 /// Error replies the server may generate for client commands.
 pub enum ErrorReply {
     UnknownError400 { command: String, message: String },
     NoSuchNickname401 { nickname: String },
     NoSuchChannel403 { channel: String },
+    NoOrigin409,
+    InvalidCapCommand410 { command: String },
+    NoMotd422,
     TooManyChannels405 { channel: String },
     NoRecipient411 { command: String },
     NoTextToSend412,
+    InputTooLong417,
     UnknownCommand421 { command: String },
     NoNicknameGiven431,
     NicknameInUse433 { nickname: String },
@@ -17,6 +21,7 @@ pub enum ErrorReply {
     NeedMoreParameters461 { command: String },
     AlreadyRegistered462,
     PasswordMismatch464,
+    YoureBannedCreep465,
     BadChannelKey475 { channel: String },
     NoNickname,
     NotRegistered451,
@@ -30,90 +35,176 @@ pub enum ErrorReply {
     BannedFromChannel474 { channel: String },
     NoReply,
     NoPrivileges481,
+    CantKillServer483,
     UsersDontMatch502,
     UserModeUnknownFlag501,
     InviteOnlyChannel473 { channel: String },
     ErroneousNickname432 { nickname: String },
+    SaslFail904,
+    SaslAborted906,
+    SaslAlready907,
 }
 
-impl Display for ErrorReply {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
-            ErrorReply::NoSuchNickname401 { nickname } => {
-                format!("401 {nickname} :No such nick/channel")
-            }
-            ErrorReply::NoSuchChannel403 { channel } => {
-                format!("403 {channel} :No such channel")
-            }
-            ErrorReply::TooManyChannels405 { channel } => {
-                format!("405 {channel} :You have joined too many channels")
-            }
-            ErrorReply::NoRecipient411 { command } => {
-                format!("411 :No recipient given ({command})")
-            }
-            ErrorReply::NoTextToSend412 => String::from("412 :No text to send"),
-            ErrorReply::UnknownCommand421 { command } => {
-                format!("421 {command} :Unknown command")
-            }
-            ErrorReply::NoNicknameGiven431 => "431 :No nickname given".to_string(),
-            ErrorReply::NicknameInUse433 { nickname } => {
-                format!("433 {nickname} :Nickname is already in use")
-            }
-            ErrorReply::NickCollision436 { nickname } => {
-                format!("436 {nickname} :Nickname collision KILL")
-            }
-            ErrorReply::NotOnChannel442 { channel } => {
-                format!("442 {channel} :You're not on that channel")
-            }
-            ErrorReply::UserOnChannel443 { nickname, channel } => {
-                format!("443 {nickname} {channel} :Is already on channel")
-            }
-            ErrorReply::NeedMoreParameters461 { command } => {
-                format!("461 {command} :Not enough parameters")
-            }
-            ErrorReply::AlreadyRegistered462 => "462 :You may not reregister".to_string(),
-            ErrorReply::PasswordMismatch464 => "464 :Password incorrect".to_string(),
-            ErrorReply::NoNickname => "200 :No nickname registered".to_string(),
-            ErrorReply::NotRegistered451 => "451 :You have not registered".to_string(),
-            ErrorReply::ParsingError => "200 :Parsing error".to_string(),
-            ErrorReply::UnknownMode472 { mode } => {
-                format!("472 {mode} :Is unknown mode char to me")
-            }
-            ErrorReply::KeySet467 { channel } => {
-                format!("467 {channel} :Channel key already set")
-            }
-            ErrorReply::ChanOPrivIsNeeded482 { channel } => {
-                format!("482 {channel} :You're not channel operator")
-            }
-            ErrorReply::CannotSendToChannel404 { channel } => {
-                format!("404 {channel} :Cannot send to channel")
-            }
-            ErrorReply::BadChannelKey475 { channel } => {
-                format!("475 {channel} :Cannot join channel (+k)")
-            }
-            ErrorReply::ChannelIsFull471 { channel } => {
-                format!("471 {channel} :Cannot join channel (+l)")
-            }
-            ErrorReply::BannedFromChannel474 { channel } => {
-                format!("474 {channel} :Cannot join channel (+b)")
-            }
-            ErrorReply::NoReply => "".to_string(),
-            ErrorReply::UnknownError400 { command, message } => {
-                format!("400 {command} :{message}")
-            }
-            ErrorReply::NoPrivileges481 => {
-                "481 :Permission Denied- You're not an IRC operator".to_string()
-            }
-            ErrorReply::NoSuchServer402 { server } => format!("402 {server} :No such server"),
-            ErrorReply::UsersDontMatch502 => "502 :Cant change mode for other users".to_string(),
-            ErrorReply::UserModeUnknownFlag501 => "501 :Unknown MODE flag".to_string(),
-            ErrorReply::InviteOnlyChannel473 { channel } => {
-                format!("473 {channel} :Cannot join channel (+i)")
-            }
-            ErrorReply::ErroneousNickname432 { nickname } => {
-                format!("432 {nickname} :Erroneous nickname")
-            }
+impl ToMessage for ErrorReply {
+    /// Fails for [`ErrorReply::NoReply`], which is never sent.
+    fn to_message(&self) -> Result<Message, ParsingError> {
+        let message = Message::builder();
+
+        let message = match self {
+            ErrorReply::NoSuchNickname401 { nickname } => message
+                .command("401")
+                .param(nickname)
+                .trailing("No such nick/channel"),
+            ErrorReply::NoSuchChannel403 { channel } => message
+                .command("403")
+                .param(channel)
+                .trailing("No such channel"),
+            ErrorReply::NoOrigin409 => message.command("409").trailing("No origin specified"),
+            ErrorReply::InvalidCapCommand410 { command } => message
+                .command("410")
+                .param(command)
+                .trailing("Invalid CAP command"),
+            ErrorReply::NoMotd422 => message.command("422").trailing("MOTD File is missing"),
+            ErrorReply::TooManyChannels405 { channel } => message
+                .command("405")
+                .param(channel)
+                .trailing("You have joined too many channels"),
+            ErrorReply::NoRecipient411 { command } => message
+                .command("411")
+                .trailing(format!("No recipient given ({command})")),
+            ErrorReply::NoTextToSend412 => message.command("412").trailing("No text to send"),
+            ErrorReply::InputTooLong417 => {
+                message.command("417").trailing("Input line was too long")
+            }
+            ErrorReply::UnknownCommand421 { command } => message
+                .command("421")
+                .param(command)
+                .trailing("Unknown command"),
+            ErrorReply::NoNicknameGiven431 => message.command("431").trailing("No nickname given"),
+            ErrorReply::NicknameInUse433 { nickname } => message
+                .command("433")
+                .param(nickname)
+                .trailing("Nickname is already in use"),
+            ErrorReply::NickCollision436 { nickname } => message
+                .command("436")
+                .param(nickname)
+                .trailing("Nickname collision KILL"),
+            ErrorReply::NotOnChannel442 { channel } => message
+                .command("442")
+                .param(channel)
+                .trailing("You're not on that channel"),
+            ErrorReply::UserOnChannel443 { nickname, channel } => message
+                .command("443")
+                .param(nickname)
+                .param(channel)
+                .trailing("Is already on channel"),
+            ErrorReply::NeedMoreParameters461 { command } => message
+                .command("461")
+                .param(command)
+                .trailing("Not enough parameters"),
+            ErrorReply::AlreadyRegistered462 => {
+                message.command("462").trailing("You may not reregister")
+            }
+            ErrorReply::PasswordMismatch464 => {
+                message.command("464").trailing("Password incorrect")
+            }
+            ErrorReply::YoureBannedCreep465 => message
+                .command("465")
+                .trailing("You are banned from this server"),
+            ErrorReply::NoNickname => message.command("200").trailing("No nickname registered"),
+            ErrorReply::NotRegistered451 => {
+                message.command("451").trailing("You have not registered")
+            }
+            ErrorReply::ParsingError => message.command("200").trailing("Parsing error"),
+            ErrorReply::UnknownMode472 { mode } => message
+                .command("472")
+                .param(mode)
+                .trailing("Is unknown mode char to me"),
+            ErrorReply::KeySet467 { channel } => message
+                .command("467")
+                .param(channel)
+                .trailing("Channel key already set"),
+            ErrorReply::ChanOPrivIsNeeded482 { channel } => message
+                .command("482")
+                .param(channel)
+                .trailing("You're not channel operator"),
+            ErrorReply::CannotSendToChannel404 { channel } => message
+                .command("404")
+                .param(channel)
+                .trailing("Cannot send to channel"),
+            ErrorReply::BadChannelKey475 { channel } => message
+                .command("475")
+                .param(channel)
+                .trailing("Cannot join channel (+k)"),
+            ErrorReply::ChannelIsFull471 { channel } => message
+                .command("471")
+                .param(channel)
+                .trailing("Cannot join channel (+l)"),
+            ErrorReply::BannedFromChannel474 { channel } => message
+                .command("474")
+                .param(channel)
+                .trailing("Cannot join channel (+b)"),
+            ErrorReply::NoReply => return Err(ParsingError::EmptyMessage),
+            ErrorReply::UnknownError400 {
+                command,
+                message: text,
+            } => message.command("400").param(command).trailing(text),
+            ErrorReply::CantKillServer483 => {
+                message.command("483").trailing("You can't kill a server!")
+            }
+            ErrorReply::NoPrivileges481 => message
+                .command("481")
+                .trailing("Permission Denied- You're not an IRC operator"),
+            ErrorReply::NoSuchServer402 { server } => message
+                .command("402")
+                .param(server)
+                .trailing("No such server"),
+            ErrorReply::UsersDontMatch502 => message
+                .command("502")
+                .trailing("Cant change mode for other users"),
+            ErrorReply::UserModeUnknownFlag501 => {
+                message.command("501").trailing("Unknown MODE flag")
+            }
+            ErrorReply::InviteOnlyChannel473 { channel } => message
+                .command("473")
+                .param(channel)
+                .trailing("Cannot join channel (+i)"),
+            ErrorReply::ErroneousNickname432 { nickname } => message
+                .command("432")
+                .param(nickname)
+                .trailing("Erroneous nickname"),
+            ErrorReply::SaslFail904 => message
+                .command("904")
+                .trailing("SASL authentication failed"),
+            ErrorReply::SaslAborted906 => message
+                .command("906")
+                .trailing("SASL authentication aborted"),
+            ErrorReply::SaslAlready907 => message
+                .command("907")
+                .trailing("You have already authenticated using SASL"),
         };
-        write!(f, "{string}")
+
+        message.build()
+    }
+}
+
+impl From<CommandError> for ErrorReply {
+    fn from(error: CommandError) -> Self {
+        match error {
+            CommandError::UnknownCommand { command } => ErrorReply::UnknownCommand421 { command },
+            CommandError::NeedMoreParameters { command } => {
+                ErrorReply::NeedMoreParameters461 { command }
+            }
+            CommandError::NoNicknameGiven => ErrorReply::NoNicknameGiven431,
+            CommandError::NoRecipient { command } => ErrorReply::NoRecipient411 { command },
+            CommandError::NoTextToSend => ErrorReply::NoTextToSend412,
+            CommandError::NoOrigin => ErrorReply::NoOrigin409,
+            CommandError::InvalidCapCommand { subcommand } => ErrorReply::InvalidCapCommand410 {
+                command: subcommand,
+            },
+            CommandError::InvalidParameter { command, message } => {
+                ErrorReply::UnknownError400 { command, message }
+            }
+        }
     }
 }
@@ -4,17 +4,10 @@ pub mod errors;
 pub mod notifications;
 /// This module contains replies valid command's may generate.
 pub mod replies;
+/// This module contains the framing numeric replies need before being sent.
+pub mod reply;
 
 pub use errors::ErrorReply;
 pub use notifications::Notification;
-pub use replies::CommandResponse;
-
-fn to_trail(message: &Option<String>) -> String {
-    message
-        .clone()
-        .map(|mut string| {
-            string.insert(0, ':');
-            string
-        })
-        .unwrap_or_default()
-}
+pub use replies::{format_server_time, CommandResponse};
+pub use reply::ServerReply;
@@ -1,11 +1,8 @@
-use std::fmt::Display;
-
 use crate::macros::own;
-use crate::server::consts::commands::*;
+use crate::message::command::names::*;
+use crate::message::{Message, ParsingError, ToMessage};
 use crate::server::data_structures::*;
 
-use super::to_trail;
-
 /// Notifications that can be sent for different commands.
 pub enum Notification {
     Quit {
@@ -44,6 +41,7 @@ pub enum Notification {
     Nick {
         nickname: String,
         hopcount: usize,
+        signon: u64,
     },
     NickUpdate {
         old_nickname: String,
@@ -76,107 +74,197 @@ pub enum Notification {
         servername: String,
         comment: Option<String>,
     },
+    Ping {
+        servername: String,
+    },
+    Pass {
+        password: String,
+    },
+    Pong {
+        servername: String,
+        token: String,
+    },
+    Kill {
+        killer: String,
+        victim: String,
+        comment: String,
+    },
+    Cap {
+        servername: String,
+        target: String,
+        subcommand: String,
+        capabilities: String,
+    },
+    Authenticate {
+        data: String,
+    },
+    Account {
+        nickname: String,
+        account: Option<String>,
+    },
 }
 
-impl Display for Notification {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
-            Notification::Quit { nickname, message } => {
-                format!(":{nickname} {QUIT_COMMAND} :{message}")
-            }
+impl ToMessage for Notification {
+    fn to_message(&self) -> Result<Message, ParsingError> {
+        let message = Message::builder();
+
+        let message = match self {
+            Notification::Quit {
+                nickname,
+                message: text,
+            } => message
+                .prefix(nickname)
+                .command(QUIT_COMMAND)
+                .trailing(text),
             Notification::Invite {
                 inviting_client,
                 invited_client,
                 channel,
-            } => {
-                format!(":{inviting_client} {INVITE_COMMAND} {invited_client} {channel}")
-            }
+            } => message
+                .prefix(inviting_client)
+                .command(INVITE_COMMAND)
+                .param(invited_client)
+                .param(channel),
             Notification::Privmsg {
-                sender: prefix,
+                sender,
                 target,
-                message,
-            } => {
-                format!(":{prefix} {PRIVMSG_COMMAND} {target} :{message}")
-            }
+                message: text,
+            } => message
+                .prefix(sender)
+                .command(PRIVMSG_COMMAND)
+                .param(target)
+                .trailing(text),
             Notification::Notice {
-                sender: prefix,
+                sender,
                 target,
-                message,
-            } => {
-                format!(":{prefix} {NOTICE_COMMAND} {target} :{message}")
-            }
+                message: text,
+            } => message
+                .prefix(sender)
+                .command(NOTICE_COMMAND)
+                .param(target)
+                .trailing(text),
             Notification::Kick {
                 kicker,
                 channel,
                 kicked,
                 comment,
-            } => {
-                format!(
-                    ":{kicker} {KICK_COMMAND} {channel} {kicked} {}",
-                    to_trail(comment)
-                )
-            }
-            Notification::Part { nickname, channel } => {
-                format!(":{nickname} {PART_COMMAND} {channel}")
-            }
-            Notification::Join { nickname, channel } => {
-                format!(":{nickname} {JOIN_COMMAND} {channel}")
-            }
-            Notification::Nick { nickname, hopcount } => {
-                format!("{NICK_COMMAND} {nickname} {hopcount}")
-            }
-            Notification::User { client } => {
-                format!(
-                    ":{} {USER_COMMAND} {} {} {} :{}",
-                    client.nickname,
-                    client.username,
-                    client.hostname,
-                    client.servername,
-                    client.realname
-                )
-            }
+            } => message
+                .prefix(kicker)
+                .command(KICK_COMMAND)
+                .param(channel)
+                .param(kicked)
+                .optional_trailing(comment.as_ref()),
+            Notification::Part { nickname, channel } => message
+                .prefix(nickname)
+                .command(PART_COMMAND)
+                .param(channel),
+            Notification::Join { nickname, channel } => message
+                .prefix(nickname)
+                .command(JOIN_COMMAND)
+                .param(channel),
+            Notification::Nick {
+                nickname,
+                hopcount,
+                signon,
+            } => message
+                .command(NICK_COMMAND)
+                .param(nickname)
+                .param(hopcount)
+                .param(signon),
+            Notification::User { client } => message
+                .prefix(&client.nickname)
+                .command(USER_COMMAND)
+                .param(&client.username)
+                .param(&client.hostname)
+                .param(&client.servername)
+                .trailing(&client.realname),
             Notification::Server {
                 servername,
                 hopcount,
                 serverinfo,
-            } => {
-                format!("{SERVER_COMMAND} {servername} {hopcount} :{serverinfo}")
-            }
+            } => message
+                .command(SERVER_COMMAND)
+                .param(servername)
+                .param(hopcount)
+                .trailing(serverinfo),
             Notification::NickUpdate {
                 old_nickname,
                 new_nickname,
-            } => format!(":{old_nickname} NICK {new_nickname}"),
-            Notification::Away { nickname, message } => {
-                format!(":{nickname} {AWAY_COMMAND} {}", to_trail(message))
-            }
-            Notification::Topic {
+            } => message
+                .prefix(old_nickname)
+                .command(NICK_COMMAND)
+                .param(new_nickname),
+            Notification::Away {
                 nickname,
+                message: text,
+            } => message
+                .prefix(nickname)
+                .command(AWAY_COMMAND)
+                .optional_trailing(text.as_ref()),
+            Notification::Topic {
                 channel,
                 topic,
-            } => {
-                format!(":{nickname} {TOPIC_COMMAND} {channel} {topic}")
-            }
+                nickname,
+            } => message
+                .prefix(nickname)
+                .command(TOPIC_COMMAND)
+                .param(channel)
+                .trailing(topic),
             Notification::Mode {
                 sender,
                 target,
                 request,
-            } => {
-                format!(":{sender} {MODE_COMMAND} {target} {request}",)
-            }
+            } => message
+                .prefix(sender)
+                .command(MODE_COMMAND)
+                .param(target)
+                .params(request.split_whitespace()),
             Notification::SQuit {
                 sender,
                 servername,
                 comment,
-            } => {
-                format!(
-                    "{} {SQUIT_COMMAND} {servername} {}",
-                    to_trail(sender),
-                    to_trail(comment)
-                )
+            } => message
+                .optional_prefix(sender.as_ref())
+                .command(SQUIT_COMMAND)
+                .param(servername)
+                .optional_trailing(comment.as_ref()),
+            Notification::Ping { servername } => message.command(PING_COMMAND).trailing(servername),
+            Notification::Pass { password } => message.command(PASS_COMMAND).param(password),
+            Notification::Pong { servername, token } => message
+                .prefix(servername)
+                .command(PONG_COMMAND)
+                .param(servername)
+                .trailing(token),
+            Notification::Kill {
+                killer,
+                victim,
+                comment,
+            } => message
+                .prefix(killer)
+                .command(KILL_COMMAND)
+                .param(victim)
+                .trailing(comment),
+            Notification::Cap {
+                servername,
+                target,
+                subcommand,
+                capabilities,
+            } => message
+                .prefix(servername)
+                .command(CAP_COMMAND)
+                .param(target)
+                .param(subcommand)
+                .trailing(capabilities),
+            Notification::Authenticate { data } => {
+                message.command(AUTHENTICATE_COMMAND).param(data)
             }
+            Notification::Account { nickname, account } => message
+                .prefix(nickname)
+                .command(ACCOUNT_COMMAND)
+                .param(account.as_deref().unwrap_or(LOGGED_OUT_ACCOUNT)),
         };
 
-        write!(f, "{string}")
+        message.build()
     }
 }
 
@@ -212,9 +300,13 @@ impl Notification {
         Notification::User { client }
     }
 
-    pub fn nick(nickname: &str, hopcount: usize) -> Self {
+    pub fn nick(nickname: &str, hopcount: usize, signon: u64) -> Self {
         let nickname = nickname.to_string();
-        Notification::Nick { nickname, hopcount }
+        Notification::Nick {
+            nickname,
+            hopcount,
+            signon,
+        }
     }
 
     pub fn nick_update(old_nickname: &str, new_nickname: &str) -> Self {
@@ -318,4 +410,49 @@ impl Notification {
             comment,
         }
     }
+
+    pub fn ping(servername: &str) -> Self {
+        let servername = servername.to_string();
+        Notification::Ping { servername }
+    }
+
+    pub fn pass(password: &str) -> Self {
+        let password = password.to_string();
+        Notification::Pass { password }
+    }
+
+    pub fn pong(servername: &str, token: &str) -> Self {
+        own!(servername, token);
+        Notification::Pong { servername, token }
+    }
+
+    pub fn kill(killer: &str, victim: &str, comment: &str) -> Self {
+        own!(killer, victim, comment);
+        Notification::Kill {
+            killer,
+            victim,
+            comment,
+        }
+    }
+
+    pub fn cap(servername: &str, target: &str, subcommand: &str, capabilities: &str) -> Self {
+        own!(servername, target, subcommand, capabilities);
+        Notification::Cap {
+            servername,
+            target,
+            subcommand,
+            capabilities,
+        }
+    }
+
+    pub fn authenticate(data: &str) -> Self {
+        own!(data);
+        Notification::Authenticate { data }
+    }
+
+    pub fn account(nickname: &str, account: &Option<String>) -> Self {
+        let nickname = nickname.to_string();
+        let account = account.clone();
+        Notification::Account { nickname, account }
+    }
 }
@@ -1,11 +1,12 @@
-use std::fmt::Display;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::{
     macros::own,
-    server::{
-        consts::{commands::QUIT_COMMAND, user_flag::UserFlag},
-        data_structures::ClientInfo,
+    message::{
+        command::names::{ERROR_COMMAND, QUIT_COMMAND},
+        Message, MessageBuilder, ParsingError, ToMessage,
     },
+    server::{consts::user_flag::UserFlag, data_structures::ClientInfo},
 };
 
 /// Responses the server can generate for valid client commands.
@@ -27,6 +28,11 @@ pub enum CommandResponse {
     EndOfWhois318 {
         nickname: String,
     },
+    WhoisSendq320 {
+        nickname: String,
+        queued: usize,
+        limit: usize,
+    },
     WhoisChannels319 {
         nickname: String,
         channels: Vec<String>,
@@ -61,6 +67,9 @@ pub enum CommandResponse {
         channel: String,
     },
     YouAreOper381,
+    Rehashing382 {
+        file: String,
+    },
     BanList367 {
         channel: String,
         banmask: String,
@@ -82,148 +91,350 @@ pub enum CommandResponse {
     Quit {
         message: String,
     },
+    Error {
+        message: String,
+    },
     UserModeIs221 {
         user_modes: String,
     },
     Welcome001 {
         client: ClientInfo,
     },
+    YourHost002 {
+        servername: String,
+        version: String,
+    },
+    Created003 {
+        created: SystemTime,
+    },
+    MyInfo004 {
+        servername: String,
+        version: String,
+        user_modes: String,
+        channel_modes: String,
+    },
+    ISupport005 {
+        tokens: Vec<String>,
+    },
+    MotdStart375 {
+        servername: String,
+    },
+    Motd372 {
+        line: String,
+    },
+    EndOfMotd376,
+    WhoisAccount330 {
+        nickname: String,
+        account: String,
+    },
+    LoggedIn900 {
+        mask: String,
+        account: String,
+    },
+    SaslSuccess903,
+    SaslMechanisms908 {
+        mechanisms: String,
+    },
 }
 
-impl Display for CommandResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
-            CommandResponse::WhoisUser311 { client_info } => {
-                format!(
-                    "311 {} {} {} *: {}",
-                    client_info.nickname,
-                    client_info.username,
-                    client_info.hostname,
-                    client_info.realname,
-                )
-            }
+impl ToMessage for CommandResponse {
+    fn to_message(&self) -> Result<Message, ParsingError> {
+        let message = Message::builder();
+
+        let message = match self {
+            CommandResponse::WhoisUser311 { client_info } => message
+                .command("311")
+                .param(&client_info.nickname)
+                .param(&client_info.username)
+                .param(&client_info.hostname)
+                .param("*")
+                .trailing(&client_info.realname),
             CommandResponse::WhoisServer312 {
                 nickname,
                 server,
                 server_info,
-            } => {
-                format!("312 {nickname} {server} :{server_info}")
-            }
-            CommandResponse::WhoisOperator313 { nickname } => {
-                format!("313 {nickname} :Is an IRC operator")
-            }
-            CommandResponse::EndOfWho315 { name } => {
-                format!(
-                    "315 {} :End of /WHO list",
-                    name.to_owned().unwrap_or_default()
-                )
-            }
-
-            CommandResponse::EndOfWhois318 { nickname } => {
-                format!("318 {nickname} :End of /WHOIS list")
-            }
-            CommandResponse::WhoisChannels319 { nickname, channels } => {
-                format!("319 {nickname} : {}", channels.join(" "))
-            }
-            CommandResponse::ListStart321 => "321 :Channel :Users Name".to_string(),
+            } => message
+                .command("312")
+                .param(nickname)
+                .param(server)
+                .trailing(server_info),
+            CommandResponse::WhoisOperator313 { nickname } => message
+                .command("313")
+                .param(nickname)
+                .trailing("Is an IRC operator"),
+            CommandResponse::EndOfWho315 { name } => message
+                .command("315")
+                .optional_param(name.as_ref())
+                .trailing("End of /WHO list"),
+            CommandResponse::EndOfWhois318 { nickname } => message
+                .command("318")
+                .param(nickname)
+                .trailing("End of /WHOIS list"),
+            CommandResponse::WhoisSendq320 {
+                nickname,
+                queued,
+                limit,
+            } => message
+                .command("320")
+                .param(nickname)
+                .trailing(format!("SendQ {queued}/{limit} bytes")),
+            CommandResponse::WhoisChannels319 { nickname, channels } => message
+                .command("319")
+                .param(nickname)
+                .trailing(format!(" {}", channels.join(" "))),
+            CommandResponse::ListStart321 => message.command("321").trailing("Channel :Users Name"),
             CommandResponse::List322 {
                 channel,
                 topic,
                 prv,
             } => {
+                let message = message.command("322").param(channel);
                 if *prv {
-                    format!("322 {channel} Prv")
+                    message.param("Prv")
                 } else {
-                    format!("322 {channel} :{topic}")
+                    message.trailing(topic)
                 }
             }
-            CommandResponse::ListEnd323 => "323 :End of /LIST".to_string(),
-            CommandResponse::NoTopic331 { channel } => {
-                format!("331 {channel} :No topic is set")
-            }
+            CommandResponse::ListEnd323 => message.command("323").trailing("End of /LIST"),
+            CommandResponse::NoTopic331 { channel } => message
+                .command("331")
+                .param(channel)
+                .trailing("No topic is set"),
             CommandResponse::Topic332 { channel, topic } => {
-                format!("332 {channel} :{topic}")
+                message.command("332").param(channel).trailing(topic)
             }
             CommandResponse::Inviting341 { channel, nickname } => {
-                format!("341 {channel} {nickname}")
+                message.command("341").param(channel).param(nickname)
             }
             CommandResponse::WhoReply352 {
                 channel,
                 client_info,
-            } => build_whoreply_message(client_info, channel),
-            CommandResponse::NameReply353 { channel, clients } => {
-                format!("353 {channel} :{}", clients.join(" "))
-            }
-            CommandResponse::EndOfNames366 { channel } => {
-                format!("366 {channel} :End of /NAMES list")
+            } => build_whoreply_message(message, client_info, channel),
+            CommandResponse::NameReply353 { channel, clients } => message
+                .command("353")
+                .param(channel)
+                .trailing(clients.join(" ")),
+            CommandResponse::EndOfNames366 { channel } => message
+                .command("366")
+                .param(channel)
+                .trailing("End of /NAMES list"),
+            CommandResponse::YouAreOper381 => message
+                .command("381")
+                .trailing("You are now an IRC operator"),
+            CommandResponse::Rehashing382 { file } => {
+                message.command("382").param(file).trailing("Rehashing")
             }
-            CommandResponse::YouAreOper381 => "381 :You are now an IRC operator".to_string(),
             CommandResponse::BanList367 { channel, banmask } => {
-                format!("367 {channel} {banmask}")
-            }
-            CommandResponse::EndOfBanList368 { channel } => {
-                format!("368 {channel} :End of channel ban list")
+                message.command("367").param(channel).param(banmask)
             }
+            CommandResponse::EndOfBanList368 { channel } => message
+                .command("368")
+                .param(channel)
+                .trailing("End of channel ban list"),
             CommandResponse::ChannelModeIs324 {
                 channel,
                 mode,
                 mode_params,
-            } => format!(
-                "324 {channel} {mode} {}",
-                mode_params.as_ref().unwrap_or(&vec![]).join(",")
-            ),
-            CommandResponse::UnAway => "305 :You are no longer marked as being away".to_string(),
-            CommandResponse::NowAway => "306 :You have been marked as being away".to_string(),
-            CommandResponse::Away { nickname, message } => {
-                format!("301 {nickname} :{message}")
-            }
-            CommandResponse::Quit { message } => {
-                format!("{QUIT_COMMAND} :{message}")
+            } => message
+                .command("324")
+                .param(channel)
+                .param(mode)
+                .optional_param(
+                    mode_params
+                        .as_ref()
+                        .map(|params| params.join(","))
+                        .filter(|params| !params.is_empty()),
+                ),
+            CommandResponse::UnAway => message
+                .command("305")
+                .trailing("You are no longer marked as being away"),
+            CommandResponse::NowAway => message
+                .command("306")
+                .trailing("You have been marked as being away"),
+            CommandResponse::Away {
+                nickname,
+                message: text,
+            } => message.command("301").param(nickname).trailing(text),
+            CommandResponse::Quit { message: text } => message.command(QUIT_COMMAND).trailing(text),
+            CommandResponse::Error { message: text } => {
+                message.command(ERROR_COMMAND).trailing(text)
             }
-            CommandResponse::UserModeIs221 { user_modes } => {
-                format!("221 {user_modes}")
+            CommandResponse::UserModeIs221 { user_modes } => message
+                .command("221")
+                .optional_param(Some(user_modes).filter(|modes| !modes.is_empty())),
+            CommandResponse::Welcome001 { client } => message.command("001").trailing(format!(
+                "Welcome to the Internet Relay Network {}!{}@{}",
+                client.nickname, client.username, client.hostname
+            )),
+            CommandResponse::YourHost002 {
+                servername,
+                version,
+            } => message.command("002").trailing(format!(
+                "Your host is {servername}, running version {version}"
+            )),
+            CommandResponse::Created003 { created } => message
+                .command("003")
+                .trailing(format!("This server was created {}", format_date(created))),
+            CommandResponse::MyInfo004 {
+                servername,
+                version,
+                user_modes,
+                channel_modes,
+            } => message
+                .command("004")
+                .param(servername)
+                .param(version)
+                .param(user_modes)
+                .param(channel_modes),
+            CommandResponse::ISupport005 { tokens } => message
+                .command("005")
+                .params(tokens)
+                .trailing("are supported by this server"),
+            CommandResponse::MotdStart375 { servername } => message
+                .command("375")
+                .trailing(format!("- {servername} Message of the day -")),
+            CommandResponse::Motd372 { line } => {
+                message.command("372").trailing(format!("- {line}"))
             }
-            CommandResponse::Welcome001 { client } => {
-                format!(
-                    "001 {} :Welcome to {} Network, {} !{} @{}",
-                    client.realname,
-                    client.servername,
-                    client.nickname,
-                    client.username,
-                    client.hostname
-                )
+            CommandResponse::EndOfMotd376 => {
+                message.command("376").trailing("End of /MOTD command")
             }
+            CommandResponse::WhoisAccount330 { nickname, account } => message
+                .command("330")
+                .param(nickname)
+                .param(account)
+                .trailing("is logged in as"),
+            CommandResponse::LoggedIn900 { mask, account } => message
+                .command("900")
+                .param(mask)
+                .param(account)
+                .trailing(format!("You are now logged in as {account}")),
+            CommandResponse::SaslSuccess903 => message
+                .command("903")
+                .trailing("SASL authentication successful"),
+            CommandResponse::SaslMechanisms908 { mechanisms } => message
+                .command("908")
+                .param(mechanisms)
+                .trailing("are available SASL mechanisms"),
         };
-        write!(f, "{string}")
+
+        message.build()
     }
 }
 
-fn build_whoreply_message(client_info: &ClientInfo, channel: &Option<String>) -> String {
-    let basic_info = format!(
-        "{} {} {} {}",
-        client_info.username, client_info.hostname, client_info.servername, client_info.nickname,
+/// Formats time as a UTC date, such as `2022-11-30 18:05:42 UTC`.
+fn format_date(time: &SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(time);
+
+    format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} UTC")
+}
+
+/// Formats time as the value of the `server-time` tag, such as `2022-11-30T18:05:42.123Z`.
+pub fn format_server_time(time: &SystemTime) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(time);
+    let millis = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_millis())
+        .unwrap_or_default();
+
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{millis:03}Z")
+}
+
+/// Splits time into UTC year, month, day, hour, minute and second.
+fn civil_time(time: &SystemTime) -> (u64, u64, u64, u64, u64, u64) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    let (hour, minute, second) = (
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
     );
 
+    // Converts days since epoch to a civil date, following Howard Hinnant's algorithm.
+    let z = days + 719468;
+    let era = z / 146097;
+    let doe = z % 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    (year, month, day, hour, minute, second)
+}
+
+fn build_whoreply_message(
+    message: MessageBuilder,
+    client_info: &ClientInfo,
+    channel: &Option<String>,
+) -> MessageBuilder {
     let flags = client_info
         .flags
         .keys()
         .map(UserFlag::to_char)
         .collect::<String>();
 
-    format!(
-        "352 {} {} {} :{} {}",
-        channel.as_ref().unwrap_or(&"*".to_string()),
-        basic_info,
-        flags,
-        client_info.hopcount,
-        client_info.realname,
-    )
+    message
+        .command("352")
+        .param(channel.as_deref().unwrap_or("*"))
+        .param(&client_info.username)
+        .param(&client_info.hostname)
+        .param(&client_info.servername)
+        .param(&client_info.nickname)
+        .optional_param(Some(flags).filter(|flags| !flags.is_empty()))
+        .trailing(format!("{} {}", client_info.hopcount, client_info.realname))
 }
 
 impl CommandResponse {
     pub fn welcome(client: ClientInfo) -> Self {
         CommandResponse::Welcome001 { client }
     }
+
+    pub fn your_host(servername: &str, version: &str) -> Self {
+        own!(servername, version);
+        Self::YourHost002 {
+            servername,
+            version,
+        }
+    }
+
+    pub fn created(created: SystemTime) -> Self {
+        Self::Created003 { created }
+    }
+
+    pub fn my_info(servername: &str, version: &str, user_modes: &str, channel_modes: &str) -> Self {
+        own!(servername, version, user_modes, channel_modes);
+        Self::MyInfo004 {
+            servername,
+            version,
+            user_modes,
+            channel_modes,
+        }
+    }
+
+    pub fn isupport(tokens: &[String]) -> Self {
+        let tokens = tokens.to_vec();
+        Self::ISupport005 { tokens }
+    }
+
+    pub fn motd_start(servername: &str) -> Self {
+        let servername = servername.to_string();
+        Self::MotdStart375 { servername }
+    }
+
+    pub fn motd(line: &str) -> Self {
+        let line = line.to_string();
+        Self::Motd372 { line }
+    }
+
+    pub fn end_of_motd() -> Self {
+        Self::EndOfMotd376
+    }
     pub fn channel_mode_is(channel: &str, mode: char, mode_params: Option<Vec<String>>) -> Self {
         let channel = channel.to_string();
 
@@ -254,6 +465,11 @@ impl CommandResponse {
         Self::YouAreOper381
     }
 
+    pub fn rehashing(file: &str) -> Self {
+        own!(file);
+        Self::Rehashing382 { file }
+    }
+
     pub fn end_of_who(name: Option<String>) -> Self {
         Self::EndOfWho315 { name }
     }
@@ -285,6 +501,15 @@ impl CommandResponse {
         Self::WhoisOperator313 { nickname }
     }
 
+    pub fn whois_sendq(nickname: &str, queued: usize, limit: usize) -> Self {
+        let nickname = nickname.to_string();
+        Self::WhoisSendq320 {
+            nickname,
+            queued,
+            limit,
+        }
+    }
+
     pub fn whois_server(nickname: &str, servername: &str, serverinfo: &str) -> Self {
         let nickname = nickname.to_string();
         let servername = servername.to_string();
@@ -361,4 +586,28 @@ impl CommandResponse {
         let message = message.to_string();
         Self::Quit { message }
     }
+
+    pub fn error(message: &str) -> Self {
+        let message = message.to_string();
+        Self::Error { message }
+    }
+
+    pub fn whois_account(nickname: &str, account: &str) -> Self {
+        own!(nickname, account);
+        Self::WhoisAccount330 { nickname, account }
+    }
+
+    pub fn logged_in(mask: &str, account: &str) -> Self {
+        own!(mask, account);
+        Self::LoggedIn900 { mask, account }
+    }
+
+    pub fn sasl_success() -> Self {
+        Self::SaslSuccess903
+    }
+
+    pub fn sasl_mechanisms(mechanisms: &str) -> Self {
+        own!(mechanisms);
+        Self::SaslMechanisms908 { mechanisms }
+    }
 }
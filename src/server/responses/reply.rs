@@ -0,0 +1,45 @@
+use crate::message::{Message, ParsingError, ToMessage};
+
+/// Numeric response framed as RFC 1459 requires, with the server name as prefix
+/// and the recipient's nickname as first parameter.
+///
+/// Responses that are not numeric (such as `QUIT` or `ERROR`) are left untouched.
+pub struct ServerReply {
+    servername: String,
+    nickname: String,
+    response: Result<Message, ParsingError>,
+}
+
+impl ServerReply {
+    pub fn new(servername: &str, nickname: &str, response: &dyn ToMessage) -> Self {
+        Self {
+            servername: servername.to_string(),
+            nickname: nickname.to_string(),
+            response: response.to_message(),
+        }
+    }
+}
+
+impl ToMessage for ServerReply {
+    fn to_message(&self) -> Result<Message, ParsingError> {
+        let response = self.response.clone()?;
+
+        if !is_numeric(response.get_command()) {
+            return Ok(response);
+        }
+
+        let (_, code, parameters, trailing) = response.unpack();
+
+        Message::builder()
+            .prefix(&self.servername)
+            .command(code)
+            .param(&self.nickname)
+            .params(parameters)
+            .optional_trailing(trailing)
+            .build()
+    }
+}
+
+fn is_numeric(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_digit())
+}
@@ -2,7 +2,7 @@ use std::io;
 
 use crate::{
     macros::{ok_or_return, some_or_return},
-    message::{CreationError, Message},
+    message::{command::names::SERVER_COMMAND, Command, CreationError, LineReader},
 };
 
 use super::{
@@ -10,11 +10,10 @@ use super::{
     consts::{
         channel::DISTRIBUTED_CHANNEL,
         channel_flag::ChannelFlag,
-        commands::SERVER_COMMAND,
         modes::{ADD_OPERATOR, SET_BANMASK, SET_KEY, SET_OPERATOR, SET_SPEAKER, SET_USER_LIMIT},
     },
     database::DatabaseHandle,
-    responses::{ErrorReply, Notification},
+    responses::{CommandResponse, ErrorReply, Notification, ServerReply},
 };
 
 use crate::server::data_structures::*;
@@ -24,41 +23,59 @@ use crate::server::data_structures::*;
 /// It is in charge of sharing all local information to the new server and registering incoming information in database.
 pub struct ServerConnectionSetup<C: Connection> {
     stream: C,
+    /// handed to the registered server, so nothing queued is lost
+    output: OutputQueue<C>,
     database: DatabaseHandle<C>,
     servername: String,
+    reader: LineReader,
 }
 
 impl<C: Connection> ServerConnectionSetup<C> {
-    /// Creates a [`ServerConnectionSetup`] from a connection stream
+    /// Creates a [`ServerConnectionSetup`] from a connection stream, the queue written through,
     ///   and a database in which to register the new connection.
-    pub fn new(stream: C, database: DatabaseHandle<C>) -> Self {
+    pub fn new(stream: C, output: OutputQueue<C>, database: DatabaseHandle<C>) -> Self {
         Self {
             stream,
+            output,
             database,
             servername: Default::default(),
+            reader: LineReader::new(),
         }
     }
 
-    /// Register as server to an outcoming connection
-    pub fn register_outcoming(&mut self) -> io::Result<()> {
+    /// Register as server to an outcoming connection, expected to be `servername`.
+    ///
+    /// Fails if there is no link password for that server.
+    pub fn register_outcoming(&mut self, servername: &str) -> io::Result<()> {
+        let password = self
+            .database
+            .get_link_password(servername)
+            .ok_or_else(unknown_link_error)?;
+
         let own_serverinfo = self.database.get_own_server_info();
         let own_servername = self.database.get_server_name();
+        self.send_pass_notification(&password)?;
         self.send_server_notification(own_servername, own_serverinfo)?;
-        self.receive_server_notification()?;
+        self.receive_server_notification(servername)?;
         self.send_server_data()
     }
 
     /// Register external server from an incoming connection.
+    ///
+    /// Fails if the password sent does not match the link password for that server.
     pub fn register_incoming(
         &mut self,
         servername: String,
         hopcount: usize,
         serverinfo: String,
+        password: Option<String>,
     ) -> io::Result<()> {
+        let link_password = self.assert_link_password_matches(&servername, &password)?;
         self.handle_server_command(servername, hopcount, serverinfo)?;
 
         let own_servername = self.database.get_server_name();
         let own_serverinfo = self.database.get_own_server_info();
+        self.send_pass_notification(&link_password)?;
         self.send_server_notification(own_servername, own_serverinfo)?;
         self.send_server_data()
     }
@@ -67,37 +84,82 @@ impl<C: Connection> ServerConnectionSetup<C> {
         self.servername.clone()
     }
 
+    /// Takes the reader used during setup, which may have buffered lines sent after registering.
+    pub fn take_reader(&mut self) -> LineReader {
+        std::mem::take(&mut self.reader)
+    }
+
     fn send_server_notification(
         &mut self,
         servername: String,
         serverinfo: String,
     ) -> io::Result<()> {
-        self.stream
+        self.output
             .send(&Notification::server(&servername, 1, &serverinfo))
     }
 
-    /// Waits for server command from incoming connection and handles it.
+    fn send_pass_notification(&mut self, password: &str) -> io::Result<()> {
+        self.output.send(&Notification::pass(password))
+    }
+
+    /// Waits for pass and server command from incoming connection and handles them.
     ///
-    /// Fails if server command is not valid or there was a parsing error.
-    fn receive_server_notification(&mut self) -> io::Result<()> {
-        let message = match Message::read_from(&mut self.stream) {
-            Ok(message) => message,
-            Err(error) => return Err(parse_creation_error(error)),
+    /// Fails if server command is not valid, it does not come from the expected server,
+    /// its password does not match or there was a parsing error.
+    fn receive_server_notification(&mut self, expected_servername: &str) -> io::Result<()> {
+        let mut command = self.read_command()?;
+
+        let mut password = None;
+        if let Command::Pass(pass) = command {
+            password = Some(pass.password);
+            command = self.read_command()?;
+        }
+
+        let server = match command {
+            Command::Server(server) if server.servername == expected_servername => server,
+            _ => return Err(invalid_input_error()),
         };
+        self.assert_link_password_matches(&server.servername, &password)?;
+
+        self.handle_server_command(server.servername, server.hopcount, server.info)
+    }
 
-        let (_, command, mut params, trail) = message.unpack();
-        assert_is_valid_server_message(&command, &params, &trail)?;
+    /// Writes everything queued so far, then reads next message and builds the command it carries.
+    fn read_command(&mut self) -> io::Result<Command> {
+        self.output.flush()?;
 
-        let hopcount = params
-            .remove(1)
-            .parse::<usize>()
-            .expect("Verified in assert");
+        let message = self
+            .reader
+            .read_message(&mut self.stream)
+            .map_err(parse_creation_error)?;
 
-        let servername = params.remove(0);
-        let serverinfo = trail.expect("Verified in assert");
-        self.handle_server_command(servername, hopcount, serverinfo)?;
+        Command::try_from(message).map_err(|_| invalid_input_error())
+    }
 
-        Ok(())
+    /// Returns the link password for the server, if it matches the one received.
+    ///
+    /// Otherwise, the connection is notified and closed.
+    fn assert_link_password_matches(
+        &mut self,
+        servername: &str,
+        password: &Option<String>,
+    ) -> io::Result<String> {
+        match self.database.get_link_password(servername) {
+            Some(link_password) if Some(&link_password) == password.as_ref() => Ok(link_password),
+            _ => {
+                let own_servername = self.database.get_server_name();
+                let error = ErrorReply::PasswordMismatch464;
+                self.output
+                    .send(&ServerReply::new(&own_servername, servername, &error))?;
+
+                let message = format!("Closing Link: {servername} (Bad password)");
+                self.output.send(&CommandResponse::error(&message))?;
+                self.output.flush()?;
+                self.stream.shutdown()?;
+
+                Err(password_mismatch_error())
+            }
+        }
     }
 
     /// Registers server from incoming connection.
@@ -110,7 +172,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
         serverinfo: String,
     ) -> Result<(), io::Error> {
         let server = ImmediateServer::new(
-            self.stream.try_clone()?,
+            self.output.clone(),
             servername.clone(),
             serverinfo,
             hopcount,
@@ -127,8 +189,10 @@ impl<C: Connection> ServerConnectionSetup<C> {
         if self.database.contains_server(servername) {
             let command = SERVER_COMMAND.to_string();
             let message = "Servername already registered".to_string();
-            self.stream
-                .send(&ErrorReply::UnknownError400 { command, message })?;
+            let error = ErrorReply::UnknownError400 { command, message };
+            let own_servername = self.database.get_server_name();
+            self.output
+                .send(&ServerReply::new(&own_servername, servername, &error))?;
 
             return Err(already_registered_error());
         }
@@ -151,6 +215,10 @@ impl<C: Connection> ServerConnectionSetup<C> {
                 self.send_oper_notification(&client)?;
             }
 
+            if client.account.is_some() {
+                self.send_account_notification(&client)?;
+            }
+
             if let Some(away) = client.away {
                 self.send_away_notification(&client.nickname, away)?;
             }
@@ -180,16 +248,24 @@ impl<C: Connection> ServerConnectionSetup<C> {
     }
 
     fn send_user_notification(&mut self, client: &ClientInfo) -> io::Result<()> {
-        self.stream.send(&Notification::user(client))
+        self.output.send(&Notification::user(client))
+    }
+
+    fn send_account_notification(&mut self, client: &ClientInfo) -> io::Result<()> {
+        self.output
+            .send(&Notification::account(&client.nickname, &client.account))
     }
 
     fn send_nick_notification(&mut self, client: &ClientInfo) -> io::Result<()> {
-        self.stream
-            .send(&Notification::nick(&client.nickname, client.hopcount))
+        self.output.send(&Notification::nick(
+            &client.nickname,
+            client.hopcount,
+            client.signon,
+        ))
     }
 
     fn send_oper_notification(&mut self, client: &ClientInfo) -> io::Result<()> {
-        self.stream.send(&Notification::mode(
+        self.output.send(&Notification::mode(
             &client.nickname,
             &client.nickname,
             ADD_OPERATOR,
@@ -197,7 +273,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
     }
 
     fn send_join_notification(&mut self, nickname: &str, channel: &str) -> io::Result<()> {
-        self.stream.send(&Notification::join(nickname, channel))
+        self.output.send(&Notification::join(nickname, channel))
     }
 
     pub(super) fn send_channel_mode_is_notification(&mut self, channel: &str) -> io::Result<()> {
@@ -233,7 +309,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{SET_SPEAKER} {speaker}");
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         }
         Ok(())
     }
@@ -248,7 +324,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{SET_BANMASK} {banmask}");
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         }
         Ok(())
     }
@@ -263,7 +339,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{SET_OPERATOR} {operator}");
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         }
         Ok(())
     }
@@ -278,7 +354,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{SET_KEY} {key}");
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         };
         Ok(())
     }
@@ -293,7 +369,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{SET_USER_LIMIT} {limit}");
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         };
         Ok(())
     }
@@ -308,7 +384,7 @@ impl<C: Connection> ServerConnectionSetup<C> {
             let request = format!("+{}", flag.to_char());
             let notification = Notification::mode(sender, channel, &request);
 
-            self.stream.send(&notification)?;
+            self.output.send(&notification)?;
         }
         Ok(())
     }
@@ -321,12 +397,12 @@ impl<C: Connection> ServerConnectionSetup<C> {
     ) -> io::Result<()> {
         let topic = some_or_return!(topic, Ok(()));
         let notification = Notification::topic(sender, channel, &topic);
-        self.stream.send(&notification)
+        self.output.send(&notification)
     }
 
     fn send_away_notification(&mut self, nickname: &str, away: String) -> io::Result<()> {
         let notification = Notification::away(nickname, &Some(away));
-        self.stream.send(&notification)
+        self.output.send(&notification)
     }
 }
 
@@ -337,26 +413,6 @@ fn parse_creation_error(err: CreationError) -> io::Error {
     }
 }
 
-fn assert_is_valid_server_message(
-    command: &str,
-    params: &Vec<String>,
-    trail: &Option<String>,
-) -> io::Result<()> {
-    if command != SERVER_COMMAND {
-        return Err(invalid_input_error());
-    }
-
-    if params.len() < 2 || trail.is_none() {
-        return Err(invalid_input_error());
-    }
-
-    if params[1].parse::<usize>().is_err() {
-        return Err(invalid_input_error());
-    }
-
-    Ok(())
-}
-
 fn invalid_input_error() -> io::Error {
     io::Error::new(
         io::ErrorKind::InvalidInput,
@@ -364,6 +420,20 @@ fn invalid_input_error() -> io::Error {
     )
 }
 
+fn unknown_link_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "There is no link password for server",
+    )
+}
+
+fn password_mismatch_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        "Link password does not match",
+    )
+}
+
 fn already_registered_error() -> io::Error {
     io::Error::new(io::ErrorKind::Unsupported, "Server is already registered")
 }
@@ -0,0 +1,262 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::server::channel_store::{
+    format_configuration, parse_configuration, push_entry, push_text, words, CONFIGURATION_KEYS,
+};
+use crate::server::config::{
+    parse_sections, read_if_exists, write_atomically, ConfigError, Entry, Section,
+};
+use crate::server::consts::user_flag::UserFlag;
+use crate::server::data_structures::{ChannelConfiguration, ClientInfo, ServerInfo};
+
+#[cfg(test)]
+mod tests;
+
+/// Version of the format snapshots are written in.
+/// Snapshots written in any other version are refused.
+pub const SNAPSHOT_VERSION: usize = 1;
+
+const SNAPSHOT_SECTION: &str = "snapshot";
+const SERVER_SECTION: &str = "server";
+const CLIENT_SECTION: &str = "client";
+const CHANNEL_SECTION: &str = "channel";
+const SNAPSHOT_KEYS: [&str; 3] = ["version", "servername", "created"];
+const SERVER_KEYS: [&str; 2] = ["info", "hopcount"];
+const CLIENT_KEYS: [&str; 9] = [
+    "username",
+    "hostname",
+    "servername",
+    "realname",
+    "hopcount",
+    "signon",
+    "away",
+    "account",
+    "flags",
+];
+const CHANNEL_KEYS: [&str; 3] = ["founder", "members", "invites"];
+const HEADER: &str = "# Database snapshot, restored when the server starts.\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// State of the network as a server knew it, without any connection.
+pub struct Snapshot {
+    /// server the snapshot was taken from
+    pub servername: String,
+    /// unix timestamp of when the snapshot was taken
+    pub created: u64,
+    pub servers: Vec<ServerInfo>,
+    pub clients: Vec<ClientInfo>,
+    pub channels: Vec<ChannelSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A channel with its members, invites and configuration.
+pub struct ChannelSnapshot {
+    pub name: String,
+    pub founder: Option<String>,
+    pub members: Vec<String>,
+    pub invites: Vec<String>,
+    pub config: ChannelConfiguration,
+}
+
+/// File a snapshot is saved to, in the same format as the configuration file:
+///
+/// ```text
+/// [snapshot]
+/// version = 1
+/// servername = servername
+/// created = 1669831542
+///
+/// [client nickname]
+/// username = username
+/// ...
+///
+/// [channel #channel]
+/// members = nickname other
+/// topic = "channel topic"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotFile {
+    path: PathBuf,
+}
+
+impl SnapshotFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Reads the saved snapshot. Returns None if none was saved.
+    pub fn load(&self) -> Result<Option<Snapshot>, ConfigError> {
+        match read_if_exists(&self.path)? {
+            Some(content) => parse_snapshot(&content).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Replaces the saved snapshot with snapshot.
+    pub fn save(&self, snapshot: &Snapshot) -> io::Result<()> {
+        write_atomically(&self.path, &format_snapshot(snapshot))
+    }
+}
+
+fn parse_snapshot(content: &str) -> Result<Snapshot, ConfigError> {
+    let sections = parse_sections(content)?;
+    let mut sections = sections.iter();
+
+    let mut snapshot = match sections.next() {
+        Some(section) if section.name == SNAPSHOT_SECTION => parse_header(section)?,
+        _ => {
+            let section = SNAPSHOT_SECTION.to_string();
+            return Err(ConfigError::MissingSection { section });
+        }
+    };
+
+    for section in sections {
+        match (section.name.as_str(), &section.argument) {
+            (SERVER_SECTION, Some(name)) => snapshot.servers.push(parse_server(section, name)?),
+            (CLIENT_SECTION, Some(name)) => snapshot.clients.push(parse_client(section, name)?),
+            (CHANNEL_SECTION, Some(name)) => snapshot.channels.push(parse_channel(section, name)?),
+            _ => {
+                let line = section.line;
+                let section = section.name.clone();
+                return Err(ConfigError::UnknownSection { line, section });
+            }
+        }
+    }
+
+    Ok(snapshot)
+}
+
+fn parse_header(section: &Section) -> Result<Snapshot, ConfigError> {
+    section.check_keys(&SNAPSHOT_KEYS)?;
+
+    let entry = section.required("version")?;
+    if entry.number()? != SNAPSHOT_VERSION {
+        let reason = format!("unsupported version, expected {SNAPSHOT_VERSION}");
+        return Err(entry.invalid(&reason));
+    }
+
+    Ok(Snapshot {
+        servername: section.required("servername")?.word()?,
+        created: section.required("created")?.number()? as u64,
+        servers: vec![],
+        clients: vec![],
+        channels: vec![],
+    })
+}
+
+fn parse_server(section: &Section, name: &str) -> Result<ServerInfo, ConfigError> {
+    section.check_keys(&SERVER_KEYS)?;
+
+    let serverinfo = section.required("info")?.text();
+    let hopcount = section.required("hopcount")?.number()?;
+
+    Ok(ServerInfo::new(name.to_string(), serverinfo, hopcount))
+}
+
+fn parse_client(section: &Section, name: &str) -> Result<ClientInfo, ConfigError> {
+    section.check_keys(&CLIENT_KEYS)?;
+
+    let mut client = ClientInfo {
+        nickname: name.to_string(),
+        username: section.required("username")?.word()?,
+        hostname: section.required("hostname")?.word()?,
+        servername: section.required("servername")?.word()?,
+        realname: section.required("realname")?.text(),
+        hopcount: optional_number(section.optional("hopcount")?)?,
+        signon: section.required("signon")?.number()? as u64,
+        away: section.optional("away")?.map(Entry::text),
+        flags: Default::default(),
+        account: match section.optional("account")? {
+            Some(entry) => Some(entry.word()?),
+            None => None,
+        },
+    };
+    if let Some(entry) = section.optional("flags")? {
+        for character in entry.value.chars() {
+            match UserFlag::from_char(character) {
+                UserFlag::InvalidFlag => {
+                    return Err(entry.invalid(&format!("unknown flag {character}")))
+                }
+                flag => client.add_flag(flag),
+            }
+        }
+    }
+
+    Ok(client)
+}
+
+fn parse_channel(section: &Section, name: &str) -> Result<ChannelSnapshot, ConfigError> {
+    section.check_keys(&[&CHANNEL_KEYS[..], &CONFIGURATION_KEYS].concat())?;
+
+    Ok(ChannelSnapshot {
+        name: name.to_string(),
+        founder: match section.optional("founder")? {
+            Some(entry) => Some(entry.word()?),
+            None => None,
+        },
+        members: words(section, "members")?,
+        invites: words(section, "invites")?,
+        config: parse_configuration(section)?,
+    })
+}
+
+/// Zero is left out when written, since it is the default.
+fn optional_number(entry: Option<&Entry>) -> Result<usize, ConfigError> {
+    match entry {
+        Some(entry) => entry.number(),
+        None => Ok(0),
+    }
+}
+
+fn format_snapshot(snapshot: &Snapshot) -> String {
+    let mut content = HEADER.to_string();
+
+    content.push_str(&format!("\n[{SNAPSHOT_SECTION}]\n"));
+    content.push_str(&format!("version = {SNAPSHOT_VERSION}\n"));
+    content.push_str(&format!("servername = {}\n", snapshot.servername));
+    content.push_str(&format!("created = {}\n", snapshot.created));
+
+    for server in &snapshot.servers {
+        content.push_str(&format!("\n[{SERVER_SECTION} {}]\n", server.servername));
+        push_text(&mut content, "info", &server.serverinfo);
+        push_entry(&mut content, "hopcount", &server.hopcount.to_string());
+    }
+
+    for client in &snapshot.clients {
+        content.push_str(&format!("\n[{CLIENT_SECTION} {}]\n", client.nickname));
+        push_entry(&mut content, "username", &client.username);
+        push_entry(&mut content, "hostname", &client.hostname);
+        push_entry(&mut content, "servername", &client.servername);
+        push_text(&mut content, "realname", &client.realname);
+        if client.hopcount > 0 {
+            push_entry(&mut content, "hopcount", &client.hopcount.to_string());
+        }
+        push_entry(&mut content, "signon", &client.signon.to_string());
+        if let Some(away) = &client.away {
+            push_text(&mut content, "away", away);
+        }
+        if let Some(account) = &client.account {
+            push_entry(&mut content, "account", account);
+        }
+        let mut flags: Vec<char> = client.flags.keys().map(UserFlag::to_char).collect();
+        flags.sort_unstable();
+        push_entry(
+            &mut content,
+            "flags",
+            &flags.into_iter().collect::<String>(),
+        );
+    }
+
+    for channel in &snapshot.channels {
+        content.push_str(&format!("\n[{CHANNEL_SECTION} {}]\n", channel.name));
+        if let Some(founder) = &channel.founder {
+            push_entry(&mut content, "founder", founder);
+        }
+        push_entry(&mut content, "members", &channel.members.join(" "));
+        push_entry(&mut content, "invites", &channel.invites.join(" "));
+        format_configuration(&mut content, &channel.config);
+    }
+
+    content
+}
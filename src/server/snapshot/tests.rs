@@ -0,0 +1,103 @@
+use std::{env, fs};
+
+use crate::server::consts::channel_flag::ChannelFlag;
+
+use super::*;
+
+fn temporary_file(name: &str) -> (PathBuf, SnapshotFile) {
+    let path = env::temp_dir().join(format!("{name}-{}.db", std::process::id()));
+    let _ = fs::remove_file(&path);
+    (path.clone(), SnapshotFile::new(path))
+}
+
+fn snapshot() -> Snapshot {
+    let mut client = ClientInfo {
+        nickname: "nick".to_string(),
+        username: "user".to_string(),
+        hostname: "127.0.0.1".to_string(),
+        servername: "servername".to_string(),
+        realname: "real name".to_string(),
+        hopcount: 0,
+        signon: 1669831542,
+        away: Some("gone for lunch".to_string()),
+        flags: Default::default(),
+        account: Some("nick".to_string()),
+    };
+    client.add_flag(UserFlag::Invisible);
+    client.add_flag(UserFlag::Operator);
+
+    let mut config = ChannelConfiguration::new();
+    config.topic = Some("a channel topic".to_string());
+    config.flags = vec![ChannelFlag::InviteOnly];
+    config.operators = vec!["nick".to_string()];
+    config.banmasks = vec!["*!*@host".to_string()];
+
+    Snapshot {
+        servername: "servername".to_string(),
+        created: 1669831600,
+        servers: vec![ServerInfo::new(
+            "servername2".to_string(),
+            "other server".to_string(),
+            1,
+        )],
+        clients: vec![client],
+        channels: vec![ChannelSnapshot {
+            name: "#channel".to_string(),
+            founder: None,
+            members: vec!["nick".to_string()],
+            invites: vec!["other".to_string()],
+            config,
+        }],
+    }
+}
+
+#[test]
+fn file_that_was_never_saved_holds_no_snapshot() {
+    let (_, file) = temporary_file("missing-snapshot");
+
+    assert_eq!(None, file.load().unwrap());
+}
+
+#[test]
+fn saved_snapshot_can_be_loaded() {
+    let (path, file) = temporary_file("saved-snapshot");
+
+    file.save(&snapshot()).unwrap();
+
+    assert_eq!(Some(snapshot()), file.load().unwrap());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn empty_and_padded_text_survives_a_save() {
+    let (path, file) = temporary_file("padded-snapshot");
+    let mut snapshot = snapshot();
+    snapshot.servers[0].serverinfo = String::new();
+    snapshot.clients[0].realname = String::new();
+    snapshot.clients[0].away = Some("  back soon  ".to_string());
+    snapshot.channels[0].config.topic = Some(" \"quoted\" topic ".to_string());
+
+    file.save(&snapshot).unwrap();
+
+    assert_eq!(Some(snapshot), file.load().unwrap());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn snapshot_without_header_is_invalid() {
+    let content = "[channel #channel]\ntopic = topic\n";
+
+    assert_eq!(
+        Err(ConfigError::MissingSection {
+            section: "snapshot".to_string()
+        }),
+        parse_snapshot(content)
+    );
+}
+
+#[test]
+fn snapshot_of_other_version_is_invalid() {
+    let content = "[snapshot]\nversion = 2\nservername = servername\ncreated = 1669831600\n";
+
+    assert!(parse_snapshot(content).is_err());
+}
@@ -46,10 +46,6 @@ impl Connection for MockTcpStream {
     fn shutdown(&self) -> io::Result<()> {
         Ok(())
     }
-
-    fn set_read_timeout(&self, _duration: Option<std::time::Duration>) -> io::Result<()> {
-        Ok(())
-    }
 }
 
 impl PartialEq for MockTcpStream {
@@ -73,11 +69,11 @@ impl MockTcpStream {
         self.write_lock().clear();
     }
 
-    fn read_lock(&self) -> MutexGuard<Vec<u8>> {
+    fn read_lock(&self) -> MutexGuard<'_, Vec<u8>> {
         self.read_buffer.lock().unwrap()
     }
 
-    fn write_lock(&self) -> MutexGuard<Vec<u8>> {
+    fn write_lock(&self) -> MutexGuard<'_, Vec<u8>> {
         self.write_buffer.lock().unwrap()
     }
 
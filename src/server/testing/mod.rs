@@ -1,11 +1,68 @@
 mod mock_stream;
 
+use std::path::PathBuf;
+use std::{env, fs, io, process};
+
+use crate::message::Message;
+
 use super::{
+    connection_handler::ConnectionHandlerStructure,
     data_structures::*,
     database::{Database, DatabaseHandle},
+    SENDQ_BYTES,
 };
 pub use mock_stream::MockTcpStream;
 
+/// Prefix, middle parameters and trailing parameter of a command, as read from a connection.
+pub type RawArguments = (Option<String>, Vec<String>, Option<String>);
+
+/// Lets tests hand a command to a handler as if it was read from its connection.
+pub trait HandleRawCommand {
+    fn raw_command(&mut self, command: &str, arguments: RawArguments) -> io::Result<bool>;
+}
+
+impl<H: ConnectionHandlerStructure<MockTcpStream>> HandleRawCommand for H {
+    fn raw_command(&mut self, command: &str, arguments: RawArguments) -> io::Result<bool> {
+        let (prefix, parameters, trailing) = arguments;
+        let message = Message::builder()
+            .optional_prefix(prefix)
+            .command(command)
+            .params(parameters)
+            .optional_trailing(trailing)
+            .build()
+            .unwrap();
+
+        let handled = self.handle_message(message);
+        self.flush_output()?;
+        handled
+    }
+}
+
+/// Lets tests read what was queued for other connections, as if their handlers had been polled.
+pub trait ReadQueuedOutput {
+    fn flushed_local_stream(&self, nickname: &str) -> MockTcpStream;
+    fn flushed_server_stream(&self, server: &str) -> MockTcpStream;
+}
+
+impl ReadQueuedOutput for DatabaseHandle<MockTcpStream> {
+    fn flushed_local_stream(&self, nickname: &str) -> MockTcpStream {
+        flushed_stream(&self.get_local_queue(nickname).unwrap())
+    }
+
+    fn flushed_server_stream(&self, server: &str) -> MockTcpStream {
+        flushed_stream(&self.get_server_queue(server).unwrap())
+    }
+}
+
+/// Writes everything queued, returning the connection it was written to.
+pub fn flushed_stream(queue: &OutputQueue<MockTcpStream>) -> MockTcpStream {
+    queue.flush().unwrap();
+    queue.try_clone_stream().unwrap()
+}
+
+/// Signon timestamp given to every dummy client.
+pub const DUMMY_SIGNON: u64 = 1_000_000_000;
+
 /// Creates dummy local client used for tests.
 pub fn dummy_client(nickname: &str) -> LocalClient<MockTcpStream> {
     ClientBuilder::new()
@@ -14,17 +71,18 @@ pub fn dummy_client(nickname: &str) -> LocalClient<MockTcpStream> {
         .hostname("127.0.0.1")
         .servername("servername")
         .realname("realname")
-        .stream(MockTcpStream::new())
+        .signon(DUMMY_SIGNON)
+        .queue(OutputQueue::new(MockTcpStream::new(), SENDQ_BYTES))
         .build_local_client()
         .unwrap()
 }
 
 /// Creates dummy immediate server used for tests.
 pub fn dummy_server(servername: &str) -> ImmediateServer<MockTcpStream> {
-    let stream = MockTcpStream::new();
+    let queue = OutputQueue::new(MockTcpStream::new(), SENDQ_BYTES);
     let servername = servername.to_string();
     let serverinfo = "serverinfo".to_string();
-    ImmediateServer::new(stream, servername, serverinfo, 1)
+    ImmediateServer::new(queue, servername, serverinfo, 1)
 }
 
 /// Creates dummy database used for tests.
@@ -45,6 +103,7 @@ pub fn dummy_external_client(nickname: &str, servername: &str) -> ExternalClient
         .realname("realname")
         .immediate(servername)
         .hopcount(1)
+        .signon(DUMMY_SIGNON)
         .build_external_client()
         .unwrap()
 }
@@ -53,3 +112,23 @@ pub fn dummy_external_client(nickname: &str, servername: &str) -> ExternalClient
 pub fn dummy_distant_server(servername: &str) -> ServerInfo {
     ServerInfo::new(servername.to_string(), "serverinfo".to_string(), 2)
 }
+
+/// Writes a dummy MOTD file, unique to each test, and returns its path.
+pub fn dummy_motd_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{name}_{}.motd", process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+/// Creates dummy operator account, usable from any host, used for tests.
+pub fn dummy_operator(name: &str, password: &str) -> OperatorBlock {
+    let password = PasswordHash::generate(password);
+    OperatorBlock::new(name, password, None, OperatorPrivilege::all())
+}
+
+/// Writes a dummy configuration file, unique to each test, and returns its path.
+pub fn dummy_config_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{name}_{}.conf", process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
@@ -3,7 +3,9 @@ use std::{thread, time::Duration};
 
 use internet_relay_chat::client::Client;
 
-use crate::utils::{create_lemonpie_server, create_server};
+use crate::utils::{
+    configure, create_lemonpie_server, create_server, skip_registration_burst, skip_until,
+};
 
 #[test]
 fn can_create_server_that_listens_to_address() {
@@ -53,7 +55,10 @@ fn client_can_send_and_receive_message_from_server() {
     assert!(client.send(message).is_ok());
 
     let response = client.read().unwrap();
-    assert_eq!("421 HOLA :Unknown command", response.to_string());
+    assert_eq!(
+        ":lemonpie 421 * HOLA :Unknown command",
+        response.to_string()
+    );
 }
 
 #[test]
@@ -70,9 +75,15 @@ fn client_can_send_and_receive_multiple_messages_from_server() {
     client.send(message2).unwrap();
 
     let response1 = client.read().unwrap();
-    assert_eq!("421 HOLA :Unknown command", response1.to_string());
+    assert_eq!(
+        ":lemonpie 421 * HOLA :Unknown command",
+        response1.to_string()
+    );
     let response2 = client.read().unwrap();
-    assert_eq!("421 PROBANDO :Unknown command", response2.to_string());
+    assert_eq!(
+        ":lemonpie 421 * PROBANDO :Unknown command",
+        response2.to_string()
+    );
 }
 
 #[test]
@@ -100,16 +111,23 @@ fn client_can_register_in_server() {
 
     let response = client.read().unwrap();
     assert_eq!(
-        "001 realname :Welcome to lemonpie Network, nickname !username @127.0.0.1",
+        ":lemonpie 001 nickname :Welcome to the Internet Relay Network nickname!username@127.0.0.1",
         response.to_string()
     );
+    skip_registration_burst(&mut client);
 
     let message3 = "JOIN #channel";
     client.send(message3).unwrap();
     let response = client.read().unwrap();
-    assert_eq!("331 #channel :No topic is set", response.to_string());
+    assert_eq!(
+        ":lemonpie 331 nickname #channel :No topic is set",
+        response.to_string()
+    );
     let response2 = client.read().unwrap();
-    assert_eq!("353 #channel :nickname", response2.to_string());
+    assert_eq!(
+        ":lemonpie 353 nickname #channel :nickname",
+        response2.to_string()
+    );
 }
 
 #[test]
@@ -118,15 +136,21 @@ fn can_shutdown_server() {
     let server = create_lemonpie_server(address.clone());
 
     let mut client = Client::connect(address).unwrap();
+    client.send("HOLA").unwrap();
+    client.read().unwrap();
 
     server.quit();
+    assert_eq!(
+        "ERROR :Closing Link: * (Server shutting down)",
+        client.read().unwrap().to_string()
+    );
     assert!(client.read().is_err());
 }
 
 #[test]
 fn can_connect_two_servers() {
     let address1 = "127.0.0.1:9010".to_string();
-    let _server1 = create_lemonpie_server(address1.clone());
+    let server1 = create_lemonpie_server(address1.clone());
 
     let mut client1 = Client::connect(address1.clone()).unwrap();
 
@@ -137,15 +161,16 @@ fn can_connect_two_servers() {
 
     let response1 = client1.read().unwrap();
     assert_eq!(
-        "001 realname1 :Welcome to lemonpie Network, nickname1 !username1 @127.0.0.1",
+        ":lemonpie 001 nickname1 :Welcome to the Internet Relay Network nickname1!username1@127.0.0.1",
         response1.to_string()
     );
+    skip_registration_burst(&mut client1);
 
     let address2 = "127.0.0.1:9011".to_string();
     let mut server2 = create_server("chocotorta", "New IRC server");
     server2.listen_to(address2.clone()).unwrap();
 
-    let mut client2 = Client::connect(address2).unwrap();
+    let mut client2 = Client::connect(address2.clone()).unwrap();
 
     let nick2 = "NICK nickname2";
     let user2 = "USER username2 :realname2";
@@ -154,11 +179,20 @@ fn can_connect_two_servers() {
 
     let response2 = client2.read().unwrap();
     assert_eq!(
-        "001 realname2 :Welcome to chocotorta Network, nickname2 !username2 @127.0.0.1",
+        ":chocotorta 001 nickname2 :Welcome to the Internet Relay Network nickname2!username2@127.0.0.1",
         response2.to_string()
     );
+    skip_registration_burst(&mut client2);
 
-    server2.connect_to(&address1);
+    let config1 = format!(
+        "[server]\nname = lemonpie\ninfo = serverinfo\nlisten = {address1}\n\n[link chocotorta]\npassword = linkpassword\n"
+    );
+    let config2 = format!(
+        "[server]\nname = chocotorta\ninfo = New IRC server\nlisten = {address2}\n\n[link lemonpie]\npassword = linkpassword\n"
+    );
+    configure(&server1, &config1);
+    configure(&server2, &config2);
+    server2.connect_to("lemonpie", &address1);
     thread::sleep(Duration::from_millis(100));
 
     let thread1 = thread::spawn(move || {
@@ -176,3 +210,58 @@ fn can_connect_two_servers() {
     thread1.join().unwrap();
     thread2.join().unwrap();
 }
+
+#[test]
+fn connections_over_limit_per_host_are_refused() {
+    let address = "127.0.0.1:9012".to_string();
+    let server = create_lemonpie_server(address.clone());
+    let config =
+        format!("[server]\nname = lemonpie\ninfo = serverinfo\nlisten = {address}\n\n[limits]\nmax_per_ip = 1\n");
+    configure(&server, &config);
+
+    let mut client1 = Client::connect(address.clone()).unwrap();
+    client1.send("HOLA").unwrap();
+    client1.read().unwrap();
+
+    let mut client2 = Client::connect(address).unwrap();
+    assert_eq!(
+        "ERROR :Closing Link: 127.0.0.1 (Too many connections from your host)",
+        client2.read().unwrap().to_string()
+    );
+    assert_eq!(1, server.refused_connections().too_many_from_host);
+}
+
+#[test]
+fn client_dropping_its_connection_quits_the_network() {
+    let address = "127.0.0.1:9013".to_string();
+    let _server = create_lemonpie_server(address.clone());
+
+    let mut alice = Client::connect(address.clone()).unwrap();
+    alice.send("NICK alice").unwrap();
+    alice.send("USER alice :Alice").unwrap();
+    skip_registration_burst(&mut alice);
+    alice.send("JOIN #x").unwrap();
+    skip_until(&mut alice, "353");
+
+    let mut bob = Client::connect(address.clone()).unwrap();
+    bob.send("NICK bob").unwrap();
+    bob.send("USER bob :Bob").unwrap();
+    skip_registration_burst(&mut bob);
+    bob.send("JOIN #x").unwrap();
+    skip_until(&mut bob, "353");
+
+    drop(alice);
+
+    let quit = bob.read().unwrap().to_string();
+    assert!(quit.starts_with(":alice QUIT :"));
+    bob.send("NAMES #x").unwrap();
+    assert_eq!(":lemonpie 353 bob #x :bob", bob.read().unwrap().to_string());
+
+    let mut new_alice = Client::connect(address).unwrap();
+    new_alice.send("NICK alice").unwrap();
+    new_alice.send("USER alice :Alice").unwrap();
+    assert_eq!(
+        ":lemonpie 001 alice :Welcome to the Internet Relay Network alice!alice@127.0.0.1",
+        new_alice.read().unwrap().to_string()
+    );
+}
@@ -1,4 +1,5 @@
-use internet_relay_chat::server::Server;
+use internet_relay_chat::client::Client;
+use internet_relay_chat::server::{Server, ServerConfig};
 
 pub fn create_server(servername: &str, serverinfo: &str) -> Server {
     let servername = servername.to_string();
@@ -14,3 +15,30 @@ pub fn create_lemonpie_server(address: String) -> Server {
 
     server
 }
+
+/// Applies configuration file content to server, as if it had been read at startup.
+pub fn configure(server: &Server, content: &str) {
+    let config: ServerConfig = content.parse().unwrap();
+    server.configure(&config);
+}
+
+/// Reads the rest of the registration burst, up to the end of the MOTD.
+pub fn skip_registration_burst(client: &mut Client) {
+    loop {
+        let response = client.read().unwrap().to_string();
+        let code = response.split(' ').nth(1).unwrap_or_default();
+        if code == "376" || code == "422" {
+            return;
+        }
+    }
+}
+
+/// Reads responses up to and including the first one with the given code.
+pub fn skip_until(client: &mut Client, code: &str) {
+    loop {
+        let response = client.read().unwrap().to_string();
+        if response.split(' ').nth(1) == Some(code) {
+            return;
+        }
+    }
+}